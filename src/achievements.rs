@@ -0,0 +1,129 @@
+use crate::difficulty;
+use crate::error::ApiError;
+use crate::game::{ClientId, GameId};
+use crate::json_response;
+use crate::registration;
+use crate::repository::StoredGame;
+use crate::service::Service;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+use wordle_core::GameState;
+
+/// Answers rated at least this are the hardest tier
+const HARDEST_RATING: u32 = 90;
+
+/// Something a client has done that the server recognises, awarded once per client
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    FirstWin,
+    SolvedInTwo,
+    ThirtyStreak,
+    HardestWord,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 4] = [
+        Achievement::FirstWin,
+        Achievement::SolvedInTwo,
+        Achievement::ThirtyStreak,
+        Achievement::HardestWord,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "first_win",
+            Achievement::SolvedInTwo => "solved_in_two",
+            Achievement::ThirtyStreak => "thirty_streak",
+            Achievement::HardestWord => "hardest_word",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Achievement> {
+        Achievement::ALL.into_iter().find(|x| x.as_str() == value)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "Won a game",
+            Achievement::SolvedInTwo => "Solved a game in two goes or fewer",
+            Achievement::ThirtyStreak => "Won a game every day for 30 days in a row",
+            Achievement::HardestWord => "Solved one of the hardest answers",
+        }
+    }
+
+    /// Every achievement the just finished game earns, given the UTC days the client has won a
+    /// game started on, this one's included, latest first
+    pub fn earned(game: &StoredGame, days_won: &[u64]) -> Vec<Achievement> {
+        if game.state != GameState::Won {
+            return Vec::new();
+        }
+
+        let hardest = match game.mode_state.boards.as_slice() {
+            [board] => difficulty::rating(board.word()).is_some_and(|x| x >= HARDEST_RATING),
+            _ => false,
+        };
+
+        Achievement::ALL
+            .into_iter()
+            .filter(|x| match x {
+                Achievement::FirstWin => true,
+                Achievement::SolvedInTwo => game.goes <= 2,
+                Achievement::ThirtyStreak => days_in_a_row(days_won) >= 30,
+                Achievement::HardestWord => hardest,
+            })
+            .collect()
+    }
+}
+
+/// Days in a row with a win on them, running back from the latest of `days`, latest first
+fn days_in_a_row(days: &[u64]) -> usize {
+    let earlier = days.windows(2).take_while(|x| x[0] == x[1] + 1).count();
+
+    if days.is_empty() {
+        0
+    } else {
+        earlier + 1
+    }
+}
+
+/// An achievement as it was awarded
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Granted {
+    pub achievement: Achievement,
+    pub description: &'static str,
+    /// The game that earned it
+    pub game_id: GameId,
+}
+
+impl Granted {
+    pub fn new(achievement: Achievement, game_id: GameId) -> Granted {
+        Granted {
+            achievement,
+            description: achievement.description(),
+            game_id,
+        }
+    }
+}
+
+/// `GET /achievements/<client>` lists every achievement the client has been awarded, in the order
+/// they were
+pub fn handle(service: &Service, request: &Request, client: &str) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+
+    json_response(&service.achievements(&client)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::achievements::days_in_a_row;
+
+    #[test]
+    fn streaks_are_of_days_won_on() {
+        assert_eq!(days_in_a_row(&[]), 0);
+        assert_eq!(days_in_a_row(&[9, 8, 7, 5]), 3);
+        // a day without a win breaks the streak, however many were won before it
+        assert_eq!(days_in_a_row(&[9, 7, 6]), 1);
+    }
+}
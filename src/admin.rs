@@ -0,0 +1,442 @@
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::events::{self, GameEvent};
+use crate::game::{self, ClientId, GameId, GameOptions, GameState};
+use crate::transfer::{self, Conflict, Dump, EventRecord, Record, RejectionRecord};
+use crate::{bool_param, db, json_response, maintenance, reminders};
+use rouille::{Request, Response};
+use rusqlite::OptionalExtension;
+use serde::Serialize;
+use std::io::{BufRead, BufReader};
+use uuid::Uuid;
+
+/// Admin endpoints require `Authorization: Bearer <WORDLE_ADMIN_TOKEN>`
+pub fn authorize(config: &Config, request: &Request) -> Result<(), ApiError> {
+    match (&config.admin_token, bearer_token(request)) {
+        (Some(expected), Some(provided)) if expected == provided => Ok(()),
+        _ => Err(ApiError::Unauthorized),
+    }
+}
+
+/// The token an `Authorization: Bearer <token>` header carries
+pub fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .header("Authorization")
+        .and_then(|x| x.strip_prefix("Bearer "))
+}
+
+pub fn handle_repairs(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let conn = config.connection()?;
+
+    json_response(&db::repairs(&conn)?)
+}
+
+pub fn handle_maintenance(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let conn = config.connection()?;
+
+    json_response(&maintenance::run(&conn, config.archive_after_days)?)
+}
+
+/// Sends whichever streak reminders are due now, without waiting for the next scheduled check
+pub fn handle_reminders(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    json_response(&reminders::run(
+        &config.connection()?,
+        game::now(),
+        config.mock.as_deref(),
+        config.private_webhooks,
+    )?)
+}
+
+#[derive(Serialize)]
+struct ReadOnly {
+    read_only: bool,
+    message: Option<String>,
+}
+
+pub fn handle_read_only(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let conn = config.connection()?;
+    let message = maintenance::read_only(&conn)?;
+
+    json_response(&ReadOnly {
+        read_only: message.is_some(),
+        message,
+    })
+}
+
+/// `?enabled=<bool>` toggles read-only mode, with an optional `&message=` for refused requests
+pub fn handle_set_read_only(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let enabled = bool_param(request, "enabled")?.ok_or_else(|| ApiError::InvalidParam {
+        name: String::from("enabled"),
+        value: String::new(),
+    })?;
+    let message = request.get_param("message").unwrap_or_default();
+
+    let conn = config.connection()?;
+    maintenance::set_read_only(&conn, enabled.then_some(message.as_str()))?;
+    db::audit(
+        &conn,
+        "set_read_only",
+        "instance",
+        &serde_json::json!({ "enabled": enabled, "message": message }),
+    )?;
+
+    handle_read_only(config, request)
+}
+
+/// Everything known about a game, hidden word included
+#[derive(Serialize)]
+struct GameDetails {
+    game_id: String,
+    client: String,
+    word: String,
+    goes: usize,
+    state: GameState,
+    options: GameOptions,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    rejected_guesses: Vec<RejectionRecord>,
+    events: Vec<EventRecord>,
+}
+
+pub fn handle_game(
+    config: &Config,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+    let game_id = GameId::parse(game_id)?;
+
+    let conn = config.connection()?;
+    let game = transfer::games(&conn, Some(game_id.as_str()))?
+        .pop()
+        .ok_or_else(|| ApiError::GameNotFound(game_id.to_string()))?;
+
+    json_response(&GameDetails {
+        rejected_guesses: transfer::rejections(&conn, Some(game_id.as_str()))?,
+        events: transfer::events(&conn, Some(game_id.as_str()))?,
+        game_id: game.game_id,
+        client: game.client,
+        word: game.word,
+        goes: game.goes,
+        state: game.state,
+        options: GameOptions {
+            invalid_guesses_count: game.invalid_guesses_count,
+            mode: game.mode,
+            max_guesses: game.max_guesses,
+            length: game.length,
+            language: game.language,
+        },
+        created_at: game.created_at,
+        updated_at: game.updated_at,
+    })
+}
+
+/// `POST /admin/game/<game_id>/undo` takes back the game's latest guess, for when a client bug
+/// played one its player never meant to, replaying the rest of the game's events to put it back as
+/// it was before the guess. Only games still in the `game` table can be changed, archived ones
+/// stay as they finished.
+pub fn handle_undo_guess(
+    config: &Config,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+    let game_id = GameId::parse(game_id)?;
+
+    let mut conn = config.connection()?;
+    let tx = conn.transaction()?;
+
+    let found = tx
+        .query_row("SELECT 1 FROM game WHERE game_id = ?1", [&game_id], |_| {
+            Ok(())
+        })
+        .optional()?;
+    if found.is_none() {
+        return Err(ApiError::GameNotFound(game_id.to_string()));
+    }
+
+    let mut events = tx
+        .prepare("SELECT event_id, data FROM game_event WHERE game_id = ?1 ORDER BY event_id")?
+        .query_map([&game_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .map(|row| {
+            let (event_id, data) = row?;
+            Ok((event_id, serde_json::from_str::<GameEvent>(&data)?))
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    let guessed = events
+        .iter()
+        .rposition(|(_, event)| matches!(event, GameEvent::Guessed { .. }))
+        .ok_or_else(|| ApiError::RuleViolation(String::from("the game has no guess to undo")))?;
+    let (event_id, GameEvent::Guessed { guess }) = events.remove(guessed) else {
+        unreachable!("the event was found as a guess");
+    };
+    let mut undone = vec![event_id];
+    // a winning guess is followed by the game being solved, which goes with it
+    if matches!(events.get(guessed), Some((_, GameEvent::Solved))) {
+        undone.push(events.remove(guessed).0);
+    }
+
+    let remaining = events.into_iter().map(|(_, x)| x).collect::<Vec<_>>();
+    let game = events::project(&remaining).map_err(ApiError::RuleViolation)?;
+    for event_id in undone {
+        tx.execute("DELETE FROM game_event WHERE event_id = ?1", [event_id])?;
+    }
+    tx.execute(
+        "DELETE FROM game_guess
+        WHERE game_id = ?1 AND go = (SELECT MAX(go) FROM game_guess WHERE game_id = ?1)",
+        [&game_id],
+    )?;
+    tx.execute(
+        "UPDATE game SET goes = ?1, solved = ?2, state = ?3, word = ?4, mode_state = ?5,
+            updated_at = datetime(?7, 'unixepoch')
+        WHERE game_id = ?6",
+        (
+            game.goes,
+            game.state == GameState::Won,
+            game.state.as_str(),
+            game.mode_state.boards[0].word(),
+            serde_json::to_string(&game.mode_state)?,
+            &game_id,
+            game::now(),
+        ),
+    )?;
+    db::audit(
+        &tx,
+        "undo_guess",
+        game_id.as_str(),
+        &serde_json::json!({ "guess": guess, "goes": game.goes, "state": game.state }),
+    )?;
+    tx.commit()?;
+
+    handle_game(config, request, game_id.as_str())
+}
+
+/// `POST /import[?format=ndjson|json][&on_conflict=fail|skip|overwrite]` loads a dump in the format
+/// `GET /export` or the CLI wrote it, all or nothing. A game whose id is already taken fails the
+/// whole import unless it's to be skipped or overwritten.
+pub fn handle_import(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let conflict = match request.get_param("on_conflict").as_deref() {
+        None | Some("fail") => Conflict::Fail,
+        Some("skip") => Conflict::Skip,
+        Some("overwrite") => Conflict::Overwrite,
+        Some(conflict) => {
+            return Err(ApiError::InvalidParam {
+                name: String::from("on_conflict"),
+                value: conflict.to_string(),
+            })
+        }
+    };
+    let ndjson = match request.get_param("format").as_deref() {
+        None | Some("ndjson") => true,
+        Some("json") => false,
+        Some(format) => {
+            return Err(ApiError::InvalidParam {
+                name: String::from("format"),
+                value: format.to_string(),
+            })
+        }
+    };
+
+    let body =
+        BufReader::new(request.data().ok_or_else(|| {
+            ApiError::InvalidBody(String::from("the body has already been read"))
+        })?);
+    let dump: Dump = if ndjson {
+        let mut records = Vec::new();
+        for line in body.lines() {
+            let line = line.map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+            if !line.trim().is_empty() {
+                let record = serde_json::from_str::<Record>(&line)
+                    .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+                records.push(record);
+            }
+        }
+
+        Dump::from_records(records)
+    } else {
+        serde_json::from_reader(body).map_err(|error| ApiError::InvalidBody(error.to_string()))?
+    };
+
+    let mut conn = config.connection()?;
+    let summary = transfer::import(&mut conn, dump, conflict)?;
+    db::audit(
+        &conn,
+        "import",
+        "instance",
+        &serde_json::to_value(&summary)?,
+    )?;
+
+    json_response(&summary)
+}
+
+#[derive(Serialize)]
+struct Erasure {
+    client: ClientId,
+    mode: &'static str,
+    games: usize,
+    rejected_guesses: usize,
+    logged_guesses: usize,
+}
+
+/// Deletes every game played by a client, or with `?mode=anonymize` detaches them from the client
+/// so that only anonymous aggregates survive
+pub fn handle_erase_client(
+    config: &Config,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+    let client = ClientId::parse(client)?;
+
+    let anonymize = match request.get_param("mode").as_deref() {
+        None | Some("delete") => false,
+        Some("anonymize") => true,
+        Some(mode) => {
+            return Err(ApiError::InvalidParam {
+                name: String::from("mode"),
+                value: mode.to_string(),
+            })
+        }
+    };
+
+    let mut conn = config.connection()?;
+    let tx = conn.transaction()?;
+
+    // links to their games would still show their name, or their games' new one
+    tx.execute(
+        "DELETE FROM share_link WHERE game_id IN (SELECT game_id FROM game_history WHERE client = ?1)",
+        [&client],
+    )?;
+    tx.execute(
+        "DELETE FROM replay_token WHERE game_id IN (SELECT game_id FROM game_history WHERE client = ?1)",
+        [&client],
+    )?;
+
+    // messages are only ever personal, whether they're the client's or tell others about them
+    tx.execute(
+        "DELETE FROM inbox WHERE client = ?1 OR json_extract(data, '$.client') = ?1",
+        [&client],
+    )?;
+    // nobody's left to play as them, and the name's free for whoever's next to register it
+    tx.execute("DELETE FROM client WHERE client = ?1", [&client])?;
+
+    // the guess log refuses to give up anyone's guesses but those of the client being erased,
+    // which go even when anonymizing, as they're kept by client
+    db::set_setting(&tx, "erasing_client", Some(client.as_str()))?;
+    let logged_guesses = tx.execute("DELETE FROM guess_log WHERE client = ?1", [&client])?;
+    db::set_setting(&tx, "erasing_client", None)?;
+
+    let erasure = if anonymize {
+        let anonymous = format!("anonymous-{}", Uuid::new_v4());
+        // creation events name the client too, so have to be rewritten along with the games
+        tx.execute(
+            "UPDATE game_event SET data = json_set(data, '$.client', ?1)
+            WHERE kind = 'created' AND game_id IN (SELECT game_id FROM game_history WHERE client = ?2)",
+            (&anonymous, &client),
+        )?;
+        let mut games = 0;
+        for table in ["game", "game_archive"] {
+            games += tx.execute(
+                &format!("UPDATE {table} SET client = ?1 WHERE client = ?2"),
+                (&anonymous, &client),
+            )?;
+        }
+        tx.execute(
+            "UPDATE achievement SET client = ?1 WHERE client = ?2",
+            (&anonymous, &client),
+        )?;
+        // a profile is nothing but personal details, so isn't kept even when anonymizing
+        tx.execute("DELETE FROM profile WHERE client = ?1", [&client])?;
+
+        Erasure {
+            client: client.clone(),
+            mode: "anonymize",
+            games,
+            rejected_guesses: 0,
+            logged_guesses,
+        }
+    } else {
+        let rejected_guesses = tx.execute(
+            "DELETE FROM rejected_guess
+            WHERE game_id IN (SELECT game_id FROM game_history WHERE client = ?1)",
+            [&client],
+        )?;
+        for table in ["game_guess", "game_event"] {
+            tx.execute(
+                &format!(
+                    "DELETE FROM {table} WHERE game_id IN (SELECT game_id FROM game_history WHERE client = ?1)"
+                ),
+                [&client],
+            )?;
+        }
+        let mut games = 0;
+        for table in ["game", "game_archive"] {
+            games += tx.execute(&format!("DELETE FROM {table} WHERE client = ?1"), [&client])?;
+        }
+        tx.execute("DELETE FROM achievement WHERE client = ?1", [&client])?;
+        tx.execute("DELETE FROM profile WHERE client = ?1", [&client])?;
+
+        Erasure {
+            client: client.clone(),
+            mode: "delete",
+            games,
+            rejected_guesses,
+            logged_guesses,
+        }
+    };
+    // friendships would still tie their friends to them, so go whichever way the games do
+    tx.execute(
+        "DELETE FROM friend WHERE client = ?1 OR friend = ?1",
+        [&client],
+    )?;
+    tx.execute("DELETE FROM friend_invite WHERE client = ?1", [&client])?;
+    tx.execute("DELETE FROM reminder WHERE client = ?1", [&client])?;
+    tx.execute("DELETE FROM guest WHERE client = ?1", [&client])?;
+    // a group goes with its owner, their members only come off its roster
+    tx.execute(
+        "DELETE FROM player_group_member
+        WHERE client = ?1 OR group_id IN (SELECT group_id FROM player_group WHERE owner = ?1)",
+        [&client],
+    )?;
+    tx.execute(
+        "DELETE FROM player_group_game
+        WHERE client = ?1 OR group_id IN (SELECT group_id FROM player_group WHERE owner = ?1)",
+        [&client],
+    )?;
+    tx.execute("DELETE FROM daily_game WHERE client = ?1", [&client])?;
+    // a challenge goes with whoever chose it, like a group does
+    tx.execute(
+        "DELETE FROM challenge_game
+        WHERE client = ?1
+            OR challenge_id IN (SELECT challenge_id FROM challenge WHERE client = ?1)",
+        [&client],
+    )?;
+    tx.execute("DELETE FROM challenge WHERE client = ?1", [&client])?;
+    tx.execute("DELETE FROM player_group WHERE owner = ?1", [&client])?;
+    tx.execute("DELETE FROM club_member WHERE client = ?1", [&client])?;
+
+    db::audit(
+        &tx,
+        "erase_client",
+        client.as_str(),
+        &serde_json::to_value(&erasure)?,
+    )?;
+    tx.commit()?;
+
+    json_response(&erasure)
+}
@@ -0,0 +1,210 @@
+use crate::dictionary;
+use crate::error::ApiError;
+use crate::game::GameId;
+use crate::json_response;
+use crate::service::Service;
+use crate::solver;
+use rouille::Response;
+use serde::Serialize;
+use wordle_core::CharMatch;
+
+#[derive(Serialize)]
+struct Analysis {
+    game_id: GameId,
+    answer: String,
+    /// The average of every guess's score
+    skill: u32,
+    luck: u32,
+    guesses: Vec<GuessAnalysis>,
+}
+
+/// How one guess went, both scored out of 100
+#[derive(Serialize, PartialEq, Debug)]
+struct GuessAnalysis {
+    guess: String,
+    /// The answers still possible before the guess was played
+    candidates_before: usize,
+    /// The answers still possible once its evaluation was seen
+    candidates_after: usize,
+    /// How many candidates the guess was expected to leave, were each equally likely the answer
+    expected_remaining: f64,
+    /// The candidate expected to leave the fewest candidates, which the reference solver would
+    /// have played
+    best_guess: String,
+    /// The fewest any of the candidates was expected to leave
+    best_expected_remaining: f64,
+    /// The answers that would still have been possible had the best guess been played instead
+    best_candidates_after: usize,
+    /// How close the guess came to the best expected reduction, 100 for the best possible guess
+    skill: u32,
+    /// How the candidates actually left compare with what the guess might have left, 50 for an
+    /// average outcome and 100 for the best one
+    luck: u32,
+}
+
+/// `GET /game/<game_id>/analysis` scores each guess of a finished game for skill, how well it
+/// narrowed down the possible answers compared with the best guess there was, and for luck, how
+/// many answers it happened to leave compared with how many it might have
+pub fn handle(service: &Service, game_id: &str) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    let game = service.find_game(&game_id)?;
+
+    if !game.state.is_finished() {
+        return Err(ApiError::RuleViolation(String::from(
+            "games are only analysed once they're finished",
+        )));
+    }
+    if game.mode_state.boards.len() != 1 {
+        return Err(ApiError::RuleViolation(String::from(
+            "only games with a single board can be analysed",
+        )));
+    }
+    // the reference solver only knows the bundled words
+    if game.options.length != dictionary::LENGTH || game.options.language != dictionary::LANGUAGE {
+        return Err(ApiError::RuleViolation(String::from(
+            "only games of five-letter English words can be analysed",
+        )));
+    }
+
+    let answer = game.mode_state.boards[0].word().to_string();
+    let answers = dictionary::answers();
+    if !answers.contains(&answer) {
+        return Err(ApiError::RuleViolation(String::from(
+            "the answer is no longer in the answer list",
+        )));
+    }
+
+    let guesses = analyse(
+        answers,
+        &answer,
+        solver::opening(),
+        &game.mode_state.revealed,
+    );
+    let average = |score: fn(&GuessAnalysis) -> u32| {
+        let total: u32 = guesses.iter().map(score).sum();
+        total / guesses.len().max(1) as u32
+    };
+
+    json_response(&Analysis {
+        game_id,
+        answer,
+        skill: average(|x| x.skill),
+        luck: average(|x| x.luck),
+        guesses,
+    })
+}
+
+/// Scores every guess against the best there was at the time, given the best opening guess
+fn analyse(
+    answers: &[String],
+    answer: &str,
+    opening: (&str, f64),
+    revealed: &[(String, Vec<CharMatch>)],
+) -> Vec<GuessAnalysis> {
+    revealed
+        .iter()
+        .enumerate()
+        .map(|(i, (guess, _))| {
+            let before = solver::candidates(answers, &revealed[..i]);
+            let after = solver::candidates(answers, &revealed[..=i]).len();
+            let n = before.len() as f64;
+
+            let expected = solver::expected_remaining(guess, &before);
+            let (best_guess, best) = if i == 0 {
+                (opening.0.to_string(), opening.1)
+            } else {
+                best_guess(&before)
+            };
+            let best_pattern = solver::pattern(answer, &best_guess);
+            let best_after = before
+                .iter()
+                .filter(|x| solver::pattern(x, &best_guess) == best_pattern)
+                .count();
+
+            let skill = if n - best > f64::EPSILON {
+                (n - expected) / (n - best)
+            } else {
+                1.0
+            };
+
+            // the chance of having been left with more candidates than were, counting half of the
+            // chance of being left with exactly as many
+            let buckets = solver::buckets(guess, &before);
+            let outcomes = before
+                .iter()
+                .map(|x| buckets[&solver::pattern(x, guess)])
+                .collect::<Vec<_>>();
+            let worse = outcomes.iter().filter(|x| **x > after).count() as f64;
+            let same = outcomes.iter().filter(|x| **x == after).count() as f64;
+            let luck = (worse + same / 2.0) / n;
+
+            GuessAnalysis {
+                guess: guess.clone(),
+                candidates_before: before.len(),
+                candidates_after: after,
+                expected_remaining: expected,
+                best_guess,
+                best_expected_remaining: best,
+                best_candidates_after: best_after,
+                skill: score(skill),
+                luck: score(luck),
+            }
+        })
+        .collect()
+}
+
+fn best_guess(candidates: &[&str]) -> (String, f64) {
+    solver::scored_best_guess(candidates).map_or((String::new(), 0.0), |(guess, expected)| {
+        (guess.to_string(), expected)
+    })
+}
+
+fn score(fraction: f64) -> u32 {
+    (fraction.clamp(0.0, 1.0) * 100.0).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::{analyse, best_guess};
+    use wordle_core::{evaluate_guess, CharMatch};
+
+    fn revealed(answer: &str, guesses: &[&str]) -> Vec<(String, Vec<CharMatch>)> {
+        guesses
+            .iter()
+            .map(|x| (x.to_string(), evaluate_guess(answer, x)))
+            .collect()
+    }
+
+    #[test]
+    fn scores_each_guess_against_the_candidates_left() {
+        let answers = ["crane", "crate", "trace", "shine", "spine", "swine"]
+            .map(String::from)
+            .to_vec();
+
+        let (opener, expected) =
+            best_guess(&answers.iter().map(String::as_str).collect::<Vec<_>>());
+
+        // dozen only rules out crate and trace, then spine is as good a guess as there is
+        let analysis = analyse(
+            &answers,
+            "crane",
+            (&opener, expected),
+            &revealed("crane", &["dozen", "spine", "crane"]),
+        );
+
+        assert_eq!(analysis.len(), 3);
+        assert_eq!(analysis[0].candidates_before, 6);
+        assert!(analysis[0].skill < 100);
+        assert_eq!(analysis[1].candidates_before, 4);
+        assert_eq!(analysis[1].skill, 100);
+        assert_eq!(
+            analysis[1].best_expected_remaining,
+            analysis[1].expected_remaining
+        );
+        // shine and spine split the candidates the same way, shine coming first
+        assert_eq!(analysis[1].best_guess, "shine");
+        assert_eq!(analysis[1].best_candidates_after, 1);
+        assert_eq!(analysis[2].candidates_before, 1);
+        assert_eq!(analysis[2].candidates_after, 1);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::config::Config;
+use rouille::{Request, Response};
+use std::fs;
+use std::path::Path;
+
+/// Files compiled into the binary, so that a bare binary can serve the UI
+const EMBEDDED: &[(&str, &[u8])] = &[
+    ("favicon.svg", include_bytes!("../assets/favicon.svg")),
+    ("ui.html", include_bytes!("../assets/ui.html")),
+];
+
+/// How long browsers may reuse anything other than a page without asking again
+const MAX_AGE: u64 = 24 * 60 * 60;
+
+/// Serves `/assets/<name>` and the favicon, or `None` for any other path so that it can 404 as usual
+pub fn handle(config: &Config, request: &Request) -> Option<Response> {
+    if request.method() != "GET" {
+        return None;
+    }
+
+    let url = request.url();
+    if url == "/favicon.ico" {
+        return serve(config, "favicon.svg");
+    }
+
+    serve(config, url.strip_prefix("/assets/")?)
+}
+
+/// Serves an asset by name, preferring a file of the same name in the configured asset directory so
+/// that an instance can be rebranded without rebuilding. Names are a single path segment, nothing
+/// outside the directory can be reached.
+pub fn serve(config: &Config, name: &str) -> Option<Response> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return None;
+    }
+
+    let data = config
+        .asset_dir
+        .as_ref()
+        .and_then(|dir| fs::read(Path::new(dir).join(name)).ok())
+        .or_else(|| {
+            EMBEDDED
+                .iter()
+                .find(|(x, _)| *x == name)
+                .map(|(_, data)| data.to_vec())
+        })?;
+
+    let extension = name.rsplit_once('.').map_or("", |(_, x)| x);
+    let response = Response::from_data(rouille::extension_to_mime(extension), data);
+
+    // pages are revalidated so a new release shows up at once, what they load can be cached
+    Some(if extension == "html" {
+        response.with_no_cache()
+    } else {
+        response.with_public_cache(MAX_AGE)
+    })
+}
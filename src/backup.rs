@@ -0,0 +1,150 @@
+use crate::db::{self, LATEST_VERSION};
+use rusqlite::{Connection, DatabaseName};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Serialize, Debug)]
+pub struct BackupReport {
+    pub schema_version: usize,
+    pub size: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("'{0}' already exists, backups never overwrite a file")]
+    Exists(String),
+
+    #[error("there's no backup at '{0}'")]
+    Missing(String),
+
+    #[error("the backup failed its integrity check: {0:?}")]
+    Corrupt(Vec<String>),
+
+    #[error(
+        "the backup is at schema version {0}, newer than the {LATEST_VERSION} this build knows"
+    )]
+    TooNew(usize),
+
+    #[error("the restored database is at schema version {found}, not the backup's {expected}")]
+    Mismatch { expected: usize, found: usize },
+
+    #[error("storage error: {0}")]
+    Storage(#[from] rusqlite::Error),
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Copies the database into a new file with SQLite's online backup, so it stays consistent
+/// however busy the server is, then checks the copy can be opened and restored from
+pub fn backup(conn: &Connection, path: &Path) -> Result<BackupReport, BackupError> {
+    if path.exists() {
+        return Err(BackupError::Exists(path.display().to_string()));
+    }
+
+    conn.backup(DatabaseName::Main, path, None)?;
+    let schema_version = verify(&Connection::open(path)?)?;
+
+    Ok(BackupReport {
+        schema_version,
+        size: fs::metadata(path)?.len(),
+    })
+}
+
+/// Replaces everything in the database with the backup's contents, refusing a backup that's
+/// corrupt or from a newer build. One from an older build is restored as it is, to be migrated.
+pub fn restore(conn: &mut Connection, path: &Path) -> Result<usize, BackupError> {
+    // opening a path that doesn't exist would make an empty database to restore
+    if !path.exists() {
+        return Err(BackupError::Missing(path.display().to_string()));
+    }
+    let expected = verify(&Connection::open(path)?)?;
+
+    conn.restore(DatabaseName::Main, path, None::<fn(_)>)?;
+
+    let found = db::schema_version(conn)?;
+    if found != expected {
+        return Err(BackupError::Mismatch { expected, found });
+    }
+
+    Ok(found)
+}
+
+/// The schema version of a database that's sound and that this build can migrate
+fn verify(conn: &Connection) -> Result<usize, BackupError> {
+    let integrity = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    if integrity != ["ok"] {
+        return Err(BackupError::Corrupt(integrity));
+    }
+
+    let version = db::schema_version(conn)?;
+    if version > LATEST_VERSION {
+        return Err(BackupError::TooNew(version));
+    }
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backup::{backup, restore, BackupError};
+    use crate::db::{migrate_to, LATEST_VERSION};
+    use rusqlite::Connection;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    fn temp_path() -> PathBuf {
+        env::temp_dir().join(format!("wordle-backup-{}.db", Uuid::new_v4()))
+    }
+
+    #[test]
+    fn restores_what_was_backed_up() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+        conn.execute(
+            "INSERT INTO game (game_id, client, word) VALUES ('a', 'bob', 'crane')",
+            [],
+        )
+        .unwrap();
+
+        let path = temp_path();
+        let report = backup(&conn, &path).unwrap();
+        assert_eq!(report.schema_version, LATEST_VERSION);
+        assert!(matches!(backup(&conn, &path), Err(BackupError::Exists(_))));
+
+        let mut target = Connection::open_in_memory().unwrap();
+        assert_eq!(restore(&mut target, &path).unwrap(), LATEST_VERSION);
+        let word: String = target
+            .query_row("SELECT word FROM game WHERE game_id = 'a'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(word, "crane");
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn refuses_backups_from_newer_builds() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+        let path = temp_path();
+        backup(&conn, &path).unwrap();
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", LATEST_VERSION + 1)
+            .unwrap();
+
+        let result = restore(&mut conn, &path);
+        assert!(matches!(result, Err(BackupError::TooNew(_))));
+
+        fs::remove_file(path).unwrap();
+    }
+}
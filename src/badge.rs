@@ -0,0 +1,66 @@
+use crate::error::ApiError;
+use crate::game::ClientId;
+use crate::registration;
+use crate::service::Service;
+use crate::stats::Record;
+use rouille::{Request, Response};
+
+const LABEL: &str = "wordle";
+
+/// How long a badge may be cached for, embedding sites fetch them on every page view
+const MAX_AGE: u64 = 5 * 60;
+
+/// `GET /badge/<client>.svg` is a live badge of the client's win rate and streak, in the style of
+/// shields.io, for embedding in profiles
+pub fn handle(service: &Service, request: &Request, client: &str) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client.strip_suffix(".svg").unwrap_or(client))?;
+    registration::authorize(service, request, &client)?;
+    let record = service.record(&client)?;
+
+    Ok(Response::from_data("image/svg+xml", svg(&record)).with_public_cache(MAX_AGE))
+}
+
+fn svg(record: &Record) -> String {
+    let (value, colour) = match record.win_rate() {
+        None => (String::from("no games yet"), "#9f9f9f"),
+        Some(rate) => (
+            format!("{:.0}% won · streak {}", rate * 100.0, record.streak),
+            if rate >= 0.75 {
+                "#4c1"
+            } else if rate >= 0.5 {
+                "#dfb317"
+            } else {
+                "#e05d44"
+            },
+        ),
+    };
+
+    let (label_width, value_width) = (text_width(LABEL), text_width(&value));
+    let width = label_width + value_width;
+    let (label_x, value_x) = (label_width / 2, label_width + value_width / 2);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{LABEL}: {value}">
+<title>{LABEL}: {value}</title>
+<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+<g clip-path="url(#r)">
+<rect width="{label_width}" height="20" fill="#555"/>
+<rect x="{label_width}" width="{value_width}" height="20" fill="{colour}"/>
+<rect width="{width}" height="20" fill="url(#s)"/>
+</g>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{LABEL}</text>
+<text x="{label_x}" y="14">{LABEL}</text>
+<text x="{value_x}" y="15" fill="#010101" fill-opacity=".3">{value}</text>
+<text x="{value_x}" y="14">{value}</text>
+</g>
+</svg>
+"##
+    )
+}
+
+/// Roughly how wide the text renders in 11px Verdana, with padding either side
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
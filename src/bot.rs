@@ -0,0 +1,82 @@
+use crate::dictionary;
+use crate::error::ApiError;
+use crate::json_response;
+use crate::solver::{self, Solver, MAX_GOES};
+use rouille::{Request, Response};
+use serde::Serialize;
+use wordle_core::{evaluate_guess, AnswerSelector, CharMatch, Random, Selection};
+
+#[derive(Serialize)]
+struct Transcript {
+    seed: u64,
+    answer: String,
+    opener: String,
+    goes: usize,
+    solved: bool,
+    guesses: Vec<Turn>,
+}
+
+#[derive(Serialize)]
+struct Turn {
+    guess: String,
+    evaluation: Vec<CharMatch>,
+    /// The answers still possible once the evaluation is seen
+    candidates: usize,
+}
+
+/// `GET /bot/play?seed=<u64>[&opener=<word>]` has the reference solver play a whole game against
+/// the answer the seed chooses, the same one for the same seed on every server running the same
+/// release, so that any bot can be benchmarked on identical words. The solver opens with its own best
+/// guess unless given one.
+pub fn handle(request: &Request) -> Result<Response, ApiError> {
+    let seed = request.get_param("seed").unwrap_or_default();
+    let seed = seed.parse::<u64>().map_err(|_| ApiError::InvalidParam {
+        name: String::from("seed"),
+        value: seed,
+    })?;
+
+    let opener = match request.get_param("opener") {
+        Some(opener) => {
+            let normalized = dictionary::normalize(&opener);
+            if !dictionary::is_valid_guess(&normalized) {
+                return Err(ApiError::InvalidParam {
+                    name: String::from("opener"),
+                    value: opener,
+                });
+            }
+
+            normalized
+        }
+        None => solver::opening().0.to_string(),
+    };
+
+    let answers = dictionary::answers();
+    let answer = Random::seeded(seed)
+        .select(answers, &Selection::default())
+        .unwrap_or_default();
+
+    let mut revealed = Vec::new();
+    let guesses = Solver::new(answers)
+        .solve(answer, Some(&opener))
+        .into_iter()
+        .map(|guess| {
+            let evaluation = evaluate_guess(answer, guess);
+            revealed.push((guess.to_string(), evaluation.clone()));
+
+            Turn {
+                guess: guess.to_string(),
+                evaluation,
+                candidates: solver::candidates(answers, &revealed).len(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    json_response(&Transcript {
+        seed,
+        answer: answer.to_string(),
+        opener,
+        goes: guesses.len(),
+        solved: guesses.len() <= MAX_GOES,
+        guesses,
+    })
+}
@@ -0,0 +1,48 @@
+use crate::error::ApiError;
+use crate::game::ClientId;
+use crate::registration;
+use crate::service::Service;
+use crate::{json_response, mode_param};
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+
+/// A word someone chose for others to play, each of them playing it as a game of their own
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Challenge {
+    pub challenge_id: String,
+    /// Who chose the word
+    pub creator: ClientId,
+    /// Never shown, players only find it out by finishing their game
+    #[serde(skip)]
+    pub word: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CreateChallenge {
+    client: String,
+    word: String,
+}
+
+/// `POST /challenge` sets the word of `{"client": "...", "word": "..."}` as a challenge for anyone
+/// to play, with the id to share for them to play it by
+pub fn handle_create(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    let challenge: CreateChallenge = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    json_response(&service.create_challenge(&ClientId::parse(&challenge.client)?, &challenge.word)?)
+}
+
+/// `GET /challenge/<challenge_id>/<client>[?mode=<mode>][&hard=<bool>]` starts the client's game of
+/// the challenge's word, or carries on with the one they've started already
+pub fn handle_play(
+    service: &Service,
+    request: &Request,
+    challenge_id: &str,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize_new_game(service, request, &client)?;
+
+    json_response(&service.play_challenge(challenge_id, &client, mode_param(request)?)?)
+}
@@ -0,0 +1,138 @@
+use crate::cli::{migrate, CliResult};
+use crate::db::{self, GameFilter};
+use crate::events;
+use crate::game::GameState;
+use clap::{Args, Subcommand};
+
+#[derive(Subcommand)]
+pub enum AdminCommand {
+    /// List or purge games
+    Games {
+        #[command(subcommand)]
+        action: GamesAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GamesAction {
+    /// List every matching game
+    List(FilterArgs),
+
+    /// Delete every matching game, along with its rejected guesses and events
+    Purge {
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// List what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Replay every game's events under the current rules, rewriting any game that comes out
+    /// differently
+    Rebuild {
+        /// List what would change without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Args)]
+pub struct FilterArgs {
+    /// Only games played by this client
+    #[arg(long)]
+    client: Option<String>,
+
+    /// Only games in this state (in_progress, won, lost, forfeited or expired)
+    #[arg(long)]
+    state: Option<GameState>,
+
+    /// Only games created at least this many days ago
+    #[arg(long, value_name = "DAYS")]
+    older_than: Option<u32>,
+}
+
+impl From<FilterArgs> for GameFilter {
+    fn from(args: FilterArgs) -> Self {
+        GameFilter {
+            client: args.client,
+            state: args.state,
+            older_than_days: args.older_than,
+        }
+    }
+}
+
+pub fn run(command: AdminCommand) -> CliResult {
+    match command {
+        AdminCommand::Games { action } => match action {
+            GamesAction::List(filter) => list(&filter.into()),
+            GamesAction::Purge { filter, dry_run } => purge(&filter.into(), dry_run),
+            GamesAction::Rebuild { dry_run } => rebuild(dry_run),
+        },
+    }
+}
+
+fn list(filter: &GameFilter) -> CliResult {
+    let conn = migrate::open_current()?;
+    let games = db::find_games(&conn, filter)?;
+
+    for game in &games {
+        println!(
+            "{}  {:<12} {}  goes={:<3} {:<19} {}",
+            game.game_id,
+            game.state.as_str(),
+            game.word,
+            game.goes,
+            game.created_at.as_deref().unwrap_or("-"),
+            game.client,
+        );
+    }
+    println!("{} game(s)", games.len());
+
+    Ok(())
+}
+
+fn purge(filter: &GameFilter, dry_run: bool) -> CliResult {
+    if dry_run {
+        list(filter)?;
+        println!("dry run, nothing was deleted");
+
+        return Ok(());
+    }
+
+    let mut conn = migrate::open_current()?;
+    let purged = db::purge_games(&mut conn, filter)?;
+    println!("purged {purged} game(s)");
+
+    Ok(())
+}
+
+fn rebuild(dry_run: bool) -> CliResult {
+    let mut conn = migrate::open_current()?;
+    let report = events::rebuild(&mut conn, dry_run)?;
+
+    for game in &report.rebuilt {
+        println!(
+            "{}  goes {} => {}, {} => {}, {} => {}",
+            game.game_id,
+            game.goes.0,
+            game.goes.1,
+            game.state.0.as_str(),
+            game.state.1.as_str(),
+            game.word.0,
+            game.word.1,
+        );
+    }
+    for (game_id, reason) in &report.failed {
+        eprintln!("{game_id}  can't be replayed: {reason}");
+    }
+
+    println!(
+        "{} of {} game(s) {}",
+        report.rebuilt.len(),
+        report.checked,
+        if dry_run { "would change" } else { "rebuilt" }
+    );
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::backup;
+use crate::cli::CliResult;
+use crate::db::{self, LATEST_VERSION};
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct BackupArgs {
+    /// Where to write the backup, which mustn't exist yet
+    output: PathBuf,
+}
+
+#[derive(Args)]
+pub struct RestoreArgs {
+    /// The backup to restore
+    input: PathBuf,
+}
+
+pub fn backup(args: BackupArgs) -> CliResult {
+    let conn = db::get_connection()?;
+    let report = backup::backup(&conn, &args.output)?;
+
+    println!(
+        "backed up {} bytes at schema version {} to {}",
+        report.size,
+        report.schema_version,
+        args.output.display()
+    );
+
+    Ok(())
+}
+
+pub fn restore(args: RestoreArgs) -> CliResult {
+    let mut conn = db::get_connection()?;
+    let version = backup::restore(&mut conn, &args.input)?;
+
+    println!(
+        "restored {} at schema version {version}",
+        args.input.display()
+    );
+    if version < LATEST_VERSION {
+        println!("run `wordle-api migrate` to bring it up to version {LATEST_VERSION}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::cli::CliResult;
+use crate::db::{self, LATEST_VERSION};
+use clap::Args;
+use rusqlite::Connection;
+
+#[derive(Args)]
+pub struct MigrateArgs {
+    /// The schema version to migrate to, defaults to the latest
+    #[arg(long, value_name = "VERSION")]
+    to: Option<usize>,
+
+    /// Print the migrations that would be applied without applying them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+pub fn run(args: MigrateArgs) -> CliResult {
+    let mut conn = db::get_connection()?;
+    let version = db::schema_version(&conn)?;
+    let target = args.to.unwrap_or(LATEST_VERSION);
+
+    if target > LATEST_VERSION {
+        return Err(format!("the latest schema version is {LATEST_VERSION}, not {target}").into());
+    }
+    if target < version {
+        return Err(format!(
+            "the database is already at version {version}, migrations cannot be reverted"
+        )
+        .into());
+    }
+
+    if args.dry_run {
+        for (version, migration) in db::pending(&conn, target)? {
+            println!("-- version {version}\n{migration}\n");
+        }
+        println!("schema version {version}, would migrate to {target}");
+
+        return Ok(());
+    }
+
+    let applied = apply(&mut conn, target)?;
+    println!("applied {applied} migration(s), schema version {target}");
+
+    Ok(())
+}
+
+/// Migrates to the target version, logging any legacy values the migrations had to repair
+pub fn apply(conn: &mut Connection, target: usize) -> Result<usize, rusqlite::Error> {
+    let version = db::schema_version(conn)?;
+    let applied = db::migrate_to(conn, target)?;
+
+    // the repair table only exists from the migration that fills it
+    if applied > 0
+        && db::schema_version(conn)? >= db::REPAIR_VERSION
+        && version < db::REPAIR_VERSION
+    {
+        for repair in db::repairs(conn)? {
+            eprintln!(
+                "repaired game '{}': {} {:?} => {}",
+                repair.game_id, repair.field, repair.original, repair.repaired
+            );
+        }
+    }
+
+    Ok(applied)
+}
+
+/// Opens the database for a command that needs the latest schema, without migrating it
+pub fn open_current() -> Result<Connection, Box<dyn std::error::Error>> {
+    ensure_current(db::get_connection()?)
+}
+
+/// Refuses a connection to a database that isn't at the latest schema
+pub fn ensure_current(conn: Connection) -> Result<Connection, Box<dyn std::error::Error>> {
+    let version = db::schema_version(&conn)?;
+
+    if version != LATEST_VERSION {
+        return Err(format!(
+            "the database is at schema version {version} but {LATEST_VERSION} is required, run `wordle-api migrate` first"
+        )
+        .into());
+    }
+
+    Ok(conn)
+}
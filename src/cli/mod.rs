@@ -0,0 +1,69 @@
+pub mod admin;
+pub mod backup;
+pub mod migrate;
+pub mod play;
+pub mod simulate;
+pub mod stats;
+pub mod transfer;
+
+use clap::{Args, Parser, Subcommand};
+use std::error::Error;
+
+#[derive(Parser)]
+#[command(version, about = "A wordle server, and the tools to run one")]
+pub struct Cli {
+    /// What to do, runs the server when left out
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the HTTP server
+    Serve(ServeArgs),
+
+    /// Apply, or preview, pending schema migrations
+    Migrate(migrate::MigrateArgs),
+
+    /// Report on the games in the database, without the server
+    Stats {
+        #[command(subcommand)]
+        command: stats::StatsCommand,
+    },
+
+    /// Play a game in the terminal, offline, against the built-in word lists
+    Play(play::PlayArgs),
+
+    /// Play games with the reference solver against the answer list, reporting how many goes
+    /// they took
+    Simulate(simulate::SimulateArgs),
+
+    /// Dump every game, and everything recorded against them, for importing elsewhere
+    Export(transfer::ExportArgs),
+
+    /// Load a dump made by `export`
+    Import(transfer::ImportArgs),
+
+    /// Copy the database into a new file, safely while the server is running
+    Backup(backup::BackupArgs),
+
+    /// Replace the database with a backup made by `backup`
+    Restore(backup::RestoreArgs),
+
+    /// Inspect and clean up the database directly, without the server
+    Admin {
+        #[command(subcommand)]
+        command: admin::AdminCommand,
+    },
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Serve from a fresh in-memory database with seeded answers, for testing clients and bots
+    /// against. Requests can set the time with `X-Mock-Time` and a new game's answer with
+    /// `X-Mock-Answer`, and webhooks are captured at `/mock/webhooks` rather than sent.
+    #[arg(long)]
+    pub mock: bool,
+}
+
+pub type CliResult = Result<(), Box<dyn Error>>;
@@ -0,0 +1,81 @@
+use crate::cli::CliResult;
+use crate::dictionary;
+use clap::Args;
+use std::io::{self, BufRead, Write};
+use wordle_core::{evaluate_guess, AnswerSelector, MatchType, Random, Selection};
+
+#[derive(Args)]
+pub struct PlayArgs {
+    /// Play against this word rather than a random answer
+    #[arg(long)]
+    answer: Option<String>,
+
+    /// How many goes before the game is lost
+    #[arg(long, default_value_t = 6)]
+    max_goes: usize,
+}
+
+pub fn run(args: PlayArgs) -> CliResult {
+    let answer = match args.answer {
+        Some(answer) => {
+            let answer = dictionary::normalize(&answer);
+            if !dictionary::is_valid_guess(&answer) {
+                return Err(format!("'{answer}' is not a valid guess").into());
+            }
+
+            answer
+        }
+        None => Random::from_entropy()
+            .select(dictionary::answers(), &Selection::default())
+            .ok_or("the answer list is empty")?
+            .to_string(),
+    };
+
+    println!(
+        "Guess the {}-letter word in {} goes, or enter nothing to give up",
+        answer.chars().count(),
+        args.max_goes
+    );
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut goes = 0;
+
+    while goes < args.max_goes {
+        print!("{}> ", goes + 1);
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next() else { break };
+        let guess = dictionary::normalize(&line?);
+        if guess.is_empty() {
+            break;
+        }
+
+        if guess.chars().count() != answer.chars().count() || !dictionary::is_valid_guess(&guess) {
+            println!("'{guess}' is not a valid guess");
+            continue;
+        }
+
+        goes += 1;
+        let evaluation = evaluate_guess(&answer, &guess);
+        let row = evaluation
+            .iter()
+            .map(|x| match x.match_type {
+                MatchType::Perfect => '🟩',
+                MatchType::Partial => '🟨',
+                MatchType::None => '⬛',
+            })
+            .collect::<String>();
+        println!("   {row}  {}", guess.to_uppercase());
+
+        if guess == answer {
+            println!("Solved in {goes}!");
+
+            return Ok(());
+        }
+    }
+
+    println!("The word was '{answer}'");
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::cli::CliResult;
+use crate::dictionary;
+use crate::solver::{self, Simulation};
+use clap::Args;
+use wordle_core::{AnswerSelector, Random, Selection};
+
+#[derive(Args)]
+pub struct SimulateArgs {
+    /// How many games to play against randomly chosen answers, every answer is played once when
+    /// left out
+    #[arg(long)]
+    games: Option<usize>,
+
+    /// Seeds the choice of answers, so that runs can be repeated
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// The first guess of every game, otherwise the solver's own best opening guess
+    #[arg(long)]
+    opener: Option<String>,
+
+    /// Games taking more goes than this are reported as failures
+    #[arg(long, default_value_t = 6)]
+    max_goes: usize,
+}
+
+pub fn run(args: SimulateArgs) -> CliResult {
+    let answers = dictionary::answers();
+
+    let opener = match &args.opener {
+        Some(opener) => {
+            let opener = dictionary::normalize(opener);
+            if !dictionary::is_valid_guess(&opener) {
+                return Err(format!("'{opener}' is not a valid guess").into());
+            }
+
+            opener
+        }
+        None if answers.is_empty() => return Err("there are no answers to simulate".into()),
+        None => solver::opening().0.to_string(),
+    };
+
+    let mut targets = answers.iter().map(|x| x.as_str()).collect::<Vec<_>>();
+    if let Some(games) = args.games {
+        let selector = match args.seed {
+            Some(seed) => Random::seeded(seed),
+            None => Random::from_entropy(),
+        };
+
+        targets = (0..games)
+            .filter_map(|_| selector.select(answers, &Selection::default()))
+            .collect();
+    }
+
+    let simulation = Simulation::run(answers, &targets, &opener, args.max_goes);
+    let failures: usize = simulation
+        .distribution
+        .iter()
+        .filter(|(goes, _)| **goes > args.max_goes)
+        .map(|(_, games)| games)
+        .sum();
+    let busiest = simulation.distribution.values().copied().max().unwrap_or(0);
+
+    println!("opener: {opener}");
+    println!("games:  {}", simulation.games);
+    println!();
+    for (goes, games) in &simulation.distribution {
+        let bar = "#".repeat((games * 50).div_ceil(busiest.max(1)));
+        println!("{goes:>3} {games:>6}  {bar}");
+    }
+    println!();
+    println!("mean goes: {:.3}", simulation.mean_goes);
+    println!(
+        "failures:  {failures} ({:.2}% over {} goes)",
+        100.0 * simulation.failure_rate,
+        args.max_goes
+    );
+
+    Ok(())
+}
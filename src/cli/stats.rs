@@ -0,0 +1,155 @@
+use crate::cli::{migrate, CliResult};
+use crate::stats::{self, ClientStats, Summary, WordStats};
+use clap::{Args, Subcommand, ValueEnum};
+use serde::Serialize;
+
+#[derive(Subcommand)]
+pub enum StatsCommand {
+    /// Print a summary of the whole instance, its top clients, and its hardest words
+    Report(ReportArgs),
+}
+
+#[derive(Args)]
+pub struct ReportArgs {
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// How many clients and words to list
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Serialize)]
+struct Report {
+    summary: Summary,
+    top_clients: Vec<ClientStats>,
+    hardest_words: Vec<WordStats>,
+}
+
+pub fn run(command: StatsCommand) -> CliResult {
+    match command {
+        StatsCommand::Report(args) => report(args),
+    }
+}
+
+fn report(args: ReportArgs) -> CliResult {
+    let conn = migrate::open_current()?;
+
+    let mut top_clients = stats::client_stats(&conn)?;
+    top_clients.sort_by(|a, b| {
+        b.num_solved.cmp(&a.num_solved).then(
+            a.avg_goes
+                .unwrap_or(f64::MAX)
+                .total_cmp(&b.avg_goes.unwrap_or(f64::MAX)),
+        )
+    });
+    top_clients.truncate(args.top);
+
+    let report = Report {
+        summary: stats::summary(&conn)?,
+        top_clients,
+        hardest_words: stats::hardest_words(&conn, args.top)?,
+    };
+
+    match args.format {
+        Format::Text => print_text(&report),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        Format::Csv => print_csv(&report),
+    }
+
+    Ok(())
+}
+
+fn print_text(report: &Report) {
+    let summary = &report.summary;
+    println!("games:    {}", summary.num_games);
+    println!("solved:   {}", summary.num_solved);
+    println!("clients:  {}", summary.num_clients);
+    println!("rejected: {}", summary.num_rejected);
+    println!("avg goes: {}", format_avg(summary.avg_goes));
+
+    println!("\ntop clients");
+    for client in &report.top_clients {
+        println!(
+            "  {:<24} solved {:>5} of {:<5} avg goes {}",
+            client.client,
+            client.num_solved,
+            client.num_games,
+            format_avg(client.avg_goes)
+        );
+    }
+
+    println!("\nhardest words");
+    for word in &report.hardest_words {
+        println!(
+            "  {}  solved {:>5} of {:<5} avg goes {}",
+            word.word,
+            word.num_solved,
+            word.num_games,
+            format_avg(word.avg_goes)
+        );
+    }
+}
+
+/// Each section as its own table with a header row, separated by a blank line
+fn print_csv(report: &Report) {
+    let summary = &report.summary;
+    println!("num_games,num_solved,num_clients,num_rejected,avg_goes");
+    println!(
+        "{},{},{},{},{}",
+        summary.num_games,
+        summary.num_solved,
+        summary.num_clients,
+        summary.num_rejected,
+        csv_avg(summary.avg_goes)
+    );
+
+    println!("\nclient,num_solved,num_games,num_rejected,avg_goes,max_goes");
+    for client in &report.top_clients {
+        println!(
+            "{},{},{},{},{},{}",
+            csv_field(&client.client),
+            client.num_solved,
+            client.num_games,
+            client.num_rejected,
+            csv_avg(client.avg_goes),
+            client.max_goes.map(|x| x.to_string()).unwrap_or_default()
+        );
+    }
+
+    println!("\nword,num_solved,num_games,avg_goes");
+    for word in &report.hardest_words {
+        println!(
+            "{},{},{},{}",
+            csv_field(&word.word),
+            word.num_solved,
+            word.num_games,
+            csv_avg(word.avg_goes)
+        );
+    }
+}
+
+fn format_avg(avg: Option<f64>) -> String {
+    avg.map(|x| format!("{x:.2}"))
+        .unwrap_or_else(|| String::from("-"))
+}
+
+fn csv_avg(avg: Option<f64>) -> String {
+    avg.map(|x| format!("{x:.4}")).unwrap_or_default()
+}
+
+/// Quotes a field if it contains anything that would otherwise break the row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
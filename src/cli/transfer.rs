@@ -0,0 +1,119 @@
+use crate::cli::{migrate, CliResult};
+use crate::transfer::{self, Conflict, Dump, Record};
+use clap::{Args, ValueEnum};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Format {
+    /// A single JSON document
+    Json,
+    /// One JSON record per line
+    Ndjson,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum OnConflict {
+    Fail,
+    Skip,
+    Overwrite,
+}
+
+impl From<OnConflict> for Conflict {
+    fn from(value: OnConflict) -> Self {
+        match value {
+            OnConflict::Fail => Conflict::Fail,
+            OnConflict::Skip => Conflict::Skip,
+            OnConflict::Overwrite => Conflict::Overwrite,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
+    /// Where to write the dump, stdout when left out
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// The dump to import, `-` for stdin
+    input: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
+    /// What to do with games that already exist
+    #[arg(long, value_enum, default_value_t = OnConflict::Fail)]
+    on_conflict: OnConflict,
+}
+
+pub fn export(args: ExportArgs) -> CliResult {
+    let conn = migrate::open_current()?;
+    let dump = transfer::export(&conn)?;
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    match args.format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut output, &dump)?;
+            writeln!(output)?;
+        }
+        Format::Ndjson => {
+            for record in dump.into_records() {
+                serde_json::to_writer(&mut output, &record)?;
+                writeln!(output)?;
+            }
+        }
+    }
+
+    output.flush()?;
+
+    Ok(())
+}
+
+pub fn import(args: ImportArgs) -> CliResult {
+    let input: Box<dyn Read> = if args.input.as_os_str() == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(File::open(&args.input)?)
+    };
+    let input = BufReader::new(input);
+
+    let dump = match args.format {
+        Format::Json => serde_json::from_reader(input)?,
+        Format::Ndjson => {
+            let mut records = Vec::new();
+            for line in input.lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    records.push(serde_json::from_str::<Record>(&line)?);
+                }
+            }
+
+            Dump::from_records(records)
+        }
+    };
+
+    let mut conn = migrate::open_current()?;
+    let summary = transfer::import(&mut conn, dump, args.on_conflict.into())?;
+
+    println!(
+        "imported {} game(s), overwrote {}, skipped {}, with {} rejected guess(es) and {} event(s)",
+        summary.imported,
+        summary.overwritten,
+        summary.skipped,
+        summary.rejected_guesses,
+        summary.events
+    );
+
+    Ok(())
+}
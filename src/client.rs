@@ -0,0 +1,232 @@
+use crate::achievements::Achievement;
+use crate::difficulty::Difficulty;
+use crate::game::{GameOptions, GameState, PlayedGuess};
+use crate::stats::ClientStats;
+use reqwest::blocking::RequestBuilder;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use wordle_core::{CharMatch, MatchType};
+
+/// A typed client for a running server, so that bots don't need to build requests or parse
+/// responses themselves
+pub struct Client {
+    /// The server's url, without a trailing slash
+    url: String,
+    http: reqwest::blocking::Client,
+    /// Sent with every request, for playing as a registered client
+    key: Option<String>,
+}
+
+/// Options for a new game, anything left unset falls back to the server's default
+#[derive(Serialize, Default)]
+pub struct NewGame<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_guesses_count: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct CreateBody<'a> {
+    client: &'a str,
+    #[serde(flatten)]
+    options: &'a NewGame<'a>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Registration {
+    pub client: String,
+    pub key: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Game {
+    pub game_id: String,
+    pub state: GameState,
+    pub options: GameOptions,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct GuessResult {
+    pub state: GameState,
+    pub solved: bool,
+    /// Once the game has run out of goes
+    #[serde(default)]
+    pub lost: bool,
+    pub answer: Option<String>,
+    pub guess: String,
+    pub goes: usize,
+    pub evaluation: Vec<CharMatch>,
+    /// Every board's evaluation, for modes played on more than one
+    pub boards: Option<Vec<Board>>,
+    /// Every guess played in the game so far, this one last
+    #[serde(default)]
+    pub guesses: Vec<PlayedGuess>,
+    /// How hard the answer was, once a game played on a single board is over
+    pub difficulty: Option<Difficulty>,
+    /// Achievements the guess earned that the client didn't already have
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+    /// Whether the guess had already been played, and so cost no go
+    #[serde(default)]
+    pub repeated: bool,
+    /// The best every letter guessed so far has been matched
+    #[serde(default)]
+    pub letters: BTreeMap<char, MatchType>,
+    /// The grid to share, once the game is over
+    pub share: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Board {
+    pub solved: bool,
+    pub answer: Option<String>,
+    pub evaluation: Vec<CharMatch>,
+}
+
+/// Every way a call can fail, one variant per status code the server returns
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Unauthorized(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    GameFinished(String),
+
+    #[error("{0}")]
+    RuleViolation(String),
+
+    #[error("{0}")]
+    RateLimited(String),
+
+    #[error("{0}")]
+    Server(String),
+
+    #[error("{0}")]
+    ReadOnly(String),
+
+    #[error("unexpected status {status}: {message}")]
+    Unexpected { status: u16, message: String },
+
+    #[error("failed to reach the server: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("malformed response: {0}")]
+    Malformed(String),
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl Client {
+    /// A client for the server at `url`, e.g. `http://localhost:85` or `https://wordle.example`
+    pub fn new(url: &str) -> Result<Client, ClientError> {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(ClientError::BadRequest(format!(
+                "'{url}' is not an http:// or https:// url"
+            )));
+        }
+
+        Ok(Client {
+            url: url.trim_end_matches('/').to_string(),
+            http: reqwest::blocking::Client::builder().build()?,
+            key: None,
+        })
+    }
+
+    /// Plays as the client registered with the key
+    pub fn with_key(self, key: &str) -> Client {
+        Client {
+            key: Some(key.to_string()),
+            ..self
+        }
+    }
+
+    /// Registers the client, answering with the key to play as them with from then on
+    pub fn register(&self, client: &str) -> Result<Registration, ClientError> {
+        self.post("/clients", &serde_json::json!({ "client": client }))
+    }
+
+    pub fn create_game(&self, client: &str, options: &NewGame) -> Result<Game, ClientError> {
+        self.post("/games", &CreateBody { client, options })
+    }
+
+    pub fn guess(&self, game_id: &str, guess: &str) -> Result<GuessResult, ClientError> {
+        self.post(
+            &format!("/games/{}/guesses", encode(game_id)),
+            &serde_json::json!({ "guess": guess }),
+        )
+    }
+
+    pub fn stats(&self) -> Result<Vec<ClientStats>, ClientError> {
+        self.send(self.request(Method::GET, "/stats"))
+    }
+
+    fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, ClientError> {
+        self.send(self.request(Method::POST, path).json(body))
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.http.request(method, format!("{}{path}", self.url));
+
+        match &self.key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let response = request.send()?;
+        let status = response.status();
+        let body = response.text()?;
+
+        if status == StatusCode::OK {
+            return serde_json::from_str(&body)
+                .map_err(|error| ClientError::Malformed(error.to_string()));
+        }
+
+        let message = serde_json::from_str::<ErrorBody>(&body)
+            .map(|x| x.error)
+            .unwrap_or(body);
+
+        Err(match status.as_u16() {
+            400 => ClientError::BadRequest(message),
+            401 => ClientError::Unauthorized(message),
+            404 => ClientError::NotFound(message),
+            409 => ClientError::GameFinished(message),
+            422 => ClientError::RuleViolation(message),
+            429 => ClientError::RateLimited(message),
+            500 => ClientError::Server(message),
+            503 => ClientError::ReadOnly(message),
+            status => ClientError::Unexpected { status, message },
+        })
+    }
+}
+
+/// Percent-encodes everything but unreserved characters, for use in a path segment or query value
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|x| match x {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                char::from(x).to_string()
+            }
+            _ => format!("%{x:02X}"),
+        })
+        .collect()
+}
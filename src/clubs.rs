@@ -0,0 +1,197 @@
+use crate::error::ApiError;
+use crate::game::{self, ClientId, GameState};
+use crate::groups::Member;
+use crate::json_response;
+use crate::registration;
+use crate::repository::FinishedGame;
+use crate::service::Service;
+use crate::stats::ClientStats;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+
+const MAX_NAME: usize = 64;
+
+/// A team clients join for themselves, one club at a time, so that no game counts for two clubs
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Club {
+    /// The club's id in urls: 3 to 32 lowercase letters, digits and hyphens
+    pub club: String,
+    pub name: String,
+}
+
+impl Club {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let slug = (3..=32).contains(&self.club.len())
+            && self
+                .club
+                .bytes()
+                .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == b'-')
+            // the standings live alongside the clubs
+            && self.club != "standings";
+        if !slug {
+            return Err(ApiError::InvalidParam {
+                name: String::from("club"),
+                value: self.club.clone(),
+            });
+        }
+
+        let length = self.name.trim().chars().count();
+        if length == 0 || length > MAX_NAME || self.name.chars().any(char::is_control) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("name"),
+                value: self.name.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// How a club's members have done across all their games
+#[derive(Serialize, Debug)]
+pub struct ClubStats {
+    pub club: String,
+    pub name: String,
+    pub num_members: usize,
+    pub num_games: usize,
+    pub num_solved: usize,
+    pub avg_goes: Option<f64>,
+}
+
+impl ClubStats {
+    /// Totals up the members' own stats
+    pub fn new(club: Club, num_members: usize, members: &[&ClientStats]) -> ClubStats {
+        let num_solved = members.iter().map(|x| x.num_solved).sum::<usize>();
+        let total_goes = members
+            .iter()
+            .map(|x| x.avg_goes.unwrap_or(0.0) * x.num_solved as f64)
+            .sum::<f64>();
+
+        ClubStats {
+            club: club.club,
+            name: club.name,
+            num_members,
+            num_games: members.iter().map(|x| x.num_games).sum(),
+            num_solved,
+            avg_goes: (num_solved > 0).then(|| total_goes / num_solved as f64),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ClubDetail {
+    #[serde(flatten)]
+    pub stats: ClubStats,
+    pub members: Vec<Member>,
+}
+
+/// The week so far, club against club
+#[derive(Serialize, Debug)]
+pub struct Standings {
+    /// The Monday the week started on, as `YYYY-MM-DD` in UTC
+    pub week_start: String,
+    pub standings: Vec<Standing>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Standing {
+    /// From 1, the best win rate first, then the fewest goes on average. Clubs that haven't
+    /// finished a game this week come last.
+    pub rank: usize,
+    pub club: String,
+    pub name: String,
+    pub num_members: usize,
+    pub num_games: usize,
+    pub num_won: usize,
+    /// Games won with a hint, counted among `num_won` but left out of `win_rate` and `avg_goes`
+    pub num_assisted: usize,
+    pub win_rate: Option<f64>,
+    pub avg_goes: Option<f64>,
+}
+
+impl Standing {
+    pub fn new(club: Club, num_members: usize, games: &[FinishedGame]) -> Standing {
+        let won = games
+            .iter()
+            .filter(|x| x.game.state == GameState::Won)
+            .collect::<Vec<_>>();
+        let num_won = won.len();
+        // only wins without a hint rank a club
+        let won = won
+            .into_iter()
+            .filter(|x| !x.assisted)
+            .map(|x| x.game.goes)
+            .collect::<Vec<_>>();
+
+        Standing {
+            rank: 0,
+            club: club.club,
+            name: club.name,
+            num_members,
+            num_games: games.len(),
+            num_won,
+            num_assisted: num_won - won.len(),
+            win_rate: (!games.is_empty()).then(|| won.len() as f64 / games.len() as f64),
+            avg_goes: (!won.is_empty())
+                .then(|| won.iter().sum::<usize>() as f64 / won.len() as f64),
+        }
+    }
+}
+
+/// The Monday of the week the day is in, counting in days since the epoch, which was a Thursday
+pub fn week_start(day: u64) -> u64 {
+    day.saturating_sub((day + 3) % 7)
+}
+
+/// `POST /clubs` creates a club
+pub fn handle_create(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    let club: Club = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    json_response(&service.create_club(club)?)
+}
+
+/// `GET /clubs` lists every club with its members' stats
+pub fn handle_list(service: &Service) -> Result<Response, ApiError> {
+    json_response(&service.clubs()?)
+}
+
+/// `GET /clubs/<club>` is the club's stats and members
+pub fn handle_get(service: &Service, club: &str) -> Result<Response, ApiError> {
+    json_response(&service.club(club)?)
+}
+
+/// `PUT /clubs/<club>/members/<client>` joins the club, leaving any other, and `DELETE` leaves it
+pub fn handle_member(
+    service: &Service,
+    request: &Request,
+    club: &str,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    service.set_club_member(club, &client, request.method() == "PUT")?;
+
+    json_response(&service.club(club)?)
+}
+
+/// `GET /clubs/standings` ranks the clubs by how their members' games have gone this week
+pub fn handle_standings(service: &Service) -> Result<Response, ApiError> {
+    json_response(&service.club_standings(week_start(game::today()))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clubs::week_start;
+    use crate::game::{date, parse_date};
+
+    #[test]
+    fn weeks_start_on_mondays() {
+        let monday = parse_date("2026-10-12").unwrap();
+
+        assert_eq!(week_start(monday), monday);
+        assert_eq!(date(week_start(monday + 6)), "2026-10-12");
+        assert_eq!(date(week_start(monday + 7)), "2026-10-19");
+    }
+}
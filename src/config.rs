@@ -0,0 +1,341 @@
+use crate::cors::{self, Cors};
+use crate::db;
+use crate::dictionary::{self, WordLists};
+use crate::game::Rollover;
+use crate::mock::Mock;
+use crate::request_log::{Level, RequestLog, Target};
+use crate::testing;
+use rusqlite::Connection;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{env, fs, io};
+use uuid::Uuid;
+
+/// The furthest any place is from UTC, in minutes
+const MAX_UTC_OFFSET: i32 = 14 * 60;
+
+/// Server-wide settings, read from the environment at startup
+#[derive(Clone)]
+pub struct Config {
+    /// The database path, or SQLite URI, every request opens its own connection to
+    pub database: String,
+    /// Whether guesses rejected as invalid words use up a go, unless a game chooses otherwise
+    pub invalid_guesses_count: bool,
+    /// The bearer token required by the admin endpoints, which are disabled when unset
+    pub admin_token: Option<String>,
+    /// Whether the server applies pending migrations on startup, rather than refusing to start
+    pub auto_migrate: bool,
+    /// How often to VACUUM and ANALYZE the database in the background, if at all
+    pub maintenance_interval: Option<Duration>,
+    /// Seeds the choice of answers so that the same sequence of games gets the same answers
+    pub answer_seed: Option<u64>,
+    /// Whether a client is only given an answer they've had before once they've had every answer
+    pub no_repeat_answers: bool,
+    /// A directory whose files are served in place of the embedded assets of the same name
+    pub asset_dir: Option<String>,
+    /// Where the server is reached from outside, for links it hands out, e.g. `https://wordle.example`
+    pub public_url: Option<String>,
+    /// The secret Telegram must send with webhook updates, which are refused when unset
+    pub telegram_secret: Option<String>,
+    /// The secret a bot relaying Discord interactions must send them with, as
+    /// `Authorization: Bearer <secret>`, which are refused when unset
+    pub discord_secret: Option<String>,
+    /// How many requests a minute each address may make to the solver endpoints, which are
+    /// expensive to answer, or unlimited when unset
+    pub solver_rate_limit: Option<u32>,
+    /// How many requests a minute each address may make to any endpoint, or unlimited when unset
+    pub rate_limit: Option<u32>,
+    /// How many games a minute each client may create and guess in altogether, whichever
+    /// addresses they play from, or unlimited when unset
+    pub client_rate_limit: Option<u32>,
+    /// Whether games can only be created for clients registered at `POST /clients`, or guests
+    pub registered_clients_only: bool,
+    /// Whether reminder webhooks may be sent to loopback, link-local and private addresses, such
+    /// as a receiver on the server's own network, which anyone could otherwise have it POST to
+    pub private_webhooks: bool,
+    /// How often to check for streak reminders that are due, if at all
+    pub reminder_interval: Option<Duration>,
+    /// How many days after a game's last guess maintenance moves it to the archive, if ever
+    pub archive_after_days: Option<u32>,
+    /// Set by `serve --mock`, which pretends whatever each request asks and captures webhooks
+    /// rather than sending them
+    pub mock: Option<Arc<Mock>>,
+    /// The communities served under `/t/<tenant>/`, each from a database of its own alongside
+    /// `database`, so none of them sees another's games
+    pub tenants: Vec<String>,
+    /// When the daily puzzle, every group's word and the answer schedule, moves on to the next day
+    pub rollover: Rollover,
+    /// How many goes a game is lost after, guesses and counted rejections alike, or unlimited when
+    /// unset. Modes played on several boards get a go more for each board after the first.
+    pub max_guesses: Option<usize>,
+    /// The words games of each language and length are played with, those besides the bundled
+    /// five-letter English ones read from the directory named by `WORDLE_WORD_LIST_DIR`
+    pub word_lists: WordLists,
+    /// The directory `word_lists` were read from, whose `tenants/<tenant>` directories have the
+    /// words each tenant plays with in place of those
+    pub word_list_dir: Option<String>,
+    /// Where a line of JSON is written for every request, if anywhere: at `WORDLE_LOG_LEVEL`,
+    /// `error`, `warn`, `info` or `off`, to `WORDLE_LOG_TARGET`, `stderr`, `stdout` or a file
+    pub request_log: Option<Arc<RequestLog>>,
+    /// Which origins browsers may call the API from, none unless `WORDLE_CORS_ORIGINS` names some,
+    /// with the methods and headers of `WORDLE_CORS_METHODS` and `WORDLE_CORS_HEADERS`
+    pub cors: Option<Cors>,
+}
+
+impl Config {
+    pub fn from_env() -> Config {
+        let word_list_dir = env::var("WORDLE_WORD_LIST_DIR")
+            .ok()
+            .filter(|x| !x.is_empty());
+
+        Config {
+            database: String::from(db::DATABASE),
+            invalid_guesses_count: env_bool("WORDLE_INVALID_GUESSES_COUNT", false),
+            admin_token: env::var("WORDLE_ADMIN_TOKEN")
+                .ok()
+                .filter(|x| !x.is_empty()),
+            auto_migrate: env_bool("WORDLE_AUTO_MIGRATE", true),
+            maintenance_interval: env_parse::<u64>("WORDLE_MAINTENANCE_INTERVAL_HOURS")
+                .filter(|hours| *hours > 0)
+                .map(|hours| Duration::from_secs(hours * 60 * 60)),
+            answer_seed: env_parse("WORDLE_ANSWER_SEED"),
+            no_repeat_answers: env_bool("WORDLE_NO_REPEAT_ANSWERS", false),
+            asset_dir: env::var("WORDLE_ASSET_DIR").ok().filter(|x| !x.is_empty()),
+            public_url: env::var("WORDLE_PUBLIC_URL").ok().filter(|x| !x.is_empty()),
+            telegram_secret: env::var("WORDLE_TELEGRAM_SECRET")
+                .ok()
+                .filter(|x| !x.is_empty()),
+            discord_secret: env::var("WORDLE_DISCORD_SECRET")
+                .ok()
+                .filter(|x| !x.is_empty()),
+            solver_rate_limit: Some(env_parse("WORDLE_SOLVER_RATE_LIMIT").unwrap_or(30))
+                .filter(|x| *x > 0),
+            rate_limit: Some(env_parse("WORDLE_RATE_LIMIT").unwrap_or(600)).filter(|x| *x > 0),
+            client_rate_limit: Some(env_parse("WORDLE_CLIENT_RATE_LIMIT").unwrap_or(120))
+                .filter(|x| *x > 0),
+            registered_clients_only: env_bool("WORDLE_REGISTERED_CLIENTS_ONLY", false),
+            private_webhooks: env_bool("WORDLE_PRIVATE_WEBHOOKS", false),
+            reminder_interval: Some(env_parse("WORDLE_REMINDER_INTERVAL_MINUTES").unwrap_or(5))
+                .filter(|minutes| *minutes > 0)
+                .map(|minutes: u64| Duration::from_secs(minutes * 60)),
+            archive_after_days: env_parse("WORDLE_ARCHIVE_AFTER_DAYS").filter(|days| *days > 0),
+            mock: None,
+            tenants: env::var("WORDLE_TENANTS")
+                .map(|value| parse_tenants(&value))
+                .unwrap_or_default(),
+            rollover: Rollover {
+                utc_offset: env_parse::<i32>("WORDLE_DAILY_UTC_OFFSET")
+                    .filter(|minutes| minutes.abs() <= MAX_UTC_OFFSET)
+                    .unwrap_or(0),
+                hour: env_parse("WORDLE_DAILY_ROLLOVER_HOUR")
+                    .filter(|hour| *hour < 24)
+                    .unwrap_or(0),
+            },
+            max_guesses: Some(env_parse("WORDLE_MAX_GUESSES").unwrap_or(6)).filter(|x| *x > 0),
+            word_lists: match &word_list_dir {
+                None => WordLists::default(),
+                Some(dir) => WordLists::load(Path::new(dir)).unwrap_or_else(|error| {
+                    eprintln!("word lists couldn't be read from {dir}, only the bundled words will be played: {error}");
+                    WordLists::default()
+                }),
+            },
+            word_list_dir,
+            request_log: request_log(),
+            cors: env::var("WORDLE_CORS_ORIGINS")
+                .ok()
+                .filter(|x| !x.trim().is_empty())
+                .map(|origins| {
+                    let mut cors = Cors::new(&origins);
+                    if let Ok(methods) = env::var("WORDLE_CORS_METHODS") {
+                        cors.methods = cors::list(&methods);
+                    }
+                    if let Ok(headers) = env::var("WORDLE_CORS_HEADERS") {
+                        cors.headers = cors::list(&headers);
+                    }
+                    cors
+                }),
+        }
+    }
+
+    /// The configuration a tenant is served with, the same but for its database and any words of
+    /// its own in the word list directory's `tenants/<tenant>`, which are played in place of the
+    /// shared ones of the same language and length. They're kept apart from the directories named
+    /// for languages, which a tenant could otherwise be mistaken for.
+    pub fn for_tenant(&self, tenant: &str) -> Config {
+        let (path, query) = match self.database.split_once('?') {
+            Some((path, query)) => (path, format!("?{query}")),
+            None => (self.database.as_str(), String::new()),
+        };
+        let path = match path.rsplit_once('.') {
+            Some((stem, extension)) if !extension.contains('/') => {
+                format!("{stem}-{tenant}.{extension}")
+            }
+            _ => format!("{path}-{tenant}"),
+        };
+
+        let word_lists = match &self.word_list_dir {
+            None => self.word_lists.clone(),
+            Some(dir) => {
+                let dir = Path::new(dir).join("tenants").join(tenant);
+                self.word_lists.overlay(&dir).unwrap_or_else(|error| {
+                    eprintln!("{tenant}'s word lists couldn't be read from {}, the shared words will be played: {error}", dir.display());
+                    self.word_lists.clone()
+                })
+            }
+        };
+
+        Config {
+            database: format!("{path}{query}"),
+            tenants: Vec::new(),
+            word_lists,
+            ..self.clone()
+        }
+    }
+
+    /// What tests start out with: a fresh in-memory database, migrated when it's first served, and
+    /// the admin token tests authorize with, but nothing scheduled, limited, or logged
+    pub fn for_tests() -> Config {
+        Config {
+            database: format!(
+                "file:wordle-test-{}?mode=memory&cache=shared",
+                Uuid::new_v4()
+            ),
+            invalid_guesses_count: false,
+            admin_token: Some(String::from(testing::ADMIN_TOKEN)),
+            auto_migrate: true,
+            maintenance_interval: None,
+            answer_seed: None,
+            no_repeat_answers: false,
+            asset_dir: None,
+            public_url: None,
+            telegram_secret: None,
+            discord_secret: None,
+            solver_rate_limit: None,
+            rate_limit: None,
+            client_rate_limit: None,
+            registered_clients_only: false,
+            private_webhooks: false,
+            reminder_interval: None,
+            archive_after_days: None,
+            mock: None,
+            tenants: Vec::new(),
+            rollover: Rollover::default(),
+            max_guesses: Some(6),
+            word_lists: WordLists::default(),
+            word_list_dir: None,
+            request_log: None,
+            cors: None,
+        }
+    }
+
+    /// The configuration for a mock server: a fresh in-memory database, so every run starts out
+    /// the same, and answers seeded unless a seed is configured
+    pub fn mock(self) -> Config {
+        Config {
+            database: format!(
+                "file:wordle-mock-{}?mode=memory&cache=shared",
+                Uuid::new_v4()
+            ),
+            auto_migrate: true,
+            answer_seed: self.answer_seed.or(Some(0)),
+            mock: Some(Arc::new(Mock::default())),
+            ..self
+        }
+    }
+
+    pub fn connection(&self) -> Result<Connection, rusqlite::Error> {
+        db::open(&self.database)
+    }
+}
+
+/// Replaces the bundled words with those in the files named by `WORDLE_ANSWERS_FILE` and
+/// `WORDLE_GUESSES_FILE`, one word a line, either of which may be left unset to keep the bundled
+/// ones. It has to be done before anything plays, so that everything plays with the same words.
+pub fn replace_words() -> io::Result<()> {
+    let read = |name: &str| match env::var(name).ok().filter(|x| !x.is_empty()) {
+        None => Ok(None),
+        Some(path) => fs::read_to_string(&path)
+            .map(Some)
+            .map_err(|error| io::Error::new(error.kind(), format!("{path}: {error}"))),
+    };
+    let (answers, guesses) = (read("WORDLE_ANSWERS_FILE")?, read("WORDLE_GUESSES_FILE")?);
+    if answers.is_none() && guesses.is_none() {
+        return Ok(());
+    }
+
+    if dictionary::replace(answers.as_deref(), guesses.as_deref()) {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the words can only be replaced with five-letter answers, before anything has played",
+        ))
+    }
+}
+
+/// Every request is logged at `info` to stderr unless configured otherwise
+fn request_log() -> Option<Arc<RequestLog>> {
+    let level = env::var("WORDLE_LOG_LEVEL").unwrap_or_else(|_| String::from("info"));
+    if level.trim().eq_ignore_ascii_case("off") {
+        return None;
+    }
+    let level = Level::parse(&level).unwrap_or_else(|| {
+        panic!("WORDLE_LOG_LEVEL must be one of error/warn/info/off, got '{level}'")
+    });
+
+    let target = env::var("WORDLE_LOG_TARGET")
+        .ok()
+        .filter(|x| !x.is_empty())
+        .unwrap_or_else(|| String::from("stderr"));
+    let target = Target::open(&target).unwrap_or_else(|error| {
+        eprintln!("requests can't be logged to {target}, they'll be logged to stderr: {error}");
+        Target::Stderr
+    });
+
+    Some(Arc::new(RequestLog::new(level, target)))
+}
+
+fn env_bool(name: &str, default: bool) -> bool {
+    match env::var(name) {
+        Ok(value) => parse_bool(&value)
+            .unwrap_or_else(|| panic!("{name} must be one of true/false/1/0, got '{value}'")),
+        Err(_) => default,
+    }
+}
+
+fn env_parse<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().map(|value| {
+        value
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} has an invalid value '{value}'"))
+    })
+}
+
+/// Tenants are named in urls and database files, so only lowercase letters, digits and hyphens
+fn parse_tenants(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(|tenant| {
+            let valid = tenant.len() <= 32
+                && tenant
+                    .bytes()
+                    .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == b'-');
+            assert!(valid, "WORDLE_TENANTS has an invalid tenant '{tenant}'");
+
+            tenant.to_string()
+        })
+        .collect()
+}
+
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
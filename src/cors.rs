@@ -0,0 +1,135 @@
+use rouille::{Request, Response};
+
+/// The response headers a cross-origin script may read, besides the ones browsers always expose
+const EXPOSED: &str = "Api-Version, X-Request-Id, Retry-After, Deprecation, X-RateLimit-Limit, \
+X-RateLimit-Remaining, X-RateLimit-Reset";
+
+/// How long browsers may cache a preflight's answer for, in seconds
+const MAX_AGE: &str = "600";
+
+/// Which origins, methods and headers browsers are allowed to call the API with cross-origin
+#[derive(Clone, Debug)]
+pub struct Cors {
+    pub origins: Origins,
+    pub methods: Vec<String>,
+    pub headers: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Origins {
+    /// `*`, whichever page the script was loaded by
+    Any,
+    /// Only these, e.g. `https://wordle.example`
+    Listed(Vec<String>),
+}
+
+impl Cors {
+    /// Allows the origins, `*` or a comma-separated list, with every method the API answers and
+    /// the headers its clients send
+    pub fn new(origins: &str) -> Cors {
+        Cors {
+            origins: Origins::parse(origins),
+            methods: list("GET, POST, PUT, DELETE"),
+            headers: list("Authorization, Content-Type, Accept-Language, X-Request-Id"),
+        }
+    }
+
+    /// What to answer the request's `Origin` with in `Access-Control-Allow-Origin`, if it's
+    /// allowed at all
+    fn allowed(&self, request: &Request) -> Option<String> {
+        let origin = request.header("Origin")?;
+        match &self.origins {
+            Origins::Any => Some(String::from("*")),
+            Origins::Listed(origins) => origins
+                .iter()
+                .any(|x| x.eq_ignore_ascii_case(origin))
+                .then(|| origin.to_string()),
+        }
+    }
+
+    /// The answer to a preflight, an `OPTIONS` request asking whether a method may be used, which
+    /// is answered the same whichever path it's for. Any other request is left to the API.
+    pub fn preflight(&self, request: &Request) -> Option<Response> {
+        if request.method() != "OPTIONS" {
+            return None;
+        }
+        let method = request.header("Access-Control-Request-Method")?;
+
+        let response = Response::empty_204().with_unique_header("Vary", "Origin");
+        let Some(origin) = self.allowed(request) else {
+            // without the headers that allow it, the browser refuses the request it asked about
+            return Some(response);
+        };
+        let allowed = self
+            .methods
+            .iter()
+            .any(|x| x.eq_ignore_ascii_case(method.trim()));
+        if !allowed {
+            return Some(response);
+        }
+
+        Some(
+            response
+                .with_unique_header("Access-Control-Allow-Origin", origin)
+                .with_unique_header("Access-Control-Allow-Methods", self.methods.join(", "))
+                .with_unique_header("Access-Control-Allow-Headers", self.headers.join(", "))
+                .with_unique_header("Access-Control-Max-Age", MAX_AGE),
+        )
+    }
+
+    /// Allows the request's origin to read the response, if it's one of those allowed
+    pub fn apply(&self, request: &Request, response: Response) -> Response {
+        let response = match self.origins {
+            Origins::Any => response,
+            // the answer depends on the origin, so caches mustn't hand it to another
+            Origins::Listed(_) => response.with_unique_header("Vary", "Origin"),
+        };
+
+        match self.allowed(request) {
+            Some(origin) => response
+                .with_unique_header("Access-Control-Allow-Origin", origin)
+                .with_unique_header("Access-Control-Expose-Headers", EXPOSED),
+            None => response,
+        }
+    }
+}
+
+impl Origins {
+    fn parse(value: &str) -> Origins {
+        let origins = list(value);
+        if origins.iter().any(|x| x == "*") {
+            Origins::Any
+        } else {
+            Origins::Listed(
+                origins
+                    .into_iter()
+                    .map(|x| x.trim_end_matches('/').to_string())
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// A comma-separated list, without its blanks
+pub fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cors::{list, Origins};
+
+    #[test]
+    fn origins_are_listed_or_any() {
+        assert_eq!(Origins::parse(" * "), Origins::Any);
+        assert_eq!(
+            Origins::parse("https://a.example/, ,https://b.example"),
+            Origins::Listed(list("https://a.example, https://b.example"))
+        );
+    }
+}
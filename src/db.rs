@@ -0,0 +1,777 @@
+use crate::events::GameEvent;
+use crate::game::{
+    self, ClientId, GameId, GameMode, GameState, GroupId, Guess, ModeState, Rejection, Word,
+};
+use rusqlite::types::{ToSqlOutput, Type};
+use rusqlite::ToSql;
+use rusqlite::{Connection, OptionalExtension, Row};
+use serde::Serialize;
+
+/// Schema migrations, applied in order. The index of the last applied migration (plus one) is
+/// tracked in sqlite's `user_version` pragma, so each migration only ever runs once.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE IF NOT EXISTS game (
+        game_id TEXT NOT NULL,
+        client  TEXT NOT NULL,
+        word    TEXT NOT NULL,
+        goes    INTEGER DEFAULT 0,
+        solved  INTEGER DEFAULT 0
+    )",
+    "ALTER TABLE game ADD COLUMN state TEXT NOT NULL DEFAULT 'in_progress';
+    UPDATE game SET state = 'won' WHERE solved = 1;",
+    "ALTER TABLE game ADD COLUMN invalid_guesses_count INTEGER NOT NULL DEFAULT 0",
+    // keep the earliest of any duplicated games, which is the row lookups have always returned
+    "DELETE FROM game WHERE rowid NOT IN (SELECT MIN(rowid) FROM game GROUP BY game_id);
+    CREATE UNIQUE INDEX game_game_id ON game (game_id);",
+    // repair anything legacy rows could hold that the current code would refuse to read, noting
+    // each repair, then rebuild the table so the same can't be written again
+    "CREATE TABLE schema_repair (
+        repair_id   INTEGER PRIMARY KEY,
+        game_id     TEXT NOT NULL,
+        field       TEXT NOT NULL,
+        original    TEXT,
+        repaired    TEXT NOT NULL,
+        repaired_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+
+    INSERT INTO schema_repair (game_id, field, original, repaired)
+        SELECT game_id, 'goes', goes, 0 FROM game
+        WHERE goes IS NULL OR typeof(goes) <> 'integer' OR goes < 0;
+    UPDATE game SET goes = 0 WHERE goes IS NULL OR typeof(goes) <> 'integer' OR goes < 0;
+
+    INSERT INTO schema_repair (game_id, field, original, repaired)
+        SELECT game_id, 'solved', solved, CASE WHEN state = 'won' THEN 1 ELSE 0 END FROM game
+        WHERE solved IS NULL OR solved NOT IN (0, 1);
+    UPDATE game SET solved = CASE WHEN state = 'won' THEN 1 ELSE 0 END
+        WHERE solved IS NULL OR solved NOT IN (0, 1);
+
+    INSERT INTO schema_repair (game_id, field, original, repaired)
+        SELECT game_id, 'state', state, CASE WHEN solved = 1 THEN 'won' ELSE 'in_progress' END
+        FROM game
+        WHERE state NOT IN ('in_progress', 'won', 'lost', 'forfeited', 'expired')
+            OR (state = 'won') <> (solved = 1);
+    UPDATE game SET state = CASE WHEN solved = 1 THEN 'won' ELSE 'in_progress' END
+        WHERE state NOT IN ('in_progress', 'won', 'lost', 'forfeited', 'expired')
+            OR (state = 'won') <> (solved = 1);
+
+    CREATE TABLE game_new (
+        game_id               TEXT    NOT NULL,
+        client                TEXT    NOT NULL,
+        word                  TEXT    NOT NULL,
+        goes                  INTEGER NOT NULL DEFAULT 0 CHECK (goes >= 0),
+        solved                INTEGER NOT NULL DEFAULT 0 CHECK (solved IN (0, 1)),
+        state                 TEXT    NOT NULL DEFAULT 'in_progress',
+        invalid_guesses_count INTEGER NOT NULL DEFAULT 0 CHECK (invalid_guesses_count IN (0, 1))
+    );
+    INSERT INTO game_new (rowid, game_id, client, word, goes, solved, state, invalid_guesses_count)
+        SELECT rowid, game_id, client, word, goes, solved, state, invalid_guesses_count FROM game;
+    DROP TABLE game;
+    ALTER TABLE game_new RENAME TO game;
+    CREATE UNIQUE INDEX game_game_id ON game (game_id);",
+    "CREATE TABLE rejected_guess (
+        rejection_id INTEGER PRIMARY KEY,
+        game_id      TEXT    NOT NULL,
+        guess        TEXT    NOT NULL,
+        reason       TEXT    NOT NULL,
+        counted      INTEGER NOT NULL CHECK (counted IN (0, 1)),
+        rejected_at  TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX rejected_guess_game_id ON rejected_guess (game_id);",
+    // games created before this migration have no way of knowing when they were created
+    "ALTER TABLE game ADD COLUMN created_at TEXT",
+    "ALTER TABLE game ADD COLUMN updated_at TEXT",
+    "CREATE TABLE audit_log (
+        audit_id   INTEGER PRIMARY KEY,
+        action     TEXT NOT NULL,
+        subject    TEXT NOT NULL,
+        detail     TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );",
+    "CREATE TABLE setting (
+        name  TEXT PRIMARY KEY NOT NULL,
+        value TEXT NOT NULL
+    );",
+    // existing games are classic, and a classic game with no mode state is played against `word`
+    "ALTER TABLE game ADD COLUMN mode TEXT NOT NULL DEFAULT 'classic';
+    ALTER TABLE game ADD COLUMN mode_state TEXT;",
+    // games from before events were recorded start from a snapshot of their row as it stands
+    "CREATE TABLE game_event (
+        event_id   INTEGER PRIMARY KEY,
+        game_id    TEXT NOT NULL,
+        kind       TEXT NOT NULL,
+        data       TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX game_event_game_id ON game_event (game_id);
+
+    INSERT INTO game_event (game_id, kind, data, created_at)
+        SELECT game_id, 'created', json_object(
+            'type', 'created',
+            'client', client,
+            'word', word,
+            'goes', goes,
+            'state', state,
+            'options', json_object(
+                'invalid_guesses_count', json(CASE WHEN invalid_guesses_count THEN 'true' ELSE 'false' END),
+                'mode', mode
+            ),
+            'mode_state', json(mode_state)
+        ), COALESCE(created_at, CURRENT_TIMESTAMP)
+        FROM game ORDER BY rowid;",
+    // simulating an opener plays a game against every answer, so each is kept until the list changes
+    "CREATE TABLE opener_simulation (
+        opener       TEXT NOT NULL,
+        list_version TEXT NOT NULL,
+        data         TEXT NOT NULL,
+        created_at   TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (opener, list_version)
+    );",
+    // refreshed by maintenance, so serving the report never scans every game's events
+    "CREATE TABLE opener_report (
+        opener    TEXT PRIMARY KEY,
+        num_games INTEGER NOT NULL,
+        num_won   INTEGER NOT NULL,
+        avg_goes  REAL
+    );",
+    "CREATE TABLE achievement (
+        client      TEXT NOT NULL,
+        achievement TEXT NOT NULL,
+        game_id     TEXT NOT NULL,
+        granted_at  TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (client, achievement)
+    );",
+    "CREATE TABLE profile (
+        client     TEXT PRIMARY KEY,
+        data       TEXT NOT NULL,
+        updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );",
+    // friendships are mutual, so each is stored both ways round
+    "CREATE TABLE friend (
+        client     TEXT NOT NULL,
+        friend     TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (client, friend)
+    );
+    CREATE TABLE friend_invite (
+        client     TEXT PRIMARY KEY,
+        code       TEXT NOT NULL UNIQUE,
+        created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );",
+    // the day is the client's local one, counted in days since the epoch
+    "CREATE TABLE reminder (
+        client        TEXT PRIMARY KEY,
+        data          TEXT NOT NULL,
+        last_sent_day INTEGER,
+        updated_at    TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );",
+    "CREATE TABLE player_group (
+        group_id   TEXT PRIMARY KEY,
+        name       TEXT NOT NULL,
+        owner      TEXT NOT NULL,
+        key        TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE player_group_member (
+        group_id TEXT NOT NULL,
+        client   TEXT NOT NULL,
+        added_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (group_id, client)
+    );
+    -- each member plays the group's word for a day in a single game, the day counted since the epoch
+    CREATE TABLE player_group_game (
+        group_id TEXT NOT NULL,
+        client   TEXT NOT NULL,
+        day      INTEGER NOT NULL,
+        game_id  TEXT NOT NULL,
+        PRIMARY KEY (group_id, client, day)
+    );",
+    // a client is in one club at most, so none of their games count twice
+    "CREATE TABLE club (
+        club       TEXT PRIMARY KEY,
+        name       TEXT NOT NULL,
+        created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE club_member (
+        client    TEXT PRIMARY KEY,
+        club      TEXT NOT NULL,
+        joined_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX club_member_club ON club_member (club);",
+    "CREATE TABLE share_link (
+        token        TEXT PRIMARY KEY,
+        game_id      TEXT    NOT NULL,
+        show_guesses INTEGER NOT NULL CHECK (show_guesses IN (0, 1)),
+        created_at   TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX share_link_game_id ON share_link (game_id);",
+    // finished games are moved here once they're old enough, so every read of a game's history
+    // goes through game_history, which has them all in the order they were created
+    "CREATE TABLE game_archive (
+        seq                   INTEGER NOT NULL,
+        game_id               TEXT    NOT NULL,
+        client                TEXT    NOT NULL,
+        word                  TEXT    NOT NULL,
+        goes                  INTEGER NOT NULL,
+        solved                INTEGER NOT NULL,
+        state                 TEXT    NOT NULL,
+        invalid_guesses_count INTEGER NOT NULL,
+        mode                  TEXT    NOT NULL,
+        mode_state            TEXT,
+        created_at            TEXT,
+        updated_at            TEXT,
+        archived_at           TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE UNIQUE INDEX game_archive_game_id ON game_archive (game_id);
+    CREATE INDEX game_archive_client ON game_archive (client);
+    CREATE VIEW game_history AS
+        SELECT rowid AS seq, game_id, client, word, goes, solved, state, invalid_guesses_count,
+            mode, mode_state, created_at, updated_at
+        FROM game
+        UNION ALL
+        SELECT seq, game_id, client, word, goes, solved, state, invalid_guesses_count, mode,
+            mode_state, created_at, updated_at
+        FROM game_archive;",
+    // every guess as it was made, kept apart from the games so that nothing done to them can
+    // rewrite it. Only erasing a client takes rows out, and only theirs.
+    "CREATE TABLE guess_log (
+        log_id     INTEGER PRIMARY KEY,
+        game_id    TEXT    NOT NULL,
+        client     TEXT    NOT NULL,
+        guess      TEXT    NOT NULL,
+        outcome    TEXT    NOT NULL,
+        counted    INTEGER NOT NULL CHECK (counted IN (0, 1)),
+        logged_at  TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE INDEX guess_log_client ON guess_log (client);
+    CREATE INDEX guess_log_game_id ON guess_log (game_id);
+    CREATE TRIGGER guess_log_no_update BEFORE UPDATE ON guess_log
+    BEGIN
+        SELECT RAISE(ABORT, 'the guess log is append-only');
+    END;
+    CREATE TRIGGER guess_log_no_delete BEFORE DELETE ON guess_log
+    WHEN OLD.client IS NOT (SELECT value FROM setting WHERE name = 'erasing_client')
+    BEGIN
+        SELECT RAISE(ABORT, 'the guess log is append-only');
+    END;",
+    // the daily words decided ahead of time, the day counted since the epoch. Pinned words were
+    // chosen by an operator, the rest generated and replaced whenever the schedule is regenerated.
+    "CREATE TABLE answer_schedule (
+        day        INTEGER PRIMARY KEY,
+        word       TEXT    NOT NULL,
+        pinned     INTEGER NOT NULL CHECK (pinned IN (0, 1)),
+        note       TEXT,
+        created_at TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );",
+    // replays are only shared for a while, so each token expires, in seconds since the epoch
+    "CREATE TABLE replay_token (
+        token      TEXT PRIMARY KEY,
+        game_id    TEXT    NOT NULL,
+        expires_at INTEGER NOT NULL
+    );
+    CREATE INDEX replay_token_game_id ON replay_token (game_id);",
+    // what's happened to each client since they last looked, until they acknowledge it, sent_at in
+    // seconds since the epoch
+    "CREATE TABLE inbox (
+        message_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        client     TEXT    NOT NULL,
+        data       TEXT    NOT NULL,
+        sent_at    INTEGER NOT NULL
+    );
+    CREATE INDEX inbox_client ON inbox (client, message_id);",
+    // games from before the limit were played until they were won, so keep no limit
+    "ALTER TABLE game ADD COLUMN max_guesses INTEGER;
+    ALTER TABLE game_archive ADD COLUMN max_guesses INTEGER;
+    DROP VIEW game_history;
+    CREATE VIEW game_history AS
+        SELECT rowid AS seq, game_id, client, word, goes, solved, state, invalid_guesses_count,
+            mode, mode_state, created_at, updated_at, max_guesses
+        FROM game
+        UNION ALL
+        SELECT seq, game_id, client, word, goes, solved, state, invalid_guesses_count, mode,
+            mode_state, created_at, updated_at, max_guesses
+        FROM game_archive;",
+    // created_at in seconds since the epoch
+    "CREATE TABLE guest (
+        client     TEXT PRIMARY KEY,
+        key        TEXT    NOT NULL,
+        created_at INTEGER NOT NULL
+    );",
+    // every guess played, evaluation being the JSON of its evaluation against the first board.
+    // Games from before still have their guesses in their events.
+    "CREATE TABLE game_guess (
+        game_id    TEXT    NOT NULL,
+        go         INTEGER NOT NULL,
+        guess      TEXT    NOT NULL,
+        evaluation TEXT    NOT NULL,
+        guessed_at TEXT    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (game_id, go)
+    );",
+    // the day, in days since the epoch, is the one the daily word was played for
+    "CREATE TABLE daily_game (
+        client  TEXT NOT NULL,
+        day     INTEGER NOT NULL,
+        game_id TEXT NOT NULL,
+        PRIMARY KEY (client, day)
+    );",
+    // games from before other lengths could be played were all played with five letters
+    "ALTER TABLE game ADD COLUMN length INTEGER NOT NULL DEFAULT 5;
+    ALTER TABLE game_archive ADD COLUMN length INTEGER NOT NULL DEFAULT 5;
+    DROP VIEW game_history;
+    CREATE VIEW game_history AS
+        SELECT rowid AS seq, game_id, client, word, goes, solved, state, invalid_guesses_count,
+            mode, mode_state, created_at, updated_at, max_guesses, length
+        FROM game
+        UNION ALL
+        SELECT seq, game_id, client, word, goes, solved, state, invalid_guesses_count, mode,
+            mode_state, created_at, updated_at, max_guesses, length
+        FROM game_archive;",
+    // games from before other languages could be played were all played in English
+    "ALTER TABLE game ADD COLUMN language TEXT NOT NULL DEFAULT 'en';
+    ALTER TABLE game_archive ADD COLUMN language TEXT NOT NULL DEFAULT 'en';
+    DROP VIEW game_history;
+    CREATE VIEW game_history AS
+        SELECT rowid AS seq, game_id, client, word, goes, solved, state, invalid_guesses_count,
+            mode, mode_state, created_at, updated_at, max_guesses, length, language
+        FROM game
+        UNION ALL
+        SELECT seq, game_id, client, word, goes, solved, state, invalid_guesses_count, mode,
+            mode_state, created_at, updated_at, max_guesses, length, language
+        FROM game_archive;",
+    // client is whoever chose the word, created_at in seconds since the epoch
+    "CREATE TABLE challenge (
+        challenge_id TEXT PRIMARY KEY,
+        client       TEXT    NOT NULL,
+        word         TEXT    NOT NULL,
+        created_at   INTEGER NOT NULL
+    );
+    CREATE TABLE challenge_game (
+        challenge_id TEXT NOT NULL,
+        client       TEXT NOT NULL,
+        game_id      TEXT NOT NULL,
+        PRIMARY KEY (challenge_id, client)
+    );",
+    // a client with a key can only be played as by whoever has it, created_at in seconds since
+    // the epoch
+    "CREATE TABLE api_key (
+        client     TEXT PRIMARY KEY,
+        key        TEXT    NOT NULL,
+        created_at INTEGER NOT NULL
+    );",
+    // every client registered, metadata being the JSON object they registered with. Their display
+    // name is their profile's.
+    "ALTER TABLE api_key RENAME TO client;
+    ALTER TABLE client ADD COLUMN metadata TEXT NOT NULL DEFAULT '{}';",
+];
+
+/// A legacy value that a migration had to replace
+#[derive(Serialize)]
+pub struct Repair {
+    pub game_id: String,
+    pub field: String,
+    pub original: Option<String>,
+    pub repaired: String,
+    pub repaired_at: String,
+}
+
+/// Where the server and the command line tools keep their games
+pub const DATABASE: &str = "wordle.db";
+
+pub fn get_connection() -> Result<Connection, rusqlite::Error> {
+    open(DATABASE)
+}
+
+/// Opens a database by path, or by SQLite URI such as a shared in-memory database
+pub fn open(database: &str) -> Result<Connection, rusqlite::Error> {
+    Connection::open(database)
+}
+
+/// The version produced by the migration that repairs legacy rows into `schema_repair`
+pub const REPAIR_VERSION: usize = 5;
+
+/// The schema version this build expects, i.e. the version after every migration is applied
+pub const LATEST_VERSION: usize = MIGRATIONS.len();
+
+pub fn schema_version(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// The migrations that would be applied to reach the target version, along with the version each
+/// one migrates to
+pub fn pending(
+    conn: &Connection,
+    target: usize,
+) -> Result<Vec<(usize, &'static str)>, rusqlite::Error> {
+    let version = schema_version(conn)?;
+
+    Ok(MIGRATIONS
+        .iter()
+        .enumerate()
+        .take(target)
+        .skip(version)
+        .map(|(index, migration)| (index + 1, *migration))
+        .collect())
+}
+
+/// Applies pending migrations up to and including the target version, returning how many were
+/// applied. Migrations only go forwards, so a target below the current version does nothing.
+pub fn migrate_to(conn: &mut Connection, target: usize) -> Result<usize, rusqlite::Error> {
+    let pending = pending(conn, target)?;
+
+    for (version, migration) in &pending {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+    }
+
+    Ok(pending.len())
+}
+
+pub fn repairs(conn: &Connection) -> Result<Vec<Repair>, rusqlite::Error> {
+    conn.prepare(
+        "SELECT game_id, field, original, repaired, repaired_at FROM schema_repair ORDER BY repair_id",
+    )?
+    .query_map([], |row| {
+        Ok(Repair {
+            game_id: row.get(0)?,
+            field: row.get(1)?,
+            original: row.get(2)?,
+            repaired: row.get(3)?,
+            repaired_at: row.get(4)?,
+        })
+    })?
+    .collect()
+}
+
+/// Narrows down the games an admin operation applies to, every field left as `None` matches all
+#[derive(Default)]
+pub struct GameFilter {
+    pub client: Option<String>,
+    pub state: Option<GameState>,
+    /// Only games created at least this many days ago, including those too old to have a
+    /// recorded creation time
+    pub older_than_days: Option<u32>,
+}
+
+impl GameFilter {
+    const WHERE: &'static str = "(?1 IS NULL OR client = ?1)
+        AND (?2 IS NULL OR state = ?2)
+        AND (?3 IS NULL OR created_at IS NULL OR created_at < datetime('now', '-' || ?3 || ' days'))";
+
+    fn params(&self) -> (&Option<String>, Option<&str>, &Option<u32>) {
+        (
+            &self.client,
+            self.state.map(GameState::as_str),
+            &self.older_than_days,
+        )
+    }
+}
+
+pub struct GameSummary {
+    pub game_id: String,
+    pub client: String,
+    pub word: String,
+    pub goes: usize,
+    pub state: GameState,
+    pub created_at: Option<String>,
+}
+
+pub fn find_games(
+    conn: &Connection,
+    filter: &GameFilter,
+) -> Result<Vec<GameSummary>, rusqlite::Error> {
+    conn.prepare(&format!(
+        "SELECT game_id, client, word, goes, state, created_at FROM game_history WHERE {} ORDER BY seq",
+        GameFilter::WHERE
+    ))?
+    .query_map(filter.params(), |row| {
+        Ok(GameSummary {
+            game_id: row.get(0)?,
+            client: row.get(1)?,
+            word: row.get(2)?,
+            goes: row.get(3)?,
+            state: get_state(row, 4)?,
+            created_at: row.get(5)?,
+        })
+    })?
+    .collect()
+}
+
+/// Deletes every matching game along with its rejected guesses and events, returning how many games went
+pub fn purge_games(conn: &mut Connection, filter: &GameFilter) -> Result<usize, rusqlite::Error> {
+    let tx = conn.transaction()?;
+
+    for table in ["rejected_guess", "game_guess", "game_event"] {
+        tx.execute(
+            &format!(
+                "DELETE FROM {table} WHERE game_id IN (SELECT game_id FROM game_history WHERE {})",
+                GameFilter::WHERE
+            ),
+            filter.params(),
+        )?;
+    }
+    let mut purged = 0;
+    for table in ["game", "game_archive"] {
+        purged += tx.execute(
+            &format!("DELETE FROM {table} WHERE {}", GameFilter::WHERE),
+            filter.params(),
+        )?;
+    }
+
+    tx.commit()?;
+
+    Ok(purged)
+}
+
+/// Reads a game state column, which has to be parsed here as the core crate knows nothing of sqlite
+pub fn get_state(row: &Row, index: usize) -> Result<GameState, rusqlite::Error> {
+    row.get::<_, String>(index)?
+        .parse()
+        .map_err(|error: String| {
+            rusqlite::Error::FromSqlConversionFailure(index, Type::Text, error.into())
+        })
+}
+
+macro_rules! to_sql_as_str {
+    ($($name:ident),*) => {
+        $(
+            impl ToSql for $name {
+                fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                    Ok(ToSqlOutput::from(self.as_str()))
+                }
+            }
+        )*
+    };
+}
+
+to_sql_as_str!(GameId, GroupId, ClientId, Word, Guess);
+
+pub fn get_mode(row: &Row, index: usize) -> Result<&'static dyn GameMode, rusqlite::Error> {
+    let name = row.get::<_, String>(index)?;
+
+    game::modes().get(&name).ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(
+            index,
+            Type::Text,
+            format!("unknown game mode '{name}'").into(),
+        )
+    })
+}
+
+/// The mode state stored at `index`, or for games stored without one, a single board of `word`
+pub fn get_mode_state(row: &Row, index: usize, word: &str) -> Result<ModeState, rusqlite::Error> {
+    match row.get::<_, Option<String>>(index)? {
+        Some(json) => serde_json::from_str(&json).map_err(|error| {
+            rusqlite::Error::FromSqlConversionFailure(index, Type::Text, error.into())
+        }),
+        None => Ok(ModeState::single(word)),
+    }
+}
+
+pub fn setting(conn: &Connection, name: &str) -> Result<Option<String>, rusqlite::Error> {
+    conn.query_row("SELECT value FROM setting WHERE name = ?1", [name], |row| {
+        row.get(0)
+    })
+    .optional()
+}
+
+/// Sets, or with `None` clears, a setting shared by everything using the database
+pub fn set_setting(
+    conn: &Connection,
+    name: &str,
+    value: Option<&str>,
+) -> Result<(), rusqlite::Error> {
+    match value {
+        Some(value) => conn.execute(
+            "INSERT INTO setting (name, value) VALUES (?1, ?2)
+            ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+            (name, value),
+        )?,
+        None => conn.execute("DELETE FROM setting WHERE name = ?1", [name])?,
+    };
+
+    Ok(())
+}
+
+/// Records an administrative action in the audit log, `detail` being any JSON describing it
+pub fn audit(
+    conn: &Connection,
+    action: &str,
+    subject: &str,
+    detail: &serde_json::Value,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO audit_log (action, subject, detail) VALUES (?1, ?2, ?3)",
+        (action, subject, detail.to_string()),
+    )?;
+
+    Ok(())
+}
+
+/// Records a guess that was refused, and whether it still used up one of the game's goes
+pub fn record_rejection(
+    conn: &Connection,
+    game_id: &GameId,
+    guess: &str,
+    reason: Rejection,
+    counted: bool,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO rejected_guess (game_id, guess, reason, counted) VALUES (?1, ?2, ?3, ?4)",
+        (game_id, guess, reason.as_str(), counted),
+    )?;
+
+    Ok(())
+}
+
+/// Appends a guess to the guess log, as made by whoever's playing the game. `outcome` is
+/// `accepted`, or why it was rejected.
+pub fn log_guess(
+    conn: &Connection,
+    game_id: &GameId,
+    guess: &str,
+    outcome: &str,
+    counted: bool,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO guess_log (game_id, client, guess, outcome, counted)
+        SELECT game_id, client, ?2, ?3, ?4 FROM game WHERE game_id = ?1",
+        (game_id, guess, outcome, counted),
+    )?;
+
+    Ok(())
+}
+
+/// Appends events to a game's history, in order
+pub fn append_events(
+    conn: &Connection,
+    game_id: &str,
+    events: &[GameEvent],
+) -> Result<(), rusqlite::Error> {
+    let mut statement = conn.prepare(
+        "INSERT INTO game_event (game_id, kind, data, created_at)
+        VALUES (?1, ?2, ?3, datetime(?4, 'unixepoch'))",
+    )?;
+
+    for event in events {
+        let data = serde_json::to_string(event)
+            .map_err(|error| rusqlite::Error::ToSqlConversionFailure(error.into()))?;
+        statement.execute((game_id, event.kind(), data, game::now()))?;
+    }
+
+    Ok(())
+}
+
+/// A game's history, oldest first
+pub fn events(conn: &Connection, game_id: &str) -> Result<Vec<GameEvent>, rusqlite::Error> {
+    conn.prepare("SELECT data FROM game_event WHERE game_id = ?1 ORDER BY event_id")?
+        .query_map([game_id], |row| {
+            serde_json::from_str(&row.get::<_, String>(0)?).map_err(|error| {
+                rusqlite::Error::FromSqlConversionFailure(0, Type::Text, error.into())
+            })
+        })?
+        .collect()
+}
+
+/// Whether the error is a violation of a UNIQUE (or PRIMARY KEY) constraint
+pub fn is_unique_violation(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(failure, _)
+            if failure.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE
+                || failure.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_PRIMARYKEY
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{
+        events, is_unique_violation, migrate_to, repairs, schema_version, LATEST_VERSION,
+        MIGRATIONS,
+    };
+    use rusqlite::Connection;
+
+    #[test]
+    fn migration_removes_duplicate_games() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO game (game_id, client, word) VALUES ('a', 'first', 'crane');
+            INSERT INTO game (game_id, client, word) VALUES ('a', 'second', 'crane');
+            INSERT INTO game (game_id, client, word) VALUES ('b', 'third', 'crane');",
+        )
+        .unwrap();
+
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+
+        let clients = conn
+            .prepare("SELECT client FROM game ORDER BY game_id")
+            .unwrap()
+            .query_map([], |row| row.get::<_, String>(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(clients, vec!["first", "third"]);
+
+        // every surviving game's history starts from a snapshot of its row
+        let game = crate::events::project(&events(&conn, "a").unwrap()).unwrap();
+        assert_eq!(game.client.as_str(), "first");
+        assert_eq!(game.mode_state.words(), vec!["crane"]);
+
+        let duplicate = conn.execute(
+            "INSERT INTO game (game_id, client, word) VALUES ('a', 'fourth', 'crane')",
+            (),
+        );
+        assert!(is_unique_violation(&duplicate.unwrap_err()));
+    }
+
+    #[test]
+    fn migration_repairs_legacy_values() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO game (game_id, client, word, goes, solved) VALUES ('a', 'x', 'crane', NULL, 1);
+            INSERT INTO game (game_id, client, word, goes, solved) VALUES ('b', 'x', 'crane', 2, NULL);
+            INSERT INTO game (game_id, client, word, goes, solved) VALUES ('c', 'x', 'crane', 3, 0);",
+        )
+        .unwrap();
+
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+
+        let repaired = repairs(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|x| (x.game_id, x.field, x.original, x.repaired))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            repaired,
+            vec![
+                ("a".into(), "goes".into(), None, "0".into()),
+                ("b".into(), "solved".into(), None, "0".into()),
+            ]
+        );
+
+        let null_goes = conn.execute(
+            "INSERT INTO game (game_id, client, word, goes) VALUES ('d', 'x', 'crane', NULL)",
+            (),
+        );
+        assert!(null_goes.is_err());
+    }
+
+    #[test]
+    fn migrates_up_to_the_target_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        assert_eq!(migrate_to(&mut conn, 2).unwrap(), 2);
+        assert_eq!(schema_version(&conn).unwrap(), 2);
+
+        assert_eq!(migrate_to(&mut conn, 1).unwrap(), 0);
+        assert_eq!(schema_version(&conn).unwrap(), 2);
+
+        assert_eq!(
+            migrate_to(&mut conn, LATEST_VERSION).unwrap(),
+            LATEST_VERSION - 2
+        );
+        assert_eq!(schema_version(&conn).unwrap(), LATEST_VERSION);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::{fs, io};
+pub use wordle_core::dictionary::{
+    answers, guesses, is_valid_guess, normalize, replace, WordList, LANGUAGE, LENGTH, LENGTHS,
+};
+
+static ANSWERS_VERSION: OnceLock<String> = OnceLock::new();
+
+/// Identifies the answer list, changing whenever any answer does, for caching what's worked out
+/// from it
+pub fn answers_version() -> &'static str {
+    ANSWERS_VERSION.get_or_init(|| {
+        let mut crc = crc32fast::Hasher::new();
+        for answer in answers() {
+            crc.update(answer.as_bytes());
+            crc.update(b"\n");
+        }
+
+        format!("{:08x}", crc.finalize())
+    })
+}
+
+/// The words of every language and length games can be played in: the bundled ones, along with any
+/// the operator supplied
+#[derive(Clone, Debug)]
+pub struct WordLists(Arc<BTreeMap<(String, usize), WordList>>);
+
+impl Default for WordLists {
+    fn default() -> WordLists {
+        WordLists(Arc::new(BTreeMap::from([(
+            (LANGUAGE.to_string(), LENGTH),
+            WordList::standard(),
+        )])))
+    }
+}
+
+impl WordLists {
+    /// The bundled words, along with those of any other length `dir` has an `answers-<length>.txt`
+    /// for, one word a line. Anything in `guesses-<length>.txt` is accepted as a guess besides the
+    /// answers. Other languages than English are read the same way from a directory named by their
+    /// language tag, e.g. `de/answers-5.txt`.
+    pub fn load(dir: &Path) -> io::Result<WordLists> {
+        let mut lists = BTreeMap::from([((LANGUAGE.to_string(), LENGTH), WordList::standard())]);
+        read_all(&mut lists, dir, LENGTHS.filter(|x| *x != LENGTH))?;
+
+        Ok(WordLists(Arc::new(lists)))
+    }
+
+    /// These words, but for those `dir` has lists of its own for, read the same way as by `load`,
+    /// the five-letter English ones included. A `dir` that doesn't exist changes nothing.
+    pub fn overlay(&self, dir: &Path) -> io::Result<WordLists> {
+        if !dir.is_dir() {
+            return Ok(self.clone());
+        }
+
+        let mut lists = (*self.0).clone();
+        read_all(&mut lists, dir, LENGTHS)?;
+
+        Ok(WordLists(Arc::new(lists)))
+    }
+
+    pub fn get(&self, language: &str, length: usize) -> Option<&WordList> {
+        self.0.get(&(language.to_string(), length))
+    }
+}
+
+/// The primary subtag of a language tag, in lowercase, e.g. `pt` for `pt-BR`
+pub fn language(tag: &str) -> String {
+    tag.split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Whether a directory could be named for a language, being a two or three letter subtag
+fn is_language(name: &str) -> bool {
+    (2..=3).contains(&name.len()) && name.chars().all(|x| x.is_ascii_lowercase())
+}
+
+/// Reads the English words of each of `lengths` that `dir` has, and those of every length in the
+/// directories named for other languages, over whichever `lists` already has
+fn read_all(
+    lists: &mut BTreeMap<(String, usize), WordList>,
+    dir: &Path,
+    lengths: impl Iterator<Item = usize>,
+) -> io::Result<()> {
+    for length in lengths {
+        if let Some(list) = read(dir, length)? {
+            lists.insert((LANGUAGE.to_string(), length), list);
+        }
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let language = entry.file_name().to_string_lossy().into_owned();
+        if !entry.file_type()?.is_dir() || language == LANGUAGE || !is_language(&language) {
+            continue;
+        }
+
+        for length in LENGTHS {
+            if let Some(list) = read(&entry.path(), length)? {
+                lists.insert((language.clone(), length), list);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The words of `length` letters that `dir` has, if it has any answers of that length
+fn read(dir: &Path, length: usize) -> io::Result<Option<WordList>> {
+    let answers = match fs::read_to_string(dir.join(format!("answers-{length}.txt"))) {
+        Ok(answers) => answers,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let guesses = match fs::read_to_string(dir.join(format!("guesses-{length}.txt"))) {
+        Ok(guesses) => guesses,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(error),
+    };
+
+    let list = WordList::new(length, answers.lines(), guesses.lines());
+    Ok((!list.answers().is_empty()).then_some(list))
+}
@@ -0,0 +1,98 @@
+use crate::dictionary;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Every answer's rating, worked out together the first time one is needed
+static RATINGS: OnceLock<HashMap<&'static str, u32>> = OnceLock::new();
+
+/// How hard a game's answer is, given once the game is over
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Difficulty {
+    /// Out of 100, the share of answers that are easier than this one
+    pub rating: u32,
+    /// The average goes taken to win a game with this answer, across every client
+    pub average_goes: Option<f64>,
+}
+
+/// The answer's rating out of 100, or none for words that aren't in the answer list
+pub fn rating(word: &str) -> Option<u32> {
+    RATINGS
+        .get_or_init(|| ratings(dictionary::answers()))
+        .get(word)
+        .copied()
+}
+
+/// Ranks the answers by how many other answers they differ from by one letter, the traps that use
+/// up goes one letter at a time, then by their repeated letters and how rare their letters are
+fn ratings(answers: &[String]) -> HashMap<&str, u32> {
+    let mut wildcards = HashMap::<(usize, String), usize>::new();
+    let mut letters = HashMap::<char, usize>::new();
+    for answer in answers {
+        for i in 0..answer.len() {
+            *wildcards.entry((i, wildcard(answer, i))).or_default() += 1;
+        }
+        for letter in answer.chars().collect::<HashSet<_>>() {
+            *letters.entry(letter).or_default() += 1;
+        }
+    }
+
+    let scores = answers
+        .iter()
+        .map(|answer| {
+            let neighbours: usize = (0..answer.len())
+                .map(|i| wildcards[&(i, wildcard(answer, i))] - 1)
+                .sum();
+
+            let distinct = answer.chars().collect::<HashSet<_>>();
+            let repeated = answer.chars().count() - distinct.len();
+            let commonness = distinct
+                .iter()
+                .map(|x| letters[x] as f64 / answers.len() as f64)
+                .sum::<f64>()
+                / distinct.len().max(1) as f64;
+
+            (
+                answer.as_str(),
+                neighbours as f64 + repeated as f64 + 1.0 - commonness,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    scores
+        .iter()
+        .map(|(answer, score)| {
+            let easier = scores.iter().filter(|(_, x)| x < score).count();
+
+            (*answer, (easier * 100 / scores.len()) as u32)
+        })
+        .collect()
+}
+
+/// The word with the letter at `index` blanked out
+fn wildcard(word: &str, index: usize) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, x)| if i == index { '_' } else { x })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::difficulty::ratings;
+
+    #[test]
+    fn words_with_many_neighbours_rate_hardest() {
+        let answers = [
+            "light", "might", "night", "sight", "tight", "crane", "plumb",
+        ]
+        .map(String::from)
+        .to_vec();
+        let ratings = ratings(&answers);
+
+        assert!(ratings["sight"] > ratings["crane"]);
+        assert!(ratings["sight"] > ratings["plumb"]);
+        assert_eq!(ratings.values().min(), Some(&0));
+        assert!(ratings.values().all(|x| *x < 100));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::admin::bearer_token;
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::game::{ClientId, GameId};
+use crate::json_response;
+use crate::repository::StoredGame;
+use crate::service::{CreateGame, Service, SubmitGuess};
+use crate::share;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Discord refuses messages with more characters than this
+const MESSAGE_LIMIT: usize = 2000;
+
+const PING: u8 = 1;
+const APPLICATION_COMMAND: u8 = 2;
+const PONG: u8 = 1;
+const CHANNEL_MESSAGE: u8 = 4;
+/// Only the user who ran the command sees the reply
+const EPHEMERAL: u32 = 1 << 6;
+
+#[derive(Serialize)]
+struct Message {
+    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flags: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct InteractionResponse {
+    #[serde(rename = "type")]
+    kind: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Interaction {
+    #[serde(rename = "type")]
+    kind: u8,
+    data: Option<Command>,
+    /// Set for commands run in a server
+    member: Option<Member>,
+    /// Set for commands run in a direct message
+    user: Option<User>,
+}
+
+#[derive(Deserialize)]
+struct Command {
+    name: String,
+    #[serde(default)]
+    options: Vec<CommandOption>,
+}
+
+#[derive(Deserialize)]
+struct CommandOption {
+    name: String,
+    value: Value,
+}
+
+#[derive(Deserialize)]
+struct Member {
+    user: User,
+}
+
+#[derive(Deserialize)]
+struct User {
+    id: String,
+}
+
+/// A game's result as a Discord message, ready to be posted as it is
+pub fn handle_result(service: &Service, game_id: &str) -> Result<Response, ApiError> {
+    let game = service.find_game(&GameId::parse(game_id)?)?;
+
+    json_response(&Message {
+        content: message(&game, &service.display_name(&game.client)?),
+        flags: None,
+    })
+}
+
+/// Answers a Discord interaction payload for the `new [mode]`, `guess <game> <word>` and
+/// `result <game>` slash commands, playing as the user who ran them. Discord's own request
+/// signatures aren't checked, so this is meant to be called by a bot relaying interactions it has
+/// already verified, with the secret the server is configured with, rather than registered as the
+/// application's interactions endpoint directly.
+pub fn handle_interaction(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    authorize(service.config(), request)?;
+    let interaction: Interaction = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    if interaction.kind == PING {
+        return json_response(&InteractionResponse {
+            kind: PONG,
+            data: None,
+        });
+    }
+
+    let command = match (interaction.kind, &interaction.data) {
+        (APPLICATION_COMMAND, Some(command)) => command,
+        _ => {
+            return Err(ApiError::InvalidBody(String::from(
+                "only pings and application commands are supported",
+            )))
+        }
+    };
+
+    let reply = match run(service, &interaction, command) {
+        Ok(content) => Message {
+            content,
+            flags: None,
+        },
+        // internal failures are the server's problem, not something to tell the user about
+        Err(error) if error.status_code() >= 500 => return Err(error),
+        Err(error) => Message {
+            content: error.to_string(),
+            flags: Some(EPHEMERAL),
+        },
+    };
+
+    json_response(&InteractionResponse {
+        kind: CHANNEL_MESSAGE,
+        data: Some(reply),
+    })
+}
+
+fn authorize(config: &Config, request: &Request) -> Result<(), ApiError> {
+    match (&config.discord_secret, bearer_token(request)) {
+        (Some(expected), Some(provided)) if expected == provided => Ok(()),
+        _ => Err(ApiError::Unauthorized),
+    }
+}
+
+impl Interaction {
+    /// The client the user who ran the command plays as
+    fn client(&self) -> Result<ClientId, ApiError> {
+        let user = self
+            .member
+            .as_ref()
+            .map(|x| &x.user)
+            .or(self.user.as_ref())
+            .ok_or_else(|| ApiError::InvalidBody(String::from("the interaction has no user")))?;
+
+        ClientId::parse(&format!("discord-{}", user.id))
+    }
+}
+
+fn run(
+    service: &Service,
+    interaction: &Interaction,
+    command: &Command,
+) -> Result<String, ApiError> {
+    let option = |name: &str| {
+        command
+            .options
+            .iter()
+            .find(|x| x.name == name)
+            .and_then(|x| x.value.as_str())
+    };
+    let required = |name: &str| {
+        option(name).ok_or_else(|| ApiError::InvalidParam {
+            name: name.to_string(),
+            value: String::new(),
+        })
+    };
+
+    match command.name.as_str() {
+        "new" => {
+            let client = interaction.client()?;
+            service.ensure_registered(&client)?;
+            let game = service.create_game(CreateGame {
+                client,
+                invalid_guesses_count: None,
+                mode: option("mode").map(str::to_string),
+                group: None,
+                daily: false,
+                length: None,
+                language: None,
+                challenge: None,
+            })?;
+
+            Ok(format!(
+                "Started a {} game, play it with `/guess game:{} word:<word>`",
+                game.options.mode, game.game_id
+            ))
+        }
+        "guess" => {
+            let game_id = GameId::parse(required("game")?)?;
+            // users only ever play their own games, whoever's game id they were shown
+            if service.find_game(&game_id)?.client != interaction.client()? {
+                return Err(ApiError::Unauthorized);
+            }
+            service.submit_guess(SubmitGuess {
+                game_id: game_id.clone(),
+                guess: required("word")?.to_string(),
+            })?;
+
+            let game = service.find_game(&game_id)?;
+            Ok(message(&game, &service.display_name(&game.client)?))
+        }
+        "result" => {
+            let game = service.find_game(&GameId::parse(required("game")?)?)?;
+            Ok(message(&game, &service.display_name(&game.client)?))
+        }
+        name => Err(ApiError::InvalidParam {
+            name: String::from("command"),
+            value: name.to_string(),
+        }),
+    }
+}
+
+/// The game's outcome and emoji grid in Discord markdown, with the answer behind a spoiler once the
+/// game is over. The grid is cut short rather than going over Discord's limit.
+fn message(game: &StoredGame, player: &str) -> String {
+    let mut content = format!(
+        "**Wordle-API** · {player} · {} · {}",
+        game.mode.name(),
+        share::outcome(game)
+    );
+    let footer = match game.state.is_finished() {
+        true => format!(
+            "\n||{}||",
+            game.mode_state.words().join(", ").to_uppercase()
+        ),
+        false => String::new(),
+    };
+
+    let grid = share::emoji_grid(game);
+    for (i, line) in grid.iter().enumerate() {
+        let remaining = grid.len() - i;
+        let elision = format!("\n… and {remaining} more");
+
+        if char_count(&content) + char_count(line) + 1 + char_count(&elision) + char_count(&footer)
+            > MESSAGE_LIMIT
+        {
+            content.push_str(&elision);
+            break;
+        }
+
+        content.push('\n');
+        content.push_str(line);
+    }
+
+    content.push_str(&footer);
+
+    content
+}
+
+fn char_count(value: &str) -> usize {
+    value.chars().count()
+}
@@ -0,0 +1,181 @@
+use crate::i18n::{self, Locale};
+use rouille::Response;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Every way a request can fail, so that each failure maps onto exactly one status code:
+///
+/// | error               | status |
+/// |---------------------|--------|
+/// | `GameNotFound`      | 404    |
+/// | `ProfileNotFound`   | 404    |
+/// | `GroupNotFound`     | 404    |
+/// | `ClubNotFound`      | 404    |
+/// | `ShareNotFound`     | 404    |
+/// | `ChallengeNotFound` | 404    |
+/// | `ClientNotFound`    | 404    |
+/// | `NotFound`          | 404    |
+/// | `InvalidGuess`      | 400    |
+/// | `InvalidParam`      | 400    |
+/// | `InvalidBody`       | 400    |
+/// | `Unauthorized`      | 401    |
+/// | `GameFinished`      | 409    |
+/// | `GameExists`        | 409    |
+/// | `ClientRegistered`  | 409    |
+/// | `RuleViolation`     | 422    |
+/// | `RateLimited`       | 429    |
+/// | `ReadOnly`          | 503    |
+/// | `Storage`           | 500    |
+/// | `Serialization`     | 500    |
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("game '{0}' does not exist")]
+    GameNotFound(String),
+
+    #[error("'{0}' has no public profile")]
+    ProfileNotFound(String),
+
+    #[error("group '{0}' does not exist")]
+    GroupNotFound(String),
+
+    #[error("club '{0}' does not exist")]
+    ClubNotFound(String),
+
+    #[error("share link '{0}' does not exist")]
+    ShareNotFound(String),
+
+    #[error("challenge '{0}' does not exist")]
+    ChallengeNotFound(String),
+
+    #[error("client '{0}' is not registered")]
+    ClientNotFound(String),
+
+    /// No endpoint answers at the path
+    #[error("nothing is served at '{0}'")]
+    NotFound(String),
+
+    #[error("'{0}' is not a valid guess")]
+    InvalidGuess(String),
+
+    #[error("'{value}' is not a valid value for '{name}'")]
+    InvalidParam { name: String, value: String },
+
+    #[error("invalid request body: {0}")]
+    InvalidBody(String),
+
+    #[error("missing or invalid token")]
+    Unauthorized,
+
+    #[error("game '{0}' is already finished")]
+    GameFinished(String),
+
+    #[error("game '{0}' already exists")]
+    GameExists(String),
+
+    #[error("client '{0}' is already registered")]
+    ClientRegistered(String),
+
+    #[error("guess breaks the rules of this mode: {0}")]
+    RuleViolation(String),
+
+    #[error("too many requests, try again in {0} seconds")]
+    RateLimited(u64),
+
+    #[error("{0}")]
+    ReadOnly(String),
+
+    #[error("storage error: {0}")]
+    Storage(#[from] rusqlite::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: &'static str,
+    error: String,
+    request_id: String,
+}
+
+impl ApiError {
+    pub fn status_code(&self) -> u16 {
+        match self {
+            ApiError::GameNotFound(_)
+            | ApiError::ProfileNotFound(_)
+            | ApiError::GroupNotFound(_)
+            | ApiError::ClubNotFound(_)
+            | ApiError::ShareNotFound(_)
+            | ApiError::ChallengeNotFound(_)
+            | ApiError::ClientNotFound(_)
+            | ApiError::NotFound(_) => 404,
+            ApiError::InvalidGuess(_)
+            | ApiError::InvalidParam { .. }
+            | ApiError::InvalidBody(_) => 400,
+            ApiError::Unauthorized => 401,
+            ApiError::GameFinished(_) | ApiError::GameExists(_) | ApiError::ClientRegistered(_) => {
+                409
+            }
+            ApiError::RuleViolation(_) => 422,
+            ApiError::RateLimited(_) => 429,
+            ApiError::Storage(_) | ApiError::Serialization(_) => 500,
+            ApiError::ReadOnly(_) => 503,
+        }
+    }
+
+    /// What the error is to programs, the same whatever language the message is in
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::GameNotFound(_) => "game_not_found",
+            ApiError::ProfileNotFound(_) => "profile_not_found",
+            ApiError::GroupNotFound(_) => "group_not_found",
+            ApiError::ClubNotFound(_) => "club_not_found",
+            ApiError::ShareNotFound(_) => "share_not_found",
+            ApiError::ChallengeNotFound(_) => "challenge_not_found",
+            ApiError::ClientNotFound(_) => "client_not_found",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::InvalidGuess(_) => "invalid_guess",
+            ApiError::InvalidParam { .. } => "invalid_param",
+            ApiError::InvalidBody(_) => "invalid_body",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::GameFinished(_) => "game_finished",
+            ApiError::GameExists(_) => "game_exists",
+            ApiError::ClientRegistered(_) => "client_registered",
+            ApiError::RuleViolation(_) => "rule_violation",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::ReadOnly(_) => "read_only",
+            ApiError::Storage(_) | ApiError::Serialization(_) => "internal",
+        }
+    }
+
+    /// Internal failures only expose a generic message to the client, the underlying cause is
+    /// logged against the request id instead
+    pub fn into_response(self, request_id: &str, locale: Locale) -> Response {
+        let status_code = self.status_code();
+        let retry_after = match self {
+            ApiError::RateLimited(seconds) => Some(seconds),
+            _ => None,
+        };
+        if let ApiError::Storage(_) | ApiError::Serialization(_) = self {
+            eprintln!("[{request_id}] {self}");
+        }
+
+        let body = ErrorBody {
+            code: self.code(),
+            error: i18n::error_message(&self, locale),
+            request_id: request_id.to_string(),
+        };
+
+        // the error body contains nothing that can fail to serialize
+        let response =
+            Response::from_data(crate::JSON, serde_json::to_string_pretty(&body).unwrap())
+                .with_status_code(status_code)
+                .with_additional_header("X-Request-Id", request_id.to_string())
+                .with_additional_header("Content-Language", locale.tag());
+
+        match retry_after {
+            Some(seconds) => response.with_additional_header("Retry-After", seconds.to_string()),
+            None => response,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::db;
+use crate::game::{self, ClientId, GameOptions, GameState, HintLevel, ModeState, Rejection};
+use crate::repository::StoredGame;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened to a game. Every game is stored as the events it has seen, oldest first,
+/// and its row is only ever what replaying them produces, so a row can always be rebuilt, or
+/// recomputed under changed rules, from its events.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GameEvent {
+    /// The game was created, or for games from before events were recorded, the row as it stood
+    Created {
+        client: String,
+        word: String,
+        goes: usize,
+        state: GameState,
+        options: GameOptions,
+        /// Absent for games that only ever had a single board, of `word`
+        mode_state: Option<ModeState>,
+    },
+    Guessed {
+        guess: String,
+    },
+    Rejected {
+        guess: String,
+        reason: Rejection,
+        counted: bool,
+    },
+    /// A hint was given, which counts against the stats
+    Hinted {
+        level: HintLevel,
+        penalty: usize,
+        /// Whether it used up a go, which hints from before they cost one didn't
+        #[serde(default)]
+        counted: bool,
+    },
+    Solved,
+    Forfeited,
+    /// The game's day passed before it was finished
+    Expired,
+}
+
+impl GameEvent {
+    pub fn created(game: &StoredGame) -> GameEvent {
+        GameEvent::Created {
+            client: game.client.to_string(),
+            word: game.mode_state.boards[0].word().to_string(),
+            goes: game.goes,
+            state: game.state,
+            options: game.options.clone(),
+            mode_state: Some(game.mode_state.clone()),
+        }
+    }
+
+    /// The name stored alongside each event, to query by without parsing it
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GameEvent::Created { .. } => "created",
+            GameEvent::Guessed { .. } => "guessed",
+            GameEvent::Rejected { .. } => "rejected",
+            GameEvent::Hinted { .. } => "hinted",
+            GameEvent::Solved => "solved",
+            GameEvent::Forfeited => "forfeited",
+            GameEvent::Expired => "expired",
+        }
+    }
+}
+
+/// Replays a game's events into its current state, failing if they don't start with its creation
+/// or name a mode that no longer exists
+pub fn project(events: &[GameEvent]) -> Result<StoredGame, String> {
+    let (first, rest) = events.split_first().ok_or("the game has no events")?;
+
+    let GameEvent::Created {
+        client,
+        word,
+        goes,
+        state,
+        options,
+        mode_state,
+    } = first
+    else {
+        return Err(format!("the game starts with a '{}' event", first.kind()));
+    };
+
+    let mut game = StoredGame {
+        client: ClientId::stored(client.clone()),
+        goes: *goes,
+        state: *state,
+        mode: game::modes()
+            .get(&options.mode)
+            .ok_or_else(|| format!("unknown game mode '{}'", options.mode))?,
+        options: options.clone(),
+        mode_state: mode_state
+            .clone()
+            .unwrap_or_else(|| ModeState::single(word)),
+    };
+
+    for event in rest {
+        match event {
+            GameEvent::Created { .. } => return Err(String::from("the game was created twice")),
+            GameEvent::Guessed { guess } => {
+                game.mode.evaluate(&mut game.mode_state, guess);
+                game.goes += 1;
+                let won = game.mode.is_won(&game.mode_state);
+                if let Some(state) = game.state.guess(won, game.options.out_of_goes(game.goes)) {
+                    game.state = state;
+                }
+            }
+            GameEvent::Rejected { counted, .. } => {
+                game.goes += usize::from(*counted);
+                if game.state == GameState::InProgress && game.options.out_of_goes(game.goes) {
+                    game.state = GameState::Lost;
+                }
+            }
+            GameEvent::Hinted { counted, .. } => game.goes += usize::from(*counted),
+            GameEvent::Solved => game.state = GameState::Won,
+            GameEvent::Forfeited => game.state = GameState::Forfeited,
+            GameEvent::Expired => game.state = GameState::Expired,
+        }
+    }
+
+    Ok(game)
+}
+
+/// A game whose row no longer matches what its events produce
+#[derive(Serialize, Debug)]
+pub struct Rebuilt {
+    pub game_id: String,
+    pub goes: (usize, usize),
+    pub state: (GameState, GameState),
+    pub word: (String, String),
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct RebuildReport {
+    pub checked: usize,
+    pub rebuilt: Vec<Rebuilt>,
+    /// Games whose events couldn't be replayed, left as they are
+    pub failed: Vec<(String, String)>,
+}
+
+/// Replays every game's events under the current rules, rewriting each row that differs from the
+/// result unless `dry_run`. Archived games are left as they were finished.
+pub fn rebuild(conn: &mut Connection, dry_run: bool) -> Result<RebuildReport, rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let mut report = RebuildReport::default();
+
+    let rows = tx
+        .prepare("SELECT game_id, goes, state, word, mode_state FROM game ORDER BY rowid")?
+        .query_map([], |row| {
+            let word: String = row.get(3)?;
+
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, usize>(1)?,
+                db::get_state(row, 2)?,
+                db::get_mode_state(row, 4, &word)?,
+                word,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (game_id, goes, state, mode_state, word) in rows {
+        report.checked += 1;
+
+        let game = match project(&db::events(&tx, &game_id)?) {
+            Ok(game) => game,
+            Err(reason) => {
+                report.failed.push((game_id, reason));
+                continue;
+            }
+        };
+
+        if (game.goes, game.state, &game.mode_state) == (goes, state, &mode_state) {
+            continue;
+        }
+
+        if !dry_run {
+            tx.execute(
+                "UPDATE game SET goes = ?1, solved = ?2, state = ?3, word = ?4, mode_state = ?5
+                WHERE game_id = ?6",
+                (
+                    game.goes,
+                    game.state == GameState::Won,
+                    game.state.as_str(),
+                    game.mode_state.boards[0].word(),
+                    serde_json::to_string(&game.mode_state)
+                        .map_err(|error| rusqlite::Error::ToSqlConversionFailure(error.into()))?,
+                    &game_id,
+                ),
+            )?;
+        }
+
+        report.rebuilt.push(Rebuilt {
+            game_id,
+            goes: (goes, game.goes),
+            state: (state, game.state),
+            word: (word, game.mode_state.boards[0].word().to_string()),
+        });
+    }
+
+    tx.commit()?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::{project, GameEvent};
+    use crate::game::{GameOptions, GameState, HintLevel, Rejection};
+
+    fn created(mode: &str) -> GameEvent {
+        GameEvent::Created {
+            client: String::from("bob"),
+            word: String::from("crane"),
+            goes: 0,
+            state: GameState::InProgress,
+            options: GameOptions {
+                invalid_guesses_count: true,
+                mode: mode.to_string(),
+                max_guesses: Some(3),
+                length: 5,
+                language: String::from("en"),
+            },
+            mode_state: None,
+        }
+    }
+
+    fn guessed(guess: &str) -> GameEvent {
+        GameEvent::Guessed {
+            guess: guess.to_string(),
+        }
+    }
+
+    #[test]
+    fn replays_guesses_and_counted_rejections() {
+        let game = project(&[
+            created("classic"),
+            guessed("moist"),
+            GameEvent::Rejected {
+                guess: String::from("xxxxx"),
+                reason: Rejection::InvalidWord,
+                counted: true,
+            },
+            guessed("crane"),
+            GameEvent::Solved,
+        ])
+        .unwrap();
+
+        assert_eq!((game.goes, game.state), (3, GameState::Won));
+        assert_eq!(game.mode_state.revealed.len(), 2);
+    }
+
+    #[test]
+    fn runs_out_of_goes_on_a_counted_rejection() {
+        let game = project(&[
+            created("classic"),
+            guessed("moist"),
+            guessed("pilot"),
+            GameEvent::Rejected {
+                guess: String::from("xxxxx"),
+                reason: Rejection::InvalidWord,
+                counted: true,
+            },
+        ])
+        .unwrap();
+
+        assert_eq!((game.goes, game.state), (3, GameState::Lost));
+    }
+
+    #[test]
+    fn hints_use_up_a_go_unless_they_were_given_for_free() {
+        let free: GameEvent =
+            serde_json::from_str(r#"{"type": "hinted", "level": "half", "penalty": 1}"#).unwrap();
+        let game = project(&[
+            created("classic"),
+            free,
+            GameEvent::Hinted {
+                level: HintLevel::Green,
+                penalty: 3,
+                counted: true,
+            },
+        ])
+        .unwrap();
+
+        assert_eq!((game.goes, game.state), (1, GameState::InProgress));
+    }
+
+    #[test]
+    fn refuses_histories_that_do_not_start_with_creation() {
+        assert!(project(&[]).is_err());
+        assert!(project(&[guessed("crane")]).is_err());
+        assert!(project(&[created("nonsense")]).is_err());
+    }
+}
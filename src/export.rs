@@ -0,0 +1,390 @@
+use crate::admin::authorize;
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::game::{self, ClientId, GameState};
+use crate::transfer::{self, Filter, Record, MATCHES};
+use crate::{db, stats};
+use rouille::{Request, Response, ResponseBody};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Read};
+
+/// How many rows are read at a time, so that a large export never has to be held in memory
+const PAGE: usize = 500;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Format {
+    /// A single JSON document, laid out like the CLI's dumps
+    Json,
+    /// One JSON record per line
+    Ndjson,
+    /// One finished game per line, with nothing to tell who played it or when beyond the day
+    Research,
+}
+
+/// A finished game as the research dataset has it
+#[derive(Serialize, Debug)]
+struct ResearchGame {
+    /// Numbered in the order players first appear in the export, so their games can be told
+    /// apart without them being identifiable, or linked across exports
+    player: String,
+    /// The day the game was started on, as `YYYY-MM-DD` in UTC
+    day: Option<String>,
+    mode: String,
+    state: GameState,
+    goes: usize,
+    /// One per board
+    answers: Vec<String>,
+    guesses: Vec<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Section {
+    Games,
+    RejectedGuesses,
+    Events,
+    Clients,
+}
+
+impl Section {
+    fn name(self) -> &'static str {
+        match self {
+            Section::Games => "games",
+            Section::RejectedGuesses => "rejected_guesses",
+            Section::Events => "events",
+            Section::Clients => "clients",
+        }
+    }
+
+    fn next(self) -> Option<Section> {
+        match self {
+            Section::Games => Some(Section::RejectedGuesses),
+            Section::RejectedGuesses => Some(Section::Events),
+            Section::Events => Some(Section::Clients),
+            Section::Clients => None,
+        }
+    }
+}
+
+/// Reads the export out a page at a time as the response is written
+struct Export {
+    conn: Connection,
+    filter: Filter,
+    format: Format,
+    /// None once everything's been read
+    section: Option<Section>,
+    /// The key of the last row read in the current section
+    after: i64,
+    /// Whether the current section has any records yet, for separating them in JSON
+    started: bool,
+    buffer: Vec<u8>,
+    position: usize,
+    /// The research dataset's players, by client
+    players: HashMap<String, usize>,
+}
+
+impl Export {
+    fn new(conn: Connection, filter: Filter, format: Format) -> Export {
+        let buffer = match format {
+            Format::Json => b"{\"games\":[".to_vec(),
+            Format::Ndjson | Format::Research => Vec::new(),
+        };
+
+        Export {
+            conn,
+            filter,
+            format,
+            section: Some(Section::Games),
+            after: 0,
+            started: false,
+            buffer,
+            position: 0,
+            players: HashMap::new(),
+        }
+    }
+
+    /// Buffers the next page of the current section, moving on to the next section once it's
+    /// run out
+    fn fill(&mut self) -> Result<(), ApiError> {
+        let Some(section) = self.section else {
+            return Ok(());
+        };
+        if self.format == Format::Research {
+            return self.fill_research();
+        }
+
+        let page = self.page(section)?;
+        if page.is_empty() {
+            self.section = section.next();
+            self.after = 0;
+            self.started = false;
+            if self.format == Format::Json {
+                match self.section {
+                    Some(next) => self
+                        .buffer
+                        .extend_from_slice(format!("],\"{}\":[", next.name()).as_bytes()),
+                    None => self.buffer.extend_from_slice(b"]}\n"),
+                }
+            }
+
+            return Ok(());
+        }
+
+        for (key, record) in page {
+            self.after = key;
+            match self.format {
+                Format::Json => {
+                    if self.started {
+                        self.buffer.push(b',');
+                    }
+                    match record {
+                        Record::Game(x) => serde_json::to_writer(&mut self.buffer, &x)?,
+                        Record::RejectedGuess(x) => serde_json::to_writer(&mut self.buffer, &x)?,
+                        Record::GameEvent(x) => serde_json::to_writer(&mut self.buffer, &x)?,
+                        Record::Client(x) => serde_json::to_writer(&mut self.buffer, &x)?,
+                    }
+                }
+                Format::Ndjson | Format::Research => {
+                    serde_json::to_writer(&mut self.buffer, &record)?;
+                    self.buffer.push(b'\n');
+                }
+            }
+            self.started = true;
+        }
+
+        Ok(())
+    }
+
+    /// Buffers the next page of finished games for the research dataset, which has nothing else
+    fn fill_research(&mut self) -> Result<(), ApiError> {
+        let (client, since, until) = self.filter.params();
+        let games = self
+            .conn
+            .prepare(&format!(
+                "SELECT seq, client, date(created_at), word, goes, state, mode, mode_state
+                FROM game_history
+                WHERE {MATCHES} AND state != 'in_progress' AND seq > ?4 ORDER BY seq LIMIT ?5"
+            ))?
+            .query_map((client, since, until, self.after, PAGE), |row| {
+                let word: String = row.get(3)?;
+                let mode_state = db::get_mode_state(row, 7, &word)?;
+
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    ResearchGame {
+                        player: String::new(),
+                        day: row.get(2)?,
+                        mode: row.get(6)?,
+                        state: db::get_state(row, 5)?,
+                        goes: row.get(4)?,
+                        answers: mode_state
+                            .boards
+                            .iter()
+                            .map(|x| x.word().to_string())
+                            .collect(),
+                        guesses: mode_state.revealed.into_iter().map(|x| x.0).collect(),
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if games.is_empty() {
+            self.section = None;
+        }
+        for (key, client, mut game) in games {
+            self.after = key;
+            let next = self.players.len() + 1;
+            game.player = format!("p{}", self.players.entry(client).or_insert(next));
+
+            serde_json::to_writer(&mut self.buffer, &game)?;
+            self.buffer.push(b'\n');
+        }
+
+        Ok(())
+    }
+
+    fn page(&self, section: Section) -> Result<Vec<(i64, Record)>, rusqlite::Error> {
+        let (conn, filter, after) = (&self.conn, &self.filter, self.after);
+
+        Ok(match section {
+            Section::Games => transfer::games_page(conn, filter, after, PAGE)?
+                .into_iter()
+                .map(|(key, x)| (key, Record::Game(x)))
+                .collect(),
+            Section::RejectedGuesses => transfer::rejections_page(conn, filter, after, PAGE)?
+                .into_iter()
+                .map(|(key, x)| (key, Record::RejectedGuess(x)))
+                .collect(),
+            Section::Events => transfer::events_page(conn, filter, after, PAGE)?
+                .into_iter()
+                .map(|(key, x)| (key, Record::GameEvent(x)))
+                .collect(),
+            // one row per client, so read all at once, keyed by how many came before
+            Section::Clients if after > 0 => Vec::new(),
+            Section::Clients => stats::filtered_client_stats(conn, filter)?
+                .into_iter()
+                .zip(1..)
+                .map(|(x, key)| (key, Record::Client(x)))
+                .collect(),
+        })
+    }
+}
+
+impl Read for Export {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if self.section.is_none() {
+                return Ok(0);
+            }
+            self.buffer.clear();
+            self.position = 0;
+            self.fill().map_err(io::Error::other)?;
+        }
+
+        let read = buf.len().min(self.buffer.len() - self.position);
+        buf[..read].copy_from_slice(&self.buffer[self.position..self.position + read]);
+        self.position += read;
+
+        Ok(read)
+    }
+}
+
+/// `GET /export[?format=ndjson|json|research][&client=<client>][&since=<YYYY-MM-DD>][&until=<YYYY-MM-DD>]`
+/// streams every game, hidden words included, along with their rejected guesses, their events and
+/// each client's aggregates, for the operator. `research` is instead a dataset that can be shared:
+/// each finished game's guesses and outcome, without clients or times.
+pub fn handle(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let format = match request.get_param("format").as_deref() {
+        None | Some("ndjson") => Format::Ndjson,
+        Some("json") => Format::Json,
+        Some("research") => Format::Research,
+        Some(format) => {
+            return Err(ApiError::InvalidParam {
+                name: String::from("format"),
+                value: format.to_string(),
+            })
+        }
+    };
+    let filter = filter(request)?;
+
+    let content_type = match format {
+        Format::Json => crate::JSON,
+        Format::Ndjson | Format::Research => "application/x-ndjson",
+    };
+    let export = Export::new(config.connection()?, filter, format);
+
+    Ok(Response {
+        status_code: 200,
+        headers: vec![("Content-Type".into(), content_type.into())],
+        data: ResponseBody::from_reader(export),
+        upgrade: None,
+    })
+}
+
+/// The `client`, `since` and `until` params of an export
+pub fn filter(request: &Request) -> Result<Filter, ApiError> {
+    let day = |name: &str| match request.get_param(name) {
+        Some(day) if game::parse_date(&day).is_none() => Err(ApiError::InvalidParam {
+            name: name.to_string(),
+            value: day,
+        }),
+        day => Ok(day),
+    };
+
+    Ok(Filter {
+        client: match request.get_param("client") {
+            Some(client) => Some(ClientId::parse(&client)?.as_str().to_string()),
+            None => None,
+        },
+        since: day("since")?,
+        until: day("until")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{migrate_to, LATEST_VERSION};
+    use crate::export::{Export, Format};
+    use crate::transfer::Filter;
+    use rusqlite::Connection;
+    use serde_json::{json, Value};
+    use std::io::Read;
+
+    fn export(filter: Filter, format: Format) -> String {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+        // more than a page of games
+        conn.execute_batch(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 501)
+            INSERT INTO game (game_id, client, word, goes, solved, state, created_at)
+                SELECT 'g' || i, 'c' || (i % 2), 'crane', 1, 1, 'won', '2026-10-14 12:00:00' FROM n;
+            INSERT INTO rejected_guess (game_id, guess, reason, counted)
+                VALUES ('g1', 'xxxxx', 'invalid_word', 0);",
+        )
+        .unwrap();
+
+        let mut output = String::new();
+        Export::new(conn, filter, format)
+            .read_to_string(&mut output)
+            .unwrap();
+
+        output
+    }
+
+    #[test]
+    fn streams_every_page() {
+        let lines = export(Filter::default(), Format::Ndjson)
+            .lines()
+            .map(|x| serde_json::from_str::<Value>(x).unwrap()["type"].clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.iter().filter(|x| *x == "game").count(), 501);
+        assert_eq!(lines.iter().filter(|x| *x == "rejected_guess").count(), 1);
+        assert_eq!(lines.iter().filter(|x| *x == "client").count(), 2);
+    }
+
+    #[test]
+    fn filters_the_json_document() {
+        let filter = Filter {
+            client: Some(String::from("c1")),
+            since: Some(String::from("2026-10-14")),
+            until: None,
+        };
+        let dump: Value = serde_json::from_str(&export(filter, Format::Json)).unwrap();
+
+        assert_eq!(dump["games"].as_array().unwrap().len(), 251);
+        assert_eq!(dump["rejected_guesses"].as_array().unwrap().len(), 1);
+        assert_eq!(dump["clients"][0]["num_games"], 251);
+
+        let filter = Filter {
+            until: Some(String::from("2026-10-13")),
+            ..Filter::default()
+        };
+        let dump: Value = serde_json::from_str(&export(filter, Format::Json)).unwrap();
+        assert_eq!(dump["games"], Value::Array(Vec::new()));
+        assert_eq!(dump["clients"], Value::Array(Vec::new()));
+    }
+
+    #[test]
+    fn research_datasets_number_their_players() {
+        let output = export(Filter::default(), Format::Research);
+        let games = output
+            .lines()
+            .map(|x| serde_json::from_str::<Value>(x).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(games.len(), 501);
+        assert_eq!(
+            (&games[0]["player"], &games[1]["player"]),
+            (&json!("p1"), &json!("p2"))
+        );
+        assert_eq!(games[2]["player"], "p1");
+        assert_eq!(games[0]["day"], "2026-10-14");
+        assert_eq!(games[0]["answers"], json!(["crane"]));
+        assert!(!output.contains("\"c1\"") && !output.contains("12:00"));
+    }
+}
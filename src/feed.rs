@@ -0,0 +1,155 @@
+use crate::base_url;
+use crate::error::ApiError;
+use crate::service::Service;
+use crate::share::escape;
+use crate::stats::{self, DaySummary};
+use rouille::{Request, Response};
+use std::fmt::Write;
+
+/// How many days the feeds go back
+const DAYS: usize = 30;
+
+/// `GET /feed/atom` and `GET /feed/rss` publish a summary of each finished day's games. The
+/// summaries are only totals, no answer ever appears in them.
+pub fn handle(service: &Service, request: &Request, format: &str) -> Result<Response, ApiError> {
+    let conn = service.config().connection()?;
+    let summaries = stats::daily_summaries(&conn, DAYS)?;
+    let base = base_url(service.config(), request);
+
+    match format {
+        "atom" => Ok(Response::from_data(
+            "application/atom+xml; charset=utf-8",
+            atom(&base, &summaries),
+        )),
+        "rss" => Ok(Response::from_data(
+            "application/rss+xml; charset=utf-8",
+            rss(&base, &summaries),
+        )),
+        format => Err(ApiError::InvalidParam {
+            name: String::from("format"),
+            value: format.to_string(),
+        }),
+    }
+    .map(|x| x.with_public_cache(60 * 60))
+}
+
+fn atom(base: &str, summaries: &[DaySummary]) -> String {
+    let updated = summaries
+        .first()
+        .map_or_else(|| String::from("1970-01-01"), |x| x.day.clone());
+
+    let mut feed = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<title>Wordle-API daily results</title>
+<id>{base}/feed/atom</id>
+<link rel="self" href="{base}/feed/atom"/>
+<link href="{base}/"/>
+<updated>{updated}T23:59:59Z</updated>
+<author><name>Wordle-API</name></author>
+"#
+    );
+
+    for summary in summaries {
+        let _ = write!(
+            feed,
+            r#"<entry>
+<title>{title}</title>
+<id>{base}/feed/atom#{day}</id>
+<updated>{day}T23:59:59Z</updated>
+<content type="html">{content}</content>
+</entry>
+"#,
+            title = title(summary),
+            day = summary.day,
+            content = escape(&content(summary)),
+        );
+    }
+    feed.push_str("</feed>\n");
+
+    feed
+}
+
+fn rss(base: &str, summaries: &[DaySummary]) -> String {
+    let mut feed = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0">
+<channel>
+<title>Wordle-API daily results</title>
+<link>{base}/</link>
+<description>How each day's games went</description>
+"#
+    );
+
+    for summary in summaries {
+        let _ = write!(
+            feed,
+            r#"<item>
+<title>{title}</title>
+<guid isPermaLink="false">{base}/feed/rss#{day}</guid>
+<pubDate>{date}</pubDate>
+<description>{content}</description>
+</item>
+"#,
+            title = title(summary),
+            day = summary.day,
+            date = rfc822(&summary.day),
+            content = escape(&content(summary)),
+        );
+    }
+    feed.push_str("</channel>\n</rss>\n");
+
+    feed
+}
+
+fn title(summary: &DaySummary) -> String {
+    format!(
+        "{}: {} of {} games solved",
+        summary.day, summary.num_solved, summary.num_games
+    )
+}
+
+/// The summary as HTML, with a bar per number of goes
+fn content(summary: &DaySummary) -> String {
+    let mut html = format!(
+        "<p>{} players started {} games and solved {}",
+        summary.num_clients, summary.num_games, summary.num_solved
+    );
+    if let Some(avg_goes) = summary.avg_goes {
+        let _ = write!(html, ", in {avg_goes:.2} goes on average");
+    }
+    html.push_str(".</p>");
+
+    if !summary.distribution.is_empty() {
+        html.push_str("<ul>");
+        for (goes, count) in &summary.distribution {
+            let bar = "🟩".repeat((*count).min(20));
+            let _ = write!(html, "<li>{goes}: {bar} {count}</li>");
+        }
+        html.push_str("</ul>");
+    }
+
+    html
+}
+
+/// The end of a `YYYY-MM-DD` day in RSS's RFC 822 format
+fn rfc822(day: &str) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let mut parts = day.splitn(3, '-');
+    let (year, month, date) = (
+        parts.next().unwrap_or_default(),
+        parts
+            .next()
+            .and_then(|x| x.parse::<usize>().ok())
+            .unwrap_or(1),
+        parts.next().unwrap_or_default(),
+    );
+
+    format!(
+        "{date} {} {year} 23:59:59 +0000",
+        MONTHS[month.clamp(1, 12) - 1]
+    )
+}
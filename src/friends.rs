@@ -0,0 +1,162 @@
+use crate::error::ApiError;
+use crate::game::{ClientId, GameState};
+use crate::json_response;
+use crate::registration;
+use crate::repository::FinishedGame;
+use crate::service::Service;
+use crate::share;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+
+/// How many days back the feed of friends' finishes goes
+pub const FEED_DAYS: u64 = 7;
+const MAX_FEED: usize = 100;
+/// More of a day's games than anyone would scroll through, in case a group plays endlessly
+pub const MAX_DAILY: usize = 500;
+
+/// Who to befriend, either by their client or by the invite code they handed out, which keeps
+/// their client, and so their games, to themselves until the code is used
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum AddFriend {
+    Client(String),
+    Code(String),
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Friend {
+    pub client: ClientId,
+    pub player: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct InviteCode {
+    pub code: String,
+}
+
+/// One client's place among their friends
+#[derive(Serialize, Debug)]
+pub struct Standing {
+    /// From 1, most games solved without a hint first, then the fewest goes on average
+    pub rank: usize,
+    pub client: ClientId,
+    pub player: String,
+    pub num_games: usize,
+    pub num_solved: usize,
+    /// Games solved with a hint, counted among `num_solved` but not towards the rank
+    pub num_assisted_solved: usize,
+    pub avg_goes: Option<f64>,
+}
+
+/// A finished game as friends see it, the emoji grid but never the answer
+#[derive(Serialize, Debug)]
+pub struct Finish {
+    pub client: ClientId,
+    pub player: String,
+    /// The day the game was started, as `YYYY-MM-DD` in UTC
+    pub day: String,
+    pub mode: &'static str,
+    pub state: GameState,
+    pub goes: usize,
+    pub grid: Vec<String>,
+}
+
+impl Finish {
+    pub fn new(finished: &FinishedGame, player: String) -> Finish {
+        Finish {
+            client: finished.game.client.clone(),
+            player,
+            day: finished.day.clone(),
+            mode: finished.game.mode.name(),
+            state: finished.game.state,
+            goes: finished.game.goes,
+            grid: share::emoji_grid(&finished.game),
+        }
+    }
+}
+
+/// `GET /friends/<client>` lists the client's friends, oldest friendship first
+pub fn handle_list(service: &Service, client: &str) -> Result<Response, ApiError> {
+    json_response(&service.friends(&ClientId::parse(client)?)?)
+}
+
+/// `POST /friends/<client>` befriends whoever the body names, `{"client": "..."}` or
+/// `{"code": "..."}`, answering with the client's friends
+pub fn handle_add(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    let friend: AddFriend = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    service.add_friend(&client, friend)?;
+
+    json_response(&service.friends(&client)?)
+}
+
+/// `DELETE /friends/<client>/<friend>` ends a friendship for both clients, answering with the
+/// friends that are left
+pub fn handle_remove(
+    service: &Service,
+    request: &Request,
+    client: &str,
+    friend: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+
+    service.remove_friend(&client, &ClientId::parse(friend)?)?;
+
+    json_response(&service.friends(&client)?)
+}
+
+/// `POST /friends/<client>/invite` makes the client a new invite code, the last one they made no
+/// longer being accepted
+pub fn handle_invite(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+
+    json_response(&InviteCode {
+        code: service.new_invite_code(&client)?,
+    })
+}
+
+/// `GET /friends/<client>/leaderboard` ranks the client among their friends
+pub fn handle_leaderboard(service: &Service, client: &str) -> Result<Response, ApiError> {
+    json_response(&service.friends_leaderboard(&ClientId::parse(client)?)?)
+}
+
+/// `GET /friends/<client>/daily` is how the client and their friends did in today's games, best
+/// first
+pub fn handle_daily(service: &Service, client: &str) -> Result<Response, ApiError> {
+    json_response(&service.friends_daily(&ClientId::parse(client)?)?)
+}
+
+/// `GET /friends/<client>/feed[?limit=<n>]` is the friends' most recently finished games of the
+/// last week, not including the client's own
+pub fn handle_feed(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let limit = match request.get_param("limit") {
+        None => 20,
+        Some(limit) => limit
+            .parse::<usize>()
+            .ok()
+            .filter(|x| (1..=MAX_FEED).contains(x))
+            .ok_or(ApiError::InvalidParam {
+                name: String::from("limit"),
+                value: limit,
+            })?,
+    };
+
+    json_response(&service.friends_feed(&ClientId::parse(client)?, limit)?)
+}
@@ -0,0 +1,420 @@
+use crate::dictionary::{self, WordList};
+use crate::error::ApiError;
+use crate::mock;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+use wordle_core::{Daily, ModeRegistry};
+
+pub use wordle_core::{CharMatch, GameMode, GameState, ModeState};
+
+/// Every mode a game can be created in
+pub fn modes() -> &'static ModeRegistry {
+    static MODES: OnceLock<ModeRegistry> = OnceLock::new();
+
+    MODES.get_or_init(ModeRegistry::standard)
+}
+
+/// Seconds since the Unix epoch, unless a mock server has been told otherwise
+pub fn now() -> u64 {
+    mock::now().unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs())
+    })
+}
+
+/// Days since the Unix epoch, in UTC, the day new games are started on
+pub fn today() -> u64 {
+    now() / (24 * 60 * 60)
+}
+
+/// When the daily puzzle moves on to the next day's word, `hour` o'clock at an offset of
+/// `utc_offset` minutes from UTC, which is midnight in UTC unless the operator says otherwise.
+/// Being an offset rather than a named timezone, it doesn't follow daylight saving.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Rollover {
+    pub utc_offset: i32,
+    pub hour: u32,
+}
+
+impl Rollover {
+    /// The puzzle's day at `now`, in seconds since the epoch, counted in days since the epoch like
+    /// `today`
+    pub fn day(self, now: u64) -> u64 {
+        let shift = i64::from(self.utc_offset) * 60 - i64::from(self.hour) * 60 * 60;
+
+        now.saturating_add_signed(shift) / (24 * 60 * 60)
+    }
+
+    /// The puzzle's day now
+    pub fn today(self) -> u64 {
+        self.day(now())
+    }
+}
+
+/// A day since the Unix epoch as `YYYY-MM-DD`, the way SQLite's `date()` gives it
+pub fn date(day: u64) -> String {
+    // Howard Hinnant's civil_from_days, shifted so that each year starts in March
+    let z = day + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + u64::from(m <= 2);
+
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// Reads a `YYYY-MM-DD` date back into days since the Unix epoch
+pub fn parse_date(value: &str) -> Option<u64> {
+    let mut parts = value.splitn(3, '-').map(|x| x.parse::<u64>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    if y < 1970 || !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return None;
+    }
+
+    // the inverse of `date`, Howard Hinnant's days_from_civil
+    let y = y - u64::from(m <= 2);
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let day = (era * 146_097 + doe).checked_sub(719_468)?;
+
+    // days past the end of the month roll over into the next, so aren't the date asked for
+    (date(day) == value).then_some(day)
+}
+
+/// Chooses the daily word, the same for every client, each day
+pub const DAILY: Daily = Daily { seed: 0 };
+/// The day puzzle 0 was played on, 2021-06-19, the same day as Wordle's own first
+const FIRST_PUZZLE: u64 = 18_797;
+
+/// The number of the day's daily puzzle
+pub fn puzzle_number(day: u64) -> u64 {
+    day.saturating_sub(FIRST_PUZZLE)
+}
+
+/// The id of a game, always a hyphenated lowercase UUID
+#[derive(Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct GameId(String);
+
+impl GameId {
+    pub fn generate() -> GameId {
+        GameId(Uuid::new_v4().to_string())
+    }
+
+    pub fn parse(value: &str) -> Result<GameId, ApiError> {
+        Uuid::parse_str(value)
+            .map(|x| GameId(x.to_string()))
+            .map_err(|_| ApiError::InvalidParam {
+                name: String::from("game_id"),
+                value: value.to_string(),
+            })
+    }
+
+    /// A game id read back from storage, which is only ever written by `generate`
+    pub(crate) fn stored(value: String) -> GameId {
+        GameId(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// The id of a group of clients, a hyphenated lowercase UUID like a game's
+#[derive(Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct GroupId(String);
+
+impl GroupId {
+    pub fn generate() -> GroupId {
+        GroupId(Uuid::new_v4().to_string())
+    }
+
+    pub fn parse(value: &str) -> Result<GroupId, ApiError> {
+        Uuid::parse_str(value)
+            .map(|x| GroupId(x.to_string()))
+            .map_err(|_| ApiError::InvalidParam {
+                name: String::from("group"),
+                value: value.to_string(),
+            })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Whoever is playing: any printable string of up to 64 characters
+#[derive(Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct ClientId(String);
+
+impl ClientId {
+    pub const MAX_LENGTH: usize = 64;
+
+    pub fn parse(value: &str) -> Result<ClientId, ApiError> {
+        let length = value.chars().count();
+
+        if length == 0 || length > ClientId::MAX_LENGTH || value.chars().any(char::is_control) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("client"),
+                value: value.to_string(),
+            });
+        }
+
+        Ok(ClientId(value.to_string()))
+    }
+
+    /// A client read back from storage, which may predate the validation
+    pub(crate) fn stored(value: String) -> ClientId {
+        ClientId(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A normalized word: lowercase letters only, whether or not it's in any dictionary
+#[derive(Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct Word(String);
+
+impl Word {
+    pub fn parse(value: &str) -> Option<Word> {
+        let word = dictionary::normalize(value);
+
+        (!word.is_empty() && word.chars().all(|x| x.is_ascii_lowercase())).then_some(Word(word))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A word accepted as a guess, being in the game's word list
+#[derive(Serialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(transparent)]
+pub struct Guess(Word);
+
+impl Guess {
+    pub fn parse(value: &str, words: &WordList) -> Result<Guess, ApiError> {
+        Word::parse(value)
+            .filter(|x| words.is_valid_guess(x.as_str()))
+            .map(Guess)
+            .ok_or_else(|| ApiError::InvalidGuess(dictionary::normalize(value)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+macro_rules! display_as_str {
+    ($($name:ident),*) => {
+        $(
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str(self.as_str())
+                }
+            }
+        )*
+    };
+}
+
+display_as_str!(GameId, GroupId, ClientId, Word, Guess);
+
+/// Why a guess was refused without being evaluated
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Rejection {
+    /// The guess isn't in the dictionary
+    InvalidWord,
+    /// The game had already finished
+    GameFinished,
+    /// The guess is a word, but the game's mode doesn't allow it
+    RuleViolation,
+}
+
+impl Rejection {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Rejection::InvalidWord => "invalid_word",
+            Rejection::GameFinished => "game_finished",
+            Rejection::RuleViolation => "rule_violation",
+        }
+    }
+}
+
+/// How much a hint gives away, each level costing more against the game's stats than the last
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HintLevel {
+    /// Whether the answer has a letter that about half the remaining candidates have
+    Half,
+    /// A letter in the answer that hasn't been played as yellow or green yet
+    Yellow,
+    /// The letter at a position that hasn't been played as green yet
+    Green,
+}
+
+impl HintLevel {
+    pub fn parse(value: &str) -> Result<HintLevel, ApiError> {
+        match value {
+            "half" => Ok(HintLevel::Half),
+            "yellow" => Ok(HintLevel::Yellow),
+            "green" => Ok(HintLevel::Green),
+            _ => Err(ApiError::InvalidParam {
+                name: String::from("level"),
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HintLevel::Half => "half",
+            HintLevel::Yellow => "yellow",
+            HintLevel::Green => "green",
+        }
+    }
+
+    pub fn penalty(self) -> usize {
+        match self {
+            HintLevel::Half => 1,
+            HintLevel::Yellow => 2,
+            HintLevel::Green => 3,
+        }
+    }
+}
+
+/// Rules chosen for a game when it is created
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct GameOptions {
+    /// Whether a guess rejected as an invalid word still uses up a go
+    pub invalid_guesses_count: bool,
+    /// The name of the mode the game is played in
+    pub mode: String,
+    /// How many goes the game is lost after, or `None` for games played until they're won
+    #[serde(default)]
+    pub max_guesses: Option<usize>,
+    /// How many letters the game's words have
+    #[serde(default = "standard_length")]
+    pub length: usize,
+    /// The language tag of the game's words
+    #[serde(default = "standard_language")]
+    pub language: String,
+}
+
+/// Games from before other lengths could be played were all played with the bundled words
+fn standard_length() -> usize {
+    dictionary::LENGTH
+}
+
+/// As they were before other languages could be played
+fn standard_language() -> String {
+    dictionary::LANGUAGE.to_string()
+}
+
+/// A guess that was played, kept so that clients needn't remember a game's board themselves
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PlayedGuess {
+    /// From 1, rejected guesses that used up a go taking a number too
+    pub go: usize,
+    pub guess: String,
+    /// Against the first board
+    pub evaluation: Vec<CharMatch>,
+}
+
+impl GameOptions {
+    /// Whether the game is out of goes once it's had `goes` of them
+    pub fn out_of_goes(&self, goes: usize) -> bool {
+        self.max_guesses.is_some_and(|max| goes >= max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::WordList;
+    use crate::game::{
+        date, parse_date, puzzle_number, ClientId, GameId, Guess, Rollover, Word, FIRST_PUZZLE,
+    };
+
+    #[test]
+    fn puzzles_are_numbered_from_the_first() {
+        assert_eq!(date(FIRST_PUZZLE), "2021-06-19");
+        assert_eq!(puzzle_number(parse_date("2022-06-19").unwrap()), 365);
+    }
+
+    #[test]
+    fn puzzles_roll_over_when_the_operator_says() {
+        let midnight = parse_date("2026-10-14").unwrap() * 24 * 60 * 60;
+        let hour = 60 * 60;
+
+        assert_eq!(date(Rollover::default().day(midnight)), "2026-10-14");
+        let six = Rollover {
+            utc_offset: 0,
+            hour: 6,
+        };
+        assert_eq!(date(six.day(midnight + 5 * hour)), "2026-10-13");
+        assert_eq!(date(six.day(midnight + 6 * hour)), "2026-10-14");
+        // midnight in New York, in winter, is five in the morning in UTC
+        let new_york = Rollover {
+            utc_offset: -5 * 60,
+            hour: 0,
+        };
+        assert_eq!(date(new_york.day(midnight + 4 * hour)), "2026-10-13");
+    }
+
+    #[test]
+    fn game_ids_must_be_uuids() {
+        let game_id = GameId::generate();
+
+        assert_eq!(GameId::parse(game_id.as_str()).unwrap(), game_id);
+        assert!(GameId::parse("not-a-game").is_err());
+    }
+
+    #[test]
+    fn client_ids_must_be_short_and_printable() {
+        assert!(ClientId::parse("bob").is_ok());
+        assert!(ClientId::parse("").is_err());
+        assert!(ClientId::parse("bo\nb").is_err());
+        assert!(ClientId::parse(&"b".repeat(ClientId::MAX_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn days_are_dated_in_utc() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(11_016), "2000-02-29");
+        assert_eq!(date(20_739), "2026-10-13");
+
+        assert_eq!(parse_date("2026-10-13"), Some(20_739));
+        assert_eq!(parse_date("2000-02-29"), Some(11_016));
+        assert_eq!(parse_date("2001-02-29"), None);
+        assert_eq!(parse_date("2026-1-13"), None);
+    }
+
+    #[test]
+    fn guesses_are_normalized_dictionary_words() {
+        assert_eq!(Word::parse(" CRANE ").unwrap().as_str(), "crane");
+        assert!(Word::parse("cr4ne").is_none());
+
+        let standard = WordList::standard();
+        assert_eq!(Guess::parse("Crane", &standard).unwrap().as_str(), "crane");
+        assert!(Guess::parse("xxxxx", &standard).is_err());
+
+        let longer = WordList::new(6, ["planet"], []);
+        assert!(Guess::parse("planet", &longer).is_ok());
+        assert!(Guess::parse("crane", &longer).is_err());
+    }
+}
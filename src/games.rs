@@ -0,0 +1,120 @@
+use crate::error::ApiError;
+use crate::game::{ClientId, GameId, GroupId};
+use crate::registration;
+use crate::service::{Answer, CreateGame, Service, SubmitGuess};
+use crate::{json_response, mode_choice};
+use rouille::{Request, Response};
+use serde::Deserialize;
+use wordle_core::CharMatch;
+
+/// The options of `POST /games`, the same as the query parameters of `GET /create/<client>`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGame {
+    client: String,
+    invalid_guesses_count: Option<bool>,
+    mode: Option<String>,
+    hard: Option<bool>,
+    group: Option<String>,
+    #[serde(default)]
+    daily: bool,
+    length: Option<usize>,
+    lang: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGuess {
+    guess: String,
+}
+
+/// `POST /games` creates a game for `{"client": "...", ...}`, answering like `/create`
+pub fn handle_create(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    let game: NewGame = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+    let client = ClientId::parse(&game.client)?;
+    registration::authorize_new_game(service, request, &client)?;
+
+    json_response(&service.create_game(CreateGame {
+        client,
+        invalid_guesses_count: game.invalid_guesses_count,
+        mode: mode_choice(game.hard, game.mode)?,
+        group: game.group.map(|x| GroupId::parse(&x)).transpose()?,
+        daily: game.daily,
+        length: game.length,
+        language: game.lang,
+        challenge: None,
+    })?)
+}
+
+/// `POST /games/<game_id>/guesses[?format=compact]` plays `{"guess": "..."}`, answering like
+/// `/play`
+pub fn handle_guess(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    let compact = compact_param(request)?;
+    let guess: NewGuess = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+
+    answer_response(
+        &service.submit_guess(SubmitGuess {
+            game_id,
+            guess: guess.guess,
+        })?,
+        compact,
+    )
+}
+
+/// Marks the response of a route kept only for clients that haven't moved to its replacement
+pub fn deprecated(response: Result<Response, ApiError>) -> Result<Response, ApiError> {
+    response.map(|x| x.with_additional_header("Deprecation", "true"))
+}
+
+/// Whether `?format=compact` asks for every evaluation as a digit per character
+pub fn compact_param(request: &Request) -> Result<bool, ApiError> {
+    match request.get_param("format").as_deref() {
+        None | Some("json") => Ok(false),
+        Some("compact") => Ok(true),
+        Some(format) => Err(ApiError::InvalidParam {
+            name: String::from("format"),
+            value: format.to_string(),
+        }),
+    }
+}
+
+/// The answer to a guess, compact for clients short of bandwidth if they asked for it
+pub fn answer_response(answer: &Answer, compact: bool) -> Result<Response, ApiError> {
+    if !compact {
+        return json_response(answer);
+    }
+
+    let mut answer = serde_json::to_value(answer)?;
+    compact_evaluations(&mut answer);
+
+    json_response(&answer)
+}
+
+/// Replaces every `evaluation` within the value with its compact form
+fn compact_evaluations(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                let evaluation = (key == "evaluation")
+                    .then(|| serde_json::from_value::<Vec<CharMatch>>(value.clone()).ok())
+                    .flatten();
+                match evaluation {
+                    Some(evaluation) => {
+                        *value = serde_json::Value::String(wordle_core::compact(&evaluation));
+                    }
+                    None => compact_evaluations(value),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(compact_evaluations),
+        _ => {}
+    }
+}
@@ -0,0 +1,195 @@
+use crate::admin::bearer_token;
+use crate::error::ApiError;
+use crate::game::{self, ClientId, GameState, GroupId};
+use crate::json_response;
+use crate::service::Service;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wordle_core::Daily;
+
+const MAX_NAME: usize = 64;
+/// The modes group games may be played in, those played on the group's word alone
+pub const MODES: [&str; 2] = ["classic", "hard"];
+
+/// A class, or any other group, whose members all play the same word each day
+#[derive(Clone, Debug)]
+pub struct Group {
+    pub group_id: GroupId,
+    pub name: String,
+    pub owner: ClientId,
+    /// The secret the owner manages the roster and reads the dashboard with
+    pub key: String,
+}
+
+impl Group {
+    /// Chooses the group's word for each day, the same for every member but not for other groups
+    pub fn selector(&self) -> Daily {
+        Daily {
+            seed: u64::from(crc32fast::hash(self.group_id.as_str().as_bytes())),
+        }
+    }
+
+    /// Refuses anyone but the owner
+    pub fn authorize(&self, key: Option<&str>) -> Result<(), ApiError> {
+        match key {
+            Some(key) if key == self.key => Ok(()),
+            _ => Err(ApiError::Unauthorized),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewGroup {
+    pub name: String,
+    pub owner: String,
+}
+
+impl NewGroup {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let length = self.name.trim().chars().count();
+        if length == 0 || length > MAX_NAME || self.name.chars().any(char::is_control) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("name"),
+                value: self.name.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A new group, along with the key that's only ever shown here
+#[derive(Serialize, Debug)]
+pub struct CreatedGroup {
+    pub group_id: GroupId,
+    pub name: String,
+    pub owner: ClientId,
+    pub key: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Roster {
+    pub group_id: GroupId,
+    pub name: String,
+    pub owner: ClientId,
+    pub members: Vec<Member>,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Member {
+    pub client: ClientId,
+    pub player: String,
+}
+
+/// How the group did with a day's word
+#[derive(Serialize, Debug)]
+pub struct Dashboard {
+    pub group_id: GroupId,
+    pub name: String,
+    /// The day whose word it was, as `YYYY-MM-DD`
+    pub day: String,
+    pub num_members: usize,
+    /// Members who started the day's game
+    pub num_played: usize,
+    pub num_won: usize,
+    pub avg_goes: Option<f64>,
+    /// How many members won in each number of goes
+    pub distribution: BTreeMap<usize, usize>,
+    pub members: Vec<MemberResult>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct MemberResult {
+    pub client: ClientId,
+    pub player: String,
+    /// None until the member has started the day's game
+    pub state: Option<GameState>,
+    pub goes: usize,
+    pub grid: Vec<String>,
+}
+
+impl Dashboard {
+    pub fn new(group: &Group, day: u64, members: Vec<MemberResult>) -> Dashboard {
+        let won = members
+            .iter()
+            .filter(|x| x.state == Some(GameState::Won))
+            .map(|x| x.goes)
+            .collect::<Vec<_>>();
+
+        let mut distribution = BTreeMap::new();
+        for goes in &won {
+            *distribution.entry(*goes).or_default() += 1;
+        }
+
+        Dashboard {
+            group_id: group.group_id.clone(),
+            name: group.name.clone(),
+            day: game::date(day),
+            num_members: members.len(),
+            num_played: members.iter().filter(|x| x.state.is_some()).count(),
+            num_won: won.len(),
+            avg_goes: (!won.is_empty())
+                .then(|| won.iter().sum::<usize>() as f64 / won.len() as f64),
+            distribution,
+            members,
+        }
+    }
+}
+
+/// `POST /groups` creates a group owned by the client the body names
+pub fn handle_create(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    let group: NewGroup = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    json_response(&service.create_group(group)?)
+}
+
+/// `GET /groups/<group_id>` is the group's roster, for its owner
+pub fn handle_roster(
+    service: &Service,
+    request: &Request,
+    group_id: &str,
+) -> Result<Response, ApiError> {
+    json_response(&service.group_roster(&GroupId::parse(group_id)?, bearer_token(request))?)
+}
+
+/// `PUT /groups/<group_id>/members/<client>` adds the client to the group's roster, or with
+/// `DELETE` takes them off it, answering with the roster
+pub fn handle_member(
+    service: &Service,
+    request: &Request,
+    group_id: &str,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let group_id = GroupId::parse(group_id)?;
+    let key = bearer_token(request);
+    let client = ClientId::parse(client)?;
+
+    service.set_group_member(&group_id, key, &client, request.method() == "PUT")?;
+
+    json_response(&service.group_roster(&group_id, key)?)
+}
+
+/// `GET /groups/<group_id>/dashboard[?day=<YYYY-MM-DD>]` is how each member did with the day's
+/// word, the word being played now by default, for the group's owner
+pub fn handle_dashboard(
+    service: &Service,
+    request: &Request,
+    group_id: &str,
+) -> Result<Response, ApiError> {
+    let day = match request.get_param("day") {
+        None => service.config().rollover.today(),
+        Some(day) => game::parse_date(&day).ok_or(ApiError::InvalidParam {
+            name: String::from("day"),
+            value: day,
+        })?,
+    };
+
+    json_response(&service.group_dashboard(
+        &GroupId::parse(group_id)?,
+        bearer_token(request),
+        day,
+    )?)
+}
@@ -0,0 +1,188 @@
+use crate::admin::authorize;
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::export;
+use crate::game::GameId;
+use crate::transfer::Filter;
+use rouille::{Request, Response, ResponseBody};
+use rusqlite::{Connection, Row};
+use serde::Serialize;
+use std::io::{self, Read};
+
+/// How many entries are read at a time
+const PAGE: usize = 500;
+
+/// A guess as it was made, whatever has become of its game since
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Entry {
+    pub log_id: i64,
+    pub game_id: String,
+    pub client: String,
+    pub guess: String,
+    /// `accepted`, or why the guess was rejected
+    pub outcome: String,
+    /// Whether the guess used up a go
+    pub counted: bool,
+    pub logged_at: String,
+}
+
+/// Up to `limit` of the entries after `after` for the filter's client and days, and the game if
+/// there is one, oldest first
+pub fn entries(
+    conn: &Connection,
+    filter: &Filter,
+    game_id: Option<&GameId>,
+    after: i64,
+    limit: usize,
+) -> Result<Vec<Entry>, rusqlite::Error> {
+    let (client, since, until) = filter.params();
+
+    conn.prepare(
+        "SELECT log_id, game_id, client, guess, outcome, counted, logged_at FROM guess_log
+        WHERE (?1 IS NULL OR client = ?1)
+            AND (?2 IS NULL OR date(logged_at) >= ?2) AND (?3 IS NULL OR date(logged_at) <= ?3)
+            AND (?4 IS NULL OR game_id = ?4) AND log_id > ?5
+        ORDER BY log_id LIMIT ?6",
+    )?
+    .query_map((client, since, until, game_id, after, limit), entry)?
+    .collect()
+}
+
+fn entry(row: &Row) -> Result<Entry, rusqlite::Error> {
+    Ok(Entry {
+        log_id: row.get(0)?,
+        game_id: row.get(1)?,
+        client: row.get(2)?,
+        guess: row.get(3)?,
+        outcome: row.get(4)?,
+        counted: row.get(5)?,
+        logged_at: row.get(6)?,
+    })
+}
+
+/// Reads the log out a page at a time as the response is written
+struct LogExport {
+    conn: Connection,
+    filter: Filter,
+    game_id: Option<GameId>,
+    after: i64,
+    done: bool,
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl LogExport {
+    fn fill(&mut self) -> Result<(), ApiError> {
+        let page = entries(
+            &self.conn,
+            &self.filter,
+            self.game_id.as_ref(),
+            self.after,
+            PAGE,
+        )?;
+        self.done = page.len() < PAGE;
+
+        for entry in page {
+            self.after = entry.log_id;
+            serde_json::to_writer(&mut self.buffer, &entry)?;
+            self.buffer.push(b'\n');
+        }
+
+        Ok(())
+    }
+}
+
+impl Read for LogExport {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.buffer.clear();
+            self.position = 0;
+            self.fill().map_err(io::Error::other)?;
+        }
+
+        let read = buf.len().min(self.buffer.len() - self.position);
+        buf[..read].copy_from_slice(&self.buffer[self.position..self.position + read]);
+        self.position += read;
+
+        Ok(read)
+    }
+}
+
+/// `GET /admin/guess-log[?client=<client>][&game_id=<game_id>][&since=<YYYY-MM-DD>][&until=<YYYY-MM-DD>]`
+/// streams every guess made, accepted or rejected, as NDJSON in the order they were made, for
+/// settling disputes and looking into cheating. The days are those the guesses were made on.
+pub fn handle(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let filter = export::filter(request)?;
+    let game_id = match request.get_param("game_id") {
+        Some(game_id) => Some(GameId::parse(&game_id)?),
+        None => None,
+    };
+    let log = LogExport {
+        conn: config.connection()?,
+        filter,
+        game_id,
+        after: 0,
+        done: false,
+        buffer: Vec::new(),
+        position: 0,
+    };
+
+    Ok(Response {
+        status_code: 200,
+        headers: vec![("Content-Type".into(), "application/x-ndjson".into())],
+        data: ResponseBody::from_reader(log),
+        upgrade: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{self, migrate_to, LATEST_VERSION};
+    use crate::game::GameId;
+    use crate::guess_log::entries;
+    use crate::transfer::Filter;
+    use rusqlite::Connection;
+
+    #[test]
+    fn only_the_client_being_erased_can_leave_the_log() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+        let game_id = GameId::generate();
+        conn.execute(
+            "INSERT INTO game (game_id, client, word, goes, solved) VALUES (?1, 'bob', 'crane', 0, 0)",
+            [&game_id],
+        )
+        .unwrap();
+        db::log_guess(&conn, &game_id, "xxxxx", "invalid_word", false).unwrap();
+        db::log_guess(&conn, &game_id, "crane", "accepted", true).unwrap();
+
+        assert!(conn
+            .execute("UPDATE guess_log SET outcome = 'accepted'", [])
+            .is_err());
+        assert!(conn.execute("DELETE FROM guess_log", []).is_err());
+        // nor does anything happening to the game
+        conn.execute("DELETE FROM game", []).unwrap();
+
+        let log = entries(&conn, &Filter::default(), Some(&game_id), 0, 10).unwrap();
+        assert_eq!(
+            log.iter()
+                .map(|x| (x.guess.as_str(), x.outcome.as_str(), x.counted))
+                .collect::<Vec<_>>(),
+            [
+                ("xxxxx", "invalid_word", false),
+                ("crane", "accepted", true)
+            ]
+        );
+        assert_eq!(log[0].client, "bob");
+
+        db::set_setting(&conn, "erasing_client", Some("alice")).unwrap();
+        assert!(conn.execute("DELETE FROM guess_log", []).is_err());
+        db::set_setting(&conn, "erasing_client", Some("bob")).unwrap();
+        assert_eq!(conn.execute("DELETE FROM guess_log", []).unwrap(), 2);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::admin::bearer_token;
+use crate::error::ApiError;
+use crate::game::ClientId;
+use crate::json_response;
+use crate::service::Service;
+use crate::stats::Record;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+
+/// What every guest's client starts with, so they're never mistaken for someone who chose theirs
+pub const PREFIX: &str = "guest-";
+
+/// A client the server made up for someone to play as straight away, until they merge it into
+/// the client they go on to play as
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Guest {
+    pub client: ClientId,
+    /// The secret the guest's games are claimed with
+    pub key: String,
+}
+
+impl Guest {
+    /// Refuses anyone without the guest's key
+    pub fn authorize(&self, key: Option<&str>) -> Result<(), ApiError> {
+        match key {
+            Some(key) if key == self.key => Ok(()),
+            _ => Err(ApiError::Unauthorized),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MergeGuest {
+    into: String,
+    /// The guest's key, as the `Authorization` header is left for the key of the client merged into
+    key: String,
+}
+
+/// What a guest left behind when they were merged
+#[derive(Serialize, Debug)]
+pub struct Merged {
+    pub guest: ClientId,
+    pub client: ClientId,
+    pub games: usize,
+    /// Achievements the client didn't already have
+    pub achievements: usize,
+    /// The client's record now that the guest's games count towards it
+    pub stats: Record,
+}
+
+/// `POST /guests` makes up a guest client to play as, along with the key to merge it with
+pub fn handle_create(service: &Service) -> Result<Response, ApiError> {
+    json_response(&service.create_guest()?)
+}
+
+/// `POST /guests/<client>/merge` moves everything the guest has played into the client named by
+/// `{"into": "...", "key": "..."}`, for whoever has the guest's key, and the client's too if
+/// they've registered one. The guest is gone afterwards.
+pub fn handle_merge(
+    service: &Service,
+    request: &Request,
+    guest: &str,
+) -> Result<Response, ApiError> {
+    let guest = ClientId::parse(guest)?;
+    let merge: MergeGuest = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    json_response(&service.merge_guest(
+        &guest,
+        &merge.key,
+        &ClientId::parse(&merge.into)?,
+        bearer_token(request),
+    )?)
+}
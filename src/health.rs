@@ -0,0 +1,50 @@
+use crate::error::ApiError;
+use crate::json_response;
+use crate::service::Service;
+use rouille::Response;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Whether the instance can serve games, and if not, what it's missing
+#[derive(Serialize, Debug)]
+pub struct Readiness {
+    pub ready: bool,
+    /// By what was checked: `database`, `migrations` and `word_lists`
+    pub checks: BTreeMap<&'static str, Check>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Check {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl Check {
+    pub fn ok() -> Check {
+        Check {
+            ok: true,
+            detail: None,
+        }
+    }
+
+    pub fn failed(detail: String) -> Check {
+        Check {
+            ok: false,
+            detail: Some(detail),
+        }
+    }
+}
+
+/// `GET /healthz` answers as long as the process is up, without touching its storage
+pub fn handle_health() -> Result<Response, ApiError> {
+    json_response(&serde_json::json!({ "status": "ok" }))
+}
+
+/// `GET /readyz` checks that games can be served, answering `503` while they can't
+pub fn handle_ready(service: &Service) -> Result<Response, ApiError> {
+    let readiness = service.readiness();
+    let status = if readiness.ready { 200 } else { 503 };
+
+    Ok(json_response(&readiness)?.with_status_code(status))
+}
@@ -0,0 +1,154 @@
+use crate::error::ApiError;
+use crate::game::HintLevel;
+use crate::solver;
+use serde::Serialize;
+use std::collections::HashSet;
+use wordle_core::{CharMatch, MatchType};
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Hint {
+    pub level: HintLevel,
+    /// What the hint adds to the game's hint penalty in the stats
+    pub penalty: usize,
+    pub letter: char,
+    /// Whether the answer has the letter, only ever false for a half hint
+    pub present: bool,
+    /// The 1-based position the letter is at, only given by a green hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
+    /// How many answers are still possible once the hint is taken into account
+    pub candidates: usize,
+}
+
+/// The most useful hint of the given level for `answer`, judged against the answers still possible
+/// after the evaluations seen so far. Each level picks whatever rules out the most candidates: a
+/// half hint the unplayed letter found in closest to half of them, a yellow or green hint the
+/// letter, or position, that the fewest of them share with the answer.
+pub fn hint(
+    level: HintLevel,
+    answer: &str,
+    answers: &[String],
+    revealed: &[(String, Vec<CharMatch>)],
+) -> Result<Hint, ApiError> {
+    let mut candidates = solver::candidates(answers, revealed);
+    if !candidates.contains(&answer) {
+        candidates.push(answer);
+    }
+    let letters = answer.chars().collect::<Vec<_>>();
+
+    let (letter, present, position, remaining) = match level {
+        HintLevel::Half => {
+            let played = revealed
+                .iter()
+                .flat_map(|(guess, _)| guess.chars())
+                .collect::<HashSet<_>>();
+
+            ('a'..='z')
+                .filter(|x| !played.contains(x))
+                .map(|x| (x, candidates.iter().filter(|c| c.contains(x)).count()))
+                .filter(|(_, count)| *count > 0 && *count < candidates.len())
+                .min_by_key(|(_, count)| count.abs_diff(candidates.len() - count))
+                .map(|(x, count)| {
+                    let present = letters.contains(&x);
+                    let remaining = if present {
+                        count
+                    } else {
+                        candidates.len() - count
+                    };
+
+                    (x, present, None, remaining)
+                })
+        }
+        HintLevel::Yellow => {
+            let shown = revealed
+                .iter()
+                .flat_map(|(_, evaluation)| evaluation)
+                .filter(|x| x.match_type != MatchType::None)
+                .map(|x| x.character)
+                .collect::<HashSet<_>>();
+
+            letters
+                .iter()
+                .copied()
+                .filter(|x| !shown.contains(x))
+                .map(|x| (x, candidates.iter().filter(|c| c.contains(x)).count()))
+                .min_by_key(|(_, count)| *count)
+                .map(|(x, count)| (x, true, None, count))
+        }
+        HintLevel::Green => {
+            let perfect = revealed
+                .iter()
+                .flat_map(|(_, evaluation)| evaluation)
+                .filter(|x| x.match_type == MatchType::Perfect)
+                .map(|x| x.index)
+                .collect::<HashSet<_>>();
+
+            letters
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !perfect.contains(i))
+                .map(|(i, x)| {
+                    let count = candidates
+                        .iter()
+                        .filter(|c| c.chars().nth(i) == Some(*x))
+                        .count();
+
+                    (i, *x, count)
+                })
+                .min_by_key(|(_, _, count)| *count)
+                .map(|(i, x, count)| (x, true, Some(i + 1), count))
+        }
+    }
+    .ok_or_else(|| {
+        ApiError::RuleViolation(format!(
+            "there's no {} hint left to give for this game",
+            level.as_str()
+        ))
+    })?;
+
+    Ok(Hint {
+        level,
+        penalty: level.penalty(),
+        letter,
+        present,
+        position,
+        candidates: remaining,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::HintLevel;
+    use crate::hints::hint;
+    use wordle_core::evaluate_guess;
+
+    fn answers() -> Vec<String> {
+        ["crane", "crate", "trace", "react", "cater", "shine"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn half_hints_split_the_candidates() {
+        let hint = hint(HintLevel::Half, "crane", &answers(), &[]).unwrap();
+
+        assert_eq!(hint.penalty, 1);
+        assert!(hint.position.is_none());
+        // n and t are each in two or four of the six, n coming first
+        assert_eq!((hint.letter, hint.present, hint.candidates), ('n', true, 2));
+    }
+
+    #[test]
+    fn yellow_and_green_hints_skip_what_has_been_shown() {
+        let revealed = vec![(String::from("trace"), evaluate_guess("crate", "trace"))];
+
+        let yellow = hint(HintLevel::Yellow, "crate", &answers(), &revealed);
+        // every letter of crate was already yellow or green
+        assert!(yellow.is_err());
+
+        let green = hint(HintLevel::Green, "crate", &answers(), &revealed).unwrap();
+        assert_eq!(green.penalty, 3);
+        assert!([Some(1), Some(4)].contains(&green.position));
+        assert_eq!(green.candidates, 1);
+    }
+}
@@ -0,0 +1,190 @@
+use crate::error::ApiError;
+use rouille::Request;
+
+/// A language the human-readable parts of the API are written in. Machine-readable parts, error
+/// codes and every JSON field, are the same whichever is chosen.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    /// The language tag it's announced with in `Content-Language`
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Fr => "fr",
+        }
+    }
+
+    /// Any tag of a supported language, regional variants included, e.g. `fr-CA`
+    fn parse(tag: &str) -> Option<Locale> {
+        let language = tag.split(['-', '_']).next()?.trim();
+
+        [Locale::En, Locale::Es, Locale::Fr]
+            .into_iter()
+            .find(|x| x.tag().eq_ignore_ascii_case(language))
+    }
+
+    /// The locale asked for by `?lang=`, otherwise the most preferred supported language of the
+    /// `Accept-Language` header, otherwise English. Unsupported languages are passed over rather
+    /// than refused, as the refusal would have to be in some language too.
+    pub fn negotiate(request: &Request) -> Locale {
+        if let Some(locale) = request.get_param("lang").and_then(|x| Locale::parse(&x)) {
+            return locale;
+        }
+
+        request
+            .header("Accept-Language")
+            .and_then(accepted)
+            .unwrap_or_default()
+    }
+}
+
+/// The supported language an `Accept-Language` header prefers most, by quality and then order
+fn accepted(header: &str) -> Option<Locale> {
+    let mut ranges = header
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let locale = Locale::parse(parts.next()?)?;
+            let quality = parts
+                .find_map(|x| x.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |x| x.trim().parse::<f64>().ok())?;
+
+            (quality > 0.0).then_some((locale, quality))
+        })
+        .collect::<Vec<_>>();
+    // stable, so that equally preferred languages keep the order they were listed in
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges.first().map(|(locale, _)| *locale)
+}
+
+/// The error's message for people to read. Internal failures only ever say that something went
+/// wrong, and the operator's own read-only message is shown as they wrote it.
+pub fn error_message(error: &ApiError, locale: Locale) -> String {
+    match (locale, error) {
+        (_, ApiError::Storage(_) | ApiError::Serialization(_)) => String::from(match locale {
+            Locale::En => "internal server error",
+            Locale::Es => "error interno del servidor",
+            Locale::Fr => "erreur interne du serveur",
+        }),
+        (Locale::En, _) | (_, ApiError::ReadOnly(_)) => error.to_string(),
+        (Locale::Es, error) => match error {
+            ApiError::GameNotFound(x) => format!("la partida '{x}' no existe"),
+            ApiError::ProfileNotFound(x) => format!("'{x}' no tiene perfil público"),
+            ApiError::GroupNotFound(x) => format!("el grupo '{x}' no existe"),
+            ApiError::ClubNotFound(x) => format!("el club '{x}' no existe"),
+            ApiError::ShareNotFound(x) => format!("el enlace compartido '{x}' no existe"),
+            ApiError::ChallengeNotFound(x) => format!("el desafío '{x}' no existe"),
+            ApiError::ClientNotFound(x) => format!("el cliente '{x}' no está registrado"),
+            ApiError::NotFound(x) => format!("no hay nada en '{x}'"),
+            ApiError::InvalidGuess(x) => format!("'{x}' no es un intento válido"),
+            ApiError::InvalidParam { name, value } => {
+                format!("'{value}' no es un valor válido para '{name}'")
+            }
+            ApiError::InvalidBody(x) => format!("cuerpo de la petición no válido: {x}"),
+            ApiError::Unauthorized => String::from("token ausente o no válido"),
+            ApiError::GameFinished(x) => format!("la partida '{x}' ya ha terminado"),
+            ApiError::GameExists(x) => format!("la partida '{x}' ya existe"),
+            ApiError::ClientRegistered(x) => format!("el cliente '{x}' ya está registrado"),
+            ApiError::RuleViolation(x) => {
+                format!("el intento incumple las reglas de este modo: {x}")
+            }
+            ApiError::RateLimited(x) => {
+                format!("demasiadas peticiones, inténtalo de nuevo en {x} segundos")
+            }
+            ApiError::ReadOnly(_) | ApiError::Storage(_) | ApiError::Serialization(_) => {
+                unreachable!("matched above")
+            }
+        },
+        (Locale::Fr, error) => match error {
+            ApiError::GameNotFound(x) => format!("la partie '{x}' n'existe pas"),
+            ApiError::ProfileNotFound(x) => format!("'{x}' n'a pas de profil public"),
+            ApiError::GroupNotFound(x) => format!("le groupe '{x}' n'existe pas"),
+            ApiError::ClubNotFound(x) => format!("le club '{x}' n'existe pas"),
+            ApiError::ShareNotFound(x) => format!("le lien de partage '{x}' n'existe pas"),
+            ApiError::ChallengeNotFound(x) => format!("le défi '{x}' n'existe pas"),
+            ApiError::ClientNotFound(x) => format!("le client '{x}' n'est pas enregistré"),
+            ApiError::NotFound(x) => format!("rien n'est servi à '{x}'"),
+            ApiError::InvalidGuess(x) => format!("'{x}' n'est pas une proposition valide"),
+            ApiError::InvalidParam { name, value } => {
+                format!("'{value}' n'est pas une valeur valide pour '{name}'")
+            }
+            ApiError::InvalidBody(x) => format!("corps de requête invalide : {x}"),
+            ApiError::Unauthorized => String::from("jeton manquant ou invalide"),
+            ApiError::GameFinished(x) => format!("la partie '{x}' est déjà terminée"),
+            ApiError::GameExists(x) => format!("la partie '{x}' existe déjà"),
+            ApiError::ClientRegistered(x) => format!("le client '{x}' est déjà enregistré"),
+            ApiError::RuleViolation(x) => {
+                format!("la proposition enfreint les règles de ce mode : {x}")
+            }
+            ApiError::RateLimited(x) => {
+                format!("trop de requêtes, réessayez dans {x} secondes")
+            }
+            ApiError::ReadOnly(_) | ApiError::Storage(_) | ApiError::Serialization(_) => {
+                unreachable!("matched above")
+            }
+        },
+    }
+}
+
+/// The opening of the root help page, ahead of the endpoint reference, which is only written in
+/// English so as to keep one reference that's always up to date
+pub fn welcome(locale: Locale) -> &'static str {
+    match locale {
+        Locale::En => {
+            r#"<h1>Welcome to the Wordle-API!</h1>
+<p>Play in the browser at <a href="/ui">/ui</a>, or create a new game and guess words for it through the API:</p>
+<p>An instance serving several communities serves each of them the whole API under <code>/t/&lt;tenant></code>, e.g. <code>/t/&lt;tenant>/create/&lt;client></code>, with games, stats and leaderboards of their own, and words too wherever the word list directory has them under <code>tenants/&lt;tenant></code></p>
+"#
+        }
+        Locale::Es => {
+            r#"<h1>¡Bienvenido a Wordle-API!</h1>
+<p>Juega en el navegador en <a href="/ui">/ui</a>, o crea una partida nueva y adivina palabras a través de la API:</p>
+<p>Una instancia que sirve a varias comunidades les sirve a cada una la API completa bajo <code>/t/&lt;tenant></code>, p. ej. <code>/t/&lt;tenant>/create/&lt;client></code>, con sus propias partidas, estadísticas y clasificaciones, y también palabras allí donde el directorio de listas de palabras las tenga bajo <code>tenants/&lt;tenant></code></p>
+<p>La referencia de la API que sigue solo está disponible en inglés</p>
+"#
+        }
+        Locale::Fr => {
+            r#"<h1>Bienvenue sur Wordle-API !</h1>
+<p>Jouez dans le navigateur sur <a href="/ui">/ui</a>, ou créez une nouvelle partie et devinez des mots grâce à l'API :</p>
+<p>Une instance qui sert plusieurs communautés sert à chacune l'API complète sous <code>/t/&lt;tenant></code>, par ex. <code>/t/&lt;tenant>/create/&lt;client></code>, avec ses propres parties, statistiques et classements, et ses propres mots aussi là où le répertoire des listes de mots en a sous <code>tenants/&lt;tenant></code></p>
+<p>La référence de l'API qui suit n'est disponible qu'en anglais</p>
+"#
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ApiError;
+    use crate::i18n::{accepted, error_message, Locale};
+
+    #[test]
+    fn the_most_preferred_supported_language_is_chosen() {
+        assert_eq!(accepted("fr-CA,fr;q=0.9,en;q=0.8"), Some(Locale::Fr));
+        assert_eq!(accepted("de, es;q=0.5, en;q=0.7"), Some(Locale::En));
+        assert_eq!(accepted("en;q=0, es"), Some(Locale::Es));
+        assert_eq!(accepted("de, ja"), None);
+    }
+
+    #[test]
+    fn internal_failures_are_never_detailed() {
+        let error = ApiError::Storage(rusqlite::Error::InvalidQuery);
+
+        assert_eq!(
+            error_message(&error, Locale::Es),
+            "error interno del servidor"
+        );
+        assert_eq!(
+            error_message(&ApiError::Unauthorized, Locale::En),
+            "missing or invalid token"
+        );
+    }
+}
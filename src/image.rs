@@ -0,0 +1,98 @@
+use crate::error::ApiError;
+use crate::game::GameId;
+use crate::repository::StoredGame;
+use crate::service::Service;
+use rouille::Response;
+use wordle_core::MatchType;
+
+const TILE: usize = 60;
+const GAP: usize = 8;
+const PADDING: usize = 16;
+
+pub type Rgb = [u8; 3];
+
+const BACKGROUND: Rgb = [0x12, 0x12, 0x13];
+const EMPTY: Rgb = [0x2a, 0x2a, 0x2c];
+
+fn colour(match_type: MatchType) -> Rgb {
+    match match_type {
+        MatchType::Perfect => [0x53, 0x8d, 0x4e],
+        MatchType::Partial => [0xb5, 0x9f, 0x3b],
+        MatchType::None => [0x3a, 0x3a, 0x3c],
+    }
+}
+
+/// `GET /game/<game_id>/image` is the board as a PNG of coloured tiles, the same as the shared
+/// emoji grid, so it never gives the answer away
+pub fn handle(service: &Service, game_id: &str) -> Result<Response, ApiError> {
+    let game = service.find_game(&GameId::parse(game_id)?)?;
+
+    Ok(Response::from_data("image/png", render(&game)))
+}
+
+/// Draws one row of tiles per guess, or a single row of empty tiles before the first
+fn render(game: &StoredGame) -> Vec<u8> {
+    let mut rows = game
+        .mode_state
+        .revealed
+        .iter()
+        .map(|(_, evaluation)| evaluation.iter().map(|x| colour(x.match_type)).collect())
+        .collect::<Vec<Vec<Rgb>>>();
+    if rows.is_empty() {
+        rows.push(vec![EMPTY; game.mode_state.boards[0].word().len()]);
+    }
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let size = |n: usize| 2 * PADDING + n * TILE + n.saturating_sub(1) * GAP;
+    let (width, height) = (size(columns), size(rows.len()));
+
+    let mut pixels = vec![BACKGROUND; width * height];
+    for (row, tiles) in rows.iter().enumerate() {
+        for (column, tile) in tiles.iter().enumerate() {
+            let (left, top) = (
+                PADDING + column * (TILE + GAP),
+                PADDING + row * (TILE + GAP),
+            );
+
+            for y in top..top + TILE {
+                pixels[y * width + left..y * width + left + TILE].fill(*tile);
+            }
+        }
+    }
+
+    encode_png(width, height, &pixels)
+}
+
+/// A minimal truecolour PNG: a header, a single compressed image chunk and the end marker
+pub fn encode_png(width: usize, height: usize, pixels: &[Rgb]) -> Vec<u8> {
+    // every scanline starts with its filter type, which is always none
+    let mut scanlines = Vec::with_capacity(height * (1 + width * 3));
+    for line in pixels.chunks(width) {
+        scanlines.push(0);
+        scanlines.extend(line.iter().flatten());
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    // 8 bit RGB, default compression and filtering, not interlaced
+    header.extend([8, 2, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &deflate::deflate_bytes_zlib(&scanlines));
+    chunk(&mut png, b"IEND", &[]);
+
+    png
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend(crc.finalize().to_be_bytes());
+}
@@ -0,0 +1,66 @@
+use crate::error::ApiError;
+use crate::game::ClientId;
+use crate::json_response;
+use crate::registration;
+use crate::service::Service;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+
+/// The most messages a client is sent at once, the rest wait until those are acknowledged
+pub const MAX_MESSAGES: usize = 100;
+
+/// Something that happened to a client that they'd want to hear about, for clients that can't take
+/// a webhook
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InboxEvent {
+    /// Someone added the client as a friend
+    Befriended { client: String, player: String },
+    /// A group's owner put the client on its roster
+    AddedToGroup { group_id: String, name: String },
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Message {
+    /// Increasing, so acknowledging one acknowledges every message before it too
+    pub message_id: u64,
+    /// In seconds since the epoch
+    pub sent_at: u64,
+    #[serde(flatten)]
+    pub event: InboxEvent,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Acknowledge {
+    up_to: u64,
+}
+
+/// `GET /inbox/<client>` is every message the client hasn't acknowledged yet, oldest first
+pub fn handle_get(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+
+    json_response(&service.inbox(&client)?)
+}
+
+/// `POST /inbox/<client>/ack` acknowledges the messages up to and including `{"up_to": <id>}`,
+/// which are then gone for good, answering with those that are left
+pub fn handle_acknowledge(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    let acknowledge: Acknowledge = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    service.acknowledge_inbox(&client, acknowledge.up_to)?;
+
+    json_response(&service.inbox(&client)?)
+}
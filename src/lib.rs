@@ -0,0 +1,1005 @@
+pub mod achievements;
+mod admin;
+mod analysis;
+mod assets;
+mod backup;
+mod badge;
+mod bot;
+mod challenges;
+pub mod cli;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod clubs;
+pub mod config;
+pub mod cors;
+mod db;
+pub mod dictionary;
+pub mod difficulty;
+mod discord;
+pub mod error;
+pub mod events;
+mod export;
+mod feed;
+pub mod friends;
+pub mod game;
+mod games;
+pub mod groups;
+mod guess_log;
+mod guests;
+mod health;
+pub mod hints;
+mod i18n;
+mod image;
+mod inbox;
+mod maintenance;
+mod metrics;
+mod mock;
+mod openapi;
+mod overlay;
+pub mod profile;
+mod qr;
+mod rate_limit;
+mod registration;
+pub mod reminders;
+mod replay;
+pub mod repository;
+mod request_log;
+mod schedule;
+mod search;
+pub mod service;
+pub mod share;
+mod solver;
+pub mod stats;
+mod telegram;
+pub mod testing;
+mod transfer;
+
+use cli::CliResult;
+use config::Config;
+use error::ApiError;
+use game::{ClientId, GameId, GroupId, HintLevel};
+use i18n::Locale;
+use rate_limit::Allowance;
+use rouille::router;
+use rouille::Request;
+use rouille::Response;
+use rusqlite::Connection;
+use serde::Serialize;
+use service::{CreateGame, Service, SubmitGuess};
+use std::error::Error;
+use std::iter;
+use std::time::Instant;
+use uuid::Uuid;
+
+pub fn serve(config: Config) -> CliResult {
+    if config.mock.is_some() {
+        eprintln!("serving a mock, nothing it's given will be kept once it stops");
+    }
+
+    let service = Service::new(config);
+    // every tenant's database is looked after just like the default one
+    let _keep_alive = iter::once(service.config())
+        .chain(service.tenants().map(|(_, x)| x.config()))
+        .map(start)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rouille::start_server("0.0.0.0:85", move |request| {
+        handle_request(&service, request)
+    });
+}
+
+/// Migrates a database, or checks it's current, and schedules its background jobs, returning a
+/// connection to keep open for as long as the server runs
+fn start(config: &Config) -> Result<Connection, Box<dyn Error>> {
+    let mut conn = config.connection()?;
+    if config.auto_migrate {
+        cli::migrate::apply(&mut conn, db::LATEST_VERSION)?;
+    } else {
+        cli::migrate::ensure_current(conn)?;
+    }
+
+    if let Some(interval) = config.maintenance_interval {
+        maintenance::schedule(interval, config.database.clone(), config.archive_after_days);
+    }
+    if let Some(interval) = config.reminder_interval {
+        reminders::schedule(
+            interval,
+            config.database.clone(),
+            config.mock.clone(),
+            config.private_webhooks,
+        );
+    }
+
+    // a mock's in-memory database only lives as long as some connection to it is open
+    Ok(config.connection()?)
+}
+
+/// A version of the API, each keeping to the shapes and semantics it was released with, so that
+/// breaking changes land under a version of their own rather than changing one clients rely on
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ApiVersion {
+    V1,
+}
+
+impl ApiVersion {
+    /// The version a path is prefixed with, e.g. `/v1/stats`, along with the prefix. Paths without
+    /// one are the first version, as they were before there were others.
+    fn of(url: &str) -> Option<(Option<ApiVersion>, String)> {
+        let rest = url.strip_prefix("/v")?;
+        let number = rest.split('/').next().unwrap_or_default();
+        if number.is_empty() || !number.bytes().all(|x| x.is_ascii_digit()) {
+            return None;
+        }
+
+        let version = match number {
+            "1" => Some(ApiVersion::V1),
+            _ => None,
+        };
+
+        Some((version, format!("/v{number}")))
+    }
+
+    fn number(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "1",
+        }
+    }
+
+    /// Routes the request, already stripped of its version's prefix, by that version's routes
+    fn handle(self, service: &Service, request: &Request, request_id: &str) -> Response {
+        match self {
+            ApiVersion::V1 => handle_v1(service, request, request_id),
+        }
+    }
+}
+
+/// Routes a single request, the whole API short of the listening socket, to the version it's
+/// prefixed with. Every response carries the request's id in `X-Request-Id`, as do its error
+/// envelope and its log line.
+pub fn handle_request(service: &Service, request: &Request) -> Response {
+    let request_id = request_id(request);
+    let cors = service.config().cors.as_ref();
+    if let Some(preflight) = cors.and_then(|x| x.preflight(request)) {
+        return preflight.with_unique_header("X-Request-Id", request_id);
+    }
+
+    let response = match ApiVersion::of(&request.url()) {
+        None => ApiVersion::V1
+            .handle(service, request, &request_id)
+            .with_unique_header("Api-Version", ApiVersion::V1.number()),
+        Some((Some(version), prefix)) => match request.remove_prefix(&prefix) {
+            Some(request) => version
+                .handle(service, &request, &request_id)
+                .with_unique_header("Api-Version", version.number()),
+            None => not_found(request, &request_id),
+        },
+        // a version that isn't served has no answers to label with it
+        Some((None, _)) => not_found(request, &request_id),
+    };
+
+    let response = response.with_unique_header("X-Request-Id", request_id);
+    match cors {
+        Some(cors) => cors.apply(request, response),
+        None => response,
+    }
+}
+
+/// The id the client sent in `X-Request-Id`, so that its reports and the server's logs name the
+/// request alike, otherwise a new one. Ids that are overly long or that aren't printable ASCII are
+/// replaced rather than written to the logs.
+fn request_id(request: &Request) -> String {
+    match request.header("X-Request-Id").map(str::trim) {
+        Some(id)
+            if !id.is_empty() && id.len() <= 128 && id.bytes().all(|x| x.is_ascii_graphic()) =>
+        {
+            id.to_string()
+        }
+        _ => Uuid::new_v4().to_string(),
+    }
+}
+
+/// The error envelope for a path no endpoint answers at
+fn not_found(request: &Request, request_id: &str) -> Response {
+    ApiError::NotFound(request.url()).into_response(request_id, Locale::negotiate(request))
+}
+
+/// Routes a request to the first version of the API
+fn handle_v1(service: &Service, request: &Request, request_id: &str) -> Response {
+    // a tenant's requests are routed just the same, but by its own service
+    if let Some(rest) = request.url().strip_prefix("/t/") {
+        let tenant = rest.split('/').next().unwrap_or_default();
+
+        return match (
+            service.tenant(tenant),
+            request.remove_prefix(&format!("/t/{tenant}")),
+        ) {
+            (Some(service), Some(request)) => handle_v1(service, &request, request_id),
+            _ => not_found(request, request_id),
+        };
+    }
+
+    let started = Instant::now();
+    let locale = Locale::negotiate(request);
+    let config = service.config();
+    let _pretending = match config.mock {
+        Some(_) => match mock::Pretending::new(request) {
+            Ok(pretending) => Some(pretending),
+            Err(error) => return error.into_response(request_id, locale),
+        },
+        None => None,
+    };
+
+    // the answer, with what the address has left of its allowance, counted and logged
+    let finish = |result: Result<Response, ApiError>, allowance: Option<Allowance>| {
+        // anything unmatched is counted together, so that made up paths can't add labels
+        let (method, route) = match &result {
+            Err(ApiError::NotFound(_)) => ("other", String::from("other")),
+            _ => (request.method(), metrics::route(&request.url())),
+        };
+        if let Err(ApiError::Storage(_)) = &result {
+            service.metrics().storage_error();
+        }
+        let mut response = result.unwrap_or_else(|error| error.into_response(request_id, locale));
+        let took = started.elapsed();
+        service
+            .metrics()
+            .observe(method, &route, response.status_code, took);
+        if let Some(log) = &config.request_log {
+            log.record(
+                request_id,
+                request.method(),
+                &request.url(),
+                response.status_code,
+                took,
+            );
+        }
+
+        for (name, value) in allowance.iter().flat_map(|x| x.headers()) {
+            response = response.with_unique_header(name, value);
+        }
+        response
+    };
+    let allowance = match service.limit_address(&request.remote_addr().ip().to_string()) {
+        Ok(allowance) => allowance,
+        Err((allowance, error)) => return finish(Err(error), Some(allowance)),
+    };
+
+    let result = router!(request,
+        (GET) (/) => { Ok(handle_root(locale)) },
+
+        (GET) (/ui) => { Ok(asset(config, "ui.html")) },
+
+        (GET) (/healthz) => { health::handle_health() },
+
+        (GET) (/readyz) => { health::handle_ready(service) },
+
+        (GET) (/metrics) => { metrics::handle(service) },
+
+        (GET) (/stats) => { handle_stats(service) },
+
+        (GET) (/words/search) => { limit_solver(service, request).and_then(|_| search::handle(request)) },
+
+        (GET) (/bot/play) => { limit_solver(service, request).and_then(|_| bot::handle(request)) },
+
+        (GET) (/openers) => { handle_openers(config, request) },
+
+        (GET) (/openers/{opener: String}) => {
+            limit_solver(service, request).and_then(|_| handle_opener(service, &opener))
+        },
+
+        (POST) (/games) => { games::handle_create(service, request) },
+
+        (POST) (/games/{game_id: String}/guesses) => {
+            games::handle_guess(service, request, &game_id)
+        },
+
+        (GET) (/play/{game_id: String}/guess/{guess: String}) => {
+            games::deprecated(handle_play(service, request, &game_id, &guess))
+        },
+
+        (GET) (/create/{client: String}) => {
+            games::deprecated(handle_new_game(service, request, &client, false))
+        },
+
+        (GET) (/daily/{client: String}) => {
+            games::deprecated(handle_new_game(service, request, &client, true))
+        },
+
+        (POST) (/challenge) => { challenges::handle_create(service, request) },
+
+        (GET) (/challenge/{challenge_id: String}/{client: String}) => {
+            challenges::handle_play(service, request, &challenge_id, &client)
+        },
+
+        (GET) (/profiles/{client: String}) => { profile::handle_get(service, &client) },
+
+        (PUT) (/profiles/{client: String}) => { profile::handle_put(service, request, &client) },
+
+        (GET) (/friends/{client: String}) => { friends::handle_list(service, &client) },
+
+        (POST) (/friends/{client: String}) => { friends::handle_add(service, request, &client) },
+
+        (DELETE) (/friends/{client: String}/{friend: String}) => {
+            friends::handle_remove(service, request, &client, &friend)
+        },
+
+        (POST) (/friends/{client: String}/invite) => { friends::handle_invite(service, request, &client) },
+
+        (GET) (/friends/{client: String}/leaderboard) => {
+            friends::handle_leaderboard(service, &client)
+        },
+
+        (GET) (/friends/{client: String}/daily) => { friends::handle_daily(service, &client) },
+
+        (GET) (/friends/{client: String}/feed) => {
+            friends::handle_feed(service, request, &client)
+        },
+
+        (GET) (/profile/{client: String}) => { profile::handle_page(service, request, &client) },
+
+        (GET) (/reminders/{client: String}) => { reminders::handle_get(service, request, &client) },
+
+        (PUT) (/reminders/{client: String}) => { reminders::handle_put(service, request, &client) },
+
+        (DELETE) (/reminders/{client: String}) => { reminders::handle_delete(service, request, &client) },
+
+        (GET) (/inbox/{client: String}) => { inbox::handle_get(service, request, &client) },
+
+        (POST) (/inbox/{client: String}/ack) => {
+            inbox::handle_acknowledge(service, request, &client)
+        },
+
+        (POST) (/clients) => { registration::handle_register(service, request) },
+
+        (GET) (/clients/{client: String}) => { registration::handle_get(service, &client) },
+
+        (POST) (/guests) => { guests::handle_create(service) },
+
+        (POST) (/guests/{client: String}/merge) => {
+            guests::handle_merge(service, request, &client)
+        },
+
+        (POST) (/groups) => { groups::handle_create(service, request) },
+
+        (GET) (/groups/{group_id: String}) => { groups::handle_roster(service, request, &group_id) },
+
+        (PUT) (/groups/{group_id: String}/members/{client: String}) => {
+            groups::handle_member(service, request, &group_id, &client)
+        },
+
+        (DELETE) (/groups/{group_id: String}/members/{client: String}) => {
+            groups::handle_member(service, request, &group_id, &client)
+        },
+
+        (GET) (/groups/{group_id: String}/dashboard) => {
+            groups::handle_dashboard(service, request, &group_id)
+        },
+
+        (POST) (/clubs) => { clubs::handle_create(service, request) },
+
+        (GET) (/clubs) => { clubs::handle_list(service) },
+
+        (GET) (/clubs/standings) => { clubs::handle_standings(service) },
+
+        (GET) (/clubs/{club: String}) => { clubs::handle_get(service, &club) },
+
+        (PUT) (/clubs/{club: String}/members/{client: String}) => {
+            clubs::handle_member(service, request, &club, &client)
+        },
+
+        (DELETE) (/clubs/{club: String}/members/{client: String}) => {
+            clubs::handle_member(service, request, &club, &client)
+        },
+
+        (GET) (/achievements/{client: String}) => { achievements::handle(service, request, &client) },
+
+        (GET) (/badge/{client: String}) => { badge::handle(service, request, &client) },
+
+        (GET) (/discord/{game_id: String}) => { discord::handle_result(service, &game_id) },
+
+        (POST) (/discord/interactions) => { discord::handle_interaction(service, request) },
+
+        (GET) (/feed/{format: String}) => { feed::handle(service, request, &format) },
+
+        (GET) (/game/{game_id: String}) => { handle_game(service, &game_id) },
+
+        (POST) (/game/{game_id: String}/resign) => { handle_resign(service, request, &game_id) },
+
+        (GET) (/game/{game_id: String}/analysis) => {
+            limit_solver(service, request).and_then(|_| analysis::handle(service, &game_id))
+        },
+
+        (GET) (/game/{game_id: String}/hint) => {
+            limit_solver(service, request).and_then(|_| handle_hint(service, request, &game_id))
+        },
+
+        (GET) (/game/{game_id: String}/image) => { image::handle(service, &game_id) },
+
+        (GET) (/game/{game_id: String}/qr) => { qr::handle(service, request, &game_id) },
+
+        (POST) (/game/{game_id: String}/shares) => {
+            share::handle_create(service, request, &game_id)
+        },
+
+        (GET) (/game/{game_id: String}/shares) => { share::handle_list(service, request, &game_id) },
+
+        (DELETE) (/game/{game_id: String}/shares/{token: String}) => {
+            share::handle_revoke(service, request, &game_id, &token)
+        },
+
+        (GET) (/shared/{token: String}) => { share::handle_view(service, request, &token) },
+
+        (GET) (/game/{game_id: String}/replay) => {
+            replay::handle_get(service, request, &game_id)
+        },
+
+        (GET) (/replay/{token: String}) => { replay::handle_shared(service, &token) },
+
+        (GET) (/overlay/{game_id: String}) => { overlay::handle(service, request, &game_id) },
+
+        (POST) (/integrations/telegram) => { telegram::handle(service, request) },
+
+        (GET) (/export) => { export::handle(config, request) },
+
+        (POST) (/import) => { admin::handle_import(config, request) },
+
+        (GET) (/mock/webhooks) => { mock::handle_webhooks(config, request) },
+
+        (DELETE) (/mock/webhooks) => { mock::handle_webhooks(config, request) },
+
+        (GET) (/admin/schedule) => { schedule::handle_list(config, request) },
+
+        (POST) (/admin/schedule/generate) => { schedule::handle_generate(config, request) },
+
+        (PUT) (/admin/schedule/{date: String}) => { schedule::handle_pin(config, request, &date) },
+
+        (DELETE) (/admin/schedule/{date: String}) => {
+            schedule::handle_unpin(config, request, &date)
+        },
+
+        (GET) (/admin/guess-log) => { guess_log::handle(config, request) },
+
+        (GET) (/admin/repairs) => { admin::handle_repairs(config, request) },
+
+        (GET) (/admin/game/{game_id: String}) => { admin::handle_game(config, request, &game_id) },
+
+        (POST) (/admin/game/{game_id: String}/undo) => {
+            admin::handle_undo_guess(config, request, &game_id)
+        },
+
+        (POST) (/admin/maintenance) => { admin::handle_maintenance(config, request) },
+
+        (POST) (/admin/reminders) => { admin::handle_reminders(config, request) },
+
+        (GET) (/admin/read-only) => { admin::handle_read_only(config, request) },
+
+        (POST) (/admin/read-only) => { admin::handle_set_read_only(config, request) },
+
+        (DELETE) (/admin/client/{client: String}) => {
+            admin::handle_erase_client(config, request, &client)
+        },
+
+        // the router can't match a path with a dot in it
+        _ => match (request.method(), request.url().as_str()) {
+            ("GET", "/openapi.json") => openapi::handle(),
+            _ => assets::handle(config, request).ok_or_else(|| ApiError::NotFound(request.url())),
+        }
+    );
+
+    finish(result, allowance)
+}
+
+/// The help page, its welcome in whichever language the request asked for
+fn handle_root(locale: Locale) -> Response {
+    Response::html(format!("{}{REFERENCE}", i18n::welcome(locale)))
+        .with_additional_header("Content-Language", locale.tag())
+}
+
+const REFERENCE: &str = r#"<h3>GET /create/&lt;client>[?invalid_guesses_count=&lt;bool>][&mode=&lt;mode>][&hard=&lt;bool>][&group=&lt;group_id>][&length=&lt;int>][&lang=&lt;language>]</h3>
+<p>Client is your unique identifier, it can be any string</p>
+<p><code>invalid_guesses_count</code> decides whether guessing a word that isn't in the dictionary uses up a go</p>
+<p><code>mode</code> is one of:</p>
+<ul>
+    <li><code>classic</code> (the default) any word may be guessed</li>
+    <li><code>hard</code> revealed letters must be reused, and perfect letters kept in place, guesses that don't are refused with <code>422</code> and the letter they're missing. <code>hard=true</code> asks for it too</li>
+    <li><code>adversarial</code> the answer is only decided once every other word has been ruled out</li>
+    <li><code>multi</code> four words at once, each guess is evaluated against every board</li>
+</ul>
+<p><code>group</code> plays the group's word for the day, the same for every member, in <code>classic</code> or <code>hard</code> mode. Each member has one game of it a day, creating another carries on with the same one. The day moves on at midnight in UTC, or whenever the operator has set it to, and the game's <code>puzzle_date</code>, as <code>YYYY-MM-DD</code>, says whose word is being played</p>
+<p>A game is lost once it's used up <code>max_guesses</code> goes without being won, 6 unless the operator has set otherwise, with an extra go for each board after the first in <code>multi</code> mode. <code>adversarial</code> games, and any the operator has left unlimited, have no limit and show it as <code>null</code></p>
+<p><code>length</code> plays a word of that many letters, 5 unless asked for. Words of 4 to 8 letters can be played wherever the operator has supplied lists of them, other lengths are refused with <code>400</code>, as are groups and the daily word, which are only played with 5. Guesses must be of the game's length</p>
+<p><code>lang</code> plays words in that language, as well as choosing the language of messages, wherever the operator has supplied words of the game's length in it. Otherwise, as for groups and the daily word, the game is played in English. Guesses must be words of the game's language</p>
+
+=> <pre><code>{
+    "game_id": &lt;game_id>,
+    "state": "in_progress",
+    "options": { "invalid_guesses_count": &lt;bool>, "mode": &lt;string: mode>, "max_guesses": &lt;int or null>, "length": &lt;int>, "language": &lt;string: language tag> }
+}</code></pre>
+
+
+<h3>GET /daily/&lt;client>[?invalid_guesses_count=&lt;bool>][&mode=&lt;mode>][&hard=&lt;bool>]</h3>
+<p>Plays the daily word, the same for every client, in <code>classic</code> or <code>hard</code> mode. Each client has one game of it a day, asking again carries on with the same one. It answers like <code>/create</code>, along with the <code>puzzle_date</code> and the <code>puzzle_number</code>, counted from puzzle 0 on 2021-06-19. The day moves on as it does for groups, and a day with a word scheduled for every group has it as the daily word too. A daily game left unfinished once its day has passed is <code>expired</code> the next time it's guessed, hinted or resigned, which is <code>409</code> like any other finished game</p>
+
+<h3>POST /games</h3>
+<p>Creates a game from <code>{"client": "&lt;client>", "invalid_guesses_count": &lt;bool>, "mode": "&lt;mode>", "hard": &lt;bool>, "group": "&lt;group_id>", "daily": &lt;bool>, "length": &lt;int>, "lang": "&lt;language>"}</code>, everything but <code>client</code> optional and meaning the same as for <code>/create</code>, or for <code>/daily</code> with <code>"daily": true</code>, answering as they do</p>
+
+<h3>POST /games/&lt;game_id>/guesses[?format=compact]</h3>
+<p>Plays <code>{"guess": "&lt;word>"}</code>, answering like <code>/play</code></p>
+<p><code>GET /create</code>, <code>GET /daily</code> and <code>GET /play</code> change the game as much as these do, and are only kept for the clients already using them. They're deprecated, answering with <code>Deprecation: true</code></p>
+
+<h3>POST /challenge</h3>
+<p>Sets a word of your choosing, any word that could be guessed, for others to play, with <code>{"client": "&lt;client>", "word": "&lt;word>"}</code>. The word is never sent back, share the <code>challenge_id</code> for others to play it by</p>
+
+=> <pre><code>{ "challenge_id": &lt;string>, "creator": &lt;client> }</code></pre>
+
+<h3>GET /challenge/&lt;challenge_id>/&lt;client>[?mode=&lt;mode>][&hard=&lt;bool>]</h3>
+<p>Plays the challenge's word, in <code>classic</code> or <code>hard</code> mode, answering like <code>/create</code>. Each client has one game of it, asking again carries on with the same one. The answer is only shown once the game is over, as for any other game. An unknown challenge is <code>404</code></p>
+
+<h3>GET /play/&lt;game_id>/guess/&lt;word>[?format=compact]</h3>
+
+=> <pre><code>{ 
+    "state": &lt;enum of string: ["in_progress", "won", "lost", "forfeited", "expired"]>,
+    "solved": &lt;bool: solved status>,
+    "lost": &lt;bool: whether the game ran out of goes>,
+    "answer": &lt;string: the answer, once the game is over, otherwise null>,
+    "guess": &lt;string: word>,
+    "evaluation": [
+        {
+            "index": &lt;int: index of char in word>,
+            "character": &lt;string: character>,
+            "match_type": &lt;enum of string: ["None", "Partial", "Perfect"]>
+        },
+        ...
+    ],
+    "boards": [ { "solved": &lt;bool>, "answer": &lt;string or null>, "evaluation": [...] }, ... ] (multi mode only),
+    "guesses": [ { "go": &lt;int: from 1, counted rejections included>, "guess": &lt;string>, "evaluation": [...] }, ... ] (every guess so far, this one last),
+    "difficulty": {
+        "rating": &lt;int: out of 100, the share of answers that are easier>,
+        "average_goes": &lt;float: the average goes every client has taken to win with the answer>
+    } (once the game is over, single board modes only),
+    "repeated": &lt;bool: whether the word had already been guessed>,
+    "letters": { "&lt;letter>": &lt;enum of string: ["None", "Partial", "Perfect"]>, ... } (the best every letter guessed so far has been matched against the first board, for a keyboard),
+    "share": &lt;string: e.g. "Wordle-API #365 4/6" over a row of 🟩🟨⬛ per guess, the puzzle's number only for daily games> (once the game is solved or lost)
+}</code></pre>
+<p>With <code>?format=compact</code> every <code>evaluation</code> is instead a string of a digit per character, <code>2</code> for <code>Perfect</code>, <code>1</code> for <code>Partial</code> and <code>0</code> for <code>None</code>, e.g. <code>"21001"</code>, for clients short of bandwidth</p>
+<p>A word the game has already had is answered with the evaluation it was given then, <code>"repeated": true</code> and without using a go</p>
+
+<h3>GET /game/&lt;game_id></h3>
+<p>The game as it stands, for picking it back up, without using a go</p>
+
+=> <pre><code>{
+    "game_id": &lt;game_id>,
+    "state": &lt;string>,
+    "solved": &lt;bool>,
+    "lost": &lt;bool>,
+    "options": { ... },
+    "goes": &lt;int>,
+    "remaining": &lt;int: goes left before the game is lost, or null without a limit>,
+    "answer": &lt;string: once the game is over, otherwise null>,
+    "answers": [ &lt;string>, ... ] (once the game is over, every board's answer, multi mode only),
+    "guesses": [ { "go": &lt;int>, "guess": &lt;string>, "evaluation": [...] }, ... ],
+    "hints": &lt;int: hints given>
+}</code></pre>
+
+<h3>POST /game/&lt;game_id>/resign</h3>
+<p>Gives up on a game in progress, answering like <code>/game/&lt;game_id></code> with the game <code>forfeited</code> and its answer shown. Forfeited games are neither solved nor counted in <code>avg_goes</code>, the stats count them apart as <code>num_forfeited</code>. A finished game is <code>409</code></p>
+
+<h3>GET /game/&lt;game_id>/hint[?level=&lt;level>]</h3>
+<p>A hint for a game in progress, chosen from the answers still possible, in exchange for a go. A hint is refused with <code>422</code> when it would use up the game's last go. Every hint adds its penalty to the client's <code>hint_penalty</code> in the stats, and counts the game among their <code>num_assisted</code>, and among their <code>num_assisted_solved</code> too if it's solved. <code>level</code> is one of:</p>
+<ul>
+    <li><code>half</code> (penalty 1) whether the answer has a letter that about half of the possible answers have</li>
+    <li><code>yellow</code> (penalty 2) a letter of the answer that hasn't been shown yet</li>
+    <li><code>green</code> (the default, penalty 3) the letter at a position that hasn't been shown as perfect yet</li>
+</ul>
+
+=> <pre><code>{
+    "level": &lt;string: level>,
+    "penalty": &lt;int>,
+    "letter": &lt;string: character>,
+    "present": &lt;bool: whether the answer has the letter>,
+    "position": &lt;int: 1-based position, green hints only>,
+    "candidates": &lt;int: answers still possible with the hint>
+}</code></pre>
+
+<h3>GET /game/&lt;game_id>/analysis</h3>
+<p>Once a game is finished, how each guess narrowed down the possible answers. <code>skill</code> is out of 100 for how close the guess came to the best one there was, and <code>luck</code> out of 100 for how few answers it happened to leave compared with how many it might have, 50 being average</p>
+
+=> <pre><code>{
+    "game_id": &lt;game_id>,
+    "answer": &lt;string: word>,
+    "skill": &lt;int: average skill>,
+    "luck": &lt;int: average luck>,
+    "guesses": [
+        {
+            "guess": &lt;string: word>,
+            "candidates_before": &lt;int>,
+            "candidates_after": &lt;int>,
+            "expected_remaining": &lt;float: answers the guess was expected to leave>,
+            "best_guess": &lt;string: the guess expected to leave the fewest answers>,
+            "best_expected_remaining": &lt;float: the fewest any guess was expected to leave>,
+            "best_candidates_after": &lt;int: the answers the best guess would have left>,
+            "skill": &lt;int>,
+            "luck": &lt;int>
+        },
+        ...
+    ]
+}</code></pre>
+
+<h3>POST /game/&lt;game_id>/shares</h3>
+<p>Once a game is finished, makes a link to it at <code>GET /shared/&lt;token></code>, a page or with <code>?format=json</code> JSON that shows the grid alone, or the words guessed as well with <code>{"show_guesses": true}</code>. <code>GET /game/&lt;game_id>/shares</code> lists the game's links and <code>DELETE /game/&lt;game_id>/shares/&lt;token></code> revokes one, each only with the player's key if they've registered one</p>
+<h3>GET /game/&lt;game_id>/replay</h3>
+<p>Every step of the game in order, each guess with its evaluation, rejected guesses and hints, along with the seconds since the game was created and since the step before, for animating a replay. Once the game's finished, <code>?share=true</code> adds a token anyone can watch it with at <code>GET /replay/&lt;token></code> for the next day</p>
+
+=> <pre><code>{ "token": &lt;string>, "game_id": &lt;game_id>, "show_guesses": &lt;bool> }</code></pre>
+
+<h3>GET /profiles/&lt;client> and PUT /profiles/&lt;client></h3>
+<p>How the client is shown in place of their raw client string, and the settings their new games start with unless asked otherwise. <code>PUT</code> replaces the whole profile, every field is optional</p>
+
+=> <pre><code>{
+    "display_name": &lt;string: up to 32 characters>,
+    "avatar_url": &lt;string: an http(s) URL>,
+    "bio": &lt;string: up to 280 characters>,
+    "settings": { "mode": &lt;string: mode>, "invalid_guesses_count": &lt;bool> },
+    "privacy": { "private": &lt;bool>, "hide_stats": &lt;bool>, "hide_recent_games": &lt;bool>, "hide_achievements": &lt;bool>, "retention": "forever" | { "days": &lt;int> } | "aggregates_only" }
+}</code></pre>
+<p><code>retention</code> is how long the client's finished games are kept, which is forever unless they say otherwise. Each time the server's maintenance runs, games finished more than the given number of days ago are deleted, or with <code>aggregates_only</code> every finished game is kept only for the stats, without its guesses</p>
+
+<h3>POST /friends/&lt;client></h3>
+<p>Befriends another client, for both of them, by their client or by an invite code they made with <code>POST /friends/&lt;client>/invite</code>, which they can hand out without giving away their client. Answers with the client's friends, as does <code>GET /friends/&lt;client></code>, and <code>DELETE /friends/&lt;client>/&lt;friend></code> ends a friendship</p>
+
+=> <pre><code>{ "client": &lt;string> } or { "code": &lt;string> }</code></pre>
+
+<h3>GET /friends/&lt;client>/leaderboard, /daily and /feed[?limit=&lt;n>]</h3>
+<p>The client ranked among their friends by games solved without a hint and then average goes, with <code>num_assisted_solved</code> counting those solved with one, how they and their friends did in today's games, and the friends' most recently finished games from the last week. Finished games are shown by their emoji grid, never their answer</p>
+
+=> <pre><code>[
+    {
+        "client": &lt;string>,
+        "player": &lt;string: display name>,
+        "day": &lt;string: YYYY-MM-DD>,
+        "mode": &lt;string>,
+        "state": &lt;string>,
+        "goes": &lt;int>,
+        "grid": [&lt;string>, ...]
+    },
+    ...
+]</code></pre>
+
+<h3>GET /profile/&lt;client>[?format=json]</h3>
+<p>The client's public page, to share as a link: their display name, avatar and bio, along with their stats, their latest finished games as emoji grids and their achievements. Each of those can be hidden by setting <code>hide_stats</code>, <code>hide_recent_games</code> or <code>hide_achievements</code> in their profile's <code>privacy</code>, and <code>"privacy": { "private": true }</code> hides the page altogether</p>
+
+<h3>GET /inbox/&lt;client></h3>
+<p>For clients that can't take a webhook, the messages waiting for the client, oldest first, each with a <code>message_id</code>, <code>sent_at</code> in seconds since the epoch and a <code>type</code>: <code>befriended</code> when someone adds them as a friend, or <code>added_to_group</code> when a group's owner puts them on its roster. They stay until <code>POST /inbox/&lt;client>/ack</code> with <code>{"up_to": &lt;message_id>}</code> acknowledges them and every message before</p>
+
+<h3>POST /clients</h3>
+<p>Registers <code>{"client": &lt;client>, "display_name": &lt;string>, "metadata": &lt;object>}</code>, the display name and metadata being optional, answering with the same along with a <code>key</code> that's only ever shown the once. From then on the client's games can only be created, at <code>/games</code>, <code>/create</code>, <code>/daily</code> and challenges, or played, resigned, hinted and shared with, and their profile only saved, or their badge, achievements, profile page, reminders and inbox read, or their friends, reminders, inbox and club memberships changed, with <code>Authorization: Bearer &lt;key></code>, so that nobody else's games count towards their stats. A client can only be registered once, and never a guest, whose key is their own. Clients that don't register can be played as by anyone, unless the server only lets registered clients and guests start games, with <code>WORDLE_REGISTERED_CLIENTS_ONLY=true</code>. The display name is saved to the client's profile, so is shown wherever they are, <code>/stats</code> included, and the metadata, a JSON object of up to 2KB, is kept as it was given</p>
+
+<h3>GET /clients/&lt;client></h3>
+<p>The registered client's <code>player</code> name, <code>metadata</code> and when they were <code>registered_at</code>, in seconds since the epoch. Their key is never shown</p>
+
+<h3>POST /guests</h3>
+<p>Makes up a client to play as straight away, <code>guest-</code> followed by letters and digits, along with the key to keep it with, which its games can only be created and played with, as <code>Authorization: Bearer &lt;key></code>. Once the player settles on a client of their own, <code>POST /guests/&lt;client>/merge</code> with <code>{"into": &lt;client>, "key": &lt;key>}</code>, and the client's own key as <code>Authorization: Bearer &lt;key></code> if they've registered, moves the guest's games, and so their stats and streak, along with their achievements, friends, groups and club, over to that client all at once, keeping the client's own wherever they have both. The guest is gone afterwards</p>
+
+=> <pre><code>{
+    "client": &lt;string: guest client>,
+    "key": &lt;string>
+}</code></pre>
+
+<h3>PUT /reminders/&lt;client></h3>
+<p>Registers a webhook to remind the client that their streak of days won is about to be lost. Once their local time passes <code>remind_at</code> on a day they haven't started a game, after winning one yesterday, the webhook is POSTed <code>{ "event": "streak_reminder", "client": ..., "streak": &lt;int>, "day": "YYYY-MM-DD" }</code>, at most once a day. Only plain <code>http://</code> urls are supported, and only to public addresses, unless the server allows loopback, link-local and private ones with <code>WORDLE_PRIVATE_WEBHOOKS=true</code>. <code>GET</code> shows the webhook and <code>DELETE</code> removes it</p>
+
+=> <pre><code>{
+    "url": &lt;string: http:// url>,
+    "remind_at": &lt;string: HH:MM, local time>,
+    "utc_offset": &lt;int: minutes ahead of UTC, 0 by default>
+}</code></pre>
+
+<h3>POST /groups</h3>
+<p>Creates a group, such as a class, whose members all play the same word each day. The <code>key</code> it answers with is only ever shown once, and is needed as <code>Authorization: Bearer &lt;key></code> to see the roster at <code>GET /groups/&lt;group_id></code>, to add or remove members with <code>PUT</code> or <code>DELETE /groups/&lt;group_id>/members/&lt;client></code>, and to read the dashboard</p>
+
+=> <pre><code>{ "name": &lt;string>, "owner": &lt;client> }</code></pre>
+
+<h3>GET /groups/&lt;group_id>/dashboard[?day=&lt;YYYY-MM-DD>]</h3>
+<p>How each member did with the day's word, today's by default: whether they've played, their goes and their emoji grid, along with the group's totals</p>
+
+=> <pre><code>{
+    "day": &lt;string>,
+    "num_members": &lt;int>,
+    "num_played": &lt;int>,
+    "num_won": &lt;int>,
+    "avg_goes": &lt;float>,
+    "distribution": { &lt;goes>: &lt;int>, ... },
+    "members": [{ "client": ..., "player": ..., "state": &lt;string or null>, "goes": &lt;int>, "grid": [...] }, ...]
+}</code></pre>
+
+<h3>POST /clubs</h3>
+<p>Creates a club that clients join for themselves, with an id of 3 to 32 lowercase letters, digits and hyphens. <code>GET /clubs</code> lists every club with its members' stats, and <code>GET /clubs/&lt;club></code> one club along with its members</p>
+
+=> <pre><code>{ "club": &lt;string>, "name": &lt;string> }</code></pre>
+
+<h3>PUT /clubs/&lt;club>/members/&lt;client></h3>
+<p>Joins the club, leaving whichever club the client was in, since each client plays for one club at a time. <code>DELETE</code> leaves it. Both answer with the club</p>
+
+=> <pre><code>{
+    "club": &lt;string>,
+    "name": &lt;string>,
+    "num_members": &lt;int>,
+    "num_games": &lt;int>,
+    "num_solved": &lt;int>,
+    "avg_goes": &lt;float>,
+    "members": [{ "client": ..., "player": ... }, ...]
+}</code></pre>
+
+<h3>GET /clubs/standings</h3>
+<p>Club against club for the week so far, from Monday in UTC: the best win rate first, then the fewest goes on average, with clubs that haven't finished a game this week last. Games won with a hint are counted in <code>num_won</code> and <code>num_assisted</code>, but not towards the win rate or average goes</p>
+
+=> <pre><code>{
+    "week_start": &lt;string>,
+    "standings": [{ "rank": &lt;int>, "club": ..., "name": ..., "num_members": &lt;int>, "num_games": &lt;int>, "num_won": &lt;int>, "num_assisted": &lt;int>, "win_rate": &lt;float>, "avg_goes": &lt;float> }, ...]
+}</code></pre>
+
+<h3>GET /achievements/&lt;client></h3>
+<p>Every achievement the client has been awarded, each only once and in the order they were: <code>first_win</code>, <code>solved_in_two</code>, <code>thirty_streak</code> (winning a game every day for 30 days in a row, by UTC) and <code>hardest_word</code> (solving an answer rated 90 or more). The guess that earns one lists it in its <code>achievements</code></p>
+
+=> <pre><code>[ { "achievement": &lt;string>, "description": &lt;string>, "game_id": &lt;game_id: the game that earned it> }, ... ]</code></pre>
+
+<h3>GET /words/search?green=&lt;pattern>&yellow=&lt;letters>&gray=&lt;letters>[&list=guesses][&limit=&lt;int>]</h3>
+<p>The answers, or with <code>list=guesses</code> every valid guess, that fit what's known: <code>green</code> is the word with <code>.</code> for unknown letters, e.g. <code>c...e</code>; <code>yellow</code> is each letter in the word followed by the positions it isn't at, e.g. <code>r1,e45</code>; <code>gray</code> is the letters not in the word</p>
+
+=> <pre><code>{ "count": &lt;int: every match>, "words": [&lt;string>, ...] }</code></pre>
+
+<h3>GET /bot/play?seed=&lt;int>[&opener=&lt;word>]</h3>
+<p>The built-in solver plays a whole game against the answer the seed chooses, always the same answer for the same seed, as a baseline to benchmark bots against on identical words. It opens with its own best guess unless given an <code>opener</code></p>
+
+=> <pre><code>{
+    "seed": &lt;int>,
+    "answer": &lt;string: word>,
+    "opener": &lt;string: word>,
+    "goes": &lt;int>,
+    "solved": &lt;bool: whether it took no more than six goes>,
+    "guesses": [ { "guess": &lt;string: word>, "evaluation": [...], "candidates": &lt;int: answers still possible> }, ... ]
+}</code></pre>
+
+<h3>GET /openers[?limit=&lt;int>]</h3>
+<p>The words real games were opened with, most played first (50 by default), and how those games went. Unlike the simulations below, it's only refreshed whenever maintenance runs</p>
+
+=> <pre><code>{
+    "refreshed_at": &lt;string: datetime, or null if it never has been>,
+    "openers": [
+        { "opener": &lt;string: word>, "is_answer": &lt;bool>, "num_games": &lt;int>, "num_won": &lt;int>, "avg_goes": &lt;float> },
+        ...
+    ]
+}</code></pre>
+
+<h3>GET /openers/&lt;word></h3>
+<p>How the built-in solver fares across every answer when it always opens with the word. Each word is only simulated once for each version of the answer list, so the first request for a word is slow</p>
+
+=> <pre><code>{
+    "opener": &lt;string: word>,
+    "games": &lt;int>,
+    "mean_goes": &lt;float>,
+    "failure_rate": &lt;float: the share of games taking more than six goes>,
+    "distribution": { &lt;goes>: &lt;int: games>, ... }
+}</code></pre>
+
+<h3>Integrations</h3>
+<ul>
+    <li><code>GET /badge/&lt;client>.svg</code> a badge of the client's win rate and current streak, to embed in a profile</li>
+    <li><code>GET /feed/atom</code> and <code>GET /feed/rss</code> a summary of each day's games, never including an answer</li>
+    <li><code>GET /game/&lt;game_id>/image</code> the board as a PNG of coloured tiles, for sharing where images unfurl</li>
+    <li><code>GET /game/&lt;game_id>/qr[?format=png]</code> a QR code (SVG by default) linking to the game in the browser UI, to carry on with on another device</li>
+    <li><code>GET /overlay/&lt;game_id>[?format=json]</code> a live view of the board for stream overlays, without the answer</li>
+    <li><code>GET /discord/&lt;game_id></code> the game's result as a Discord message, <code>{ "content": &lt;string> }</code></li>
+    <li><code>POST /discord/interactions</code> answers Discord slash commands relayed with the secret the server is configured with as <code>Authorization: Bearer &lt;secret></code>: <code>new [mode]</code>, <code>guess &lt;game> &lt;word></code> on the user's own games, and <code>result &lt;game></code></li>
+    <li><code>POST /integrations/telegram</code> a Telegram bot webhook, registered with the secret token the server is configured with</li>
+</ul>
+
+<h3>GET /healthz</h3>
+<p>Answers <code>{"status": "ok"}</code> for as long as the process is up, without touching its storage, for liveness probes</p>
+
+<h3>GET /readyz</h3>
+<p>Whether games can be served, for readiness probes: the database can be reached, every migration has been applied and the word lists were loaded. It answers <code>503</code> until they all are, and <code>/t/&lt;tenant>/readyz</code> checks a tenant's own database</p>
+
+=> <pre><code>{
+    "ready": &lt;bool>,
+    "checks": {
+        "database": { "ok": &lt;bool>, "detail": &lt;string, only when it failed> },
+        "migrations": { ... },
+        "word_lists": { ... }
+    }
+}</code></pre>
+
+<h3>GET /metrics</h3>
+<p>Prometheus metrics: requests answered, by method, the first segment of their path and status, and how long they took, along with games created, guesses evaluated, guesses that weren't words, games in progress and requests that failed on the database. Paths no endpoint answers at are all counted as <code>other</code>, and each tenant has its own at <code>/t/&lt;tenant>/metrics</code></p>
+
+<h3>GET /openapi.json</h3>
+<p>An OpenAPI 3 document of the endpoints games are created and played through, <code>/stats</code>, and everything they answer with, to generate clients from</p>
+
+<h3>Versions</h3>
+<p>Every endpoint is served under <code>/v1</code> as well, e.g. <code>/v1/stats</code>, and answers with the version it was served as in <code>Api-Version</code>. Paths without a version are <code>/v1</code>. Changes that would break clients, to the shape of an answer or what a request does, are only ever made under a new version, leaving <code>/v1</code> as it is. A version that isn't served is <code>404</code></p>
+
+<h3>Request ids</h3>
+<p>Every response names its request in <code>X-Request-Id</code>, the id the request was sent with in <code>X-Request-Id</code> if any, otherwise a new one. Error envelopes and the server's logs name it alike, so quoting it is enough to find what happened to a request</p>
+
+<h3>Cross-origin requests</h3>
+<p>Scripts on the pages of the origins the server's configured to allow with <code>WORDLE_CORS_ORIGINS</code>, e.g. <code>https://wordle.example</code> or <code>*</code>, may call any endpoint. Preflight <code>OPTIONS</code> requests are answered with <code>204</code> and the methods and headers allowed, and other answers with <code>Access-Control-Allow-Origin</code>. Without the setting, no origin is allowed</p>
+
+<h3>Rate limits</h3>
+<p>Each address may make a burst of up to <code>WORDLE_RATE_LIMIT</code> requests, 600 unless configured otherwise, refilled steadily over a minute. Every response says what's left of the address's allowance in <code>X-RateLimit-Limit</code>, <code>X-RateLimit-Remaining</code> and <code>X-RateLimit-Reset</code>, the seconds until it's full again. Each client may likewise create and guess in games up to <code>WORDLE_CLIENT_RATE_LIMIT</code> times, 120 unless configured otherwise, whichever addresses they play from. Either limit is disabled with <code>0</code></p>
+
+<h3>Status codes</h3>
+<p>Errors are returned as <code>{ "code": &lt;string: code>, "error": &lt;string: message>, "request_id": &lt;string: id> }</code> with one of the following statuses. The <code>code</code>, e.g. <code>game_not_found</code>, never changes, while the message is written in English, Spanish or French, whichever <code>?lang=en|es|fr</code> or otherwise the <code>Accept-Language</code> header prefers, as is the welcome above:</p>
+<ul>
+    <li><code>400</code> the guess is not a valid word, or a game id, client or option is malformed</li>
+    <li><code>401</code> an admin endpoint was called without a valid admin token, a group's roster or dashboard without its key, or a registered client's games without theirs</li>
+    <li><code>404</code> the game does not exist, the client's profile page is private, the client isn't registered when only registered clients may play, or there's no endpoint at the path</li>
+    <li><code>409</code> the game is already finished and accepts no more guesses, or the client is already registered</li>
+    <li><code>422</code> the guess is a word, but the game's mode doesn't allow it</li>
+    <li><code>429</code> too many requests were made from the address, by the client to create and play games, or to the solver endpoints (search, hints, analysis, the bot and opener simulations), try again after <code>Retry-After</code> seconds</li>
+    <li><code>500</code> the server failed to read or write its storage</li>
+    <li><code>503</code> the server is in read-only maintenance mode, games can't be created or played</li>
+</ul>
+"#;
+
+fn handle_stats(service: &Service) -> Result<Response, ApiError> {
+    json_response(&service.get_stats()?)
+}
+
+/// The solver endpoints are limited by address, separately from, and more strictly than, the rest
+fn limit_solver(service: &Service, request: &Request) -> Result<(), ApiError> {
+    service.limit_solver(&request.remote_addr().ip().to_string())
+}
+
+fn handle_openers(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    let limit = match request.get_param("limit") {
+        None => 50,
+        Some(limit) => limit.parse().map_err(|_| ApiError::InvalidParam {
+            name: String::from("limit"),
+            value: limit,
+        })?,
+    };
+
+    json_response(&stats::opener_report(&config.connection()?, limit)?)
+}
+
+fn handle_opener(service: &Service, opener: &str) -> Result<Response, ApiError> {
+    json_response(&service.simulate_opener(opener)?)
+}
+
+/// `GET /play/<game_id>/guess/<word>[?format=compact]`, kept for clients that don't yet
+/// `POST /games/<game_id>/guesses`
+fn handle_play(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+    guess: &str,
+) -> Result<Response, ApiError> {
+    let compact = games::compact_param(request)?;
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+
+    games::answer_response(
+        &service.submit_guess(SubmitGuess {
+            game_id,
+            guess: guess.to_string(),
+        })?,
+        compact,
+    )
+}
+
+fn handle_game(service: &Service, game_id: &str) -> Result<Response, ApiError> {
+    json_response(&service.game_status(&GameId::parse(game_id)?)?)
+}
+
+fn handle_resign(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+
+    json_response(&service.resign(&game_id)?)
+}
+
+fn handle_hint(service: &Service, request: &Request, game_id: &str) -> Result<Response, ApiError> {
+    let level = match request.get_param("level") {
+        Some(level) => HintLevel::parse(&level)?,
+        // one of the answer's letters where it goes, unless less is asked for
+        None => HintLevel::Green,
+    };
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+
+    json_response(&service.hint(&game_id, level)?)
+}
+
+/// `GET /create/<client>` and, playing the word every client shares for the day,
+/// `GET /daily/<client>`
+fn handle_new_game(
+    service: &Service,
+    request: &Request,
+    client: &str,
+    daily: bool,
+) -> Result<Response, ApiError> {
+    let length = match request.get_param("length") {
+        None => None,
+        Some(length) => Some(length.parse().map_err(|_| ApiError::InvalidParam {
+            name: String::from("length"),
+            value: length,
+        })?),
+    };
+    let client = ClientId::parse(client)?;
+    registration::authorize_new_game(service, request, &client)?;
+
+    json_response(
+        &service.create_game(CreateGame {
+            client,
+            invalid_guesses_count: bool_param(request, "invalid_guesses_count")?,
+            mode: mode_param(request)?,
+            group: request
+                .get_param("group")
+                .map(|x| GroupId::parse(&x))
+                .transpose()?,
+            daily,
+            length,
+            language: request.get_param("lang"),
+            challenge: None,
+        })?,
+    )
+}
+
+/// An asset that is always embedded, so is never missing
+fn asset(config: &Config, name: &str) -> Response {
+    assets::serve(config, name).unwrap_or_else(Response::empty_404)
+}
+
+/// Where links handed out should point, the configured public url or else the host the request was
+/// made to
+fn base_url(config: &Config, request: &Request) -> String {
+    match &config.public_url {
+        Some(url) => url.trim_end_matches('/').to_string(),
+        None => format!("http://{}", request.header("Host").unwrap_or("localhost")),
+    }
+}
+
+/// The mode asked for by `mode`, or by `hard=true` as a shorthand for hard mode, which can't be
+/// asked for along with another mode
+fn mode_param(request: &Request) -> Result<Option<String>, ApiError> {
+    mode_choice(bool_param(request, "hard")?, request.get_param("mode"))
+}
+
+/// The mode asked for, by name or as `hard`, however the two were given
+fn mode_choice(hard: Option<bool>, mode: Option<String>) -> Result<Option<String>, ApiError> {
+    match (hard, mode) {
+        (Some(true), Some(mode)) if mode != "hard" => Err(ApiError::InvalidParam {
+            name: String::from("hard"),
+            value: format!("true with mode {mode}"),
+        }),
+        (Some(true), _) => Ok(Some(String::from("hard"))),
+        (_, mode) => Ok(mode),
+    }
+}
+
+/// An optional boolean query parameter, rejecting anything that isn't recognisably true or false
+fn bool_param(request: &Request, name: &str) -> Result<Option<bool>, ApiError> {
+    match request.get_param(name) {
+        None => Ok(None),
+        Some(value) => config::parse_bool(&value)
+            .map(Some)
+            .ok_or(ApiError::InvalidParam {
+                name: name.to_string(),
+                value,
+            }),
+    }
+}
+
+/// What every JSON body is sent as, errors included
+const JSON: &str = "application/json; charset=utf-8";
+
+fn json_response<T: Serialize>(value: &T) -> Result<Response, ApiError> {
+    Ok(Response::from_data(
+        JSON,
+        serde_json::to_string_pretty(value)?,
+    ))
+}
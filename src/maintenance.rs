@@ -0,0 +1,253 @@
+use crate::{db, stats};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize)]
+pub struct MaintenanceReport {
+    /// `["ok"]` when the database is sound, otherwise every problem `integrity_check` found
+    pub integrity: Vec<String>,
+    pub size_before: u64,
+    pub size_after: u64,
+    /// How many different openers the refreshed opener report covers
+    pub openers: usize,
+    /// How many finished games were moved to the archive
+    pub archived: usize,
+    /// How many finished games were deleted for being older than their client keeps them
+    pub expired: usize,
+    /// How many finished games had their guesses forgotten as their client keeps aggregates only
+    pub reduced: usize,
+    pub duration_ms: u128,
+}
+
+/// Checks the database's integrity, applies each client's retention preference, archives games
+/// finished more than `archive_after_days` ago, refreshes the opener report, then VACUUMs and
+/// ANALYZEs it
+pub fn run(
+    conn: &Connection,
+    archive_after_days: Option<u32>,
+) -> Result<MaintenanceReport, rusqlite::Error> {
+    let started = Instant::now();
+    let size_before = size(conn)?;
+
+    let integrity = conn
+        .prepare("PRAGMA integrity_check")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    let (expired, reduced) = retain(conn)?;
+    let archived = match archive_after_days {
+        Some(days) => archive(conn, days)?,
+        None => 0,
+    };
+    let openers = stats::refresh_opener_report(conn)?;
+    conn.execute_batch("VACUUM; ANALYZE;")?;
+
+    Ok(MaintenanceReport {
+        integrity,
+        size_before,
+        size_after: size(conn)?,
+        openers,
+        archived,
+        expired,
+        reduced,
+        duration_ms: started.elapsed().as_millis(),
+    })
+}
+
+/// Moves every game that was finished more than `days` ago out of the `game` table, which only
+/// games still being played need to be quick to find in, and into the archive. Games without a
+/// recorded time are left where they are.
+pub fn archive(conn: &Connection, days: u32) -> Result<usize, rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO game_archive (seq, game_id, client, word, goes, solved, state,
+            invalid_guesses_count, mode, mode_state, created_at, updated_at, max_guesses, length,
+            language)
+        SELECT rowid, game_id, client, word, goes, solved, state, invalid_guesses_count, mode,
+            mode_state, created_at, updated_at, max_guesses, length, language
+        FROM game
+        WHERE state != 'in_progress'
+            AND COALESCE(updated_at, created_at) < datetime('now', '-' || ?1 || ' days')",
+        [days],
+    )?;
+    // by id rather than by age, so nothing can come of the clock moving on in between
+    let archived = tx.execute(
+        "DELETE FROM game WHERE game_id IN (SELECT game_id FROM game_archive)",
+        [],
+    )?;
+    tx.commit()?;
+
+    Ok(archived)
+}
+
+/// Deletes the finished games of clients who only keep them for so many days once they're older
+/// than that, and forgets the guesses of those who only keep aggregates, returning how many games
+/// were deleted and how many had their guesses forgotten
+pub fn retain(conn: &Connection) -> Result<(usize, usize), rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+
+    let kept_for = tx
+        .prepare(
+            "SELECT client, json_extract(data, '$.privacy.retention.days') FROM profile
+            WHERE json_extract(data, '$.privacy.retention.days') IS NOT NULL",
+        )?
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut expired = 0;
+    for (client, days) in kept_for {
+        let games = tx
+            .prepare(
+                "SELECT game_id FROM game_history
+                WHERE client = ?1 AND state != 'in_progress'
+                    AND COALESCE(updated_at, created_at) < datetime('now', '-' || ?2 || ' days')",
+            )?
+            .query_map((&client, days), |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for game_id in &games {
+            forget_guesses(&tx, &client, game_id)?;
+            for table in [
+                "game_event",
+                "share_link",
+                "replay_token",
+                "player_group_game",
+                "daily_game",
+                "challenge_game",
+                "game",
+                "game_archive",
+            ] {
+                tx.execute(
+                    &format!("DELETE FROM {table} WHERE game_id = ?1"),
+                    [game_id],
+                )?;
+            }
+        }
+        expired += games.len();
+    }
+
+    let games = tx
+        .prepare(
+            "SELECT client, game_id FROM game_history
+            WHERE client IN (
+                    SELECT client FROM profile
+                    WHERE json_extract(data, '$.privacy.retention') = 'aggregates_only'
+                )
+                AND state != 'in_progress'
+                AND (
+                    json_array_length(mode_state, '$.revealed') > 0
+                    OR EXISTS (
+                        SELECT 1 FROM game_event
+                        WHERE game_event.game_id = game_history.game_id
+                            AND kind IN ('guessed', 'rejected')
+                    )
+                    OR EXISTS (
+                        SELECT 1 FROM rejected_guess WHERE rejected_guess.game_id = game_history.game_id
+                    )
+                    OR EXISTS (
+                        SELECT 1 FROM game_guess WHERE game_guess.game_id = game_history.game_id
+                    )
+                )",
+        )?
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (client, game_id) in &games {
+        forget_guesses(&tx, client, game_id)?;
+        for table in ["game", "game_archive"] {
+            tx.execute(
+                &format!(
+                    "UPDATE {table} SET mode_state = json_set(mode_state, '$.revealed', json('[]'))
+                    WHERE game_id = ?1 AND mode_state IS NOT NULL"
+                ),
+                [game_id],
+            )?;
+        }
+        // the game now starts out as it finished, so that replaying what's left of its events
+        // still ends up with the same game
+        tx.execute(
+            "UPDATE game_event SET data = (
+                SELECT json_set(
+                    game_event.data, '$.goes', goes, '$.state', state, '$.mode_state', json(mode_state)
+                )
+                FROM game_history WHERE game_id = ?1
+            )
+            WHERE game_id = ?1 AND kind = 'created'",
+            [game_id],
+        )?;
+        tx.execute(
+            "DELETE FROM game_event WHERE game_id = ?1 AND kind IN ('guessed', 'rejected')",
+            [game_id],
+        )?;
+    }
+    tx.commit()?;
+
+    Ok((expired, games.len()))
+}
+
+/// Deletes a game's guesses and rejected guesses, and its guesses from the guess log, which only
+/// lets go of them while their client is being erased
+fn forget_guesses(conn: &Connection, client: &str, game_id: &str) -> Result<(), rusqlite::Error> {
+    conn.execute("DELETE FROM rejected_guess WHERE game_id = ?1", [game_id])?;
+    conn.execute("DELETE FROM game_guess WHERE game_id = ?1", [game_id])?;
+    db::set_setting(conn, "erasing_client", Some(client))?;
+    conn.execute("DELETE FROM guess_log WHERE game_id = ?1", [game_id])?;
+    db::set_setting(conn, "erasing_client", None)?;
+
+    Ok(())
+}
+
+fn size(conn: &Connection) -> Result<u64, rusqlite::Error> {
+    let page_count: u64 = conn.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+    let page_size: u64 = conn.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+
+    Ok(page_count * page_size)
+}
+
+/// Runs maintenance in the background every `interval`, logging each report
+pub fn schedule(interval: Duration, database: String, archive_after_days: Option<u32>) {
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+
+        match db::open(&database).and_then(|conn| run(&conn, archive_after_days)) {
+            Ok(report) => eprintln!(
+                "maintenance: integrity {:?}, {} expired, {} reduced, {} archived, {} => {} bytes in {}ms",
+                report.integrity,
+                report.expired,
+                report.reduced,
+                report.archived,
+                report.size_before,
+                report.size_after,
+                report.duration_ms
+            ),
+            Err(error) => eprintln!("maintenance failed: {error}"),
+        }
+    });
+}
+
+const READ_ONLY: &str = "read_only";
+const DEFAULT_READ_ONLY_MESSAGE: &str =
+    "the server is in read-only maintenance mode, please try again later";
+
+/// The message given to anything that would write to the database while the instance is
+/// read-only, or `None` while writes are allowed
+pub fn read_only(conn: &Connection) -> Result<Option<String>, rusqlite::Error> {
+    db::setting(conn, READ_ONLY)
+}
+
+/// Puts the instance into read-only mode with an optional message, or with `None` takes it out
+pub fn set_read_only(conn: &Connection, message: Option<&str>) -> Result<(), rusqlite::Error> {
+    db::set_setting(
+        conn,
+        READ_ONLY,
+        message.map(|x| {
+            if x.is_empty() {
+                DEFAULT_READ_ONLY_MESSAGE
+            } else {
+                x
+            }
+        }),
+    )
+}
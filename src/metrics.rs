@@ -0,0 +1,194 @@
+use crate::error::ApiError;
+use crate::service::Service;
+use rouille::Response;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The upper bounds, in seconds, of the request latency histogram's buckets
+const BUCKETS: [f64; 10] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// What a service has done since it started, for Prometheus to scrape. Requests are labelled by
+/// the first segment of their path alone, so that no client, game or other id ever becomes a label.
+#[derive(Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(String, String), Route>>,
+    games_created: AtomicU64,
+    guesses_evaluated: AtomicU64,
+    invalid_guesses: AtomicU64,
+    storage_errors: AtomicU64,
+}
+
+#[derive(Default)]
+struct Route {
+    /// By status code
+    responses: BTreeMap<u16, u64>,
+    /// How many requests took no longer than each of the buckets' bounds
+    buckets: [u64; BUCKETS.len()],
+    seconds: f64,
+    count: u64,
+}
+
+impl Metrics {
+    /// Counts a request answered with `status` after `took`
+    pub fn observe(&self, method: &str, route: &str, status: u16, took: Duration) {
+        let seconds = took.as_secs_f64();
+        let mut requests = self.requests.lock().unwrap_or_else(|x| x.into_inner());
+        let route = requests
+            .entry((method.to_string(), route.to_string()))
+            .or_default();
+
+        *route.responses.entry(status).or_default() += 1;
+        for (count, bound) in route.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *count += 1;
+            }
+        }
+        route.seconds += seconds;
+        route.count += 1;
+    }
+
+    pub fn game_created(&self) {
+        self.games_created.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn guess_evaluated(&self) {
+        self.guesses_evaluated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn invalid_guess(&self) {
+        self.invalid_guesses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn storage_error(&self) {
+        self.storage_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Everything counted so far, along with how many games are in progress, in Prometheus' text
+    /// format
+    pub fn render(&self, active_games: usize) -> String {
+        let mut text = String::new();
+        let counters = [
+            (
+                "wordle_games_created_total",
+                "Games created",
+                &self.games_created,
+            ),
+            (
+                "wordle_guesses_evaluated_total",
+                "Guesses evaluated against a game's words",
+                &self.guesses_evaluated,
+            ),
+            (
+                "wordle_invalid_guesses_total",
+                "Guesses rejected for not being a word",
+                &self.invalid_guesses,
+            ),
+            (
+                "wordle_storage_errors_total",
+                "Requests that failed reading or writing the database",
+                &self.storage_errors,
+            ),
+        ];
+        for (name, help, counter) in counters {
+            let _ = writeln!(text, "# HELP {name} {help}\n# TYPE {name} counter");
+            let _ = writeln!(text, "{name} {}", counter.load(Ordering::Relaxed));
+        }
+        let _ = writeln!(
+            text,
+            "# HELP wordle_active_games Games in progress\n# TYPE wordle_active_games gauge\nwordle_active_games {active_games}"
+        );
+
+        let requests = self.requests.lock().unwrap_or_else(|x| x.into_inner());
+        text.push_str("# HELP wordle_requests_total Requests answered, by status\n");
+        text.push_str("# TYPE wordle_requests_total counter\n");
+        for ((method, route), stats) in requests.iter() {
+            for (status, count) in &stats.responses {
+                let _ = writeln!(
+                    text,
+                    "wordle_requests_total{{method=\"{method}\",route=\"{route}\",status=\"{status}\"}} {count}"
+                );
+            }
+        }
+        text.push_str("# HELP wordle_request_duration_seconds How long requests took to answer\n");
+        text.push_str("# TYPE wordle_request_duration_seconds histogram\n");
+        for ((method, route), stats) in requests.iter() {
+            let labels = format!("method=\"{method}\",route=\"{route}\"");
+            for (count, bound) in stats.buckets.iter().zip(BUCKETS) {
+                let _ = writeln!(
+                    text,
+                    "wordle_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                text,
+                "wordle_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                stats.count
+            );
+            let _ = writeln!(
+                text,
+                "wordle_request_duration_seconds_sum{{{labels}}} {}",
+                stats.seconds
+            );
+            let _ = writeln!(
+                text,
+                "wordle_request_duration_seconds_count{{{labels}}} {}",
+                stats.count
+            );
+        }
+
+        text
+    }
+}
+
+/// The label a request's path is counted under, its first segment, e.g. `/play` for
+/// `/play/<game_id>/guess/<word>`
+pub fn route(url: &str) -> String {
+    let first = url
+        .trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default();
+
+    format!("/{first}")
+}
+
+/// `GET /metrics` is every metric in Prometheus' text format
+pub fn handle(service: &Service) -> Result<Response, ApiError> {
+    Ok(Response::from_data(
+        "text/plain; version=0.0.4; charset=utf-8",
+        service.metrics_text()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::{route, Metrics};
+    use std::time::Duration;
+
+    #[test]
+    fn requests_are_counted_by_the_first_segment_of_their_path() {
+        assert_eq!(
+            route("/play/00000000-0000-4000-8000-000000000000/guess/crane"),
+            "/play"
+        );
+        assert_eq!(route("/"), "/");
+
+        let metrics = Metrics::default();
+        metrics.observe("GET", "/play", 200, Duration::from_millis(20));
+        metrics.observe("GET", "/play", 400, Duration::from_millis(2));
+        let text = metrics.render(3);
+
+        assert!(
+            text.contains("wordle_requests_total{method=\"GET\",route=\"/play\",status=\"400\"} 1")
+        );
+        assert!(text.contains(
+            "wordle_request_duration_seconds_bucket{method=\"GET\",route=\"/play\",le=\"0.01\"} 1"
+        ));
+        assert!(text
+            .contains("wordle_request_duration_seconds_count{method=\"GET\",route=\"/play\"} 2"));
+        assert!(text.contains("wordle_active_games 3"));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::config::Config;
+use crate::dictionary;
+use crate::error::ApiError;
+use crate::game;
+use crate::json_response;
+use rouille::{Request, Response};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex, MutexGuard};
+use wordle_core::{AnswerSelector, Selection};
+
+thread_local! {
+    /// What the request being handled on this thread has asked the mock server to pretend
+    static PRETENDING: RefCell<Pretend> = RefCell::default();
+}
+
+/// What a `serve --mock` server does in place of reaching the outside world, and how it's told
+/// what to pretend, for client and bot developers to test against the real binary. Nothing it's
+/// given outlives it.
+#[derive(Default)]
+pub struct Mock {
+    webhooks: Mutex<Vec<Webhook>>,
+}
+
+/// A webhook the mock server would have sent
+#[derive(Serialize, Clone, Debug)]
+pub struct Webhook {
+    pub url: String,
+    pub body: serde_json::Value,
+    /// Seconds since the Unix epoch
+    pub sent_at: u64,
+}
+
+impl Mock {
+    /// Keeps the webhook to be looked at instead of sending it, answering as the receiver would
+    /// if it had accepted it
+    pub fn capture(&self, url: &str, body: &str) -> u16 {
+        self.lock().push(Webhook {
+            url: url.to_string(),
+            body: serde_json::from_str(body).unwrap_or_else(|_| body.into()),
+            sent_at: game::now(),
+        });
+
+        200
+    }
+
+    pub fn webhooks(&self) -> Vec<Webhook> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Webhook>> {
+        // a panic while capturing leaves nothing half-written
+        self.webhooks.lock().unwrap_or_else(|x| x.into_inner())
+    }
+}
+
+#[derive(Default)]
+struct Pretend {
+    now: Option<u64>,
+    answer: Option<String>,
+}
+
+/// Pretends what the request's headers ask for until dropped: `X-Mock-Time`, the time, as seconds
+/// since the Unix epoch or `YYYY-MM-DD[THH:MM:SS[Z]]` in UTC, and `X-Mock-Answer`, the answer to
+/// any game the request starts
+pub struct Pretending(());
+
+impl Pretending {
+    pub fn new(request: &Request) -> Result<Pretending, ApiError> {
+        let invalid = |name: &str, value: &str| ApiError::InvalidParam {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        let now = match request.header("X-Mock-Time") {
+            Some(time) => Some(parse_time(time).ok_or_else(|| invalid("X-Mock-Time", time))?),
+            None => None,
+        };
+        let answer = match request.header("X-Mock-Answer") {
+            Some(answer) => {
+                let answer = answer.trim().to_lowercase();
+                if !dictionary::answers().contains(&answer) {
+                    return Err(invalid("X-Mock-Answer", &answer));
+                }
+
+                Some(answer)
+            }
+            None => None,
+        };
+
+        PRETENDING.with(|x| *x.borrow_mut() = Pretend { now, answer });
+
+        Ok(Pretending(()))
+    }
+}
+
+impl Drop for Pretending {
+    fn drop(&mut self) {
+        PRETENDING.with(|x| *x.borrow_mut() = Pretend::default());
+    }
+}
+
+/// The time the request being handled has asked to pretend it is, if any
+pub fn now() -> Option<u64> {
+    PRETENDING.with(|x| x.borrow().now)
+}
+
+/// Chooses the answer the request asked for, or else as `inner` would
+pub struct Answers {
+    pub inner: Arc<dyn AnswerSelector>,
+}
+
+impl AnswerSelector for Answers {
+    fn select<'a>(&self, answers: &'a [String], selection: &Selection) -> Option<&'a str> {
+        let requested = PRETENDING.with(|x| {
+            let answer = x.borrow().answer.clone()?;
+
+            answers.iter().find(|x| **x == answer)
+        });
+
+        requested
+            .map(String::as_str)
+            .or_else(|| self.inner.select(answers, selection))
+    }
+}
+
+/// Seconds since the Unix epoch, from either a number of them or a UTC date and time
+fn parse_time(time: &str) -> Option<u64> {
+    if let Ok(seconds) = time.parse() {
+        return Some(seconds);
+    }
+
+    let (date, time) = time.split_once('T').unwrap_or((time, "00:00:00"));
+    let day = game::parse_date(date)?;
+    let mut parts = time.trim_end_matches('Z').split(':');
+    let mut part = |max: u64| {
+        parts
+            .next()
+            .and_then(|x| x.parse::<u64>().ok())
+            .filter(|x| *x < max)
+    };
+    let seconds = part(24)? * 60 * 60 + part(60)? * 60 + part(60)?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    Some(day * 24 * 60 * 60 + seconds)
+}
+
+/// `GET /mock/webhooks` lists the webhooks a mock server would have sent, oldest first, and
+/// `DELETE` forgets them. Servers that aren't mocking have no such endpoint.
+pub fn handle_webhooks(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    let Some(mock) = &config.mock else {
+        return Err(ApiError::NotFound(request.url()));
+    };
+
+    if request.method() == "DELETE" {
+        mock.lock().clear();
+    }
+
+    json_response(&mock.webhooks())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock::parse_time;
+
+    #[test]
+    fn times_are_seconds_or_utc_dates() {
+        assert_eq!(parse_time("86400"), Some(86_400));
+        assert_eq!(parse_time("1970-01-02"), Some(86_400));
+        assert_eq!(parse_time("1970-01-02T01:00:30Z"), Some(86_400 + 3_630));
+        assert_eq!(parse_time("1970-01-02T24:00:00"), None);
+        assert_eq!(parse_time("1970-01-02T01:00"), None);
+        assert_eq!(parse_time("tomorrow"), None);
+    }
+}
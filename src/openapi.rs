@@ -0,0 +1,445 @@
+use crate::error::ApiError;
+use crate::json_response;
+use rouille::Response;
+use serde_json::{json, Value};
+
+/// A reference to one of the document's schemas
+fn schema(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+/// A JSON answer of the schema, along with the errors every endpoint can fail with
+fn answers(description: &str, name: &str) -> Value {
+    json!({
+        "200": {
+            "description": description,
+            "content": { "application/json": { "schema": schema(name) } }
+        },
+        "default": {
+            "description": "The request failed, the status saying how",
+            "content": { "application/json": { "schema": schema("Error") } }
+        }
+    })
+}
+
+fn path_param(name: &str, description: &str) -> Value {
+    json!({
+        "name": name,
+        "in": "path",
+        "required": true,
+        "description": description,
+        "schema": { "type": "string" }
+    })
+}
+
+fn query_param(name: &str, description: &str, schema: Value) -> Value {
+    json!({ "name": name, "in": "query", "description": description, "schema": schema })
+}
+
+/// A JSON request body of the schema
+fn body(name: &str) -> Value {
+    json!({
+        "required": true,
+        "content": { "application/json": { "schema": schema(name) } }
+    })
+}
+
+/// The options a game can be created with as query parameters
+fn game_params() -> Vec<Value> {
+    vec![
+        query_param(
+            "invalid_guesses_count",
+            "Whether a guess that isn't a word uses up a go",
+            json!({ "type": "boolean" }),
+        ),
+        query_param(
+            "mode",
+            "The mode to play in, classic unless asked for",
+            json!({ "type": "string", "enum": ["classic", "hard", "adversarial", "multi"] }),
+        ),
+        query_param(
+            "hard",
+            "Shorthand for the hard mode",
+            json!({ "type": "boolean" }),
+        ),
+        query_param(
+            "group",
+            "Plays the group's word for the day",
+            json!({ "type": "string" }),
+        ),
+        query_param(
+            "length",
+            "How many letters the word has",
+            json!({ "type": "integer" }),
+        ),
+        query_param(
+            "lang",
+            "The language of the words and of messages",
+            json!({ "type": "string" }),
+        ),
+    ]
+}
+
+fn compact_param() -> Value {
+    query_param(
+        "format",
+        "`compact` writes every evaluation as a digit per character",
+        json!({ "type": "string", "enum": ["json", "compact"] }),
+    )
+}
+
+/// An OpenAPI 3 description of the endpoints games are played through, and of what they answer,
+/// for generating clients from. The HTML reference at `/` covers the rest of the API.
+pub fn document() -> Value {
+    let game_id = path_param("game_id", "The game");
+    let client = path_param("client", "Whoever is playing, any string");
+    let mut create_params = vec![client.clone()];
+    create_params.extend(game_params());
+    // the daily word is the same for everyone, so only the mode and the rules are up to the client
+    let daily_params = create_params[..4].to_vec();
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Wordle-API",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "servers": [{ "url": "/v1" }],
+        // only registered clients need their key, so every endpoint can be called without one
+        "security": [{}, { "clientKey": [] }],
+        "paths": {
+            "/clients": {
+                "post": {
+                    "operationId": "registerClient",
+                    "requestBody": body("Register"),
+                    "responses": answers(
+                        "The client's key, which is never shown again",
+                        "Registration"
+                    )
+                }
+            },
+            "/clients/{client}": {
+                "get": {
+                    "operationId": "getClient",
+                    "parameters": [client.clone()],
+                    "responses": answers("Who registered the client, and with what", "RegisteredClient")
+                }
+            },
+            "/games": {
+                "post": {
+                    "operationId": "createGame",
+                    "requestBody": body("NewGame"),
+                    "responses": answers(
+                        "The new game, or the daily or group game already started",
+                        "GameIdentity"
+                    )
+                }
+            },
+            "/games/{game_id}/guesses": {
+                "post": {
+                    "operationId": "guess",
+                    "parameters": [game_id, compact_param()],
+                    "requestBody": body("NewGuess"),
+                    "responses": answers("The guess's evaluation and how the game stands", "Answer")
+                }
+            },
+            "/create/{client}": {
+                "get": {
+                    "operationId": "createGameByGet",
+                    "deprecated": true,
+                    "parameters": create_params,
+                    "responses": answers("The new game", "GameIdentity")
+                }
+            },
+            "/daily/{client}": {
+                "get": {
+                    "operationId": "playDaily",
+                    "deprecated": true,
+                    "parameters": daily_params,
+                    "responses": answers("The client's game of the day's word", "GameIdentity")
+                }
+            },
+            "/play/{game_id}/guess/{guess}": {
+                "get": {
+                    "operationId": "guessByGet",
+                    "deprecated": true,
+                    "parameters": [
+                        game_id,
+                        path_param("guess", "The word guessed"),
+                        compact_param()
+                    ],
+                    "responses": answers("The guess's evaluation and how the game stands", "Answer")
+                }
+            },
+            "/game/{game_id}": {
+                "get": {
+                    "operationId": "getGame",
+                    "parameters": [game_id],
+                    "responses": answers("How the game stands", "GameStatus")
+                }
+            },
+            "/game/{game_id}/resign": {
+                "post": {
+                    "operationId": "resign",
+                    "parameters": [game_id],
+                    "responses": answers("The forfeited game, with its answer", "GameStatus")
+                }
+            },
+            "/game/{game_id}/hint": {
+                "get": {
+                    "operationId": "hint",
+                    "parameters": [
+                        game_id,
+                        query_param(
+                            "level",
+                            "How much the hint gives away",
+                            json!({ "type": "string", "enum": ["half", "yellow", "green"], "default": "green" })
+                        )
+                    ],
+                    "responses": answers("The hint, in exchange for a go", "Hint")
+                }
+            },
+            "/stats": {
+                "get": {
+                    "operationId": "stats",
+                    "responses": {
+                        "200": {
+                            "description": "Every client's stats",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "type": "array", "items": schema("ClientStats") }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "clientKey": { "type": "http", "scheme": "bearer" }
+            }
+        }
+    })
+}
+
+fn schemas() -> Value {
+    let nullable = |schema: Value| {
+        let mut schema = schema;
+        schema["nullable"] = json!(true);
+        schema
+    };
+    let evaluation = json!({ "type": "array", "items": schema("CharMatch") });
+
+    json!({
+        "GameState": {
+            "type": "string",
+            "enum": ["in_progress", "won", "lost", "forfeited", "expired"]
+        },
+        "MatchType": { "type": "string", "enum": ["None", "Partial", "Perfect"] },
+        "CharMatch": {
+            "type": "object",
+            "required": ["index", "character", "match_type"],
+            "properties": {
+                "index": { "type": "integer" },
+                "character": { "type": "string" },
+                "match_type": schema("MatchType")
+            }
+        },
+        "GameOptions": {
+            "type": "object",
+            "required": ["invalid_guesses_count", "mode", "max_guesses", "length", "language"],
+            "properties": {
+                "invalid_guesses_count": { "type": "boolean" },
+                "mode": { "type": "string" },
+                "max_guesses": nullable(json!({ "type": "integer" })),
+                "length": { "type": "integer" },
+                "language": { "type": "string" }
+            }
+        },
+        "NewGame": {
+            "type": "object",
+            "required": ["client"],
+            "additionalProperties": false,
+            "properties": {
+                "client": { "type": "string" },
+                "invalid_guesses_count": { "type": "boolean" },
+                "mode": { "type": "string" },
+                "hard": { "type": "boolean" },
+                "group": { "type": "string" },
+                "daily": { "type": "boolean" },
+                "length": { "type": "integer" },
+                "lang": { "type": "string" }
+            }
+        },
+        "Register": {
+            "type": "object",
+            "required": ["client"],
+            "additionalProperties": false,
+            "properties": {
+                "client": { "type": "string" },
+                "display_name": { "type": "string" },
+                "metadata": { "type": "object" }
+            }
+        },
+        "Registration": {
+            "type": "object",
+            "required": ["client", "key", "display_name", "metadata"],
+            "properties": {
+                "client": { "type": "string" },
+                "key": { "type": "string" },
+                "display_name": nullable(json!({ "type": "string" })),
+                "metadata": { "type": "object" }
+            }
+        },
+        "RegisteredClient": {
+            "type": "object",
+            "required": ["client", "player", "metadata", "registered_at"],
+            "properties": {
+                "client": { "type": "string" },
+                "player": { "type": "string" },
+                "metadata": { "type": "object" },
+                "registered_at": { "type": "integer" }
+            }
+        },
+        "NewGuess": {
+            "type": "object",
+            "required": ["guess"],
+            "additionalProperties": false,
+            "properties": { "guess": { "type": "string" } }
+        },
+        "GameIdentity": {
+            "type": "object",
+            "required": ["game_id", "state", "options"],
+            "properties": {
+                "game_id": { "type": "string", "format": "uuid" },
+                "state": schema("GameState"),
+                "options": schema("GameOptions"),
+                "puzzle_date": { "type": "string", "format": "date" },
+                "puzzle_number": { "type": "integer" }
+            }
+        },
+        "PlayedGuess": {
+            "type": "object",
+            "required": ["go", "guess", "evaluation"],
+            "properties": {
+                "go": { "type": "integer" },
+                "guess": { "type": "string" },
+                "evaluation": evaluation
+            }
+        },
+        "BoardEvaluation": {
+            "type": "object",
+            "required": ["solved", "answer", "evaluation"],
+            "properties": {
+                "solved": { "type": "boolean" },
+                "answer": nullable(json!({ "type": "string" })),
+                "evaluation": evaluation
+            }
+        },
+        "Difficulty": {
+            "type": "object",
+            "required": ["rating", "average_goes"],
+            "properties": {
+                "rating": { "type": "integer" },
+                "average_goes": nullable(json!({ "type": "number" }))
+            }
+        },
+        "Answer": {
+            "type": "object",
+            "required": [
+                "state", "solved", "lost", "answer", "guess", "goes", "evaluation", "guesses",
+                "repeated", "letters"
+            ],
+            "properties": {
+                "state": schema("GameState"),
+                "solved": { "type": "boolean" },
+                "lost": { "type": "boolean" },
+                "answer": nullable(json!({ "type": "string" })),
+                "guess": { "type": "string" },
+                "goes": { "type": "integer" },
+                "evaluation": evaluation,
+                "boards": { "type": "array", "items": schema("BoardEvaluation") },
+                "guesses": { "type": "array", "items": schema("PlayedGuess") },
+                "difficulty": schema("Difficulty"),
+                "achievements": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                        "enum": ["first_win", "solved_in_two", "thirty_streak", "hardest_word"]
+                    }
+                },
+                "repeated": { "type": "boolean" },
+                "letters": { "type": "object", "additionalProperties": schema("MatchType") },
+                "share": { "type": "string" }
+            }
+        },
+        "GameStatus": {
+            "type": "object",
+            "required": [
+                "game_id", "state", "solved", "lost", "options", "goes", "remaining", "answer",
+                "guesses", "hints"
+            ],
+            "properties": {
+                "game_id": { "type": "string", "format": "uuid" },
+                "state": schema("GameState"),
+                "solved": { "type": "boolean" },
+                "lost": { "type": "boolean" },
+                "options": schema("GameOptions"),
+                "goes": { "type": "integer" },
+                "remaining": nullable(json!({ "type": "integer" })),
+                "answer": nullable(json!({ "type": "string" })),
+                "answers": { "type": "array", "items": { "type": "string" } },
+                "guesses": { "type": "array", "items": schema("PlayedGuess") },
+                "hints": { "type": "integer" }
+            }
+        },
+        "Hint": {
+            "type": "object",
+            "required": ["level", "penalty", "letter", "present", "candidates"],
+            "properties": {
+                "level": { "type": "string", "enum": ["half", "yellow", "green"] },
+                "penalty": { "type": "integer" },
+                "letter": { "type": "string" },
+                "present": { "type": "boolean" },
+                "position": { "type": "integer" },
+                "candidates": { "type": "integer" }
+            }
+        },
+        "ClientStats": {
+            "type": "object",
+            "required": [
+                "client", "player", "avg_goes", "max_goes", "num_solved", "num_games", "num_rejected",
+                "hint_penalty", "num_assisted", "num_assisted_solved", "num_forfeited"
+            ],
+            "properties": {
+                "client": { "type": "string" },
+                "player": { "type": "string" },
+                "avg_goes": nullable(json!({ "type": "number" })),
+                "max_goes": nullable(json!({ "type": "integer" })),
+                "num_solved": { "type": "integer" },
+                "num_games": { "type": "integer" },
+                "num_rejected": { "type": "integer" },
+                "hint_penalty": { "type": "integer" },
+                "num_assisted": { "type": "integer" },
+                "num_assisted_solved": { "type": "integer" },
+                "num_forfeited": { "type": "integer" }
+            }
+        },
+        "Error": {
+            "type": "object",
+            "required": ["code", "error", "request_id"],
+            "properties": {
+                "code": { "type": "string" },
+                "error": { "type": "string" },
+                "request_id": { "type": "string" }
+            }
+        }
+    })
+}
+
+/// `GET /openapi.json`
+pub fn handle() -> Result<Response, ApiError> {
+    json_response(&document())
+}
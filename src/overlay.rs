@@ -0,0 +1,104 @@
+use crate::error::ApiError;
+use crate::game::{GameId, GameState};
+use crate::json_response;
+use crate::service::Service;
+use crate::share;
+use rouille::{Request, Response};
+use serde::Serialize;
+use std::fmt::Write;
+use wordle_core::{CharMatch, MatchType};
+
+/// How often the page reloads itself, often enough to feel live on stream
+const REFRESH_SECONDS: u32 = 2;
+
+/// The board as viewers see it, never including the answer
+#[derive(Serialize)]
+struct Overlay {
+    game_id: GameId,
+    /// The client's display name, or the client itself if they haven't set one
+    player: String,
+    mode: &'static str,
+    state: GameState,
+    goes: usize,
+    rows: Vec<Vec<CharMatch>>,
+}
+
+/// `GET /overlay/<game_id>` is a self-refreshing page on a transparent background, sized for an
+/// OBS browser source, or with `?format=json` the same board as JSON for overlays of your own
+pub fn handle(service: &Service, request: &Request, game_id: &str) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    let game = service.find_game(&game_id)?;
+
+    let overlay = Overlay {
+        game_id,
+        player: service.display_name(&game.client)?,
+        mode: game.mode.name(),
+        state: game.state,
+        goes: game.goes,
+        rows: game
+            .mode_state
+            .revealed
+            .iter()
+            .map(|(_, evaluation)| evaluation.clone())
+            .collect(),
+    };
+
+    match request.get_param("format").as_deref() {
+        None | Some("html") => Ok(Response::html(page(&overlay, &share::outcome(&game)))),
+        Some("json") => json_response(&overlay),
+        Some(format) => Err(ApiError::InvalidParam {
+            name: String::from("format"),
+            value: format.to_string(),
+        }),
+    }
+}
+
+fn page(overlay: &Overlay, outcome: &str) -> String {
+    let mut rows = String::new();
+    for row in &overlay.rows {
+        rows.push_str("<div class=\"row\">");
+        for x in row {
+            let class = match x.match_type {
+                MatchType::Perfect => "perfect",
+                MatchType::Partial => "partial",
+                MatchType::None => "none",
+            };
+            // guesses are always dictionary words, there's nothing to escape
+            let _ = write!(rows, "<div class=\"tile {class}\">{}</div>", x.character);
+        }
+        rows.push_str("</div>");
+    }
+
+    // once the game is over there's nothing left to refresh for
+    let refresh = if overlay.state.is_finished() {
+        String::new()
+    } else {
+        format!("<meta http-equiv=\"refresh\" content=\"{REFRESH_SECONDS}\">")
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+{refresh}
+<style>
+    body {{ margin: 0; background: transparent; font-family: sans-serif; color: #fff; width: 320px; text-shadow: 0 0 3px #000; }}
+    .row {{ display: flex; gap: 6px; margin-bottom: 6px; }}
+    .tile {{ width: 58px; height: 58px; display: flex; align-items: center; justify-content: center; font-size: 32px; font-weight: bold; text-transform: uppercase; border-radius: 4px; }}
+    .none {{ background: #3a3a3c; }}
+    .partial {{ background: #b59f3b; }}
+    .perfect {{ background: #538d4e; }}
+    p {{ margin: 0 0 8px; font-size: 20px; }}
+</style>
+</head>
+<body>
+<p>{player} · {mode} · {outcome}</p>
+{rows}
+</body>
+</html>
+"#,
+        player = share::escape(&overlay.player),
+        mode = overlay.mode,
+    )
+}
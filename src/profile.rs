@@ -0,0 +1,250 @@
+use crate::achievements::Granted;
+use crate::error::ApiError;
+use crate::friends::Finish;
+use crate::game::{self, ClientId};
+use crate::json_response;
+use crate::registration;
+use crate::service::Service;
+use crate::share::escape;
+use crate::stats::Record;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+const MAX_DISPLAY_NAME: usize = 32;
+const MAX_AVATAR_URL: usize = 512;
+const MAX_BIO: usize = 280;
+/// How many of the latest finished games the public page lists
+pub const RECENT_GAMES: usize = 10;
+
+/// How a client presents themselves, and the options their new games start with. Every field is
+/// optional, anything unset falls back to showing the raw client, or to the server's defaults.
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub display_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub bio: Option<String>,
+    pub settings: Settings,
+    pub privacy: Privacy,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// The mode new games are played in unless another is asked for
+    pub mode: Option<String>,
+    pub invalid_guesses_count: Option<bool>,
+}
+
+/// What the client's public page shows, which is everything unless they hide it
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Privacy {
+    /// Hides the page altogether, it's answered as if there were no such client
+    pub private: bool,
+    pub hide_stats: bool,
+    pub hide_recent_games: bool,
+    pub hide_achievements: bool,
+    pub retention: Retention,
+}
+
+/// How long the client's finished games are kept once maintenance has run, which is forever
+/// unless they say otherwise
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Retention {
+    #[default]
+    Forever,
+    /// Deleted, with everything else about them, once they've been finished this many days
+    Days(u32),
+    /// Kept only for what their stats are worked out from, the day, mode, outcome and goes, with
+    /// their guesses, rejected guesses among them, forgotten
+    AggregatesOnly,
+}
+
+/// The slices of a client's profile, stats and games that they've left public
+#[derive(Serialize, Debug)]
+pub struct PublicProfile {
+    pub client: ClientId,
+    pub player: String,
+    pub avatar_url: Option<String>,
+    pub bio: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<Record>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recent_games: Option<Vec<Finish>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub achievements: Option<Vec<Granted>>,
+}
+
+impl Profile {
+    /// Refuses anything too long to show, or that can't be shown safely
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let invalid = |name: &str, value: &str| ApiError::InvalidParam {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let printable = |value: &str, max: usize| {
+            value.chars().count() <= max && !value.chars().any(char::is_control)
+        };
+
+        if let Some(name) = &self.display_name {
+            if name.trim().is_empty() || !printable(name, MAX_DISPLAY_NAME) {
+                return Err(invalid("display_name", name));
+            }
+        }
+        if let Some(url) = &self.avatar_url {
+            let web = url.starts_with("https://") || url.starts_with("http://");
+            if !web || !printable(url, MAX_AVATAR_URL) || url.contains(char::is_whitespace) {
+                return Err(invalid("avatar_url", url));
+            }
+        }
+        if let Some(bio) = &self.bio {
+            // a bio may run over several lines
+            if bio.chars().count() > MAX_BIO || bio.chars().any(|x| x.is_control() && x != '\n') {
+                return Err(invalid("bio", bio));
+            }
+        }
+        if self.privacy.retention == Retention::Days(0) {
+            return Err(invalid("retention", "0"));
+        }
+        if let Some(mode) = &self.settings.mode {
+            if game::modes().get(mode).is_none() {
+                return Err(invalid("mode", mode));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// What to call the client wherever they're shown
+    pub fn name_or<'a>(&'a self, client: &'a ClientId) -> &'a str {
+        self.display_name.as_deref().unwrap_or(client.as_str())
+    }
+}
+
+/// `GET /profiles/<client>` is the client's profile, with every field unset if they've never saved
+/// one
+pub fn handle_get(service: &Service, client: &str) -> Result<Response, ApiError> {
+    json_response(&service.profile(&ClientId::parse(client)?)?)
+}
+
+/// `PUT /profiles/<client>` replaces the client's profile with the one in the body
+pub fn handle_put(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    let profile: Profile = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    service.save_profile(&client, &profile)?;
+
+    json_response(&profile)
+}
+
+/// `GET /profile/<client>` is the client's public page, for sharing a link rather than a
+/// screenshot, or with `?format=json` the same slices as JSON
+pub fn handle_page(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    let profile = service.public_profile(&client)?;
+
+    match request.get_param("format").as_deref() {
+        None | Some("html") => Ok(Response::html(page(&profile))),
+        Some("json") => json_response(&profile),
+        Some(format) => Err(ApiError::InvalidParam {
+            name: String::from("format"),
+            value: format.to_string(),
+        }),
+    }
+}
+
+fn page(profile: &PublicProfile) -> String {
+    let player = escape(&profile.player);
+    let mut body = String::new();
+
+    if let Some(url) = &profile.avatar_url {
+        let _ = write!(
+            body,
+            "<img class=\"avatar\" src=\"{}\" alt=\"\">",
+            escape(url)
+        );
+    }
+    let _ = write!(body, "<h1>{player}</h1>");
+    if let Some(bio) = &profile.bio {
+        let _ = write!(body, "<p>{}</p>", escape(bio).replace('\n', "<br>"));
+    }
+
+    let summary = match &profile.stats {
+        Some(record) => {
+            let summary = match record.win_rate() {
+                Some(rate) => format!(
+                    "{} won of {} · {:.0}% · streak {}",
+                    record.num_won,
+                    record.num_finished,
+                    rate * 100.0,
+                    record.streak
+                ),
+                None => String::from("No games finished yet"),
+            };
+            let _ = write!(body, "<p class=\"stats\">{summary}</p>");
+
+            summary
+        }
+        None => String::from("Playing Wordle-API"),
+    };
+
+    if let Some(games) = &profile.recent_games {
+        body.push_str("<h2>Recent games</h2>");
+        for game in games {
+            let _ = write!(
+                body,
+                "<div class=\"game\"><p>{} · {} · {} in {}</p><pre>{}</pre></div>",
+                game.day,
+                game.mode,
+                game.state.as_str(),
+                game.goes,
+                game.grid.join("\n")
+            );
+        }
+    }
+
+    if let Some(achievements) = &profile.achievements {
+        body.push_str("<h2>Achievements</h2><ul>");
+        for granted in achievements {
+            let _ = write!(body, "<li>{}</li>", granted.description);
+        }
+        body.push_str("</ul>");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{player} on Wordle-API</title>
+<meta property="og:title" content="{player} on Wordle-API">
+<meta property="og:description" content="{summary}">
+<style>
+    body {{ font-family: sans-serif; max-width: 480px; margin: 32px auto; padding: 0 16px; }}
+    .avatar {{ width: 96px; height: 96px; border-radius: 50%; object-fit: cover; }}
+    .stats {{ font-size: 20px; }}
+    .game pre {{ font-size: 20px; line-height: 1.1; margin: 0 0 16px; }}
+    .game p {{ margin: 0 0 4px; color: #555; }}
+</style>
+</head>
+<body>
+{body}
+</body>
+</html>
+"#
+    )
+}
@@ -0,0 +1,619 @@
+//! QR codes for links, encoded in byte mode at error correction level M, following ISO/IEC 18004
+
+use crate::base_url;
+use crate::error::ApiError;
+use crate::game::GameId;
+use crate::image::{self, Rgb};
+use crate::service::Service;
+use rouille::{Request, Response};
+
+/// Pixels per module in PNG codes
+const SCALE: usize = 8;
+
+/// Error correction codewords in each block, by version, at level M
+const ECC_CODEWORDS_PER_BLOCK: [usize; 41] = [
+    0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28,
+    28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+];
+
+/// Blocks the codewords are split into, by version, at level M
+const ERROR_CORRECTION_BLOCKS: [usize; 41] = [
+    0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23,
+    25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+];
+
+/// How level M is written into the format information
+const LEVEL_M: u32 = 0;
+
+/// `GET /game/<game_id>/qr[?format=png]` is a QR code linking to the game in the browser UI, so it
+/// can be picked up on a phone. The link is absolute, under the configured public url or else the
+/// host the request was made to.
+pub fn handle(service: &Service, request: &Request, game_id: &str) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    service.find_game(&game_id)?;
+
+    let link = format!("{}/ui?game={game_id}", base_url(service.config(), request));
+    let code = QrCode::encode(link.as_bytes()).ok_or_else(|| ApiError::InvalidParam {
+        name: String::from("url"),
+        value: link.clone(),
+    })?;
+
+    match request.get_param("format").as_deref() {
+        None | Some("svg") => Ok(Response::from_data("image/svg+xml", code.svg())),
+        Some("png") => Ok(Response::from_data("image/png", code.png())),
+        Some(format) => Err(ApiError::InvalidParam {
+            name: String::from("format"),
+            value: format.to_string(),
+        }),
+    }
+}
+
+/// A square grid of modules, `true` for dark
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// The smallest code holding `data`, or `None` if it's too long for any version
+    pub fn encode(data: &[u8]) -> Option<QrCode> {
+        let version = (1..=40).find(|&version| {
+            let count_bits = if version < 10 { 8 } else { 16 };
+
+            4 + count_bits + data.len() * 8 <= data_codewords(version) * 8
+        })?;
+
+        let mut code = QrCode {
+            size: version * 4 + 17,
+            modules: Vec::new(),
+            function: Vec::new(),
+        };
+        code.modules = vec![false; code.size * code.size];
+        code.function = vec![false; code.size * code.size];
+
+        code.draw_function_patterns(version);
+        let codewords = interleave(version, &codewords(version, data));
+        code.draw_codewords(&codewords);
+
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                code.apply_mask(mask);
+                code.draw_format(mask);
+                let penalty = code.penalty();
+                code.apply_mask(mask);
+
+                penalty
+            })
+            .unwrap_or(0);
+        code.apply_mask(mask);
+        code.draw_format(mask);
+
+        Some(code)
+    }
+
+    pub fn dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    /// The code as an SVG of one unit per module, within the quiet zone the standard requires
+    pub fn svg(&self) -> String {
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.dark(x, y) {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x + 4, y + 4));
+                }
+            }
+        }
+
+        let extent = self.size + 8;
+
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {extent} {extent}" shape-rendering="crispEdges"><rect width="100%" height="100%" fill="#fff"/><path d="{path}" fill="#000"/></svg>
+"##
+        )
+    }
+
+    /// The code as a PNG, within the quiet zone
+    pub fn png(&self) -> Vec<u8> {
+        let extent = (self.size + 8) * SCALE;
+        let mut pixels: Vec<Rgb> = vec![[0xff; 3]; extent * extent];
+
+        for y in 0..extent {
+            for x in 0..extent {
+                let module = |z: usize| (z / SCALE).checked_sub(4).filter(|z| *z < self.size);
+                if let (Some(mx), Some(my)) = (module(x), module(y)) {
+                    if self.dark(mx, my) {
+                        pixels[y * extent + x] = [0; 3];
+                    }
+                }
+            }
+        }
+
+        image::encode_png(extent, extent, &pixels)
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4..=4_isize {
+                for dx in -4..=4_isize {
+                    let (xx, yy) = (x as isize + dx, y as isize + dy);
+                    if (0..size as isize).contains(&xx) && (0..size as isize).contains(&yy) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // the finder patterns already occupy three of the corners
+                if (i, j) == (0, 0) || (i, j) == (0, last) || (i, j) == (last, 0) {
+                    continue;
+                }
+
+                for dy in -2..=2_isize {
+                    for dx in -2..=2_isize {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function(
+                            (x as isize + dx) as usize,
+                            (y as isize + dy) as usize,
+                            dark,
+                        );
+                    }
+                }
+            }
+        }
+
+        // reserved now so that codewords avoid them, drawn properly once the mask is chosen
+        self.draw_format(0);
+
+        if version >= 7 {
+            let mut remainder = version as u32;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+            }
+            let bits = (version as u32) << 12 | remainder;
+
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format(&mut self, mask: u32) {
+        let data = LEVEL_M << 3 | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Fills every module not used by a function pattern, in the standard's zigzag of two-module
+    /// wide columns from the bottom right
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+
+        loop {
+            // the vertical timing pattern gets a column to itself
+            if right == 6 {
+                right = 5;
+            }
+
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// Masks are their own inverse, so applying one twice removes it
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+
+                if invert && !self.function[y * self.size + x] {
+                    self.modules[y * self.size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// The standard's penalties for long runs, solid blocks and an imbalance of dark and light,
+    /// which make a code harder to scan
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+
+        for horizontal in [true, false] {
+            for a in 0..size {
+                let mut run = 0;
+                let mut previous = None;
+
+                for b in 0..size {
+                    let dark = if horizontal {
+                        self.dark(b, a)
+                    } else {
+                        self.dark(a, b)
+                    };
+                    if Some(dark) == previous {
+                        run += 1;
+                    } else {
+                        run = 1;
+                        previous = Some(dark);
+                    }
+
+                    if run == 5 {
+                        penalty += 3;
+                    } else if run > 5 {
+                        penalty += 1;
+                    }
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let dark = self.dark(x, y);
+                if dark == self.dark(x + 1, y)
+                    && dark == self.dark(x, y + 1)
+                    && dark == self.dark(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let dark = self.modules.iter().filter(|x| **x).count();
+        let total = size * size;
+        // steps of 5% away from an even split
+        let k = (dark * 20)
+            .abs_diff(total * 10)
+            .div_ceil(total)
+            .saturating_sub(1);
+
+        penalty + k * 10
+    }
+}
+
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        result -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+
+    result
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[version] * ERROR_CORRECTION_BLOCKS[version]
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+
+    let count = version / 7 + 2;
+    let step = if version == 32 {
+        26
+    } else {
+        (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2
+    };
+
+    let mut positions = vec![6];
+    let mut position = version * 4 + 17 - 7;
+    for _ in 0..count - 1 {
+        positions.insert(1, position);
+        position -= step;
+    }
+
+    positions
+}
+
+/// The mode indicator, length and data, terminated and padded out to the version's capacity
+fn codewords(version: usize, data: &[u8]) -> Vec<u8> {
+    let mut bits = Vec::new();
+    let mut push = |value: usize, length: usize| {
+        for i in (0..length).rev() {
+            bits.push((value >> i) & 1 == 1);
+        }
+    };
+
+    push(0b0100, 4);
+    push(data.len(), if version < 10 { 8 } else { 16 });
+    for &byte in data {
+        push(byte.into(), 8);
+    }
+
+    let capacity = data_codewords(version) * 8;
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    bits.extend(std::iter::repeat_n(false, (8 - bits.len() % 8) % 8));
+
+    let mut codewords = bits
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0, |acc, &bit| acc << 1 | u8::from(bit)))
+        .collect::<Vec<u8>>();
+    for pad in [0xec, 0x11].into_iter().cycle() {
+        if codewords.len() >= capacity / 8 {
+            break;
+        }
+        codewords.push(pad);
+    }
+
+    codewords
+}
+
+/// Splits the data into blocks, adds each block's error correction, and interleaves them
+fn interleave(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks = ERROR_CORRECTION_BLOCKS[version];
+    let ecc_length = ECC_CODEWORDS_PER_BLOCK[version];
+    let raw = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw % blocks;
+    let short_length = raw / blocks;
+
+    let divisor = reed_solomon_divisor(ecc_length);
+    let mut split = Vec::with_capacity(blocks);
+    let mut start = 0;
+    for i in 0..blocks {
+        let length = short_length - ecc_length + usize::from(i >= short_blocks);
+        let mut block = data[start..start + length].to_vec();
+        start += length;
+
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        // short blocks are padded so every block can be read column by column
+        if i < short_blocks {
+            block.push(0);
+        }
+        block.extend(ecc);
+        split.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_length - ecc_length || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+
+    result
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0; degree];
+    result[degree - 1] = 1;
+
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = multiply(root, 0x02);
+    }
+
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (x, &coefficient) in result.iter_mut().zip(divisor) {
+            *x ^= multiply(coefficient, factor);
+        }
+    }
+
+    result
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11d);
+        z ^= ((u32::from(y) >> i) & 1) * u32::from(x);
+    }
+
+    z as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::qr::{data_codewords, QrCode};
+
+    #[test]
+    fn chooses_the_smallest_version_that_fits() {
+        assert_eq!(data_codewords(1), 16);
+        assert_eq!(data_codewords(7), 124);
+
+        assert_eq!(QrCode::encode(b"hello").unwrap().size, 21);
+        assert_eq!(QrCode::encode(&[b'a'; 100]).unwrap().size, 41);
+        assert!(QrCode::encode(&[b'a'; 3000]).is_none());
+    }
+
+    #[test]
+    fn draws_finder_patterns_in_three_corners() {
+        let code = QrCode::encode(b"https://example.com/ui?game=1").unwrap();
+        let last = code.size - 1;
+
+        for (x, y) in [(0, 0), (last, 0), (0, last)] {
+            assert!(code.dark(x, y));
+        }
+        assert!(code.dark(8, code.size - 8));
+    }
+
+    /// The code's modules row by row, `#` for dark
+    fn rows(code: &QrCode) -> Vec<String> {
+        (0..code.size)
+            .map(|y| {
+                (0..code.size)
+                    .map(|x| if code.dark(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encodes_the_same_modules_as_the_reference_encoder() {
+        // from Nayuki's qrcodegen at level M in byte mode, which chooses version 3 and mask 7
+        let code = QrCode::encode(b"https://wordle.example/ui?game=1").unwrap();
+        assert_eq!(rows(&code), SINGLE_BLOCK);
+
+        // version 5, the codewords split into two blocks, and mask 2
+        let link = b"https://wordle.example/ui?game=6f1c1a9e-3b8e-4a4f-9d5e-2c7a0b8e4d13";
+        assert_eq!(rows(&QrCode::encode(link).unwrap()), TWO_BLOCKS);
+    }
+
+    const SINGLE_BLOCK: [&str; 29] = [
+        "#######....###..#####.#######",
+        "#.....#..##..###..#.#.#.....#",
+        "#.###.#....#.#..##..#.#.###.#",
+        "#.###.#..#...###..##..#.###.#",
+        "#.###.#...##.##.#.....#.###.#",
+        "#.....#.##.#.##...###.#.....#",
+        "#######.#.#.#.#.#.#.#.#######",
+        ".........####.#..##.#........",
+        "#..#.##.##.##..#...#.#.#.....",
+        "####.#.##.###.##....###..#..#",
+        ".###..####..#...#.#####.####.",
+        "#.####.##.##..##..####.#..##.",
+        "##.#.####.#.#...###..##..#.##",
+        "#.##.#......#..#..####.......",
+        "##..###.#.###..##...####.####",
+        ".#...#.##..####.#####..#.#.#.",
+        ".##...##...#.###.#..#..#...#.",
+        ".#........##...#.....###.#..#",
+        "#...#.##.##..#...#...#.#...##",
+        "..##........##.####..#..#..##",
+        "#.#..######...#.....#####.#..",
+        "........##.###...#.##...#.###",
+        "#######..###.##.#.#.#.#.#..#.",
+        "#.....#.##..##.##.#.#...####.",
+        "#.###.#..##...##.#..#####...#",
+        "#.###.#.##..###.#.....######.",
+        "#.###.#..##.#.#..#..#...###.#",
+        "#.....#..####.#####.#...#..#.",
+        "#######.#.#.##...#..##.##..#.",
+    ];
+
+    const TWO_BLOCKS: [&str; 37] = [
+        "#######..###.#..#.#.#...#...#.#######",
+        "#.....#...#..#.#..##.#....###.#.....#",
+        "#.###.#.#...#.##...######.#.#.#.###.#",
+        "#.###.#.#......###.##...####..#.###.#",
+        "#.###.#.#...#..#..##..##..#.#.#.###.#",
+        "#.....#.##.#.#.##.#...#....#..#.....#",
+        "#######.#.#.#.#.#.#.#.#.#.#.#.#######",
+        "........#..######..##.....#.#........",
+        "#.#####...#..###..#.###..#..#.#####..",
+        "###.#..##..##.###......####....#..##.",
+        "#.#.#.#..###.##..##.#.#....#####...##",
+        ".##..#..#.#####.#....##.#....#..##..#",
+        "..#...#...###.#..##...#..###.####.###",
+        ".##.#...##..#...##.###.#.......#.....",
+        "...#..#..#...#####...#.....#######..#",
+        "####...#..#####..#######...##.###..##",
+        ".#...######.#..##....#.#.###..###.###",
+        ".#.###..####.#.#..#.#####...##.....#.",
+        "#.#.#.##.###..###.##..#.#..#.#.#.#.##",
+        "####...#..##.###...####...#..#.##...#",
+        "......##.####.####.##.#.####.##.###..",
+        "#....#.###.##.#...##...##...##...#...",
+        "##.######..#..###.#.#.#..#.#.#####.##",
+        ".#.##....########...#......######...#",
+        ".#.##.##.....#....#####.##..###.#.#..",
+        "#.#.##...#.##.###....#.#.##......#...",
+        "#...#.#.#.##.##...#.##..#.###.#.#..##",
+        "#....#...###.#..#....##...###..#.#.#.",
+        "#.###.##.#..#....##.#.#.###.#####.#.#",
+        "........###.#.#..#.#.#.#.####...##.#.",
+        "#######..#.##..###..##..#...#.#.#.#.#",
+        "#.....#.##..#.#####.###.....#...##...",
+        "#.###.#.##..#.###....#.#.########.#.#",
+        "#.###.#.#.#..###.##.#.###..#.##.#...#",
+        "#.###.#.#...#..##.##.##..........#.##",
+        "#.....#..#####.#...####....####.#...#",
+        "#######.##..#..#.#.#..#.####.##.#####",
+    ];
+}
@@ -0,0 +1,125 @@
+use crate::error::ApiError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Allows each key a burst of up to `per_minute` requests, refilled steadily over the minute
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// What's left of a key's allowance once a request has been taken from it, as told to the caller
+/// in `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Allowance {
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the allowance is full again
+    pub reset: u64,
+}
+
+impl Allowance {
+    pub fn headers(self) -> [(&'static str, String); 3] {
+        [
+            ("X-RateLimit-Limit", self.limit.to_string()),
+            ("X-RateLimit-Remaining", self.remaining.to_string()),
+            ("X-RateLimit-Reset", self.reset.to_string()),
+        ]
+    }
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> RateLimiter {
+        RateLimiter {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a request from the key's allowance, or refuses it with how long until there's another
+    pub fn check(&self, key: &str) -> Result<(), ApiError> {
+        self.take(key).map(|_| ()).map_err(|(_, error)| error)
+    }
+
+    /// Like `check`, but with what's left of the allowance, or what it was when it's refused
+    pub fn take(&self, key: &str) -> Result<Allowance, (Allowance, ApiError)> {
+        self.take_at(key, Instant::now())
+    }
+
+    fn take_at(&self, key: &str, now: Instant) -> Result<Allowance, (Allowance, ApiError)> {
+        let capacity = f64::from(self.per_minute);
+        let per_second = capacity / 60.0;
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|x| x.into_inner());
+        // keys that have filled back up are forgotten, so the map only holds recent callers
+        buckets.retain(|_, x| x.tokens + elapsed(x.refilled_at, now) * per_second < capacity);
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        bucket.tokens =
+            (bucket.tokens + elapsed(bucket.refilled_at, now) * per_second).min(capacity);
+        bucket.refilled_at = now;
+
+        let refused = bucket.tokens < 1.0;
+        if !refused {
+            bucket.tokens -= 1.0;
+        }
+        let allowance = Allowance {
+            limit: self.per_minute,
+            remaining: bucket.tokens.floor() as u32,
+            reset: ((capacity - bucket.tokens) / per_second).ceil() as u64,
+        };
+
+        if refused {
+            let retry_after = ((1.0 - bucket.tokens) / per_second).ceil() as u64;
+
+            return Err((allowance, ApiError::RateLimited(retry_after.max(1))));
+        }
+
+        Ok(allowance)
+    }
+}
+
+fn elapsed(since: Instant, now: Instant) -> f64 {
+    now.saturating_duration_since(since).as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ApiError;
+    use crate::rate_limit::{Allowance, RateLimiter};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn refuses_bursts_until_refilled() {
+        let limiter = RateLimiter::new(2);
+        let start = Instant::now();
+
+        assert!(limiter.take_at("a", start).is_ok());
+        assert_eq!(
+            limiter.take_at("a", start).ok(),
+            Some(Allowance {
+                limit: 2,
+                remaining: 0,
+                reset: 60
+            })
+        );
+        assert!(matches!(
+            limiter.take_at("a", start),
+            Err((_, ApiError::RateLimited(30)))
+        ));
+        assert!(limiter.take_at("b", start).is_ok());
+
+        assert!(limiter
+            .take_at("a", start + Duration::from_secs(30))
+            .is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::admin::bearer_token;
+use crate::error::ApiError;
+use crate::game::{ClientId, GameId};
+use crate::json_response;
+use crate::service::Service;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// The most a client's metadata may take up, as JSON
+pub const MAX_METADATA: usize = 2048;
+
+/// Whatever a client registered with besides their name, e.g. `{"bot": true}`, for nobody but
+/// whoever reads it back
+pub type Metadata = BTreeMap<String, Value>;
+
+/// `POST /clients`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewClient {
+    pub client: String,
+    /// Saved as the display name of the client's profile, which is where it's shown from
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub metadata: Metadata,
+}
+
+/// A client and the key they're played as with from then on, which is never shown again
+#[derive(Serialize, Debug)]
+pub struct Registration {
+    pub client: ClientId,
+    pub key: String,
+    pub display_name: Option<String>,
+    pub metadata: Metadata,
+}
+
+/// A registered client, as anyone can look them up
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct RegisteredClient {
+    pub client: ClientId,
+    /// What to call them, their display name or else the client itself
+    pub player: String,
+    pub metadata: Metadata,
+    /// In seconds since the epoch
+    pub registered_at: u64,
+}
+
+/// `POST /clients` registers `{"client": "...", "display_name": "...", "metadata": {...}}`, so
+/// that only whoever has the key it answers with can create and play the client's games
+pub fn handle_register(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    let client: NewClient = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    json_response(&service.register_client(client)?)
+}
+
+/// `GET /clients/<client>` is who registered the client, and with what
+pub fn handle_get(service: &Service, client: &str) -> Result<Response, ApiError> {
+    json_response(&service.registered_client(&ClientId::parse(client)?)?)
+}
+
+/// Refuses the request unless it carries the client's key as `Authorization: Bearer <key>`, if
+/// they've registered one
+pub fn authorize(service: &Service, request: &Request, client: &ClientId) -> Result<(), ApiError> {
+    service.authorize_client(client, bearer_token(request))
+}
+
+/// Refuses a new game for the client unless they may be played as, and, when only registered
+/// clients may play, unless they've registered
+pub fn authorize_new_game(
+    service: &Service,
+    request: &Request,
+    client: &ClientId,
+) -> Result<(), ApiError> {
+    service.ensure_registered(client)?;
+
+    authorize(service, request, client)
+}
+
+/// Refuses the request unless it carries the key of whoever's game it is, if they've registered
+/// one
+pub fn authorize_game(
+    service: &Service,
+    request: &Request,
+    game_id: &GameId,
+) -> Result<(), ApiError> {
+    service.authorize_game(game_id, bearer_token(request))
+}
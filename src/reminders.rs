@@ -0,0 +1,403 @@
+use crate::error::ApiError;
+use crate::game::ClientId;
+use crate::json_response;
+use crate::mock::Mock;
+use crate::registration;
+use crate::service::Service;
+use crate::{db, game};
+use rouille::{Request, Response};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MAX_URL: usize = 512;
+const MAX_UTC_OFFSET: i32 = 14 * 60;
+/// How long a webhook gets to accept a reminder, so that one slow receiver can't hold up the rest
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where and when to remind a client that their daily streak is about to be lost
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Reminder {
+    /// Where the reminder is POSTed, only plain `http://` urls are supported
+    pub url: String,
+    /// The client's local time to remind them at, as `HH:MM`
+    pub remind_at: String,
+    /// The client's offset from UTC in minutes, e.g. 60 for UTC+1
+    #[serde(default)]
+    pub utc_offset: i32,
+}
+
+impl Reminder {
+    pub fn validate(&self) -> Result<(), ApiError> {
+        let invalid = |name: &str, value: &str| ApiError::InvalidParam {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        if http_target(&self.url).is_none()
+            || self.url.len() > MAX_URL
+            || self
+                .url
+                .contains(|x: char| x.is_whitespace() || x.is_control())
+        {
+            return Err(invalid("url", &self.url));
+        }
+        if self.minutes().is_none() {
+            return Err(invalid("remind_at", &self.remind_at));
+        }
+        if self.utc_offset.abs() > MAX_UTC_OFFSET {
+            return Err(invalid("utc_offset", &self.utc_offset.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Refuses a url whose host resolves to a loopback, link-local or private address, unless
+    /// those are allowed. One that can't be resolved yet is checked again whenever it's sent to.
+    pub fn validate_host(&self, private: bool) -> Result<(), ApiError> {
+        match resolve(&self.url, private) {
+            Err(error) if error.kind() == io::ErrorKind::PermissionDenied => {
+                Err(ApiError::InvalidParam {
+                    name: String::from("url"),
+                    value: self.url.clone(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Minutes after local midnight to remind at
+    fn minutes(&self) -> Option<u64> {
+        let (hours, minutes) = self.remind_at.split_once(':')?;
+        let (hours, minutes) = (hours.parse::<u64>().ok()?, minutes.parse::<u64>().ok()?);
+
+        (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+    }
+}
+
+/// The event a client's webhook is sent
+#[derive(Serialize, Debug)]
+struct StreakReminder<'a> {
+    event: &'static str,
+    client: &'a str,
+    /// Days in a row, up to yesterday, that the client won a game on
+    streak: usize,
+    /// The client's local day that hasn't been played yet, as `YYYY-MM-DD`
+    day: String,
+}
+
+/// A reminder that was due and sent
+#[derive(Serialize, Debug)]
+pub struct Sent {
+    pub client: String,
+    pub streak: usize,
+    /// The status the webhook answered with, or none if it couldn't be reached
+    pub status: Option<u16>,
+}
+
+/// Sends every reminder that's due at `now`, in seconds since the epoch: those whose client has
+/// passed their local reminder time with a streak running up to yesterday and no game started
+/// today. Each client is reminded at most once a day, whether or not their webhook answered. A mock
+/// server captures them instead. Webhooks on loopback, link-local and private addresses are never
+/// reached unless `private` allows them.
+pub fn run(
+    conn: &Connection,
+    now: u64,
+    mock: Option<&Mock>,
+    private: bool,
+) -> Result<Vec<Sent>, ApiError> {
+    let reminders = conn
+        .prepare("SELECT client, data, last_sent_day FROM reminder")?
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<u64>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut sent = Vec::new();
+    for (client, data, last_sent_day) in reminders {
+        let reminder: Reminder = serde_json::from_str(&data)?;
+        let Some(remind_at) = reminder.minutes() else {
+            continue;
+        };
+
+        let offset = i64::from(reminder.utc_offset) * 60;
+        let local = now.saturating_add_signed(offset);
+        let today = local / (24 * 60 * 60);
+        if last_sent_day == Some(today) || local % (24 * 60 * 60) < remind_at * 60 {
+            continue;
+        }
+
+        let days = conn
+            .prepare(
+                "SELECT DISTINCT (unixepoch(created_at) + ?2) / 86400 AS day, state = 'won' FROM game_history
+                WHERE client = ?1 AND created_at IS NOT NULL ORDER BY day DESC",
+            )?
+            .query_map((&client, offset), |row| {
+                Ok((row.get::<_, u64>(0)?, row.get::<_, bool>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let started_today = days.iter().any(|(day, _)| *day == today);
+        let streak = streak(&days, today);
+        if started_today || streak == 0 {
+            continue;
+        }
+
+        let event = StreakReminder {
+            event: "streak_reminder",
+            client: &client,
+            streak,
+            day: game::date(today),
+        };
+        let body = serde_json::to_string(&event)?;
+        let status = match mock {
+            Some(mock) => Some(mock.capture(&reminder.url, &body)),
+            None => post(&reminder.url, &body, private).ok(),
+        };
+        conn.execute(
+            "UPDATE reminder SET last_sent_day = ?1 WHERE client = ?2",
+            (today, &client),
+        )?;
+
+        sent.push(Sent {
+            client,
+            streak,
+            status,
+        });
+    }
+
+    Ok(sent)
+}
+
+/// Days in a row up to yesterday with a win on them, from `(day, won)` pairs
+fn streak(days: &[(u64, bool)], today: u64) -> usize {
+    let won = |day: u64| days.iter().any(|(x, won)| *x == day && *won);
+
+    (1..=today)
+        .map(|ago| today - ago)
+        .take_while(|day| won(*day))
+        .count()
+}
+
+/// Checks for due reminders every `interval` in the background
+pub fn schedule(interval: Duration, database: String, mock: Option<Arc<Mock>>, private: bool) {
+    thread::spawn(move || loop {
+        thread::sleep(interval);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+        let result = db::open(&database)
+            .map_err(ApiError::from)
+            .and_then(|conn| run(&conn, now, mock.as_deref(), private));
+        match result {
+            Ok(sent) if sent.is_empty() => {}
+            Ok(sent) => eprintln!("reminders: sent {}", sent.len()),
+            Err(error) => eprintln!("reminders failed: {error}"),
+        }
+    });
+}
+
+/// The host and path of an `http://` url
+fn http_target(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("http://")?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+
+    (!host.is_empty()).then_some((host, path))
+}
+
+/// Whether an address is reachable from anywhere, rather than only from the server's own host or
+/// network, e.g. `127.0.0.1`, `10.0.0.1` or `169.254.169.254`
+fn is_public(ip: IpAddr) -> bool {
+    let ip = match ip {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+        ip => ip,
+    };
+
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            // 100.64.0.0/10 is shared by carrier-grade NATs
+            let shared = a == 100 && (64..128).contains(&b);
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || shared
+                || a == 0)
+        }
+        IpAddr::V6(ip) => {
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local())
+        }
+    }
+}
+
+/// The address an `http://` url is sent to, the first its host resolves to, which is refused as
+/// `PermissionDenied` if any of them isn't public and those aren't allowed
+fn resolve(url: &str, private: bool) -> io::Result<SocketAddr> {
+    let (host, _) =
+        http_target(url).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, url))?;
+    // a port, unless it's only the colons of a bracketed IPv6 address
+    let addr = if host.contains(':') && !host.ends_with(']') {
+        host.to_string()
+    } else {
+        format!("{host}:80")
+    };
+    let addrs = addr.to_socket_addrs()?.collect::<Vec<_>>();
+    if !private && addrs.iter().any(|x| !is_public(x.ip())) {
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, host));
+    }
+
+    addrs
+        .into_iter()
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, host))
+}
+
+/// POSTs a JSON body, returning the status it was answered with
+fn post(url: &str, body: &str, private: bool) -> io::Result<u16> {
+    let (host, path) =
+        http_target(url).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, url))?;
+    let addr = resolve(url, private)?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    // written all at once, so the receiver never sees the request in pieces
+    let request = format!(
+        "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(request.as_bytes())?;
+
+    // only the status line is needed
+    let mut head = [0; 64];
+    let read = stream.read(&mut head)?;
+    String::from_utf8_lossy(&head[..read])
+        .split(' ')
+        .nth(1)
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "bad status line"))
+}
+
+/// `GET /reminders/<client>` is the client's reminder webhook, or `null` if they haven't one
+pub fn handle_get(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+
+    json_response(&service.reminder(&client)?)
+}
+
+/// `PUT /reminders/<client>` registers the client's reminder webhook, replacing any they had
+pub fn handle_put(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    let reminder: Reminder = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    service.save_reminder(&client, &reminder)?;
+
+    json_response(&reminder)
+}
+
+/// `DELETE /reminders/<client>` stops the client's reminders
+pub fn handle_delete(
+    service: &Service,
+    request: &Request,
+    client: &str,
+) -> Result<Response, ApiError> {
+    let client = ClientId::parse(client)?;
+    registration::authorize(service, request, &client)?;
+    service.delete_reminder(&client)?;
+
+    json_response(&service.reminder(&client)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::reminders::{is_public, streak, Reminder};
+
+    #[test]
+    fn streaks_run_up_to_yesterday() {
+        assert_eq!(streak(&[(9, true), (8, true), (6, true)], 10), 2);
+        // a day without a win breaks the streak
+        assert_eq!(streak(&[(9, true), (8, false)], 10), 1);
+        assert_eq!(streak(&[(10, true), (8, true)], 10), 0);
+    }
+
+    #[test]
+    fn reminders_need_a_time_and_an_http_url() {
+        let reminder = |url: &str, remind_at: &str| Reminder {
+            url: url.to_string(),
+            remind_at: remind_at.to_string(),
+            utc_offset: 60,
+        };
+
+        assert!(reminder("http://example.com/hook", "18:30")
+            .validate()
+            .is_ok());
+        assert!(reminder("https://example.com", "18:30").validate().is_err());
+        assert!(reminder("http://example.com", "24:00").validate().is_err());
+        assert!(reminder("http://", "18:00").validate().is_err());
+    }
+
+    #[test]
+    fn webhooks_are_only_sent_to_public_addresses() {
+        for ip in ["93.184.215.14", "2606:2800:21f:cb07:6820:80da:af6b:8b2c"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+
+        let reminder = |url: &str| Reminder {
+            url: url.to_string(),
+            remind_at: String::from("18:00"),
+            utc_offset: 0,
+        };
+        assert!(reminder("http://127.0.0.1:8080/hook")
+            .validate_host(false)
+            .is_err());
+        assert!(reminder("http://[::1]/hook").validate_host(false).is_err());
+        assert!(reminder("http://127.0.0.1:8080/hook")
+            .validate_host(true)
+            .is_ok());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::error::ApiError;
+use crate::events::GameEvent;
+use crate::game::{GameId, GameState, HintLevel, Rejection};
+use crate::service::Service;
+use crate::{bool_param, json_response};
+use rouille::{Request, Response};
+use serde::Serialize;
+use wordle_core::{CharMatch, GameMode, ModeState};
+
+/// How long a replay's share token is accepted for
+pub const SHARE_SECONDS: u64 = 24 * 60 * 60;
+
+/// Everything that happened in a game, in order, for clients to animate
+#[derive(Serialize, Debug)]
+pub struct Replay {
+    pub game_id: GameId,
+    pub player: String,
+    pub mode: &'static str,
+    pub state: GameState,
+    pub goes: usize,
+    /// Only once the game is finished
+    pub answers: Option<Vec<String>>,
+    /// Seconds from the game being created to its latest step
+    pub duration: u64,
+    pub steps: Vec<Step>,
+    /// Only when one was asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share: Option<ReplayShare>,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Step {
+    /// Seconds since the game was created
+    pub elapsed: u64,
+    /// Seconds since the step before, or since the game was created for the first
+    pub took: u64,
+    #[serde(flatten)]
+    pub kind: StepKind,
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StepKind {
+    Guessed {
+        guess: String,
+        /// Against the first board
+        evaluation: Vec<CharMatch>,
+        /// Every board's evaluation, for modes played on more than one
+        #[serde(skip_serializing_if = "Option::is_none")]
+        boards: Option<Vec<Vec<CharMatch>>>,
+    },
+    Rejected {
+        guess: String,
+        reason: Rejection,
+        counted: bool,
+    },
+    Hinted {
+        level: HintLevel,
+    },
+    Solved,
+    Forfeited,
+    Expired,
+}
+
+/// A token anyone can watch the replay with until it expires
+#[derive(Serialize, Debug)]
+pub struct ReplayShare {
+    pub token: String,
+    /// In seconds since the epoch
+    pub expires_at: u64,
+}
+
+/// Replays a game's timeline from its created event, evaluating each guess in the game's mode as
+/// it was played. A timeline that doesn't start with the game being created has no steps to show.
+pub fn steps(mode: &dyn GameMode, timeline: &[(GameEvent, u64)]) -> Vec<Step> {
+    let Some((
+        (
+            GameEvent::Created {
+                word, mode_state, ..
+            },
+            started,
+        ),
+        rest,
+    )) = timeline.split_first()
+    else {
+        return Vec::new();
+    };
+
+    let mut state = mode_state
+        .clone()
+        .unwrap_or_else(|| ModeState::single(word));
+    // only the guesses to come are replayed, not what the game was created with
+    state.revealed.clear();
+
+    let mut last = *started;
+    let mut steps = Vec::new();
+    for (event, at) in rest {
+        let kind = match event {
+            GameEvent::Created { .. } => continue,
+            GameEvent::Guessed { guess } => {
+                let mut evaluations = mode.evaluate(&mut state, guess);
+                let boards = (evaluations.len() > 1).then(|| evaluations.clone());
+                StepKind::Guessed {
+                    guess: guess.clone(),
+                    evaluation: evaluations.swap_remove(0),
+                    boards,
+                }
+            }
+            GameEvent::Rejected {
+                guess,
+                reason,
+                counted,
+            } => StepKind::Rejected {
+                guess: guess.clone(),
+                reason: *reason,
+                counted: *counted,
+            },
+            GameEvent::Hinted { level, .. } => StepKind::Hinted { level: *level },
+            GameEvent::Solved => StepKind::Solved,
+            GameEvent::Forfeited => StepKind::Forfeited,
+            GameEvent::Expired => StepKind::Expired,
+        };
+
+        // clocks can step backwards, a replay never does
+        let at = (*at).max(last);
+        steps.push(Step {
+            elapsed: at - started,
+            took: at - last,
+            kind,
+        });
+        last = at;
+    }
+
+    steps
+}
+
+/// `GET /game/<game_id>/replay[?share=true]` is every step of the game with how long each took,
+/// along with a token to watch it by for the next day if asked for one, once the game's finished
+pub fn handle_get(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    let share = bool_param(request, "share")?.unwrap_or(false);
+
+    json_response(&service.replay(&GameId::parse(game_id)?, share)?)
+}
+
+/// `GET /replay/<token>` is the replay shared under the token, until it expires
+pub fn handle_shared(service: &Service, token: &str) -> Result<Response, ApiError> {
+    json_response(&service.shared_replay(token)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::GameEvent;
+    use crate::game::{self, GameOptions, GameState, Rejection};
+    use crate::replay::{steps, StepKind};
+    use wordle_core::MatchType;
+
+    #[test]
+    fn steps_are_timed_from_creation() {
+        let created = GameEvent::Created {
+            client: String::from("alice"),
+            word: String::from("hello"),
+            goes: 0,
+            state: GameState::InProgress,
+            options: GameOptions {
+                invalid_guesses_count: false,
+                mode: String::from("classic"),
+                max_guesses: Some(6),
+                length: 5,
+                language: String::from("en"),
+            },
+            mode_state: None,
+        };
+        let guessed = |guess: &str| GameEvent::Guessed {
+            guess: guess.to_string(),
+        };
+        let timeline = [
+            (created, 100),
+            (guessed("world"), 130),
+            (
+                GameEvent::Rejected {
+                    guess: String::from("xxxxx"),
+                    reason: Rejection::InvalidWord,
+                    counted: false,
+                },
+                135,
+            ),
+            (guessed("hello"), 160),
+        ];
+
+        let steps = steps(game::modes().get("classic").unwrap(), &timeline);
+
+        assert_eq!(
+            steps
+                .iter()
+                .map(|x| (x.elapsed, x.took))
+                .collect::<Vec<_>>(),
+            [(30, 30), (35, 5), (60, 25)]
+        );
+        let StepKind::Guessed { evaluation, .. } = &steps[2].kind else {
+            panic!("expected a guess, got {:?}", steps[2].kind);
+        };
+        assert!(evaluation
+            .iter()
+            .all(|x| x.match_type == MatchType::Perfect));
+    }
+}
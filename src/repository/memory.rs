@@ -0,0 +1,1001 @@
+use crate::achievements::{Achievement, Granted};
+use crate::challenges::Challenge;
+use crate::clubs::Club;
+use crate::db;
+use crate::error::ApiError;
+use crate::events::GameEvent;
+use crate::game::{self, ClientId, GameId, GameState, GroupId, HintLevel, PlayedGuess, Rejection};
+use crate::groups::Group;
+use crate::guests::Guest;
+use crate::inbox::{InboxEvent, Message};
+use crate::profile::Profile;
+use crate::registration::Metadata;
+use crate::reminders::Reminder;
+use crate::repository::{FinishedGame, Repository, StoredGame};
+use crate::share::ShareLink;
+use crate::solver::Simulation;
+use crate::stats::ClientStats;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+/// Keeps everything in memory, for exercising the service without a database
+#[derive(Default)]
+pub struct MemoryRepository {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    read_only: Option<String>,
+    games: HashMap<GameId, StoredGame>,
+    rejections: Vec<(GameId, String, Rejection, bool)>,
+    guesses: Vec<(GameId, PlayedGuess)>,
+    events: Vec<(GameId, GameEvent)>,
+    /// When each of `events` was recorded, in seconds since the epoch
+    recorded_at: Vec<u64>,
+    simulations: HashMap<(String, String), Simulation>,
+    achievements: Vec<(ClientId, Granted)>,
+    profiles: HashMap<ClientId, Profile>,
+    reminders: HashMap<ClientId, Reminder>,
+    share_links: Vec<ShareLink>,
+    inbox: Vec<(ClientId, Message)>,
+    /// The id the last message was sent with
+    last_message_id: u64,
+    replay_tokens: HashMap<String, (GameId, u64)>,
+    /// The day each game was started on, in days since the epoch
+    days: HashMap<GameId, u64>,
+    /// Both ways round, like SQLite keeps them
+    friends: Vec<(ClientId, ClientId)>,
+    invite_codes: HashMap<ClientId, String>,
+    /// Each guest's key
+    guests: HashMap<ClientId, String>,
+    /// Each registered client's key, metadata and when they registered
+    clients: HashMap<ClientId, (String, Metadata, u64)>,
+    groups: HashMap<GroupId, Group>,
+    group_members: Vec<(GroupId, ClientId)>,
+    group_games: HashMap<(GroupId, ClientId, u64), GameId>,
+    daily_games: HashMap<(ClientId, u64), GameId>,
+    challenges: HashMap<String, Challenge>,
+    challenge_games: HashMap<(String, ClientId), GameId>,
+    clubs: BTreeMap<String, Club>,
+    club_members: Vec<(ClientId, String)>,
+}
+
+impl MemoryRepository {
+    pub fn new() -> MemoryRepository {
+        MemoryRepository::default()
+    }
+
+    pub fn set_read_only(&self, message: Option<&str>) {
+        self.lock().read_only = message.map(String::from);
+    }
+
+    /// Every rejection recorded against a game, oldest first
+    pub fn rejections(&self, game_id: &GameId) -> Vec<(String, Rejection, bool)> {
+        self.lock()
+            .rejections
+            .iter()
+            .filter(|(x, ..)| x == game_id)
+            .map(|(_, guess, reason, counted)| (guess.clone(), *reason, *counted))
+            .collect()
+    }
+
+    /// A game's history, oldest first
+    pub fn events(&self, game_id: &GameId) -> Vec<GameEvent> {
+        self.lock()
+            .events
+            .iter()
+            .filter(|(x, _)| x == game_id)
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // nothing can be left half-written by a panic, so a poisoned lock is still usable
+        self.inner.lock().unwrap_or_else(|x| x.into_inner())
+    }
+}
+
+impl Inner {
+    fn record(&mut self, game_id: &GameId, event: GameEvent) {
+        self.events.push((game_id.clone(), event));
+        self.recorded_at.push(game::now());
+    }
+}
+
+impl Repository for MemoryRepository {
+    fn read_only(&self) -> Result<Option<String>, ApiError> {
+        Ok(self.lock().read_only.clone())
+    }
+
+    fn active_games(&self) -> Result<usize, ApiError> {
+        Ok(self
+            .lock()
+            .games
+            .values()
+            .filter(|x| x.state == GameState::InProgress)
+            .count())
+    }
+
+    fn schema_version(&self) -> Result<usize, ApiError> {
+        Ok(db::LATEST_VERSION)
+    }
+
+    fn insert_game(&self, game_id: &GameId, game: &StoredGame) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+        if inner.games.contains_key(game_id) {
+            return Ok(false);
+        }
+
+        inner.games.insert(game_id.clone(), game.clone());
+        inner.days.insert(game_id.clone(), game::today());
+        inner.record(game_id, GameEvent::created(game));
+
+        Ok(true)
+    }
+
+    fn find_game(&self, game_id: &GameId) -> Result<Option<StoredGame>, ApiError> {
+        Ok(self.lock().games.get(game_id).cloned())
+    }
+
+    fn update_game(
+        &self,
+        game_id: &GameId,
+        game: &StoredGame,
+        events: &[GameEvent],
+    ) -> Result<(), ApiError> {
+        let mut inner = self.lock();
+        if let Some(stored) = inner.games.get_mut(game_id) {
+            *stored = game.clone();
+        }
+
+        let guesses = events
+            .iter()
+            .filter(|x| matches!(x, GameEvent::Guessed { .. }))
+            .count();
+        for played in game.latest_guesses(guesses) {
+            inner.guesses.push((game_id.clone(), played));
+        }
+        for event in events {
+            inner.record(game_id, event.clone());
+        }
+
+        Ok(())
+    }
+
+    fn guesses(&self, game_id: &GameId) -> Result<Vec<PlayedGuess>, ApiError> {
+        Ok(self
+            .lock()
+            .guesses
+            .iter()
+            .filter(|(x, _)| x == game_id)
+            .map(|(_, played)| played.clone())
+            .collect())
+    }
+
+    fn record_rejection(
+        &self,
+        game_id: &GameId,
+        guess: &str,
+        reason: Rejection,
+        counted: bool,
+    ) -> Result<(), ApiError> {
+        let mut inner = self.lock();
+        inner
+            .rejections
+            .push((game_id.clone(), guess.to_string(), reason, counted));
+        inner.record(
+            game_id,
+            GameEvent::Rejected {
+                guess: guess.to_string(),
+                reason,
+                counted,
+            },
+        );
+
+        if counted {
+            if let Some(game) = inner.games.get_mut(game_id) {
+                game.goes += 1;
+                if game.options.out_of_goes(game.goes) {
+                    game.state = GameState::Lost;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record_hint(
+        &self,
+        game_id: &GameId,
+        level: HintLevel,
+        penalty: usize,
+    ) -> Result<(), ApiError> {
+        let mut inner = self.lock();
+        if let Some(game) = inner.games.get_mut(game_id) {
+            game.goes += 1;
+        }
+        inner.record(
+            game_id,
+            GameEvent::Hinted {
+                level,
+                penalty,
+                counted: true,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn grant_achievements(
+        &self,
+        client: &ClientId,
+        game_id: &GameId,
+        achievements: &[Achievement],
+    ) -> Result<Vec<Achievement>, ApiError> {
+        let mut inner = self.lock();
+
+        let mut granted = Vec::new();
+        for achievement in achievements {
+            let held = inner
+                .achievements
+                .iter()
+                .any(|(x, granted)| x == client && granted.achievement == *achievement);
+            if !held {
+                inner
+                    .achievements
+                    .push((client.clone(), Granted::new(*achievement, game_id.clone())));
+                granted.push(*achievement);
+            }
+        }
+
+        Ok(granted)
+    }
+
+    fn achievements(&self, client: &ClientId) -> Result<Vec<Granted>, ApiError> {
+        Ok(self
+            .lock()
+            .achievements
+            .iter()
+            .filter(|(x, _)| x == client)
+            .map(|(_, granted)| granted.clone())
+            .collect())
+    }
+
+    fn profile(&self, client: &ClientId) -> Result<Option<Profile>, ApiError> {
+        Ok(self.lock().profiles.get(client).cloned())
+    }
+
+    fn save_profile(&self, client: &ClientId, profile: &Profile) -> Result<(), ApiError> {
+        self.lock().profiles.insert(client.clone(), profile.clone());
+
+        Ok(())
+    }
+
+    fn reminder(&self, client: &ClientId) -> Result<Option<Reminder>, ApiError> {
+        Ok(self.lock().reminders.get(client).cloned())
+    }
+
+    fn save_reminder(&self, client: &ClientId, reminder: &Reminder) -> Result<(), ApiError> {
+        self.lock()
+            .reminders
+            .insert(client.clone(), reminder.clone());
+
+        Ok(())
+    }
+
+    fn delete_reminder(&self, client: &ClientId) -> Result<(), ApiError> {
+        self.lock().reminders.remove(client);
+
+        Ok(())
+    }
+
+    fn send_inbox(&self, client: &ClientId, event: &InboxEvent, now: u64) -> Result<(), ApiError> {
+        let mut inner = self.lock();
+
+        inner.last_message_id += 1;
+        let message = Message {
+            message_id: inner.last_message_id,
+            sent_at: now,
+            event: event.clone(),
+        };
+        inner.inbox.push((client.clone(), message));
+
+        Ok(())
+    }
+
+    fn inbox(&self, client: &ClientId, limit: usize) -> Result<Vec<Message>, ApiError> {
+        Ok(self
+            .lock()
+            .inbox
+            .iter()
+            .filter(|(x, _)| x == client)
+            .map(|(_, message)| message.clone())
+            .take(limit)
+            .collect())
+    }
+
+    fn acknowledge_inbox(&self, client: &ClientId, up_to: u64) -> Result<(), ApiError> {
+        self.lock()
+            .inbox
+            .retain(|(x, message)| x != client || message.message_id > up_to);
+
+        Ok(())
+    }
+
+    fn save_share_link(&self, link: &ShareLink) -> Result<(), ApiError> {
+        self.lock().share_links.push(link.clone());
+
+        Ok(())
+    }
+
+    fn share_link(&self, token: &str) -> Result<Option<ShareLink>, ApiError> {
+        Ok(self
+            .lock()
+            .share_links
+            .iter()
+            .find(|x| x.token == token)
+            .cloned())
+    }
+
+    fn share_links(&self, game_id: &GameId) -> Result<Vec<ShareLink>, ApiError> {
+        Ok(self
+            .lock()
+            .share_links
+            .iter()
+            .filter(|x| &x.game_id == game_id)
+            .cloned()
+            .collect())
+    }
+
+    fn delete_share_link(&self, game_id: &GameId, token: &str) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+        let before = inner.share_links.len();
+        inner
+            .share_links
+            .retain(|x| !(&x.game_id == game_id && x.token == token));
+
+        Ok(inner.share_links.len() < before)
+    }
+
+    fn save_replay_token(
+        &self,
+        token: &str,
+        game_id: &GameId,
+        expires_at: u64,
+    ) -> Result<(), ApiError> {
+        self.lock()
+            .replay_tokens
+            .insert(token.to_string(), (game_id.clone(), expires_at));
+
+        Ok(())
+    }
+
+    fn replay_token(&self, token: &str, now: u64) -> Result<Option<GameId>, ApiError> {
+        Ok(self
+            .lock()
+            .replay_tokens
+            .get(token)
+            .filter(|(_, expires_at)| *expires_at > now)
+            .map(|(game_id, _)| game_id.clone()))
+    }
+
+    fn timeline(&self, game_id: &GameId) -> Result<Vec<(GameEvent, u64)>, ApiError> {
+        let inner = self.lock();
+
+        Ok(inner
+            .events
+            .iter()
+            .zip(&inner.recorded_at)
+            .filter(|((x, _), _)| x == game_id)
+            .map(|((_, event), at)| (event.clone(), *at))
+            .collect())
+    }
+
+    fn insert_group(&self, group: &Group) -> Result<(), ApiError> {
+        self.lock()
+            .groups
+            .insert(group.group_id.clone(), group.clone());
+
+        Ok(())
+    }
+
+    fn group(&self, group_id: &GroupId) -> Result<Option<Group>, ApiError> {
+        Ok(self.lock().groups.get(group_id).cloned())
+    }
+
+    fn group_members(&self, group_id: &GroupId) -> Result<Vec<ClientId>, ApiError> {
+        Ok(self
+            .lock()
+            .group_members
+            .iter()
+            .filter(|(x, _)| x == group_id)
+            .map(|(_, client)| client.clone())
+            .collect())
+    }
+
+    fn set_group_member(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        member: bool,
+    ) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+
+        let pair = (group_id.clone(), client.clone());
+        let before = inner.group_members.len();
+        if !member {
+            inner.group_members.retain(|x| x != &pair);
+        } else if !inner.group_members.contains(&pair) {
+            inner.group_members.push(pair);
+        }
+
+        Ok(inner.group_members.len() != before)
+    }
+
+    fn group_game(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        day: u64,
+    ) -> Result<Option<GameId>, ApiError> {
+        Ok(self
+            .lock()
+            .group_games
+            .get(&(group_id.clone(), client.clone(), day))
+            .cloned())
+    }
+
+    fn scheduled_answer(&self, _day: u64) -> Result<Option<String>, ApiError> {
+        // answers are only scheduled by the admin endpoints, straight into the database
+        Ok(None)
+    }
+
+    fn save_group_game(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        day: u64,
+        game_id: &GameId,
+    ) -> Result<(), ApiError> {
+        self.lock()
+            .group_games
+            .entry((group_id.clone(), client.clone(), day))
+            .or_insert_with(|| game_id.clone());
+
+        Ok(())
+    }
+
+    fn daily_game(&self, client: &ClientId, day: u64) -> Result<Option<GameId>, ApiError> {
+        Ok(self.lock().daily_games.get(&(client.clone(), day)).cloned())
+    }
+
+    fn daily_day(&self, game_id: &GameId) -> Result<Option<u64>, ApiError> {
+        Ok(self
+            .lock()
+            .daily_games
+            .iter()
+            .find(|(_, x)| *x == game_id)
+            .map(|((_, day), _)| *day))
+    }
+
+    fn save_daily_game(
+        &self,
+        client: &ClientId,
+        day: u64,
+        game_id: &GameId,
+    ) -> Result<(), ApiError> {
+        self.lock()
+            .daily_games
+            .entry((client.clone(), day))
+            .or_insert_with(|| game_id.clone());
+
+        Ok(())
+    }
+
+    fn insert_challenge(&self, challenge: &Challenge) -> Result<(), ApiError> {
+        self.lock()
+            .challenges
+            .insert(challenge.challenge_id.clone(), challenge.clone());
+
+        Ok(())
+    }
+
+    fn challenge(&self, challenge_id: &str) -> Result<Option<Challenge>, ApiError> {
+        Ok(self.lock().challenges.get(challenge_id).cloned())
+    }
+
+    fn challenge_game(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+    ) -> Result<Option<GameId>, ApiError> {
+        Ok(self
+            .lock()
+            .challenge_games
+            .get(&(challenge_id.to_string(), client.clone()))
+            .cloned())
+    }
+
+    fn save_challenge_game(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+        game_id: &GameId,
+    ) -> Result<(), ApiError> {
+        self.lock()
+            .challenge_games
+            .entry((challenge_id.to_string(), client.clone()))
+            .or_insert_with(|| game_id.clone());
+
+        Ok(())
+    }
+
+    fn insert_club(&self, club: &Club) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+        if inner.clubs.contains_key(&club.club) {
+            return Ok(false);
+        }
+        inner.clubs.insert(club.club.clone(), club.clone());
+
+        Ok(true)
+    }
+
+    fn club(&self, club: &str) -> Result<Option<Club>, ApiError> {
+        Ok(self.lock().clubs.get(club).cloned())
+    }
+
+    fn clubs(&self) -> Result<Vec<Club>, ApiError> {
+        Ok(self.lock().clubs.values().cloned().collect())
+    }
+
+    fn club_members(&self, club: &str) -> Result<Vec<ClientId>, ApiError> {
+        Ok(self
+            .lock()
+            .club_members
+            .iter()
+            .filter(|(_, x)| x == club)
+            .map(|(client, _)| client.clone())
+            .collect())
+    }
+
+    fn join_club(&self, club: &str, client: &ClientId) -> Result<(), ApiError> {
+        let mut inner = self.lock();
+        if !inner
+            .club_members
+            .iter()
+            .any(|(x, joined)| x == client && joined == club)
+        {
+            inner.club_members.retain(|(x, _)| x != client);
+            inner.club_members.push((client.clone(), club.to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn leave_club(&self, club: &str, client: &ClientId) -> Result<(), ApiError> {
+        self.lock()
+            .club_members
+            .retain(|(x, joined)| !(x == client && joined == club));
+
+        Ok(())
+    }
+
+    fn friends(&self, client: &ClientId) -> Result<Vec<ClientId>, ApiError> {
+        Ok(self
+            .lock()
+            .friends
+            .iter()
+            .filter(|(x, _)| x == client)
+            .map(|(_, friend)| friend.clone())
+            .collect())
+    }
+
+    fn add_friend(&self, client: &ClientId, friend: &ClientId) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+
+        let pair = (client.clone(), friend.clone());
+        if inner.friends.contains(&pair) {
+            return Ok(false);
+        }
+        inner.friends.push(pair);
+        inner.friends.push((friend.clone(), client.clone()));
+
+        Ok(true)
+    }
+
+    fn remove_friend(&self, client: &ClientId, friend: &ClientId) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+
+        let before = inner.friends.len();
+        inner
+            .friends
+            .retain(|(x, y)| !((x == client && y == friend) || (x == friend && y == client)));
+
+        Ok(inner.friends.len() < before)
+    }
+
+    fn invite_code(&self, client: &ClientId) -> Result<Option<String>, ApiError> {
+        Ok(self.lock().invite_codes.get(client).cloned())
+    }
+
+    fn save_invite_code(&self, client: &ClientId, code: &str) -> Result<(), ApiError> {
+        self.lock()
+            .invite_codes
+            .insert(client.clone(), code.to_string());
+
+        Ok(())
+    }
+
+    fn invited_by(&self, code: &str) -> Result<Option<ClientId>, ApiError> {
+        Ok(self
+            .lock()
+            .invite_codes
+            .iter()
+            .find(|(_, x)| x.as_str() == code)
+            .map(|(client, _)| client.clone()))
+    }
+
+    fn insert_guest(&self, guest: &Guest) -> Result<(), ApiError> {
+        self.lock()
+            .guests
+            .insert(guest.client.clone(), guest.key.clone());
+
+        Ok(())
+    }
+
+    fn guest(&self, client: &ClientId) -> Result<Option<Guest>, ApiError> {
+        Ok(self.lock().guests.get(client).map(|key| Guest {
+            client: client.clone(),
+            key: key.clone(),
+        }))
+    }
+
+    fn insert_client(
+        &self,
+        client: &ClientId,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<bool, ApiError> {
+        let mut inner = self.lock();
+        if inner.clients.contains_key(client) {
+            return Ok(false);
+        }
+        inner.clients.insert(
+            client.clone(),
+            (key.to_string(), metadata.clone(), game::now()),
+        );
+
+        Ok(true)
+    }
+
+    fn registered_client(&self, client: &ClientId) -> Result<Option<(Metadata, u64)>, ApiError> {
+        Ok(self
+            .lock()
+            .clients
+            .get(client)
+            .map(|(_, metadata, registered_at)| (metadata.clone(), *registered_at)))
+    }
+
+    fn api_key(&self, client: &ClientId) -> Result<Option<String>, ApiError> {
+        let inner = self.lock();
+
+        Ok(inner
+            .clients
+            .get(client)
+            .map(|(key, ..)| key.clone())
+            .or_else(|| inner.guests.get(client).cloned()))
+    }
+
+    fn merge_guest(&self, guest: &ClientId, into: &ClientId) -> Result<(usize, usize), ApiError> {
+        let mut inner = self.lock();
+        let inner = &mut *inner;
+
+        let mut games = HashSet::new();
+        for (game_id, game) in &mut inner.games {
+            if &game.client == guest {
+                game.client = into.clone();
+                games.insert(game_id.clone());
+            }
+        }
+        for (game_id, event) in &mut inner.events {
+            if let GameEvent::Created { client, .. } = event {
+                if games.contains(game_id) {
+                    *client = into.to_string();
+                }
+            }
+        }
+
+        let (theirs, ours): (Vec<_>, Vec<_>) = inner
+            .achievements
+            .drain(..)
+            .partition(|(client, _)| client == guest);
+        inner.achievements = ours;
+        let mut achievements = 0;
+        for (_, granted) in theirs {
+            let had = inner
+                .achievements
+                .iter()
+                .any(|(client, x)| client == into && x.achievement == granted.achievement);
+            if !had {
+                inner.achievements.push((into.clone(), granted));
+                achievements += 1;
+            }
+        }
+
+        // whatever the client has of their own already is kept, and the guest's left behind
+        for (group_id, client) in inner.group_members.clone() {
+            if &client == guest {
+                let pair = (group_id, into.clone());
+                if !inner.group_members.contains(&pair) {
+                    inner.group_members.push(pair);
+                }
+            }
+        }
+        inner.group_members.retain(|(_, client)| client != guest);
+        let group_games = inner.group_games.clone();
+        for ((group_id, client, day), game_id) in group_games {
+            if &client == guest {
+                inner.group_games.remove(&(group_id.clone(), client, day));
+                inner
+                    .group_games
+                    .entry((group_id, into.clone(), day))
+                    .or_insert(game_id);
+            }
+        }
+        let daily_games = inner.daily_games.clone();
+        for ((client, day), game_id) in daily_games {
+            if &client == guest {
+                inner.daily_games.remove(&(client, day));
+                inner
+                    .daily_games
+                    .entry((into.clone(), day))
+                    .or_insert(game_id);
+            }
+        }
+        let challenge_games = inner.challenge_games.clone();
+        for ((challenge_id, client), game_id) in challenge_games {
+            if &client == guest {
+                inner
+                    .challenge_games
+                    .remove(&(challenge_id.clone(), client));
+                inner
+                    .challenge_games
+                    .entry((challenge_id, into.clone()))
+                    .or_insert(game_id);
+            }
+        }
+        for challenge in inner.challenges.values_mut() {
+            if &challenge.creator == guest {
+                challenge.creator = into.clone();
+            }
+        }
+        if let Some(index) = inner.club_members.iter().position(|(x, _)| x == guest) {
+            let (_, club) = inner.club_members.remove(index);
+            if !inner.club_members.iter().any(|(x, _)| x == into) {
+                inner.club_members.push((into.clone(), club));
+            }
+        }
+        if let Some(profile) = inner.profiles.remove(guest) {
+            inner.profiles.entry(into.clone()).or_insert(profile);
+        }
+        if let Some(reminder) = inner.reminders.remove(guest) {
+            inner.reminders.entry(into.clone()).or_insert(reminder);
+        }
+        if let Some(code) = inner.invite_codes.remove(guest) {
+            inner.invite_codes.entry(into.clone()).or_insert(code);
+        }
+        for (client, friend) in inner.friends.clone() {
+            let rename = |x: ClientId| if &x == guest { into.clone() } else { x };
+            let pair = (rename(client), rename(friend));
+            if pair.0 != pair.1 && !inner.friends.contains(&pair) {
+                inner.friends.push(pair);
+            }
+        }
+        inner
+            .friends
+            .retain(|(client, friend)| client != guest && friend != guest);
+        for group in inner.groups.values_mut() {
+            if &group.owner == guest {
+                group.owner = into.clone();
+            }
+        }
+        for (client, _) in &mut inner.inbox {
+            if client == guest {
+                *client = into.clone();
+            }
+        }
+        inner.guests.remove(guest);
+
+        Ok((games.len(), achievements))
+    }
+
+    fn finished_games(
+        &self,
+        clients: &[ClientId],
+        since: u64,
+        limit: usize,
+    ) -> Result<Vec<FinishedGame>, ApiError> {
+        let inner = self.lock();
+
+        // games are created in the order their first events were recorded
+        let games = inner
+            .events
+            .iter()
+            .rev()
+            .filter(|(_, event)| matches!(event, GameEvent::Created { .. }))
+            .filter_map(|(game_id, _)| {
+                Some((game_id, inner.games.get(game_id)?, inner.days[game_id]))
+            })
+            .filter(|(_, x, day)| {
+                clients.contains(&x.client) && x.state.is_finished() && *day >= since
+            })
+            .take(limit)
+            .map(|(game_id, game, day)| FinishedGame {
+                day: game::date(day),
+                game: game.clone(),
+                assisted: inner
+                    .events
+                    .iter()
+                    .any(|(id, event)| id == game_id && matches!(event, GameEvent::Hinted { .. })),
+            })
+            .collect();
+
+        Ok(games)
+    }
+
+    fn simulation(&self, opener: &str, list_version: &str) -> Result<Option<Simulation>, ApiError> {
+        Ok(self
+            .lock()
+            .simulations
+            .get(&(opener.to_string(), list_version.to_string()))
+            .cloned())
+    }
+
+    fn save_simulation(&self, list_version: &str, simulation: &Simulation) -> Result<(), ApiError> {
+        self.lock().simulations.insert(
+            (simulation.opener.clone(), list_version.to_string()),
+            simulation.clone(),
+        );
+
+        Ok(())
+    }
+
+    fn average_goes(&self, word: &str) -> Result<Option<f64>, ApiError> {
+        let goes = self
+            .lock()
+            .games
+            .values()
+            .filter(|x| x.state == GameState::Won && x.mode_state.boards[0].word() == word)
+            .map(|x| x.goes)
+            .collect::<Vec<_>>();
+
+        Ok((!goes.is_empty()).then(|| goes.iter().sum::<usize>() as f64 / goes.len() as f64))
+    }
+
+    fn previous_answers(&self, client: &ClientId) -> Result<HashSet<String>, ApiError> {
+        Ok(self
+            .lock()
+            .games
+            .values()
+            .filter(|x| &x.client == client)
+            .flat_map(|x| x.mode_state.words())
+            .collect())
+    }
+
+    fn current_game(&self, client: &ClientId) -> Result<Option<GameId>, ApiError> {
+        let inner = self.lock();
+
+        // games are created in the order their first events were recorded
+        let game_id = inner
+            .events
+            .iter()
+            .rev()
+            .filter(|(_, event)| matches!(event, GameEvent::Created { .. }))
+            .map(|(game_id, _)| game_id)
+            .find(|game_id| {
+                inner
+                    .games
+                    .get(*game_id)
+                    .is_some_and(|x| &x.client == client && x.state == GameState::InProgress)
+            });
+
+        Ok(game_id.cloned())
+    }
+
+    fn outcomes(&self, client: &ClientId) -> Result<Vec<GameState>, ApiError> {
+        let inner = self.lock();
+
+        let outcomes = inner
+            .events
+            .iter()
+            .filter(|(_, event)| matches!(event, GameEvent::Created { .. }))
+            .filter_map(|(game_id, _)| inner.games.get(game_id))
+            .filter(|x| &x.client == client && x.state.is_finished())
+            .map(|x| x.state)
+            .collect();
+
+        Ok(outcomes)
+    }
+
+    fn days_won(&self, client: &ClientId) -> Result<Vec<u64>, ApiError> {
+        let inner = self.lock();
+
+        let mut days = inner
+            .events
+            .iter()
+            .zip(&inner.recorded_at)
+            .filter(|((_, event), _)| matches!(event, GameEvent::Created { .. }))
+            .filter(|((game_id, _), _)| {
+                inner
+                    .games
+                    .get(game_id)
+                    .is_some_and(|x| &x.client == client && x.state == GameState::Won)
+            })
+            .map(|(_, at)| at / (24 * 60 * 60))
+            .collect::<Vec<_>>();
+        days.sort_unstable_by(|a, b| b.cmp(a));
+        days.dedup();
+
+        Ok(days)
+    }
+
+    fn client_stats(&self) -> Result<Vec<ClientStats>, ApiError> {
+        let inner = self.lock();
+        let mut stats: BTreeMap<&str, (ClientStats, Vec<usize>)> = BTreeMap::new();
+
+        // keyed by client, so ordered like SQLite's GROUP BY
+        for (game_id, game) in &inner.games {
+            let (entry, solved_goes) = stats.entry(game.client.as_str()).or_insert_with(|| {
+                (
+                    ClientStats {
+                        client: game.client.to_string(),
+                        player: game.client.to_string(),
+                        avg_goes: None,
+                        max_goes: None,
+                        num_solved: 0,
+                        num_games: 0,
+                        num_rejected: 0,
+                        hint_penalty: 0,
+                        num_assisted: 0,
+                        num_assisted_solved: 0,
+                        num_forfeited: 0,
+                    },
+                    Vec::new(),
+                )
+            });
+
+            entry.num_games += 1;
+            entry.num_rejected += inner.rejections.iter().filter(|x| &x.0 == game_id).count();
+            let penalties = inner
+                .events
+                .iter()
+                .filter(|(id, _)| id == game_id)
+                .filter_map(|(_, event)| match event {
+                    GameEvent::Hinted { penalty, .. } => Some(*penalty),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            entry.hint_penalty += penalties.iter().sum::<usize>();
+            entry.num_assisted += usize::from(!penalties.is_empty());
+            entry.num_assisted_solved +=
+                usize::from(!penalties.is_empty() && game.state == GameState::Won);
+            entry.num_forfeited += usize::from(game.state == GameState::Forfeited);
+            if game.state == GameState::Won {
+                entry.num_solved += 1;
+                solved_goes.push(game.goes);
+            }
+        }
+
+        Ok(stats
+            .into_values()
+            .map(|(mut entry, solved_goes)| {
+                entry.max_goes = solved_goes.iter().copied().max();
+                entry.avg_goes = (!solved_goes.is_empty())
+                    .then(|| solved_goes.iter().sum::<usize>() as f64 / solved_goes.len() as f64);
+
+                entry
+            })
+            .collect())
+    }
+}
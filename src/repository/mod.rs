@@ -0,0 +1,326 @@
+mod memory;
+mod sqlite;
+
+pub use memory::MemoryRepository;
+pub use sqlite::SqliteRepository;
+
+use crate::achievements::{Achievement, Granted};
+use crate::challenges::Challenge;
+use crate::clubs::Club;
+use crate::error::ApiError;
+use crate::events::GameEvent;
+use crate::game::{
+    ClientId, GameId, GameMode, GameOptions, GameState, GroupId, HintLevel, ModeState, PlayedGuess,
+    Rejection,
+};
+use crate::groups::Group;
+use crate::guests::Guest;
+use crate::inbox::{InboxEvent, Message};
+use crate::profile::Profile;
+use crate::registration::Metadata;
+use crate::reminders::Reminder;
+use crate::share::ShareLink;
+use crate::solver::Simulation;
+use crate::stats::ClientStats;
+use std::collections::HashSet;
+
+/// A game as the service plays it
+#[derive(Clone)]
+pub struct StoredGame {
+    pub client: ClientId,
+    pub goes: usize,
+    pub state: GameState,
+    pub options: GameOptions,
+    pub mode: &'static dyn GameMode,
+    pub mode_state: ModeState,
+}
+
+impl StoredGame {
+    /// The game's latest `count` guesses, numbered by the go each was played on, once they've been
+    /// played one after another
+    pub fn latest_guesses(&self, count: usize) -> Vec<PlayedGuess> {
+        let revealed = &self.mode_state.revealed;
+        let latest = &revealed[revealed.len().saturating_sub(count)..];
+
+        latest
+            .iter()
+            .enumerate()
+            .map(|(i, (guess, evaluation))| PlayedGuess {
+                go: self.goes + 1 + i - latest.len(),
+                guess: guess.clone(),
+                evaluation: evaluation.clone(),
+            })
+            .collect()
+    }
+}
+
+/// A game that's over, with the day it was started on as `YYYY-MM-DD`
+#[derive(Clone)]
+pub struct FinishedGame {
+    pub day: String,
+    pub game: StoredGame,
+    /// Whether it was given a hint, which standings don't count a win with
+    pub assisted: bool,
+}
+
+/// Where the service keeps its games. SQLite in production, anything else in tests. Every write
+/// appends to the game's event history along with updating its current state, so that replaying
+/// the events always reproduces what's stored.
+pub trait Repository: Send + Sync {
+    /// The message to refuse writes with while the instance is read-only
+    fn read_only(&self) -> Result<Option<String>, ApiError>;
+
+    /// How many games are in progress
+    fn active_games(&self) -> Result<usize, ApiError>;
+
+    /// The version of the schema the games are kept in, always the latest for storage that has
+    /// nothing to migrate
+    fn schema_version(&self) -> Result<usize, ApiError>;
+
+    /// Stores a new game with its creation event, or returns false without storing anything if the
+    /// id is already taken
+    fn insert_game(&self, game_id: &GameId, game: &StoredGame) -> Result<bool, ApiError>;
+
+    fn find_game(&self, game_id: &GameId) -> Result<Option<StoredGame>, ApiError>;
+
+    /// Every guess played in the game, in order
+    fn guesses(&self, game_id: &GameId) -> Result<Vec<PlayedGuess>, ApiError>;
+
+    /// Saves the game along with the events that led to its new state, and the guesses among them
+    fn update_game(
+        &self,
+        game_id: &GameId,
+        game: &StoredGame,
+        events: &[GameEvent],
+    ) -> Result<(), ApiError>;
+
+    /// Notes a guess that was refused, using up a go if `counted`
+    fn record_rejection(
+        &self,
+        game_id: &GameId,
+        guess: &str,
+        reason: Rejection,
+        counted: bool,
+    ) -> Result<(), ApiError>;
+
+    /// Notes a hint given for the game, adding its penalty to the client's stats
+    /// Notes a hint given for the game, which uses up one of its goes
+    fn record_hint(
+        &self,
+        game_id: &GameId,
+        level: HintLevel,
+        penalty: usize,
+    ) -> Result<(), ApiError>;
+
+    /// Awards whichever of the achievements the client doesn't already have, all at once, returning
+    /// those that were new
+    fn grant_achievements(
+        &self,
+        client: &ClientId,
+        game_id: &GameId,
+        achievements: &[Achievement],
+    ) -> Result<Vec<Achievement>, ApiError>;
+
+    /// Every achievement the client has been awarded, in the order they were
+    fn achievements(&self, client: &ClientId) -> Result<Vec<Granted>, ApiError>;
+
+    fn profile(&self, client: &ClientId) -> Result<Option<Profile>, ApiError>;
+
+    fn save_profile(&self, client: &ClientId, profile: &Profile) -> Result<(), ApiError>;
+
+    fn reminder(&self, client: &ClientId) -> Result<Option<Reminder>, ApiError>;
+
+    fn save_reminder(&self, client: &ClientId, reminder: &Reminder) -> Result<(), ApiError>;
+
+    fn delete_reminder(&self, client: &ClientId) -> Result<(), ApiError>;
+
+    /// Leaves a message for the client, sent at `now` in seconds since the epoch
+    fn send_inbox(&self, client: &ClientId, event: &InboxEvent, now: u64) -> Result<(), ApiError>;
+
+    /// The client's oldest messages still to be acknowledged, up to `limit` of them
+    fn inbox(&self, client: &ClientId, limit: usize) -> Result<Vec<Message>, ApiError>;
+
+    /// Deletes the client's messages up to and including `up_to`
+    fn acknowledge_inbox(&self, client: &ClientId, up_to: u64) -> Result<(), ApiError>;
+
+    fn save_share_link(&self, link: &ShareLink) -> Result<(), ApiError>;
+
+    fn share_link(&self, token: &str) -> Result<Option<ShareLink>, ApiError>;
+
+    /// The game's share links, oldest first
+    fn share_links(&self, game_id: &GameId) -> Result<Vec<ShareLink>, ApiError>;
+
+    /// Revokes the game's share link, or returns false if it has no such link
+    fn delete_share_link(&self, game_id: &GameId, token: &str) -> Result<bool, ApiError>;
+
+    /// Shares the game's replay under the token until `expires_at`, in seconds since the epoch
+    fn save_replay_token(
+        &self,
+        token: &str,
+        game_id: &GameId,
+        expires_at: u64,
+    ) -> Result<(), ApiError>;
+
+    /// The game whose replay is shared under the token, unless it expired before `now`
+    fn replay_token(&self, token: &str, now: u64) -> Result<Option<GameId>, ApiError>;
+
+    /// A game's events, oldest first, each with when it was recorded in seconds since the epoch
+    fn timeline(&self, game_id: &GameId) -> Result<Vec<(GameEvent, u64)>, ApiError>;
+
+    fn insert_group(&self, group: &Group) -> Result<(), ApiError>;
+
+    fn group(&self, group_id: &GroupId) -> Result<Option<Group>, ApiError>;
+
+    /// The group's members, in the order they were added
+    fn group_members(&self, group_id: &GroupId) -> Result<Vec<ClientId>, ApiError>;
+
+    /// Adds the client to the group, or takes them off it, returning false if they already were, or
+    /// weren't
+    fn set_group_member(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        member: bool,
+    ) -> Result<bool, ApiError>;
+
+    /// The member's game of the group's word for the day, counted in days since the epoch
+    fn group_game(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        day: u64,
+    ) -> Result<Option<GameId>, ApiError>;
+
+    /// The client's game of the daily word for the day, counted in days since the epoch
+    fn daily_game(&self, client: &ClientId, day: u64) -> Result<Option<GameId>, ApiError>;
+
+    /// The day the game is its client's daily game for, if it's one
+    fn daily_day(&self, game_id: &GameId) -> Result<Option<u64>, ApiError>;
+
+    /// Notes the client's daily game for the day, unless they already have one
+    fn save_daily_game(
+        &self,
+        client: &ClientId,
+        day: u64,
+        game_id: &GameId,
+    ) -> Result<(), ApiError>;
+
+    fn insert_challenge(&self, challenge: &Challenge) -> Result<(), ApiError>;
+
+    fn challenge(&self, challenge_id: &str) -> Result<Option<Challenge>, ApiError>;
+
+    /// The client's game of the challenge's word, if they've started one
+    fn challenge_game(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+    ) -> Result<Option<GameId>, ApiError>;
+
+    /// Notes the client's game of the challenge's word, unless they already have one
+    fn save_challenge_game(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+        game_id: &GameId,
+    ) -> Result<(), ApiError>;
+
+    /// The answer scheduled for every group's word on the day, if the day has one
+    fn scheduled_answer(&self, day: u64) -> Result<Option<String>, ApiError>;
+
+    /// Notes the member's game for the day, unless they already have one
+    fn save_group_game(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        day: u64,
+        game_id: &GameId,
+    ) -> Result<(), ApiError>;
+
+    /// Stores a new club, or returns false without storing anything if its id is taken
+    fn insert_club(&self, club: &Club) -> Result<bool, ApiError>;
+
+    fn club(&self, club: &str) -> Result<Option<Club>, ApiError>;
+
+    /// Every club, ordered by id
+    fn clubs(&self) -> Result<Vec<Club>, ApiError>;
+
+    /// The club's members, in the order they joined
+    fn club_members(&self, club: &str) -> Result<Vec<ClientId>, ApiError>;
+
+    /// Moves the client into the club, out of any other
+    fn join_club(&self, club: &str, client: &ClientId) -> Result<(), ApiError>;
+
+    fn leave_club(&self, club: &str, client: &ClientId) -> Result<(), ApiError>;
+
+    /// The client's friends, in the order they were made
+    fn friends(&self, client: &ClientId) -> Result<Vec<ClientId>, ApiError>;
+
+    /// Makes the two clients each other's friends, or returns false if they already were
+    fn add_friend(&self, client: &ClientId, friend: &ClientId) -> Result<bool, ApiError>;
+
+    /// Ends the friendship for both clients, or returns false if there wasn't one
+    fn remove_friend(&self, client: &ClientId, friend: &ClientId) -> Result<bool, ApiError>;
+
+    fn invite_code(&self, client: &ClientId) -> Result<Option<String>, ApiError>;
+
+    /// Gives the client a new invite code, the old one no longer being accepted
+    fn save_invite_code(&self, client: &ClientId, code: &str) -> Result<(), ApiError>;
+
+    /// The client whose invite code it is
+    fn invited_by(&self, code: &str) -> Result<Option<ClientId>, ApiError>;
+
+    fn insert_guest(&self, guest: &Guest) -> Result<(), ApiError>;
+
+    fn guest(&self, client: &ClientId) -> Result<Option<Guest>, ApiError>;
+
+    /// Registers the client with the key, unless they've been registered already
+    fn insert_client(
+        &self,
+        client: &ClientId,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<bool, ApiError>;
+
+    /// The metadata the client registered with and when, in seconds since the epoch, if they have
+    fn registered_client(&self, client: &ClientId) -> Result<Option<(Metadata, u64)>, ApiError>;
+
+    /// The key the client is played as with, the one they registered or, for a guest, the one
+    /// they were made up with
+    fn api_key(&self, client: &ClientId) -> Result<Option<String>, ApiError>;
+
+    /// Moves the guest's games, achievements, friends and memberships to the client all at once,
+    /// keeping the client's own wherever they have both, and forgets the guest. Returns how many
+    /// games and achievements were moved.
+    fn merge_guest(&self, guest: &ClientId, into: &ClientId) -> Result<(usize, usize), ApiError>;
+
+    /// The clients' finished games that were started on the day `since`, counted in days since the
+    /// epoch, or later, newest first
+    fn finished_games(
+        &self,
+        clients: &[ClientId],
+        since: u64,
+        limit: usize,
+    ) -> Result<Vec<FinishedGame>, ApiError>;
+
+    /// A simulation of the opener saved against this version of the answer list
+    fn simulation(&self, opener: &str, list_version: &str) -> Result<Option<Simulation>, ApiError>;
+
+    fn save_simulation(&self, list_version: &str, simulation: &Simulation) -> Result<(), ApiError>;
+
+    /// The average goes taken by every client to win a game with the answer
+    fn average_goes(&self, word: &str) -> Result<Option<f64>, ApiError>;
+
+    fn client_stats(&self) -> Result<Vec<ClientStats>, ApiError>;
+
+    /// Every answer the client has been given
+    fn previous_answers(&self, client: &ClientId) -> Result<HashSet<String>, ApiError>;
+
+    /// The client's most recently created game that is still in progress
+    fn current_game(&self, client: &ClientId) -> Result<Option<GameId>, ApiError>;
+
+    /// How each of the client's finished games ended, in the order they were created
+    fn outcomes(&self, client: &ClientId) -> Result<Vec<GameState>, ApiError>;
+
+    /// The UTC days, counted from the epoch, the client won a game started on, latest first
+    fn days_won(&self, client: &ClientId) -> Result<Vec<u64>, ApiError>;
+}
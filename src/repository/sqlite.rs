@@ -0,0 +1,1116 @@
+use crate::achievements::{Achievement, Granted};
+use crate::challenges::Challenge;
+use crate::clubs::Club;
+use crate::error::ApiError;
+use crate::events::GameEvent;
+use crate::game::{
+    self, ClientId, GameId, GameOptions, GameState, GroupId, HintLevel, PlayedGuess, Rejection,
+};
+use crate::groups::Group;
+use crate::guests::Guest;
+use crate::inbox::{InboxEvent, Message};
+use crate::profile::Profile;
+use crate::registration::Metadata;
+use crate::reminders::Reminder;
+use crate::repository::{FinishedGame, Repository, StoredGame};
+use crate::share::ShareLink;
+use crate::solver::Simulation;
+use crate::stats::{self, ClientStats};
+use crate::{db, maintenance};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashSet;
+
+/// Opens a fresh connection for every call, like the handlers always have
+pub struct SqliteRepository {
+    database: String,
+}
+
+impl SqliteRepository {
+    pub fn new(database: &str) -> SqliteRepository {
+        SqliteRepository {
+            database: database.to_string(),
+        }
+    }
+
+    fn connection(&self) -> Result<Connection, rusqlite::Error> {
+        db::open(&self.database)
+    }
+}
+
+impl Repository for SqliteRepository {
+    fn read_only(&self) -> Result<Option<String>, ApiError> {
+        Ok(maintenance::read_only(&self.connection()?)?)
+    }
+
+    fn active_games(&self) -> Result<usize, ApiError> {
+        Ok(self.connection()?.query_row(
+            "SELECT COUNT(*) FROM game WHERE state = 'in_progress'",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn schema_version(&self) -> Result<usize, ApiError> {
+        Ok(db::schema_version(&self.connection()?)?)
+    }
+
+    fn insert_game(&self, game_id: &GameId, game: &StoredGame) -> Result<bool, ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        let result = tx.execute(
+            "INSERT INTO game (game_id, client, word, goes, state, invalid_guesses_count, mode,
+                max_guesses, length, language, mode_state, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, datetime(?12, 'unixepoch'))",
+            (
+                game_id,
+                &game.client,
+                game.mode_state.boards[0].word(),
+                game.goes,
+                game.state.as_str(),
+                game.options.invalid_guesses_count,
+                &game.options.mode,
+                game.options.max_guesses,
+                game.options.length,
+                &game.options.language,
+                serde_json::to_string(&game.mode_state)?,
+                game::now(),
+            ),
+        );
+
+        match result {
+            Ok(_) => {}
+            Err(error) if db::is_unique_violation(&error) => return Ok(false),
+            Err(error) => return Err(error.into()),
+        }
+
+        db::append_events(&tx, game_id.as_str(), &[GameEvent::created(game)])?;
+        tx.commit()?;
+
+        Ok(true)
+    }
+
+    fn find_game(&self, game_id: &GameId) -> Result<Option<StoredGame>, ApiError> {
+        let game = self
+            .connection()?
+            .query_row(
+                "SELECT client, word, goes, state, invalid_guesses_count, mode, mode_state,
+                    max_guesses, length, language
+                FROM game_history WHERE game_id = ?1",
+                [game_id],
+                |row| {
+                    let word: String = row.get(1)?;
+                    let mode = db::get_mode(row, 5)?;
+
+                    Ok(StoredGame {
+                        client: ClientId::stored(row.get(0)?),
+                        goes: row.get(2)?,
+                        state: db::get_state(row, 3)?,
+                        options: GameOptions {
+                            invalid_guesses_count: row.get(4)?,
+                            mode: mode.name().to_string(),
+                            max_guesses: row.get(7)?,
+                            length: row.get(8)?,
+                            language: row.get(9)?,
+                        },
+                        mode,
+                        mode_state: db::get_mode_state(row, 6, &word)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(game)
+    }
+
+    fn guesses(&self, game_id: &GameId) -> Result<Vec<PlayedGuess>, ApiError> {
+        self.connection()?
+            .prepare("SELECT go, guess, evaluation FROM game_guess WHERE game_id = ?1 ORDER BY go")?
+            .query_map([game_id], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+            })?
+            .map(|row| {
+                let (go, guess, evaluation) = row?;
+
+                Ok(PlayedGuess {
+                    go,
+                    guess,
+                    evaluation: serde_json::from_str(&evaluation)?,
+                })
+            })
+            .collect()
+    }
+
+    fn update_game(
+        &self,
+        game_id: &GameId,
+        game: &StoredGame,
+        events: &[GameEvent],
+    ) -> Result<(), ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        db::append_events(&tx, game_id.as_str(), events)?;
+        let mut guesses = 0;
+        for event in events {
+            if let GameEvent::Guessed { guess } = event {
+                db::log_guess(&tx, game_id, guess, "accepted", true)?;
+                guesses += 1;
+            }
+        }
+        for played in game.latest_guesses(guesses) {
+            tx.execute(
+                "INSERT INTO game_guess (game_id, go, guess, evaluation) VALUES (?1, ?2, ?3, ?4)",
+                (
+                    game_id,
+                    played.go,
+                    &played.guess,
+                    serde_json::to_string(&played.evaluation)?,
+                ),
+            )?;
+        }
+        // modes that decide the answer as they go can change it with every guess
+        tx.execute(
+            "UPDATE game SET goes = ?1, solved = ?2, state = ?3, word = ?4, mode_state = ?5,
+                updated_at = datetime(?7, 'unixepoch')
+            WHERE game_id = ?6",
+            (
+                game.goes,
+                game.state == GameState::Won,
+                game.state.as_str(),
+                game.mode_state.boards[0].word(),
+                serde_json::to_string(&game.mode_state)?,
+                game_id,
+                game::now(),
+            ),
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn record_rejection(
+        &self,
+        game_id: &GameId,
+        guess: &str,
+        reason: Rejection,
+        counted: bool,
+    ) -> Result<(), ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        db::record_rejection(&tx, game_id, guess, reason, counted)?;
+        db::log_guess(&tx, game_id, guess, reason.as_str(), counted)?;
+        db::append_events(
+            &tx,
+            game_id.as_str(),
+            &[GameEvent::Rejected {
+                guess: guess.to_string(),
+                reason,
+                counted,
+            }],
+        )?;
+
+        if counted {
+            tx.execute(
+                // the last go lost on a rejection loses the game just as a wrong guess would
+                "UPDATE game SET goes = goes + 1, updated_at = datetime(?2, 'unixepoch'),
+                    state = CASE WHEN goes + 1 >= max_guesses THEN 'lost' ELSE state END
+                WHERE game_id = ?1",
+                (game_id, game::now()),
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn record_hint(
+        &self,
+        game_id: &GameId,
+        level: HintLevel,
+        penalty: usize,
+    ) -> Result<(), ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        db::append_events(
+            &tx,
+            game_id.as_str(),
+            &[GameEvent::Hinted {
+                level,
+                penalty,
+                counted: true,
+            }],
+        )?;
+        tx.execute(
+            "UPDATE game SET goes = goes + 1, updated_at = datetime(?2, 'unixepoch')
+            WHERE game_id = ?1",
+            (game_id, game::now()),
+        )?;
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    fn grant_achievements(
+        &self,
+        client: &ClientId,
+        game_id: &GameId,
+        achievements: &[Achievement],
+    ) -> Result<Vec<Achievement>, ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        let mut granted = Vec::new();
+        for achievement in achievements {
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO achievement (client, achievement, game_id) VALUES (?1, ?2, ?3)",
+                (client, achievement.as_str(), game_id),
+            )?;
+            if inserted > 0 {
+                granted.push(*achievement);
+            }
+        }
+        tx.commit()?;
+
+        Ok(granted)
+    }
+
+    fn achievements(&self, client: &ClientId) -> Result<Vec<Granted>, ApiError> {
+        let rows = self
+            .connection()?
+            .prepare(
+                "SELECT achievement, game_id FROM achievement WHERE client = ?1 ORDER BY rowid",
+            )?
+            .query_map([client], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // anything a newer release awarded that this one doesn't know of is left out
+        Ok(rows
+            .into_iter()
+            .filter_map(|(achievement, game_id)| {
+                Some(Granted::new(
+                    Achievement::parse(&achievement)?,
+                    GameId::stored(game_id),
+                ))
+            })
+            .collect())
+    }
+
+    fn profile(&self, client: &ClientId) -> Result<Option<Profile>, ApiError> {
+        let data: Option<String> = self
+            .connection()?
+            .query_row(
+                "SELECT data FROM profile WHERE client = ?1",
+                [client],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(data.map(|x| serde_json::from_str(&x)).transpose()?)
+    }
+
+    fn save_profile(&self, client: &ClientId, profile: &Profile) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO profile (client, data) VALUES (?1, ?2)
+            ON CONFLICT (client) DO UPDATE SET data = excluded.data, updated_at = CURRENT_TIMESTAMP",
+            (client, serde_json::to_string(profile)?),
+        )?;
+
+        Ok(())
+    }
+
+    fn reminder(&self, client: &ClientId) -> Result<Option<Reminder>, ApiError> {
+        let data: Option<String> = self
+            .connection()?
+            .query_row(
+                "SELECT data FROM reminder WHERE client = ?1",
+                [client],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(data.map(|x| serde_json::from_str(&x)).transpose()?)
+    }
+
+    fn save_reminder(&self, client: &ClientId, reminder: &Reminder) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO reminder (client, data) VALUES (?1, ?2)
+            ON CONFLICT (client) DO UPDATE SET data = excluded.data, updated_at = CURRENT_TIMESTAMP",
+            (client, serde_json::to_string(reminder)?),
+        )?;
+
+        Ok(())
+    }
+
+    fn delete_reminder(&self, client: &ClientId) -> Result<(), ApiError> {
+        self.connection()?
+            .execute("DELETE FROM reminder WHERE client = ?1", [client])?;
+
+        Ok(())
+    }
+
+    fn send_inbox(&self, client: &ClientId, event: &InboxEvent, now: u64) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO inbox (client, data, sent_at) VALUES (?1, ?2, ?3)",
+            (client, serde_json::to_string(event)?, now),
+        )?;
+
+        Ok(())
+    }
+
+    fn inbox(&self, client: &ClientId, limit: usize) -> Result<Vec<Message>, ApiError> {
+        let rows = self
+            .connection()?
+            .prepare(
+                "SELECT message_id, sent_at, data FROM inbox WHERE client = ?1
+                ORDER BY message_id LIMIT ?2",
+            )?
+            .query_map((client, limit), |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<Result<Vec<(u64, u64, String)>, _>>()?;
+
+        rows.into_iter()
+            .map(|(message_id, sent_at, data)| {
+                Ok(Message {
+                    message_id,
+                    sent_at,
+                    event: serde_json::from_str(&data)?,
+                })
+            })
+            .collect()
+    }
+
+    fn acknowledge_inbox(&self, client: &ClientId, up_to: u64) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "DELETE FROM inbox WHERE client = ?1 AND message_id <= ?2",
+            (client, up_to),
+        )?;
+
+        Ok(())
+    }
+
+    fn save_share_link(&self, link: &ShareLink) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO share_link (token, game_id, show_guesses) VALUES (?1, ?2, ?3)",
+            (&link.token, &link.game_id, link.show_guesses),
+        )?;
+
+        Ok(())
+    }
+
+    fn share_link(&self, token: &str) -> Result<Option<ShareLink>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT game_id, show_guesses FROM share_link WHERE token = ?1",
+                [token],
+                |row| {
+                    Ok(ShareLink {
+                        token: token.to_string(),
+                        game_id: GameId::stored(row.get(0)?),
+                        show_guesses: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn share_links(&self, game_id: &GameId) -> Result<Vec<ShareLink>, ApiError> {
+        let links = self
+            .connection()?
+            .prepare(
+                "SELECT token, show_guesses FROM share_link WHERE game_id = ?1 ORDER BY rowid",
+            )?
+            .query_map([game_id], |row| {
+                Ok(ShareLink {
+                    token: row.get(0)?,
+                    game_id: game_id.clone(),
+                    show_guesses: row.get(1)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(links)
+    }
+
+    fn delete_share_link(&self, game_id: &GameId, token: &str) -> Result<bool, ApiError> {
+        let deleted = self.connection()?.execute(
+            "DELETE FROM share_link WHERE game_id = ?1 AND token = ?2",
+            (game_id, token),
+        )?;
+
+        Ok(deleted > 0)
+    }
+
+    fn save_replay_token(
+        &self,
+        token: &str,
+        game_id: &GameId,
+        expires_at: u64,
+    ) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO replay_token (token, game_id, expires_at) VALUES (?1, ?2, ?3)",
+            (token, game_id, expires_at),
+        )?;
+
+        Ok(())
+    }
+
+    fn replay_token(&self, token: &str, now: u64) -> Result<Option<GameId>, ApiError> {
+        let game_id = self
+            .connection()?
+            .query_row(
+                "SELECT game_id FROM replay_token WHERE token = ?1 AND expires_at > ?2",
+                (token, now),
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(game_id.map(GameId::stored))
+    }
+
+    fn timeline(&self, game_id: &GameId) -> Result<Vec<(GameEvent, u64)>, ApiError> {
+        let timeline = self
+            .connection()?
+            .prepare(
+                "SELECT data, unixepoch(created_at) FROM game_event WHERE game_id = ?1
+                ORDER BY event_id",
+            )?
+            .query_map([game_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, u64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        timeline
+            .into_iter()
+            .map(|(data, at)| Ok((serde_json::from_str(&data)?, at)))
+            .collect()
+    }
+
+    fn insert_group(&self, group: &Group) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO player_group (group_id, name, owner, key) VALUES (?1, ?2, ?3, ?4)",
+            (&group.group_id, &group.name, &group.owner, &group.key),
+        )?;
+
+        Ok(())
+    }
+
+    fn group(&self, group_id: &GroupId) -> Result<Option<Group>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT name, owner, key FROM player_group WHERE group_id = ?1",
+                [group_id],
+                |row| {
+                    Ok(Group {
+                        group_id: group_id.clone(),
+                        name: row.get(0)?,
+                        owner: ClientId::stored(row.get(1)?),
+                        key: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn group_members(&self, group_id: &GroupId) -> Result<Vec<ClientId>, ApiError> {
+        let members = self
+            .connection()?
+            .prepare("SELECT client FROM player_group_member WHERE group_id = ?1 ORDER BY rowid")?
+            .query_map([group_id], |row| Ok(ClientId::stored(row.get(0)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(members)
+    }
+
+    fn set_group_member(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        member: bool,
+    ) -> Result<bool, ApiError> {
+        let query = if member {
+            "INSERT OR IGNORE INTO player_group_member (group_id, client) VALUES (?1, ?2)"
+        } else {
+            "DELETE FROM player_group_member WHERE group_id = ?1 AND client = ?2"
+        };
+        let changed = self.connection()?.execute(query, (group_id, client))?;
+
+        Ok(changed > 0)
+    }
+
+    fn group_game(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        day: u64,
+    ) -> Result<Option<GameId>, ApiError> {
+        let game_id = self
+            .connection()?
+            .query_row(
+                "SELECT game_id FROM player_group_game WHERE group_id = ?1 AND client = ?2 AND day = ?3",
+                (group_id, client, day),
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(game_id.map(GameId::stored))
+    }
+
+    fn daily_game(&self, client: &ClientId, day: u64) -> Result<Option<GameId>, ApiError> {
+        let game_id = self
+            .connection()?
+            .query_row(
+                "SELECT game_id FROM daily_game WHERE client = ?1 AND day = ?2",
+                (client, day),
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(game_id.map(GameId::stored))
+    }
+
+    fn daily_day(&self, game_id: &GameId) -> Result<Option<u64>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT day FROM daily_game WHERE game_id = ?1",
+                [game_id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn save_daily_game(
+        &self,
+        client: &ClientId,
+        day: u64,
+        game_id: &GameId,
+    ) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT OR IGNORE INTO daily_game (client, day, game_id) VALUES (?1, ?2, ?3)",
+            (client, day, game_id),
+        )?;
+
+        Ok(())
+    }
+
+    fn insert_challenge(&self, challenge: &Challenge) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO challenge (challenge_id, client, word, created_at) VALUES (?1, ?2, ?3, ?4)",
+            (
+                &challenge.challenge_id,
+                &challenge.creator,
+                &challenge.word,
+                game::now(),
+            ),
+        )?;
+
+        Ok(())
+    }
+
+    fn challenge(&self, challenge_id: &str) -> Result<Option<Challenge>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT client, word FROM challenge WHERE challenge_id = ?1",
+                [challenge_id],
+                |row| {
+                    Ok(Challenge {
+                        challenge_id: challenge_id.to_string(),
+                        creator: ClientId::stored(row.get(0)?),
+                        word: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+
+    fn challenge_game(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+    ) -> Result<Option<GameId>, ApiError> {
+        let game_id = self
+            .connection()?
+            .query_row(
+                "SELECT game_id FROM challenge_game WHERE challenge_id = ?1 AND client = ?2",
+                (challenge_id, client),
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(game_id.map(GameId::stored))
+    }
+
+    fn save_challenge_game(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+        game_id: &GameId,
+    ) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT OR IGNORE INTO challenge_game (challenge_id, client, game_id) VALUES (?1, ?2, ?3)",
+            (challenge_id, client, game_id),
+        )?;
+
+        Ok(())
+    }
+
+    fn scheduled_answer(&self, day: u64) -> Result<Option<String>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT word FROM answer_schedule WHERE day = ?1",
+                [day],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn save_group_game(
+        &self,
+        group_id: &GroupId,
+        client: &ClientId,
+        day: u64,
+        game_id: &GameId,
+    ) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT OR IGNORE INTO player_group_game (group_id, client, day, game_id)
+            VALUES (?1, ?2, ?3, ?4)",
+            (group_id, client, day, game_id),
+        )?;
+
+        Ok(())
+    }
+
+    fn insert_club(&self, club: &Club) -> Result<bool, ApiError> {
+        let result = self.connection()?.execute(
+            "INSERT INTO club (club, name) VALUES (?1, ?2)",
+            (&club.club, &club.name),
+        );
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(error) if db::is_unique_violation(&error) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn club(&self, club: &str) -> Result<Option<Club>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row("SELECT name FROM club WHERE club = ?1", [club], |row| {
+                Ok(Club {
+                    club: club.to_string(),
+                    name: row.get(0)?,
+                })
+            })
+            .optional()?)
+    }
+
+    fn clubs(&self) -> Result<Vec<Club>, ApiError> {
+        let clubs = self
+            .connection()?
+            .prepare("SELECT club, name FROM club ORDER BY club")?
+            .query_map([], |row| {
+                Ok(Club {
+                    club: row.get(0)?,
+                    name: row.get(1)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(clubs)
+    }
+
+    fn club_members(&self, club: &str) -> Result<Vec<ClientId>, ApiError> {
+        let members = self
+            .connection()?
+            .prepare("SELECT client FROM club_member WHERE club = ?1 ORDER BY joined_at, rowid")?
+            .query_map([club], |row| Ok(ClientId::stored(row.get(0)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(members)
+    }
+
+    fn join_club(&self, club: &str, client: &ClientId) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO club_member (client, club) VALUES (?1, ?2)
+            ON CONFLICT (client) DO UPDATE SET club = excluded.club, joined_at = CURRENT_TIMESTAMP
+            WHERE club != excluded.club",
+            (client, club),
+        )?;
+
+        Ok(())
+    }
+
+    fn leave_club(&self, club: &str, client: &ClientId) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "DELETE FROM club_member WHERE client = ?1 AND club = ?2",
+            (client, club),
+        )?;
+
+        Ok(())
+    }
+
+    fn friends(&self, client: &ClientId) -> Result<Vec<ClientId>, ApiError> {
+        let friends = self
+            .connection()?
+            .prepare("SELECT friend FROM friend WHERE client = ?1 ORDER BY rowid")?
+            .query_map([client], |row| Ok(ClientId::stored(row.get(0)?)))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(friends)
+    }
+
+    fn add_friend(&self, client: &ClientId, friend: &ClientId) -> Result<bool, ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        let added = tx.execute(
+            "INSERT OR IGNORE INTO friend (client, friend) VALUES (?1, ?2)",
+            (client, friend),
+        )?;
+        tx.execute(
+            "INSERT OR IGNORE INTO friend (client, friend) VALUES (?1, ?2)",
+            (friend, client),
+        )?;
+        tx.commit()?;
+
+        Ok(added > 0)
+    }
+
+    fn remove_friend(&self, client: &ClientId, friend: &ClientId) -> Result<bool, ApiError> {
+        let removed = self.connection()?.execute(
+            "DELETE FROM friend
+            WHERE (client = ?1 AND friend = ?2) OR (client = ?2 AND friend = ?1)",
+            (client, friend),
+        )?;
+
+        Ok(removed > 0)
+    }
+
+    fn invite_code(&self, client: &ClientId) -> Result<Option<String>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT code FROM friend_invite WHERE client = ?1",
+                [client],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn save_invite_code(&self, client: &ClientId, code: &str) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO friend_invite (client, code) VALUES (?1, ?2)
+            ON CONFLICT (client) DO UPDATE SET code = excluded.code, created_at = CURRENT_TIMESTAMP",
+            (client, code),
+        )?;
+
+        Ok(())
+    }
+
+    fn invited_by(&self, code: &str) -> Result<Option<ClientId>, ApiError> {
+        let client = self
+            .connection()?
+            .query_row(
+                "SELECT client FROM friend_invite WHERE code = ?1",
+                [code],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(client.map(ClientId::stored))
+    }
+
+    fn insert_guest(&self, guest: &Guest) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT INTO guest (client, key, created_at) VALUES (?1, ?2, ?3)",
+            (&guest.client, &guest.key, game::now()),
+        )?;
+
+        Ok(())
+    }
+
+    fn guest(&self, client: &ClientId) -> Result<Option<Guest>, ApiError> {
+        let key = self
+            .connection()?
+            .query_row("SELECT key FROM guest WHERE client = ?1", [client], |row| {
+                row.get(0)
+            })
+            .optional()?;
+
+        Ok(key.map(|key| Guest {
+            client: client.clone(),
+            key,
+        }))
+    }
+
+    fn insert_client(
+        &self,
+        client: &ClientId,
+        key: &str,
+        metadata: &Metadata,
+    ) -> Result<bool, ApiError> {
+        let result = self.connection()?.execute(
+            "INSERT INTO client (client, key, metadata, created_at) VALUES (?1, ?2, ?3, ?4)",
+            (client, key, serde_json::to_string(metadata)?, game::now()),
+        );
+
+        match result {
+            Ok(_) => Ok(true),
+            Err(error) if db::is_unique_violation(&error) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn registered_client(&self, client: &ClientId) -> Result<Option<(Metadata, u64)>, ApiError> {
+        let row = self
+            .connection()?
+            .query_row(
+                "SELECT metadata, created_at FROM client WHERE client = ?1",
+                [client],
+                |row| Ok((row.get::<_, String>(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        row.map(|(metadata, registered_at)| Ok((serde_json::from_str(&metadata)?, registered_at)))
+            .transpose()
+    }
+
+    fn api_key(&self, client: &ClientId) -> Result<Option<String>, ApiError> {
+        Ok(self
+            .connection()?
+            .query_row(
+                "SELECT key FROM client WHERE client = ?1
+                UNION ALL SELECT key FROM guest WHERE client = ?1",
+                [client],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn merge_guest(&self, guest: &ClientId, into: &ClientId) -> Result<(usize, usize), ApiError> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction()?;
+
+        // creation events name the client too, so that replaying them gives the games to the client
+        tx.execute(
+            "UPDATE game_event SET data = json_set(data, '$.client', ?1)
+            WHERE kind = 'created' AND game_id IN (SELECT game_id FROM game_history WHERE client = ?2)",
+            (into, guest),
+        )?;
+        let mut games = 0;
+        for table in ["game", "game_archive"] {
+            games += tx.execute(
+                &format!("UPDATE {table} SET client = ?1 WHERE client = ?2"),
+                (into, guest),
+            )?;
+        }
+        let achievements = tx.execute(
+            "UPDATE OR IGNORE achievement SET client = ?1 WHERE client = ?2",
+            (into, guest),
+        )?;
+
+        // whatever the client has of their own already is kept, and the guest's left behind
+        for table in [
+            "player_group_member",
+            "player_group_game",
+            "daily_game",
+            "challenge_game",
+            "club_member",
+            "profile",
+            "reminder",
+            "friend_invite",
+        ] {
+            tx.execute(
+                &format!("UPDATE OR IGNORE {table} SET client = ?1 WHERE client = ?2"),
+                (into, guest),
+            )?;
+        }
+        tx.execute(
+            "UPDATE OR IGNORE friend SET client = ?1 WHERE client = ?2",
+            (into, guest),
+        )?;
+        tx.execute(
+            "UPDATE OR IGNORE friend SET friend = ?1 WHERE friend = ?2",
+            (into, guest),
+        )?;
+        tx.execute(
+            "DELETE FROM friend WHERE client = ?1 OR friend = ?1 OR client = friend",
+            [guest],
+        )?;
+        tx.execute(
+            "UPDATE player_group SET owner = ?1 WHERE owner = ?2",
+            (into, guest),
+        )?;
+        tx.execute(
+            "UPDATE challenge SET client = ?1 WHERE client = ?2",
+            (into, guest),
+        )?;
+        tx.execute(
+            "UPDATE inbox SET client = ?1 WHERE client = ?2",
+            (into, guest),
+        )?;
+
+        for table in [
+            "achievement",
+            "player_group_member",
+            "player_group_game",
+            "daily_game",
+            "challenge_game",
+            "club_member",
+            "profile",
+            "reminder",
+            "friend_invite",
+            "guest",
+        ] {
+            tx.execute(&format!("DELETE FROM {table} WHERE client = ?1"), [guest])?;
+        }
+        tx.commit()?;
+
+        Ok((games, achievements))
+    }
+
+    fn finished_games(
+        &self,
+        clients: &[ClientId],
+        since: u64,
+        limit: usize,
+    ) -> Result<Vec<FinishedGame>, ApiError> {
+        let rows = self
+            .connection()?
+            .prepare(
+                "SELECT game_id, date(created_at), EXISTS (
+                    SELECT 1 FROM game_event WHERE game_event.game_id = game.game_id AND kind = 'hinted'
+                ) FROM game_history AS game
+                WHERE client IN (SELECT value FROM json_each(?1)) AND state != 'in_progress'
+                    AND created_at >= date(?2 * 86400, 'unixepoch')
+                ORDER BY seq DESC LIMIT ?3",
+            )?
+            // SQLite counts in i64, which no limit worth asking for outgrows
+            .query_map(
+                (
+                    serde_json::to_string(clients)?,
+                    since,
+                    limit.min(i64::MAX as usize),
+                ),
+                |row| {
+                    Ok((
+                        GameId::stored(row.get(0)?),
+                        row.get::<_, String>(1)?,
+                        row.get(2)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut games = Vec::new();
+        for (game_id, day, assisted) in rows {
+            if let Some(game) = self.find_game(&game_id)? {
+                games.push(FinishedGame {
+                    day,
+                    game,
+                    assisted,
+                });
+            }
+        }
+
+        Ok(games)
+    }
+
+    fn simulation(&self, opener: &str, list_version: &str) -> Result<Option<Simulation>, ApiError> {
+        let data: Option<String> = self
+            .connection()?
+            .query_row(
+                "SELECT data FROM opener_simulation WHERE opener = ?1 AND list_version = ?2",
+                [opener, list_version],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(data.map(|x| serde_json::from_str(&x)).transpose()?)
+    }
+
+    fn save_simulation(&self, list_version: &str, simulation: &Simulation) -> Result<(), ApiError> {
+        self.connection()?.execute(
+            "INSERT OR REPLACE INTO opener_simulation (opener, list_version, data)
+            VALUES (?1, ?2, ?3)",
+            (
+                &simulation.opener,
+                list_version,
+                serde_json::to_string(simulation)?,
+            ),
+        )?;
+
+        Ok(())
+    }
+
+    fn average_goes(&self, word: &str) -> Result<Option<f64>, ApiError> {
+        Ok(self.connection()?.query_row(
+            "SELECT AVG(goes) FROM game_history WHERE word = ?1 AND state = 'won'",
+            [word],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn client_stats(&self) -> Result<Vec<ClientStats>, ApiError> {
+        Ok(stats::client_stats(&self.connection()?)?)
+    }
+
+    fn previous_answers(&self, client: &ClientId) -> Result<HashSet<String>, ApiError> {
+        let answers = self
+            .connection()?
+            .prepare("SELECT DISTINCT word FROM game_history WHERE client = ?1")?
+            .query_map([client], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(answers)
+    }
+
+    fn current_game(&self, client: &ClientId) -> Result<Option<GameId>, ApiError> {
+        let game_id = self
+            .connection()?
+            .query_row(
+                "SELECT game_id FROM game WHERE client = ?1 AND state = 'in_progress'
+                ORDER BY rowid DESC LIMIT 1",
+                [client],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(game_id.map(GameId::stored))
+    }
+
+    fn outcomes(&self, client: &ClientId) -> Result<Vec<GameState>, ApiError> {
+        let outcomes = self
+            .connection()?
+            .prepare(
+                "SELECT state FROM game_history WHERE client = ?1 AND state != 'in_progress'
+                ORDER BY seq",
+            )?
+            .query_map([client], |row| db::get_state(row, 0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(outcomes)
+    }
+
+    fn days_won(&self, client: &ClientId) -> Result<Vec<u64>, ApiError> {
+        let days = self
+            .connection()?
+            .prepare(
+                "SELECT DISTINCT unixepoch(created_at) / 86400 AS day FROM game_history
+                WHERE client = ?1 AND state = 'won' AND created_at IS NOT NULL ORDER BY day DESC",
+            )?
+            .query_map([client], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        Ok(days)
+    }
+}
@@ -0,0 +1,200 @@
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How serious a request's outcome was, by its status. Each level includes those before it.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    /// Failed on the server's side, a `5xx`
+    Error,
+    /// Refused, a `4xx`
+    Warn,
+    /// Everything else
+    Info,
+}
+
+impl Level {
+    /// `error`, `warn` or `info`
+    pub fn parse(value: &str) -> Option<Level> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(Level::Error),
+            "warn" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            _ => None,
+        }
+    }
+
+    fn of(status: u16) -> Level {
+        match status {
+            500.. => Level::Error,
+            400.. => Level::Warn,
+            _ => Level::Info,
+        }
+    }
+}
+
+/// Where log lines are written
+pub enum Target {
+    Stderr,
+    Stdout,
+    File(File),
+}
+
+impl Target {
+    /// `stderr`, `stdout`, or the path of a file to append to
+    pub fn open(value: &str) -> io::Result<Target> {
+        match value {
+            "stderr" => Ok(Target::Stderr),
+            "stdout" => Ok(Target::Stdout),
+            path => Ok(Target::File(
+                OpenOptions::new().create(true).append(true).open(path)?,
+            )),
+        }
+    }
+}
+
+/// Writes a line of JSON for each request at or above its level
+pub struct RequestLog {
+    level: Level,
+    target: Mutex<Target>,
+}
+
+/// A request as it's logged. The query string is left out, as it can carry tokens.
+#[derive(Serialize, Debug)]
+struct Entry<'a> {
+    /// Seconds since the epoch
+    time: u64,
+    level: Level,
+    request_id: &'a str,
+    method: &'a str,
+    path: &'a str,
+    status: u16,
+    duration_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    client: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    game_id: Option<&'a str>,
+}
+
+impl RequestLog {
+    pub fn new(level: Level, target: Target) -> RequestLog {
+        RequestLog {
+            level,
+            target: Mutex::new(target),
+        }
+    }
+
+    /// Logs the request, unless its outcome is below the configured level. A log that can't be
+    /// written to is no reason to fail the request, so failures are ignored.
+    pub fn record(&self, request_id: &str, method: &str, path: &str, status: u16, took: Duration) {
+        let level = Level::of(status);
+        if level > self.level {
+            return;
+        }
+
+        let (client, game_id) = subjects(path);
+        let entry = Entry {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |x| x.as_secs()),
+            level,
+            request_id,
+            method,
+            path,
+            status,
+            duration_ms: took.as_secs_f64() * 1000.0,
+            client,
+            game_id,
+        };
+        let Ok(line) = serde_json::to_string(&entry) else {
+            return;
+        };
+
+        // written as one line at a time, so that concurrent requests never interleave
+        let mut target = self.target.lock().unwrap_or_else(|x| x.into_inner());
+        let _ = match &mut *target {
+            Target::Stderr => writeln!(io::stderr(), "{line}"),
+            Target::Stdout => writeln!(io::stdout(), "{line}"),
+            Target::File(file) => writeln!(file, "{line}"),
+        };
+    }
+}
+
+/// The client and the game a path is about, wherever the route names them
+fn subjects(path: &str) -> (Option<&str>, Option<&str>) {
+    let segments = path.trim_start_matches('/').split('/').collect::<Vec<_>>();
+    let at = |i: usize| segments.get(i).copied().filter(|x| !x.is_empty());
+
+    let client = match segments.as_slice() {
+        ["create" | "daily" | "profiles" | "profile" | "friends" | "reminders" | "achievements"
+        | "inbox" | "badge" | "guests", ..] => at(1),
+        ["challenge", _, ..] => at(2),
+        ["groups" | "clubs", _, "members", ..] => at(3),
+        ["admin", "client", ..] => at(2),
+        _ => None,
+    };
+    let game_id = match segments.as_slice() {
+        ["play" | "game" | "games", ..] => at(1),
+        _ => None,
+    };
+
+    (client, game_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::request_log::{subjects, Level, RequestLog, Target};
+    use std::env;
+    use std::fs;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[test]
+    fn paths_name_their_client_and_game() {
+        let game_id = "00000000-0000-4000-8000-000000000000";
+
+        assert_eq!(
+            subjects(&format!("/play/{game_id}/guess/crane")),
+            (None, Some(game_id))
+        );
+        assert_eq!(subjects("/daily/alice"), (Some("alice"), None));
+        assert_eq!(subjects("/groups/g1/members/bob"), (Some("bob"), None));
+        assert_eq!(subjects("/stats"), (None, None));
+    }
+
+    #[test]
+    fn requests_are_logged_a_line_of_json_each() {
+        let path = env::temp_dir().join(format!("wordle-log-{}.ndjson", Uuid::new_v4()));
+        let log = RequestLog::new(Level::Warn, Target::open(path.to_str().unwrap()).unwrap());
+
+        log.record("a", "GET", "/daily/alice", 200, Duration::from_millis(3));
+        log.record("b", "GET", "/game/nope", 400, Duration::from_millis(3));
+
+        let written = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines = written
+            .lines()
+            .map(|x| serde_json::from_str::<serde_json::Value>(x).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(
+            (
+                &lines[0]["request_id"],
+                &lines[0]["level"],
+                &lines[0]["game_id"]
+            ),
+            (&"b".into(), &"warn".into(), &"nope".into())
+        );
+    }
+
+    #[test]
+    fn levels_include_those_more_serious() {
+        assert!(Level::of(503) < Level::Warn);
+        assert_eq!(Level::of(404), Level::Warn);
+        assert_eq!(Level::parse("INFO"), Some(Level::Info));
+        assert_eq!(Level::parse("debug"), None);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::admin::authorize;
+use crate::config::Config;
+use crate::db;
+use crate::dictionary;
+use crate::error::ApiError;
+use crate::game;
+use crate::json_response;
+use rouille::{Request, Response};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use wordle_core::{AnswerSelector, NoRepeat, Random, Selection};
+
+/// How many days the schedule is shown or generated for unless asked otherwise
+const DAYS: u64 = 30;
+/// A year ahead is as far as anyone plans themed words
+const MAX_DAYS: u64 = 366;
+
+/// A day of the daily words' schedule
+#[derive(Serialize, PartialEq, Debug)]
+pub struct ScheduledDay {
+    /// As `YYYY-MM-DD`, in UTC
+    pub day: String,
+    /// None when nothing's scheduled, each group then playing a word of its own
+    pub word: Option<String>,
+    /// Whether an operator chose the word, rather than it being generated
+    pub pinned: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pin {
+    pub word: String,
+    /// Why the word was chosen, e.g. the event it's themed around
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// The schedule for `days` days from `from`, every day included whether or not it has a word
+pub fn schedule(
+    conn: &Connection,
+    from: u64,
+    days: u64,
+) -> Result<Vec<ScheduledDay>, rusqlite::Error> {
+    let mut statement =
+        conn.prepare("SELECT word, pinned, note FROM answer_schedule WHERE day = ?1")?;
+
+    (from..from + days)
+        .map(|day| {
+            let entry = statement
+                .query_row([day], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+                .optional()?;
+            let (word, pinned, note) = entry.unwrap_or((None, false, None));
+
+            Ok(ScheduledDay {
+                day: game::date(day),
+                word,
+                pinned,
+                note,
+            })
+        })
+        .collect()
+}
+
+/// Replaces every generated word after `today`, the puzzle's day, with a new one for the `days`
+/// days from tomorrow, leaving the pinned words, and not choosing any word already in the schedule
+/// while there are others left
+pub fn generate(conn: &Connection, today: u64, days: u64) -> Result<(), rusqlite::Error> {
+    let tomorrow = today + 1;
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "DELETE FROM answer_schedule WHERE pinned = 0 AND day >= ?1",
+        [tomorrow],
+    )?;
+    let mut previous = tx
+        .prepare("SELECT word FROM answer_schedule")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<HashSet<String>, _>>()?;
+
+    let selector = NoRepeat {
+        inner: Random::from_entropy(),
+    };
+    for day in tomorrow..tomorrow + days {
+        let selection = Selection {
+            client: None,
+            day,
+            previous,
+        };
+        let Some(word) = selector.select(dictionary::answers(), &selection) else {
+            break;
+        };
+        previous = selection.previous;
+
+        if tx.execute(
+            "INSERT INTO answer_schedule (day, word, pinned) VALUES (?1, ?2, 0)
+            ON CONFLICT (day) DO NOTHING",
+            (day, word),
+        )? > 0
+        {
+            previous.insert(word.to_string());
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+/// A future day from the url, as `YYYY-MM-DD`, which is the only kind that can be changed: today's
+/// word may already have been played
+fn future_day(config: &Config, date: &str) -> Result<u64, ApiError> {
+    game::parse_date(date)
+        .filter(|day| *day > config.rollover.today())
+        .ok_or_else(|| ApiError::InvalidParam {
+            name: String::from("day"),
+            value: date.to_string(),
+        })
+}
+
+/// How many days the `days` param asks for
+fn days_param(request: &Request) -> Result<u64, ApiError> {
+    match request.get_param("days") {
+        None => Ok(DAYS),
+        Some(days) => days
+            .parse::<u64>()
+            .ok()
+            .filter(|x| (1..=MAX_DAYS).contains(x))
+            .ok_or(ApiError::InvalidParam {
+                name: String::from("days"),
+                value: days,
+            }),
+    }
+}
+
+/// `GET /admin/schedule[?from=<YYYY-MM-DD>][&days=<n>]` is the daily words' schedule for the next
+/// 30 days from today, or as many as asked for from the given day
+pub fn handle_list(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let from = match request.get_param("from") {
+        None => config.rollover.today(),
+        Some(date) => game::parse_date(&date).ok_or(ApiError::InvalidParam {
+            name: String::from("from"),
+            value: date,
+        })?,
+    };
+
+    json_response(&schedule(
+        &config.connection()?,
+        from,
+        days_param(request)?,
+    )?)
+}
+
+/// `PUT /admin/schedule/<YYYY-MM-DD>` pins the answer for a future day, replacing whatever was
+/// scheduled for it
+pub fn handle_pin(config: &Config, request: &Request, date: &str) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let day = future_day(config, date)?;
+    let pin: Pin = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+    let word = dictionary::normalize(&pin.word);
+    if !dictionary::answers().contains(&word) {
+        return Err(ApiError::InvalidParam {
+            name: String::from("word"),
+            value: pin.word,
+        });
+    }
+
+    let conn = config.connection()?;
+    conn.execute(
+        "INSERT INTO answer_schedule (day, word, pinned, note) VALUES (?1, ?2, 1, ?3)
+        ON CONFLICT (day) DO UPDATE SET word = excluded.word, pinned = 1, note = excluded.note",
+        (day, &word, &pin.note),
+    )?;
+    db::audit(
+        &conn,
+        "pin_answer",
+        date,
+        &serde_json::json!({ "word": word, "note": pin.note }),
+    )?;
+
+    json_response(&schedule(&conn, day, 1)?[0])
+}
+
+/// `DELETE /admin/schedule/<YYYY-MM-DD>` takes whatever word was scheduled for a future day off the
+/// schedule, so that each group plays a word of its own that day
+pub fn handle_unpin(config: &Config, request: &Request, date: &str) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let day = future_day(config, date)?;
+    let conn = config.connection()?;
+    conn.execute("DELETE FROM answer_schedule WHERE day = ?1", [day])?;
+    db::audit(&conn, "unpin_answer", date, &serde_json::json!({}))?;
+
+    json_response(&schedule(&conn, day, 1)?[0])
+}
+
+/// `POST /admin/schedule/generate[?days=<n>]` generates a new word for each of the next 30 days, or
+/// as many as asked for, that hasn't a pinned one, answering with the schedule from tomorrow
+pub fn handle_generate(config: &Config, request: &Request) -> Result<Response, ApiError> {
+    authorize(config, request)?;
+
+    let days = days_param(request)?;
+    let conn = config.connection()?;
+    let today = config.rollover.today();
+    generate(&conn, today, days)?;
+    db::audit(
+        &conn,
+        "generate_schedule",
+        "instance",
+        &serde_json::json!({ "days": days }),
+    )?;
+
+    json_response(&schedule(&conn, today + 1, days)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{migrate_to, LATEST_VERSION};
+    use crate::game;
+    use crate::schedule::{generate, schedule};
+    use rusqlite::Connection;
+    use std::collections::HashSet;
+
+    #[test]
+    fn generating_keeps_the_pinned_words() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+        let today = game::today();
+        let tomorrow = today + 1;
+        conn.execute(
+            "INSERT INTO answer_schedule (day, word, pinned, note) VALUES (?1, 'crane', 1, 'launch')",
+            [tomorrow + 2],
+        )
+        .unwrap();
+
+        generate(&conn, today, 10).unwrap();
+        let first = schedule(&conn, tomorrow, 10).unwrap();
+        generate(&conn, today, 10).unwrap();
+        let second = schedule(&conn, tomorrow, 11).unwrap();
+
+        assert_eq!(first[2].word.as_deref(), Some("crane"));
+        assert!(first[2].pinned && !first[0].pinned);
+        assert_eq!(second[2], first[2]);
+        assert_eq!(second[10].word, None);
+
+        let words = first
+            .iter()
+            .filter_map(|x| x.word.clone())
+            .collect::<HashSet<_>>();
+        assert_eq!(words.len(), 10);
+    }
+}
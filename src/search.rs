@@ -0,0 +1,196 @@
+use crate::dictionary;
+use crate::error::ApiError;
+use crate::json_response;
+use rouille::{Request, Response};
+use serde::Serialize;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+/// What's known about a five letter word from the evaluations seen so far
+#[derive(Default, PartialEq, Debug)]
+pub struct Constraints {
+    /// The letter known to be at each position
+    green: [Option<char>; 5],
+    /// Letters in the word, each with the positions it's known not to be at
+    yellow: Vec<(char, Vec<usize>)>,
+    /// Letters that aren't in the word, or only as many times as they're green or yellow
+    gray: Vec<char>,
+}
+
+impl Constraints {
+    /// Parses `green` as five characters with `.` or `_` for unknown positions, `yellow` as comma
+    /// separated letters each followed by the 1-based positions it isn't at, e.g. `r1,e45`, and
+    /// `gray` as the excluded letters
+    pub fn parse(
+        green: Option<&str>,
+        yellow: Option<&str>,
+        gray: Option<&str>,
+    ) -> Result<Constraints, ApiError> {
+        let invalid = |name: &str, value: &str| ApiError::InvalidParam {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        let mut constraints = Constraints::default();
+
+        if let Some(green) = green {
+            let letters = green.chars().collect::<Vec<_>>();
+            if letters.len() != 5 {
+                return Err(invalid("green", green));
+            }
+
+            for (i, x) in letters.into_iter().enumerate() {
+                match x {
+                    '.' | '_' => {}
+                    'a'..='z' => constraints.green[i] = Some(x),
+                    _ => return Err(invalid("green", green)),
+                }
+            }
+        }
+
+        for entry in yellow
+            .unwrap_or_default()
+            .split(',')
+            .filter(|x| !x.is_empty())
+        {
+            let mut chars = entry.chars();
+            let letter = chars
+                .next()
+                .filter(char::is_ascii_lowercase)
+                .ok_or_else(|| invalid("yellow", entry))?;
+            let positions = chars
+                .map(|x| match x.to_digit(10) {
+                    Some(position @ 1..=5) => Ok(position as usize - 1),
+                    _ => Err(invalid("yellow", entry)),
+                })
+                .collect::<Result<_, _>>()?;
+
+            constraints.yellow.push((letter, positions));
+        }
+
+        let gray = gray.unwrap_or_default();
+        if !gray.chars().all(|x| x.is_ascii_lowercase()) {
+            return Err(invalid("gray", gray));
+        }
+        constraints.gray = gray.chars().collect();
+
+        Ok(constraints)
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        let letters = word.chars().collect::<Vec<_>>();
+        if letters.len() != 5 {
+            return false;
+        }
+
+        let greens_match = self
+            .green
+            .iter()
+            .zip(&letters)
+            .all(|(green, x)| green.is_none_or(|green| green == *x));
+
+        let yellows_match = self.yellow.iter().all(|(letter, excluded)| {
+            letters.contains(letter) && excluded.iter().all(|i| letters[*i] != *letter)
+        });
+
+        let grays_match = self.gray.iter().all(|gray| {
+            // a gray letter that's also green or yellow appears exactly as often as it's known to
+            let known = self.green.iter().filter(|x| **x == Some(*gray)).count()
+                + self.yellow.iter().filter(|(x, _)| x == gray).count();
+            let count = letters.iter().filter(|x| *x == gray).count();
+
+            if known == 0 {
+                count == 0
+            } else {
+                count == known
+            }
+        });
+
+        greens_match && yellows_match && grays_match
+    }
+}
+
+#[derive(Serialize)]
+struct SearchResult {
+    count: usize,
+    words: Vec<String>,
+}
+
+/// `GET /words/search?green=&yellow=&gray=[&list=guesses][&limit=]` lists the answers, or every
+/// valid guess, that fit what's known, alphabetically. `count` is every match, however many are
+/// listed.
+pub fn handle(request: &Request) -> Result<Response, ApiError> {
+    let constraints = Constraints::parse(
+        request.get_param("green").as_deref(),
+        request.get_param("yellow").as_deref(),
+        request.get_param("gray").as_deref(),
+    )?;
+
+    let limit = match request.get_param("limit") {
+        None => DEFAULT_LIMIT,
+        Some(limit) => limit
+            .parse::<usize>()
+            .ok()
+            .filter(|x| *x <= MAX_LIMIT)
+            .ok_or(ApiError::InvalidParam {
+                name: String::from("limit"),
+                value: limit,
+            })?,
+    };
+
+    let mut words = match request.get_param("list").as_deref() {
+        None | Some("answers") => dictionary::answers()
+            .iter()
+            .filter(|x| constraints.matches(x))
+            .cloned()
+            .collect::<Vec<_>>(),
+        Some("guesses") => dictionary::guesses()
+            .iter()
+            .filter(|x| constraints.matches(x))
+            .cloned()
+            .collect(),
+        Some(list) => {
+            return Err(ApiError::InvalidParam {
+                name: String::from("list"),
+                value: list.to_string(),
+            })
+        }
+    };
+    words.sort();
+    words.dedup();
+
+    let count = words.len();
+    words.truncate(limit);
+
+    json_response(&SearchResult { count, words })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::search::Constraints;
+
+    #[test]
+    fn matches_greens_yellows_and_grays() {
+        let constraints = Constraints::parse(Some("c...e"), Some("r1"), Some("st")).unwrap();
+
+        assert!(constraints.matches("crane"));
+        assert!(!constraints.matches("reach"));
+        assert!(!constraints.matches("crate"));
+        assert!(!constraints.matches("chide"));
+    }
+
+    #[test]
+    fn grays_that_are_also_known_limit_the_count() {
+        let constraints = Constraints::parse(Some("....e"), None, Some("e")).unwrap();
+
+        assert!(constraints.matches("crane"));
+        assert!(!constraints.matches("geese"));
+    }
+
+    #[test]
+    fn refuses_malformed_constraints() {
+        assert!(Constraints::parse(Some("c..e"), None, None).is_err());
+        assert!(Constraints::parse(None, Some("r6"), None).is_err());
+        assert!(Constraints::parse(None, None, Some("S")).is_err());
+    }
+}
@@ -0,0 +1,1837 @@
+use crate::achievements::{Achievement, Granted};
+use crate::challenges::Challenge;
+use crate::clubs::{self, Club, ClubDetail, ClubStats, Standings};
+use crate::config::Config;
+use crate::db;
+use crate::dictionary::{self, WordList};
+use crate::difficulty::{self, Difficulty};
+use crate::error::ApiError;
+use crate::events::GameEvent;
+use crate::friends::{self, AddFriend, Finish, Friend, Standing};
+use crate::game::{
+    self, ClientId, GameId, GameOptions, GameState, GroupId, Guess, HintLevel, PlayedGuess,
+    Rejection,
+};
+use crate::groups::{self, CreatedGroup, Dashboard, Group, Member, MemberResult, NewGroup, Roster};
+use crate::guests::{self, Guest, Merged};
+use crate::health::{Check, Readiness};
+use crate::hints::{self, Hint};
+use crate::inbox::{self, InboxEvent, Message};
+use crate::metrics::Metrics;
+use crate::mock;
+use crate::profile::{self, Profile, PublicProfile};
+use crate::rate_limit::{Allowance, RateLimiter};
+use crate::registration::{self, NewClient, RegisteredClient, Registration};
+use crate::reminders::Reminder;
+use crate::replay::{self, Replay, ReplayShare};
+use crate::repository::{FinishedGame, Repository, SqliteRepository, StoredGame};
+use crate::share::{self, ShareLink, SharedGame};
+use crate::solver::{Simulation, MAX_GOES};
+use crate::stats::{ClientStats, Record};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::slice;
+use std::sync::Arc;
+use uuid::Uuid;
+use wordle_core::{
+    letters, AnswerSelector, CharMatch, Fixed, MatchType, NoRepeat, Random, Selection,
+};
+
+/// The API's operations, independent of how requests arrive. The HTTP router only translates
+/// requests into these calls and their results into responses, so any other transport, or a test,
+/// gets exactly the same behaviour by calling the service directly.
+#[derive(Clone)]
+pub struct Service {
+    config: Config,
+    repository: Arc<dyn Repository>,
+    selector: Arc<dyn AnswerSelector>,
+    solver_limiter: Option<Arc<RateLimiter>>,
+    address_limiter: Option<Arc<RateLimiter>>,
+    client_limiter: Option<Arc<RateLimiter>>,
+    metrics: Arc<Metrics>,
+    /// A service for each tenant, every one keeping its games in its own database
+    tenants: Arc<BTreeMap<String, Service>>,
+}
+
+pub struct CreateGame {
+    pub client: ClientId,
+    /// Falls back to the server's default when not given
+    pub invalid_guesses_count: Option<bool>,
+    /// Classic when not given
+    pub mode: Option<String>,
+    /// Plays the group's word for the day rather than a word of the client's own
+    pub group: Option<GroupId>,
+    /// Plays the day's word that every client shares, rather than a word of the client's own
+    pub daily: bool,
+    /// How many letters the word has, five when not given
+    pub length: Option<usize>,
+    /// The language tag of the words to play, English when not given or when there are no words
+    /// of the length in it
+    pub language: Option<String>,
+    /// Plays the word someone chose for the challenge, rather than a word of the client's own
+    pub challenge: Option<String>,
+}
+
+pub struct SubmitGuess {
+    pub game_id: GameId,
+    /// As entered, it's only checked against the dictionary once the game is known to be playable
+    pub guess: String,
+}
+
+#[derive(Serialize)]
+pub struct GameIdentity {
+    pub game_id: GameId,
+    pub state: GameState,
+    pub options: GameOptions,
+    /// For group and daily games, the day whose word is being played as `YYYY-MM-DD`, which isn't
+    /// always the day it is in UTC or for the player
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub puzzle_date: Option<String>,
+    /// For daily games, the number of the day's puzzle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub puzzle_number: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct Answer {
+    pub state: GameState,
+    pub solved: bool,
+    /// Once the game has run out of goes, with the answer revealed
+    pub lost: bool,
+    pub answer: Option<String>,
+    pub guess: Guess,
+    pub goes: usize,
+    pub evaluation: Vec<CharMatch>,
+    /// Every board's evaluation, for modes played on more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boards: Option<Vec<BoardEvaluation>>,
+    /// Every guess played in the game so far, this one last, for clients that keep no board
+    pub guesses: Vec<PlayedGuess>,
+    /// How hard the answer was, once a game played on a single board is over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// Achievements the guess earned that the client didn't already have
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub achievements: Vec<Achievement>,
+    /// Whether the guess had already been played, in which case it's answered as it was then and
+    /// costs no go
+    pub repeated: bool,
+    /// The best every letter guessed so far has been matched against the first board, for
+    /// clients to colour a keyboard with
+    pub letters: BTreeMap<char, MatchType>,
+    /// The grid to share, once the game is solved or lost
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share: Option<String>,
+}
+
+/// Where a game has got to, for clients picking it back up
+#[derive(Serialize)]
+pub struct GameStatus {
+    pub game_id: GameId,
+    pub state: GameState,
+    pub solved: bool,
+    pub lost: bool,
+    pub options: GameOptions,
+    pub goes: usize,
+    /// Goes left before the game is lost, or `None` if it has no limit
+    pub remaining: Option<usize>,
+    /// Only once the game is over
+    pub answer: Option<String>,
+    /// Every board's answer, once the game is over, for modes played on more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answers: Option<Vec<String>>,
+    pub guesses: Vec<PlayedGuess>,
+    /// How many hints the game has been given
+    pub hints: usize,
+}
+
+#[derive(Serialize)]
+pub struct BoardEvaluation {
+    pub solved: bool,
+    pub answer: Option<String>,
+    pub evaluation: Vec<CharMatch>,
+}
+
+impl Service {
+    pub fn new(config: Config) -> Service {
+        let repository = SqliteRepository::new(&config.database);
+
+        Service::with_repository(config, Arc::new(repository))
+    }
+
+    /// A service keeping its games somewhere other than the configured database
+    pub fn with_repository(config: Config, repository: Arc<dyn Repository>) -> Service {
+        let random = match config.answer_seed {
+            Some(seed) => Random::seeded(seed),
+            None => Random::from_entropy(),
+        };
+        let selector: Arc<dyn AnswerSelector> = if config.no_repeat_answers {
+            Arc::new(NoRepeat { inner: random })
+        } else {
+            Arc::new(random)
+        };
+
+        // a mock server starts games on whatever answer a request asks for
+        let selector = match config.mock {
+            Some(_) => Arc::new(mock::Answers { inner: selector }),
+            None => selector,
+        };
+
+        let tenants = config
+            .tenants
+            .iter()
+            .map(|tenant| (tenant.clone(), Service::new(config.for_tenant(tenant))))
+            .collect();
+
+        Service {
+            tenants: Arc::new(tenants),
+            solver_limiter: config
+                .solver_rate_limit
+                .map(|x| Arc::new(RateLimiter::new(x))),
+            address_limiter: config.rate_limit.map(|x| Arc::new(RateLimiter::new(x))),
+            client_limiter: config
+                .client_rate_limit
+                .map(|x| Arc::new(RateLimiter::new(x))),
+            metrics: Arc::new(Metrics::default()),
+            config,
+            repository,
+            selector,
+        }
+    }
+
+    /// Chooses answers with the given selector, rather than as configured
+    pub fn with_selector(self, selector: Arc<dyn AnswerSelector>) -> Service {
+        Service { selector, ..self }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Every metric, in Prometheus' text format
+    pub fn metrics_text(&self) -> Result<String, ApiError> {
+        Ok(self.metrics.render(self.repository.active_games()?))
+    }
+
+    /// The service for one of the configured tenants
+    pub fn tenant(&self, tenant: &str) -> Option<&Service> {
+        self.tenants.get(tenant)
+    }
+
+    /// Every tenant's service, by tenant
+    pub fn tenants(&self) -> impl Iterator<Item = (&String, &Service)> {
+        self.tenants.iter()
+    }
+
+    /// Refuses the caller once they've made too many requests to the solver endpoints, the hints,
+    /// analysis and simulations that are expensive to work out
+    pub fn limit_solver(&self, caller: &str) -> Result<(), ApiError> {
+        match &self.solver_limiter {
+            Some(limiter) => limiter.check(caller),
+            None => Ok(()),
+        }
+    }
+
+    /// Takes a request from the address's allowance, which every request is limited by. It's
+    /// `None` when requests aren't limited, and refused along with what the allowance was.
+    pub fn limit_address(&self, address: &str) -> Result<Option<Allowance>, (Allowance, ApiError)> {
+        match &self.address_limiter {
+            Some(limiter) => limiter.take(address).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Refuses the client once they've created or guessed in too many games, so that spreading
+    /// requests over addresses doesn't get round the limit on playing
+    fn limit_client(&self, client: &ClientId) -> Result<(), ApiError> {
+        match &self.client_limiter {
+            Some(limiter) => limiter.check(client.as_str()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn create_game(&self, request: CreateGame) -> Result<GameIdentity, ApiError> {
+        self.limit_client(&request.client)?;
+        // whatever isn't asked for comes from the client's preferred settings
+        let settings = self.profile(&request.client)?.settings;
+        let name = request
+            .mode
+            .or(settings.mode)
+            .unwrap_or_else(|| String::from("classic"));
+        let mode = game::modes().get(&name).ok_or(ApiError::InvalidParam {
+            name: String::from("mode"),
+            value: name,
+        })?;
+        let length = request.length.unwrap_or(dictionary::LENGTH);
+        let shared = request.daily || request.group.is_some() || request.challenge.is_some();
+        let options = GameOptions {
+            invalid_guesses_count: request
+                .invalid_guesses_count
+                .or(settings.invalid_guesses_count)
+                .unwrap_or(self.config.invalid_guesses_count),
+            mode: mode.name().to_string(),
+            max_guesses: self
+                .config
+                .max_guesses
+                .and_then(|max| mode.guess_limit(max)),
+            length,
+            // passed over when it's unavailable, as it is for messages, and words everyone shares
+            // are always played in the bundled language
+            language: request
+                .language
+                .as_deref()
+                .map(dictionary::language)
+                .filter(|x| !shared && self.config.word_lists.get(x, length).is_some())
+                .unwrap_or_else(|| dictionary::LANGUAGE.to_string()),
+        };
+        let words = self
+            .config
+            .word_lists
+            .get(&options.language, options.length)
+            .ok_or_else(|| ApiError::InvalidParam {
+                name: String::from("length"),
+                value: options.length.to_string(),
+            })?;
+
+        self.ensure_writable()?;
+
+        if request.daily && request.group.is_some() {
+            return Err(ApiError::InvalidParam {
+                name: String::from("daily"),
+                value: String::from("true with a group"),
+            });
+        }
+        if request.challenge.is_some() && (request.daily || request.group.is_some()) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("challenge"),
+                value: String::from("with a group or the daily word"),
+            });
+        }
+        // everyone playing the same word plays it with the same words
+        if shared && length != dictionary::LENGTH {
+            return Err(ApiError::InvalidParam {
+                name: String::from("length"),
+                value: options.length.to_string(),
+            });
+        }
+
+        let day = self.config.rollover.today();
+        let puzzle_date = (request.group.is_some() || request.daily).then(|| game::date(day));
+        let puzzle_number = request.daily.then(|| game::puzzle_number(day));
+        let group = match &request.group {
+            Some(group_id) => Some(self.members_group(group_id, &request.client)?),
+            None => None,
+        };
+        let challenge = match &request.challenge {
+            Some(challenge_id) => Some(
+                self.repository
+                    .challenge(challenge_id)?
+                    .ok_or_else(|| ApiError::ChallengeNotFound(challenge_id.clone()))?,
+            ),
+            None => None,
+        };
+        if shared {
+            if !groups::MODES.contains(&mode.name()) {
+                return Err(ApiError::InvalidParam {
+                    name: String::from("mode"),
+                    value: mode.name().to_string(),
+                });
+            }
+
+            // a shared word is played once, asking again carries on with the same game
+            let played = match (&group, &challenge) {
+                (Some(group), _) => {
+                    self.repository
+                        .group_game(&group.group_id, &request.client, day)?
+                }
+                (None, Some(challenge)) => self
+                    .repository
+                    .challenge_game(&challenge.challenge_id, &request.client)?,
+                (None, None) => self.repository.daily_game(&request.client, day)?,
+            };
+            if let Some(game_id) = played {
+                let game = self.find_game(&game_id)?;
+
+                return Ok(GameIdentity {
+                    game_id,
+                    state: game.state,
+                    options: game.options,
+                    puzzle_date,
+                    puzzle_number,
+                });
+            }
+        }
+
+        let selection = Selection {
+            client: Some(request.client.as_str()),
+            day,
+            previous: self.repository.previous_answers(&request.client)?,
+        };
+        let daily = match &group {
+            Some(group) => Some(self.scheduled(day, group.selector())?),
+            None if request.daily => Some(self.scheduled(day, game::DAILY)?),
+            None => None,
+        };
+        let selector = daily.as_deref().unwrap_or(self.selector.as_ref());
+        let answers = match &challenge {
+            Some(challenge) => slice::from_ref(&challenge.word),
+            None => words.answers(),
+        };
+        let mode_state = mode.start(answers, selector, &selection);
+
+        let game = StoredGame {
+            client: request.client,
+            goes: 0,
+            state: GameState::InProgress,
+            options,
+            mode,
+            mode_state,
+        };
+
+        // a v4 collision is astronomically unlikely, but the unique index makes it safe to just retry
+        let game_id = loop {
+            let game_id = GameId::generate();
+
+            if self.repository.insert_game(&game_id, &game)? {
+                break game_id;
+            }
+        };
+        self.metrics.game_created();
+        if let Some(group) = &group {
+            self.repository
+                .save_group_game(&group.group_id, &game.client, day, &game_id)?;
+        } else if let Some(challenge) = &challenge {
+            self.repository
+                .save_challenge_game(&challenge.challenge_id, &game.client, &game_id)?;
+        } else if request.daily {
+            self.repository
+                .save_daily_game(&game.client, day, &game_id)?;
+        }
+
+        Ok(GameIdentity {
+            game_id,
+            state: game.state,
+            options: game.options,
+            puzzle_date,
+            puzzle_number,
+        })
+    }
+
+    /// Chooses the day's word for everyone playing it: whatever's scheduled for the day, if
+    /// anything is, otherwise `otherwise`'s choice
+    fn scheduled(
+        &self,
+        day: u64,
+        otherwise: impl AnswerSelector + 'static,
+    ) -> Result<Box<dyn AnswerSelector>, ApiError> {
+        Ok(match self.repository.scheduled_answer(day)? {
+            Some(word) => Box::new(Fixed { word, otherwise }),
+            None => Box::new(otherwise),
+        })
+    }
+
+    pub fn submit_guess(&self, request: SubmitGuess) -> Result<Answer, ApiError> {
+        let game_id = &request.game_id;
+        self.ensure_writable()?;
+
+        let mut game = self
+            .repository
+            .find_game(game_id)?
+            .ok_or_else(|| ApiError::GameNotFound(game_id.to_string()))?;
+        self.limit_client(&game.client)?;
+        self.expire_past_daily(game_id, &mut game)?;
+
+        let normalized = dictionary::normalize(&request.guess);
+
+        if game.state.is_finished() {
+            self.repository.record_rejection(
+                game_id,
+                &normalized,
+                Rejection::GameFinished,
+                false,
+            )?;
+
+            return Err(ApiError::GameFinished(game_id.to_string()));
+        }
+
+        let counted = game.options.invalid_guesses_count;
+        // a game whose words are no longer configured can't have anything it's guessed checked
+        let guess = match self
+            .config
+            .word_lists
+            .get(&game.options.language, game.options.length)
+            .ok_or_else(|| ApiError::InvalidGuess(normalized.clone()))
+            .and_then(|words| Guess::parse(&request.guess, words))
+        {
+            Ok(guess) => guess,
+            Err(error) => {
+                self.repository.record_rejection(
+                    game_id,
+                    &normalized,
+                    Rejection::InvalidWord,
+                    counted,
+                )?;
+                self.metrics.invalid_guess();
+
+                return Err(error);
+            }
+        };
+
+        if let Some(answer) = repeat(&game, &guess) {
+            return Ok(Answer {
+                guesses: self.repository.guesses(game_id)?,
+                ..answer
+            });
+        }
+
+        if let Err(reason) = game.mode.validate(&game.mode_state, guess.as_str()) {
+            self.repository.record_rejection(
+                game_id,
+                &normalized,
+                Rejection::RuleViolation,
+                counted,
+            )?;
+
+            return Err(ApiError::RuleViolation(reason));
+        }
+
+        let mut events = vec![GameEvent::Guessed {
+            guess: guess.to_string(),
+        }];
+        let mut answer = play_guess(&mut game, guess)
+            .ok_or_else(|| ApiError::GameFinished(game_id.to_string()))?;
+        if answer.solved {
+            events.push(GameEvent::Solved);
+        }
+        self.repository.update_game(game_id, &game, &events)?;
+        self.metrics.guess_evaluated();
+        answer.guesses = self.repository.guesses(game_id)?;
+
+        // rated and awarded after saving, so that the average and the record include this game
+        if answer.state.is_finished() {
+            if let [board] = game.mode_state.boards.as_slice() {
+                if let Some(rating) = difficulty::rating(board.word()) {
+                    answer.difficulty = Some(Difficulty {
+                        rating,
+                        average_goes: self.repository.average_goes(board.word())?,
+                    });
+                }
+            }
+
+            let day = self.repository.daily_day(game_id)?;
+            answer.share = Some(share::text(&game, day.map(game::puzzle_number)));
+
+            let earned = Achievement::earned(&game, &self.repository.days_won(&game.client)?);
+            answer.achievements =
+                self.repository
+                    .grant_achievements(&game.client, game_id, &earned)?;
+        }
+
+        Ok(answer)
+    }
+
+    /// Gives a hint for a game in progress in exchange for a go, recording its penalty against the
+    /// game. Only games with a single, already decided, answer can be hinted at, and never with
+    /// their last go, which would leave no go to use the hint with.
+    pub fn hint(&self, game_id: &GameId, level: HintLevel) -> Result<Hint, ApiError> {
+        self.ensure_writable()?;
+
+        let mut game = self.find_game(game_id)?;
+        self.expire_past_daily(game_id, &mut game)?;
+        if game.state.is_finished() {
+            return Err(ApiError::GameFinished(game_id.to_string()));
+        }
+
+        let [board] = game.mode_state.boards.as_slice() else {
+            return Err(ApiError::RuleViolation(String::from(
+                "hints are only given for games with a single board",
+            )));
+        };
+        if board.candidates.len() > 1 {
+            return Err(ApiError::RuleViolation(String::from(
+                "hints can't be given while the answer is undecided",
+            )));
+        }
+        if game.options.out_of_goes(game.goes + 1) {
+            return Err(ApiError::RuleViolation(String::from(
+                "hints can't use up the last go",
+            )));
+        }
+
+        let hint = hints::hint(
+            level,
+            board.word(),
+            self.config
+                .word_lists
+                .get(&game.options.language, game.options.length)
+                .map_or(&[][..], WordList::answers),
+            &game.mode_state.revealed,
+        )?;
+        self.repository.record_hint(game_id, level, hint.penalty)?;
+
+        Ok(hint)
+    }
+
+    /// How the reference solver fares across every answer when it opens with `opener`. Simulating
+    /// is slow, so each opener's result is saved until the answer list changes, and isn't saved at
+    /// all while the instance is read-only.
+    pub fn simulate_opener(&self, opener: &str) -> Result<Simulation, ApiError> {
+        let normalized = dictionary::normalize(opener);
+        if !dictionary::is_valid_guess(&normalized) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("opener"),
+                value: opener.to_string(),
+            });
+        }
+
+        let version = dictionary::answers_version();
+        if let Some(simulation) = self.repository.simulation(&normalized, version)? {
+            return Ok(simulation);
+        }
+
+        let answers = dictionary::answers();
+        let targets = answers.iter().map(String::as_str).collect::<Vec<_>>();
+        let simulation = Simulation::run(answers, &targets, &normalized, MAX_GOES);
+        if self.repository.read_only()?.is_none() {
+            self.repository.save_simulation(version, &simulation)?;
+        }
+
+        Ok(simulation)
+    }
+
+    /// Expires a daily game left unfinished once its day has passed, so that nobody can play
+    /// yesterday's word with today's answers to hand
+    fn expire_past_daily(&self, game_id: &GameId, game: &mut StoredGame) -> Result<(), ApiError> {
+        let passed = self
+            .repository
+            .daily_day(game_id)?
+            .is_some_and(|day| day < self.config.rollover.today());
+        if let (true, Some(state)) = (passed, game.state.expire()) {
+            game.state = state;
+            self.repository
+                .update_game(game_id, game, &[GameEvent::Expired])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn find_game(&self, game_id: &GameId) -> Result<StoredGame, ApiError> {
+        self.repository
+            .find_game(game_id)?
+            .ok_or_else(|| ApiError::GameNotFound(game_id.to_string()))
+    }
+
+    /// Gives up on a game in progress, which reveals its answer
+    pub fn resign(&self, game_id: &GameId) -> Result<GameStatus, ApiError> {
+        self.ensure_writable()?;
+
+        let mut game = self.find_game(game_id)?;
+        self.expire_past_daily(game_id, &mut game)?;
+        game.state = game
+            .state
+            .forfeit()
+            .ok_or_else(|| ApiError::GameFinished(game_id.to_string()))?;
+        self.repository
+            .update_game(game_id, &game, &[GameEvent::Forfeited])?;
+
+        self.game_status(game_id)
+    }
+
+    /// The game as it stands, without playing anything
+    pub fn game_status(&self, game_id: &GameId) -> Result<GameStatus, ApiError> {
+        let game = self.find_game(game_id)?;
+        let mut guesses = self.repository.guesses(game_id)?;
+        // games from before guesses were kept have them numbered as if none were rejected
+        if guesses.is_empty() {
+            guesses = game.latest_guesses(game.mode_state.revealed.len());
+        }
+        let hints = self
+            .repository
+            .timeline(game_id)?
+            .iter()
+            .filter(|(event, _)| matches!(event, GameEvent::Hinted { .. }))
+            .count();
+
+        Ok(GameStatus {
+            game_id: game_id.clone(),
+            state: game.state,
+            solved: game.state == GameState::Won,
+            lost: game.state == GameState::Lost,
+            remaining: game
+                .options
+                .max_guesses
+                .map(|max| max.saturating_sub(game.goes)),
+            options: game.options,
+            goes: game.goes,
+            answer: game
+                .state
+                .is_finished()
+                .then(|| game.mode_state.boards[0].word().to_string()),
+            answers: (game.state.is_finished() && game.mode_state.boards.len() > 1)
+                .then(|| game.mode_state.words()),
+            guesses,
+            hints,
+        })
+    }
+
+    /// Makes a new share link for the game, which has to be finished so the link can't help anyone
+    /// still playing
+    pub fn share_game(&self, game_id: &GameId, show_guesses: bool) -> Result<ShareLink, ApiError> {
+        self.ensure_writable()?;
+        let game = self.find_game(game_id)?;
+        if !game.state.is_finished() {
+            return Err(ApiError::RuleViolation(String::from(
+                "games are only shared once they're finished",
+            )));
+        }
+
+        let link = ShareLink {
+            token: Uuid::new_v4().simple().to_string(),
+            game_id: game_id.clone(),
+            show_guesses,
+        };
+        self.repository.save_share_link(&link)?;
+
+        Ok(link)
+    }
+
+    pub fn share_links(&self, game_id: &GameId) -> Result<Vec<ShareLink>, ApiError> {
+        self.find_game(game_id)?;
+
+        self.repository.share_links(game_id)
+    }
+
+    pub fn revoke_share(&self, game_id: &GameId, token: &str) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+        if !self.repository.delete_share_link(game_id, token)? {
+            return Err(ApiError::ShareNotFound(token.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// The game behind a share link, showing only what the link was made to
+    pub fn shared_game(&self, token: &str) -> Result<SharedGame, ApiError> {
+        let link = self
+            .repository
+            .share_link(token)?
+            .ok_or_else(|| ApiError::ShareNotFound(token.to_string()))?;
+        let game = self.find_game(&link.game_id)?;
+
+        Ok(SharedGame::new(
+            &game,
+            self.display_name(&game.client)?,
+            link.show_guesses,
+        ))
+    }
+
+    /// Every step of the game, with a token to watch it by for a day if `share` asks for one, which
+    /// like a share link is only made once the game is finished
+    pub fn replay(&self, game_id: &GameId, share: bool) -> Result<Replay, ApiError> {
+        let game = self.find_game(game_id)?;
+        let share = if share {
+            self.ensure_writable()?;
+            if !game.state.is_finished() {
+                return Err(ApiError::RuleViolation(String::from(
+                    "replays are only shared once the game is finished",
+                )));
+            }
+
+            let share = ReplayShare {
+                token: Uuid::new_v4().simple().to_string(),
+                expires_at: game::now() + replay::SHARE_SECONDS,
+            };
+            self.repository
+                .save_replay_token(&share.token, game_id, share.expires_at)?;
+
+            Some(share)
+        } else {
+            None
+        };
+
+        let steps = replay::steps(game.mode, &self.repository.timeline(game_id)?);
+
+        Ok(Replay {
+            game_id: game_id.clone(),
+            player: self.display_name(&game.client)?,
+            mode: game.mode.name(),
+            state: game.state,
+            goes: game.goes,
+            answers: game.state.is_finished().then(|| game.mode_state.words()),
+            duration: steps.last().map_or(0, |x| x.elapsed),
+            steps,
+            share,
+        })
+    }
+
+    /// The replay shared under the token, for as long as the token lasts
+    pub fn shared_replay(&self, token: &str) -> Result<Replay, ApiError> {
+        let game_id = self
+            .repository
+            .replay_token(token, game::now())?
+            .ok_or_else(|| ApiError::ShareNotFound(token.to_string()))?;
+
+        self.replay(&game_id, false)
+    }
+
+    /// The client's most recently created game that is still in progress
+    pub fn current_game(&self, client: &ClientId) -> Result<Option<GameId>, ApiError> {
+        self.repository.current_game(client)
+    }
+
+    pub fn record(&self, client: &ClientId) -> Result<Record, ApiError> {
+        Ok(Record::from_outcomes(&self.repository.outcomes(client)?))
+    }
+
+    /// The client's profile, or an empty one if they've never saved one
+    pub fn profile(&self, client: &ClientId) -> Result<Profile, ApiError> {
+        Ok(self.repository.profile(client)?.unwrap_or_default())
+    }
+
+    /// What to call the client wherever their games are shown
+    pub fn display_name(&self, client: &ClientId) -> Result<String, ApiError> {
+        Ok(self.profile(client)?.name_or(client).to_string())
+    }
+
+    /// The slices of the client's profile they've left public, or none at all if they've made it
+    /// private
+    pub fn public_profile(&self, client: &ClientId) -> Result<PublicProfile, ApiError> {
+        let profile = self.profile(client)?;
+        let privacy = &profile.privacy;
+        if privacy.private {
+            return Err(ApiError::ProfileNotFound(client.to_string()));
+        }
+
+        let recent_games = match privacy.hide_recent_games {
+            true => None,
+            false => Some(self.finishes(&self.repository.finished_games(
+                slice::from_ref(client),
+                0,
+                profile::RECENT_GAMES,
+            )?)?),
+        };
+
+        Ok(PublicProfile {
+            client: client.clone(),
+            player: profile.name_or(client).to_string(),
+            stats: (!privacy.hide_stats)
+                .then(|| self.record(client))
+                .transpose()?,
+            recent_games,
+            achievements: (!privacy.hide_achievements)
+                .then(|| self.achievements(client))
+                .transpose()?,
+            avatar_url: profile.avatar_url,
+            bio: profile.bio,
+        })
+    }
+
+    pub fn save_profile(&self, client: &ClientId, profile: &Profile) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+        profile.validate()?;
+
+        self.repository.save_profile(client, profile)
+    }
+
+    pub fn achievements(&self, client: &ClientId) -> Result<Vec<Granted>, ApiError> {
+        self.repository.achievements(client)
+    }
+
+    pub fn reminder(&self, client: &ClientId) -> Result<Option<Reminder>, ApiError> {
+        self.repository.reminder(client)
+    }
+
+    pub fn save_reminder(&self, client: &ClientId, reminder: &Reminder) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+        reminder.validate()?;
+        reminder.validate_host(self.config.private_webhooks)?;
+
+        self.repository.save_reminder(client, reminder)
+    }
+
+    pub fn delete_reminder(&self, client: &ClientId) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+
+        self.repository.delete_reminder(client)
+    }
+
+    pub fn create_group(&self, group: NewGroup) -> Result<CreatedGroup, ApiError> {
+        self.ensure_writable()?;
+        group.validate()?;
+
+        let group = Group {
+            group_id: GroupId::generate(),
+            name: group.name.trim().to_string(),
+            owner: ClientId::parse(&group.owner)?,
+            key: Uuid::new_v4().simple().to_string(),
+        };
+        self.repository.insert_group(&group)?;
+
+        Ok(CreatedGroup {
+            group_id: group.group_id,
+            name: group.name,
+            owner: group.owner,
+            key: group.key,
+        })
+    }
+
+    pub fn group_roster(&self, group_id: &GroupId, key: Option<&str>) -> Result<Roster, ApiError> {
+        let group = self.owned_group(group_id, key)?;
+        let members = self
+            .repository
+            .group_members(group_id)?
+            .into_iter()
+            .map(|client| {
+                Ok(Member {
+                    player: self.display_name(&client)?,
+                    client,
+                })
+            })
+            .collect::<Result<_, ApiError>>()?;
+
+        Ok(Roster {
+            group_id: group.group_id,
+            name: group.name,
+            owner: group.owner,
+            members,
+        })
+    }
+
+    /// Adds the client to the group's roster, or with `member` false takes them off it
+    pub fn set_group_member(
+        &self,
+        group_id: &GroupId,
+        key: Option<&str>,
+        client: &ClientId,
+        member: bool,
+    ) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+
+        let group = self.owned_group(group_id, key)?;
+        if self.repository.set_group_member(group_id, client, member)? && member {
+            let event = InboxEvent::AddedToGroup {
+                group_id: group_id.to_string(),
+                name: group.name,
+            };
+            self.repository.send_inbox(client, &event, game::now())?;
+        }
+
+        Ok(())
+    }
+
+    /// How each member did with the group's word on the day, counted in days since the epoch
+    pub fn group_dashboard(
+        &self,
+        group_id: &GroupId,
+        key: Option<&str>,
+        day: u64,
+    ) -> Result<Dashboard, ApiError> {
+        let group = self.owned_group(group_id, key)?;
+
+        let mut members = Vec::new();
+        for client in self.repository.group_members(group_id)? {
+            let game = match self.repository.group_game(group_id, &client, day)? {
+                Some(game_id) => self.repository.find_game(&game_id)?,
+                None => None,
+            };
+
+            members.push(MemberResult {
+                player: self.display_name(&client)?,
+                client,
+                state: game.as_ref().map(|x| x.state),
+                goes: game.as_ref().map_or(0, |x| x.goes),
+                grid: game.as_ref().map(share::emoji_grid).unwrap_or_default(),
+            });
+        }
+
+        Ok(Dashboard::new(&group, day, members))
+    }
+
+    /// The group, as long as the key is its owner's
+    fn owned_group(&self, group_id: &GroupId, key: Option<&str>) -> Result<Group, ApiError> {
+        let group = self
+            .repository
+            .group(group_id)?
+            .ok_or_else(|| ApiError::GroupNotFound(group_id.to_string()))?;
+        group.authorize(key)?;
+
+        Ok(group)
+    }
+
+    /// The group, as long as the client is on its roster or owns it
+    fn members_group(&self, group_id: &GroupId, client: &ClientId) -> Result<Group, ApiError> {
+        let group = self
+            .repository
+            .group(group_id)?
+            .ok_or_else(|| ApiError::GroupNotFound(group_id.to_string()))?;
+        if &group.owner != client && !self.repository.group_members(group_id)?.contains(client) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("group"),
+                value: group_id.to_string(),
+            });
+        }
+
+        Ok(group)
+    }
+
+    pub fn create_club(&self, club: Club) -> Result<ClubDetail, ApiError> {
+        self.ensure_writable()?;
+        club.validate()?;
+
+        let club = Club {
+            name: club.name.trim().to_string(),
+            ..club
+        };
+        if !self.repository.insert_club(&club)? {
+            return Err(ApiError::InvalidParam {
+                name: String::from("club"),
+                value: club.club,
+            });
+        }
+
+        self.club(&club.club)
+    }
+
+    /// Every club, along with its members' stats
+    pub fn clubs(&self) -> Result<Vec<ClubStats>, ApiError> {
+        let stats = self.repository.client_stats()?;
+
+        self.repository
+            .clubs()?
+            .into_iter()
+            .map(|club| {
+                let members = self.repository.club_members(&club.club)?;
+                let member_stats = stats
+                    .iter()
+                    .filter(|x| members.iter().any(|client| client.as_str() == x.client))
+                    .collect::<Vec<_>>();
+
+                Ok(ClubStats::new(club, members.len(), &member_stats))
+            })
+            .collect()
+    }
+
+    pub fn club(&self, club: &str) -> Result<ClubDetail, ApiError> {
+        let club = self.existing_club(club)?;
+        let members = self.repository.club_members(&club.club)?;
+        let stats = self.repository.client_stats()?;
+        let member_stats = stats
+            .iter()
+            .filter(|x| members.iter().any(|client| client.as_str() == x.client))
+            .collect::<Vec<_>>();
+
+        Ok(ClubDetail {
+            stats: ClubStats::new(club, members.len(), &member_stats),
+            members: members
+                .into_iter()
+                .map(|client| {
+                    Ok(Member {
+                        player: self.display_name(&client)?,
+                        client,
+                    })
+                })
+                .collect::<Result<_, ApiError>>()?,
+        })
+    }
+
+    /// Moves the client into the club, out of any they were in, or with `member` false takes them
+    /// out of it
+    pub fn set_club_member(
+        &self,
+        club: &str,
+        client: &ClientId,
+        member: bool,
+    ) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+        let club = self.existing_club(club)?;
+
+        if member {
+            self.repository.join_club(&club.club, client)
+        } else {
+            self.repository.leave_club(&club.club, client)
+        }
+    }
+
+    /// How each club's members have done with the games they've finished since the week started
+    pub fn club_standings(&self, week_start: u64) -> Result<Standings, ApiError> {
+        let mut standings = self
+            .repository
+            .clubs()?
+            .into_iter()
+            .map(|club| {
+                let members = self.repository.club_members(&club.club)?;
+                let games = self
+                    .repository
+                    .finished_games(&members, week_start, usize::MAX)?;
+
+                Ok(clubs::Standing::new(club, members.len(), &games))
+            })
+            .collect::<Result<Vec<_>, ApiError>>()?;
+        standings.sort_by(|a, b| {
+            b.win_rate
+                .unwrap_or(-1.0)
+                .total_cmp(&a.win_rate.unwrap_or(-1.0))
+                .then(
+                    a.avg_goes
+                        .unwrap_or(f64::MAX)
+                        .total_cmp(&b.avg_goes.unwrap_or(f64::MAX)),
+                )
+        });
+        for (i, standing) in standings.iter_mut().enumerate() {
+            standing.rank = i + 1;
+        }
+
+        Ok(Standings {
+            week_start: game::date(week_start),
+            standings,
+        })
+    }
+
+    fn existing_club(&self, club: &str) -> Result<Club, ApiError> {
+        self.repository
+            .club(club)?
+            .ok_or_else(|| ApiError::ClubNotFound(club.to_string()))
+    }
+
+    pub fn friends(&self, client: &ClientId) -> Result<Vec<Friend>, ApiError> {
+        self.repository
+            .friends(client)?
+            .into_iter()
+            .map(|friend| {
+                Ok(Friend {
+                    player: self.display_name(&friend)?,
+                    client: friend,
+                })
+            })
+            .collect()
+    }
+
+    pub fn add_friend(&self, client: &ClientId, friend: AddFriend) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+
+        let friend = match friend {
+            AddFriend::Client(friend) => ClientId::parse(&friend)?,
+            AddFriend::Code(code) => {
+                self.repository
+                    .invited_by(&code)?
+                    .ok_or(ApiError::InvalidParam {
+                        name: String::from("code"),
+                        value: code,
+                    })?
+            }
+        };
+        if &friend == client {
+            return Err(ApiError::InvalidParam {
+                name: String::from("friend"),
+                value: friend.to_string(),
+            });
+        }
+
+        if self.repository.add_friend(client, &friend)? {
+            let event = InboxEvent::Befriended {
+                client: client.to_string(),
+                player: self.display_name(client)?,
+            };
+            self.repository.send_inbox(&friend, &event, game::now())?;
+        }
+
+        Ok(())
+    }
+
+    /// The client's messages still waiting to be acknowledged, oldest first
+    pub fn inbox(&self, client: &ClientId) -> Result<Vec<Message>, ApiError> {
+        self.repository.inbox(client, inbox::MAX_MESSAGES)
+    }
+
+    pub fn acknowledge_inbox(&self, client: &ClientId, up_to: u64) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+
+        self.repository.acknowledge_inbox(client, up_to)
+    }
+
+    /// Sets a word for anyone to play, which must be one they could guess
+    pub fn create_challenge(&self, creator: &ClientId, word: &str) -> Result<Challenge, ApiError> {
+        self.ensure_writable()?;
+
+        let normalized = dictionary::normalize(word);
+        if !dictionary::is_valid_guess(&normalized) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("word"),
+                value: word.to_string(),
+            });
+        }
+
+        let challenge = Challenge {
+            challenge_id: Uuid::new_v4().simple().to_string(),
+            creator: creator.clone(),
+            word: normalized,
+        };
+        self.repository.insert_challenge(&challenge)?;
+
+        Ok(challenge)
+    }
+
+    /// The client's game of the challenge's word, started the first time they ask for it
+    pub fn play_challenge(
+        &self,
+        challenge_id: &str,
+        client: &ClientId,
+        mode: Option<String>,
+    ) -> Result<GameIdentity, ApiError> {
+        self.create_game(CreateGame {
+            client: client.clone(),
+            invalid_guesses_count: None,
+            mode,
+            group: None,
+            daily: false,
+            length: None,
+            language: None,
+            challenge: Some(challenge_id.to_string()),
+        })
+    }
+
+    /// A made-up client to play as right away, which nobody is told about but whoever asked
+    pub fn create_guest(&self) -> Result<Guest, ApiError> {
+        self.ensure_writable()?;
+
+        let guest = Guest {
+            client: ClientId::stored(format!("{}{}", guests::PREFIX, Uuid::new_v4().simple())),
+            key: Uuid::new_v4().simple().to_string(),
+        };
+        self.repository.insert_guest(&guest)?;
+
+        Ok(guest)
+    }
+
+    /// Gives the client a key that their games can only be created and played with from then on.
+    /// A client can only be registered the once, and never a guest, who has a key of their own.
+    /// The display name is saved to the client's profile, and whatever else they registered with
+    /// kept as it was given.
+    pub fn register_client(&self, registering: NewClient) -> Result<Registration, ApiError> {
+        self.ensure_writable()?;
+        let client = ClientId::parse(&registering.client)?;
+
+        let mut profile = self.profile(&client)?;
+        if registering.display_name.is_some() {
+            profile.display_name = registering.display_name.clone();
+            profile.validate()?;
+        }
+        let metadata = serde_json::to_string(&registering.metadata)?;
+        if metadata.len() > registration::MAX_METADATA {
+            return Err(ApiError::InvalidParam {
+                name: String::from("metadata"),
+                value: metadata,
+            });
+        }
+
+        let key = Uuid::new_v4().simple().to_string();
+        if client.as_str().starts_with(guests::PREFIX)
+            || !self
+                .repository
+                .insert_client(&client, &key, &registering.metadata)?
+        {
+            return Err(ApiError::ClientRegistered(client.to_string()));
+        }
+        if registering.display_name.is_some() {
+            self.repository.save_profile(&client, &profile)?;
+        }
+
+        Ok(Registration {
+            client,
+            key,
+            display_name: registering.display_name,
+            metadata: registering.metadata,
+        })
+    }
+
+    pub fn registered_client(&self, client: &ClientId) -> Result<RegisteredClient, ApiError> {
+        let (metadata, registered_at) = self
+            .repository
+            .registered_client(client)?
+            .ok_or_else(|| ApiError::ClientNotFound(client.to_string()))?;
+
+        Ok(RegisteredClient {
+            client: client.clone(),
+            player: self.display_name(client)?,
+            metadata,
+            registered_at,
+        })
+    }
+
+    /// Refuses clients that haven't registered, when only registered clients may play. Guests
+    /// count as registered, as the server made them up.
+    pub fn ensure_registered(&self, client: &ClientId) -> Result<(), ApiError> {
+        if !self.config.registered_clients_only
+            || self.repository.registered_client(client)?.is_some()
+            || self.repository.guest(client)?.is_some()
+        {
+            return Ok(());
+        }
+
+        Err(ApiError::ClientNotFound(client.to_string()))
+    }
+
+    /// Refuses anyone without the client's key, once they've registered one. Clients that never
+    /// have can be played as by anyone, as before there were keys.
+    pub fn authorize_client(&self, client: &ClientId, key: Option<&str>) -> Result<(), ApiError> {
+        match self.repository.api_key(client)? {
+            Some(expected) if key != Some(expected.as_str()) => Err(ApiError::Unauthorized),
+            _ => Ok(()),
+        }
+    }
+
+    /// Refuses anyone without the key of the client whose game it is
+    pub fn authorize_game(&self, game_id: &GameId, key: Option<&str>) -> Result<(), ApiError> {
+        let game = self
+            .repository
+            .find_game(game_id)?
+            .ok_or_else(|| ApiError::GameNotFound(game_id.to_string()))?;
+
+        self.authorize_client(&game.client, key)
+    }
+
+    /// Hands everything the guest has played over to the client, for whoever has the guest's key
+    /// and the client's, if they've registered one, so that nobody's stats can be added to by
+    /// someone else's guest. Guests can't be merged into one another, only into a client someone
+    /// chose.
+    pub fn merge_guest(
+        &self,
+        guest: &ClientId,
+        key: &str,
+        into: &ClientId,
+        into_key: Option<&str>,
+    ) -> Result<Merged, ApiError> {
+        self.ensure_writable()?;
+
+        // an unknown guest is refused just like a wrong key, so guests can't be found by trying
+        self.repository
+            .guest(guest)?
+            .ok_or(ApiError::Unauthorized)?
+            .authorize(Some(key))?;
+        if into.as_str().starts_with(guests::PREFIX) {
+            return Err(ApiError::InvalidParam {
+                name: String::from("into"),
+                value: into.to_string(),
+            });
+        }
+        self.authorize_client(into, into_key)?;
+
+        let (games, achievements) = self.repository.merge_guest(guest, into)?;
+
+        Ok(Merged {
+            guest: guest.clone(),
+            client: into.clone(),
+            games,
+            achievements,
+            stats: self.record(into)?,
+        })
+    }
+
+    pub fn remove_friend(&self, client: &ClientId, friend: &ClientId) -> Result<(), ApiError> {
+        self.ensure_writable()?;
+        self.repository.remove_friend(client, friend)?;
+
+        Ok(())
+    }
+
+    pub fn new_invite_code(&self, client: &ClientId) -> Result<String, ApiError> {
+        self.ensure_writable()?;
+
+        let code = Uuid::new_v4().simple().to_string()[..12].to_uppercase();
+        self.repository.save_invite_code(client, &code)?;
+
+        Ok(code)
+    }
+
+    /// The client and their friends, ranked by their games so far
+    pub fn friends_leaderboard(&self, client: &ClientId) -> Result<Vec<Standing>, ApiError> {
+        let group = self.friend_group(client)?;
+        let mut stats = self
+            .repository
+            .client_stats()?
+            .into_iter()
+            .filter(|x| group.iter().any(|client| client.as_str() == x.client))
+            .collect::<Vec<_>>();
+        let clean = |x: &ClientStats| x.num_solved - x.num_assisted_solved;
+        stats.sort_by(|a, b| {
+            clean(b).cmp(&clean(a)).then(
+                a.avg_goes
+                    .unwrap_or(f64::MAX)
+                    .total_cmp(&b.avg_goes.unwrap_or(f64::MAX)),
+            )
+        });
+
+        stats
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| {
+                let client = ClientId::stored(x.client);
+
+                Ok(Standing {
+                    rank: i + 1,
+                    player: self.display_name(&client)?,
+                    client,
+                    num_games: x.num_games,
+                    num_solved: x.num_solved,
+                    num_assisted_solved: x.num_assisted_solved,
+                    avg_goes: x.avg_goes,
+                })
+            })
+            .collect()
+    }
+
+    /// The games the client and their friends finished today, wins in the fewest goes first
+    pub fn friends_daily(&self, client: &ClientId) -> Result<Vec<Finish>, ApiError> {
+        let group = self.friend_group(client)?;
+        let mut games =
+            self.repository
+                .finished_games(&group, game::today(), friends::MAX_DAILY)?;
+        games.sort_by_key(|x| (x.game.state != GameState::Won, x.game.goes));
+
+        self.finishes(&games)
+    }
+
+    /// The friends' latest finished games, newest first
+    pub fn friends_feed(&self, client: &ClientId, limit: usize) -> Result<Vec<Finish>, ApiError> {
+        let friends = self.repository.friends(client)?;
+        let since = game::today().saturating_sub(friends::FEED_DAYS - 1);
+
+        self.finishes(&self.repository.finished_games(&friends, since, limit)?)
+    }
+
+    /// The client along with their friends
+    fn friend_group(&self, client: &ClientId) -> Result<Vec<ClientId>, ApiError> {
+        let mut group = self.repository.friends(client)?;
+        group.push(client.clone());
+
+        Ok(group)
+    }
+
+    fn finishes(&self, games: &[FinishedGame]) -> Result<Vec<Finish>, ApiError> {
+        games
+            .iter()
+            .map(|x| Ok(Finish::new(x, self.display_name(&x.game.client)?)))
+            .collect()
+    }
+
+    /// Every client's stats, with what to call them
+    pub fn get_stats(&self) -> Result<Vec<ClientStats>, ApiError> {
+        self.repository
+            .client_stats()?
+            .into_iter()
+            .map(|stats| {
+                Ok(ClientStats {
+                    player: self.display_name(&ClientId::stored(stats.client.clone()))?,
+                    ..stats
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that the storage can be reached and is fully migrated, and that the standard words
+    /// were loaded. Storage failures are only logged, as for any other request.
+    pub fn readiness(&self) -> Readiness {
+        let mut checks = BTreeMap::new();
+        match self.repository.schema_version() {
+            Ok(version) => {
+                checks.insert("database", Check::ok());
+                checks.insert(
+                    "migrations",
+                    if version == db::LATEST_VERSION {
+                        Check::ok()
+                    } else {
+                        Check::failed(format!(
+                            "at schema version {version} of {}",
+                            db::LATEST_VERSION
+                        ))
+                    },
+                );
+            }
+            Err(error) => {
+                eprintln!("readiness: {error}");
+                checks.insert("database", Check::failed(String::from("unreachable")));
+                checks.insert("migrations", Check::failed(String::from("unknown")));
+            }
+        }
+        let words = self
+            .config
+            .word_lists
+            .get(dictionary::LANGUAGE, dictionary::LENGTH);
+        checks.insert(
+            "word_lists",
+            match words {
+                Some(words) if !words.answers().is_empty() => Check::ok(),
+                _ => Check::failed(String::from("no answers to play")),
+            },
+        );
+
+        Readiness {
+            ready: checks.values().all(|x| x.ok),
+            checks,
+        }
+    }
+
+    /// Refuses the request if the instance is read-only
+    fn ensure_writable(&self) -> Result<(), ApiError> {
+        match self.repository.read_only()? {
+            Some(message) => Err(ApiError::ReadOnly(message)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Plays the guess, leaving the game as it should be saved
+fn play_guess(game: &mut StoredGame, guess: Guess) -> Option<Answer> {
+    let mut evaluations = game.mode.evaluate(&mut game.mode_state, guess.as_str());
+    let state = game.state.guess(
+        game.mode.is_won(&game.mode_state),
+        game.options.out_of_goes(game.goes + 1),
+    )?;
+    let reveal = |word: &str| state.is_finished().then(|| word.to_string());
+
+    game.goes += 1;
+    game.state = state;
+
+    let boards = game
+        .mode_state
+        .boards
+        .iter()
+        .zip(&evaluations)
+        .map(|(board, evaluation)| BoardEvaluation {
+            solved: board.solved,
+            answer: reveal(board.word()),
+            evaluation: evaluation.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    Some(Answer {
+        state,
+        solved: state == GameState::Won,
+        lost: state == GameState::Lost,
+        answer: reveal(game.mode_state.boards[0].word()),
+        guess,
+        goes: game.goes,
+        evaluation: evaluations.swap_remove(0),
+        boards: (boards.len() > 1).then_some(boards),
+        guesses: Vec::new(),
+        difficulty: None,
+        achievements: Vec::new(),
+        repeated: false,
+        letters: letters(game.mode_state.revealed.iter().map(|(_, x)| x)),
+        share: None,
+    })
+}
+
+/// The answer a guess the game has already had was given, evaluated again against every board
+/// without the game moving on, or `None` if it's a new guess
+fn repeat(game: &StoredGame, guess: &Guess) -> Option<Answer> {
+    let (_, evaluation) = game
+        .mode_state
+        .revealed
+        .iter()
+        .find(|(previous, _)| previous == guess.as_str())?;
+    // evaluating a guess again only narrows a board's candidates to those it already allowed
+    let boards = game
+        .mode
+        .evaluate(&mut game.mode_state.clone(), guess.as_str())
+        .into_iter()
+        .zip(&game.mode_state.boards)
+        .map(|(evaluation, board)| BoardEvaluation {
+            solved: board.solved,
+            answer: None,
+            evaluation,
+        })
+        .collect::<Vec<_>>();
+
+    Some(Answer {
+        state: game.state,
+        solved: false,
+        lost: false,
+        answer: None,
+        guess: guess.clone(),
+        goes: game.goes,
+        evaluation: evaluation.clone(),
+        boards: (boards.len() > 1).then_some(boards),
+        guesses: Vec::new(),
+        difficulty: None,
+        achievements: Vec::new(),
+        repeated: true,
+        letters: letters(game.mode_state.revealed.iter().map(|(_, x)| x)),
+        share: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Config;
+    use crate::dictionary;
+    use crate::error::ApiError;
+    use crate::events;
+    use crate::game::{ClientId, GameId, GameState, Rejection};
+    use crate::repository::{MemoryRepository, Repository};
+    use crate::service::{CreateGame, Service, SubmitGuess};
+    use crate::solver::{Simulation, MAX_GOES};
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+    use wordle_core::Daily;
+
+    fn service(invalid_guesses_count: bool) -> (Service, Arc<MemoryRepository>) {
+        configured(|config| config.invalid_guesses_count = invalid_guesses_count)
+    }
+
+    fn configured(configure: impl FnOnce(&mut Config)) -> (Service, Arc<MemoryRepository>) {
+        let repository = Arc::new(MemoryRepository::new());
+        let mut config = Config::for_tests();
+        configure(&mut config);
+
+        (
+            Service::with_repository(config, repository.clone()),
+            repository,
+        )
+    }
+
+    fn create(service: &Service) -> GameId {
+        service
+            .create_game(CreateGame {
+                client: ClientId::parse("bob").unwrap(),
+                invalid_guesses_count: None,
+                mode: None,
+                group: None,
+                daily: false,
+                length: None,
+                language: None,
+                challenge: None,
+            })
+            .unwrap()
+            .game_id
+    }
+
+    fn guess(service: &Service, game_id: &GameId, guess: &str) -> Result<usize, ApiError> {
+        service
+            .submit_guess(SubmitGuess {
+                game_id: game_id.clone(),
+                guess: guess.to_string(),
+            })
+            .map(|x| x.goes)
+    }
+
+    fn answer(repository: &MemoryRepository, game_id: &GameId) -> String {
+        let game = repository.find_game(game_id).unwrap().unwrap();
+
+        game.mode_state.boards[0].word().to_string()
+    }
+
+    #[test]
+    fn unknown_games_are_not_found() {
+        let (service, _) = service(false);
+
+        let result = guess(&service, &GameId::generate(), "crane");
+        assert!(matches!(result, Err(ApiError::GameNotFound(_))));
+    }
+
+    #[test]
+    fn invalid_guesses_only_use_a_go_when_configured_to() {
+        for counted in [false, true] {
+            let (service, repository) = service(counted);
+            let game_id = create(&service);
+
+            let result = guess(&service, &game_id, "xxxxx");
+            assert!(matches!(result, Err(ApiError::InvalidGuess(_))));
+            assert_eq!(
+                repository.rejections(&game_id),
+                vec![(String::from("xxxxx"), Rejection::InvalidWord, counted)]
+            );
+
+            let goes = guess(&service, &game_id, &answer(&repository, &game_id)).unwrap();
+            assert_eq!(goes, if counted { 2 } else { 1 });
+        }
+    }
+
+    #[test]
+    fn finished_games_refuse_further_guesses() {
+        let (service, repository) = service(false);
+        let game_id = create(&service);
+        let word = answer(&repository, &game_id);
+
+        guess(&service, &game_id, &word).unwrap();
+
+        let result = guess(&service, &game_id, &word);
+        assert!(matches!(result, Err(ApiError::GameFinished(_))));
+        assert_eq!(
+            repository.rejections(&game_id)[0].1,
+            Rejection::GameFinished
+        );
+        assert_eq!(service.get_stats().unwrap()[0].num_solved, 1);
+
+        let replayed = events::project(&repository.events(&game_id)).unwrap();
+        let stored = repository.find_game(&game_id).unwrap().unwrap();
+        assert_eq!((replayed.goes, replayed.state), (stored.goes, stored.state));
+    }
+
+    #[test]
+    fn games_are_lost_once_out_of_goes() {
+        let (service, repository) = service(true);
+        let game_id = create(&service);
+        let word = answer(&repository, &game_id);
+        let wrong = dictionary::answers()
+            .iter()
+            .filter(|x| **x != word)
+            .take(5)
+            .collect::<Vec<_>>();
+
+        // a counted rejection uses up a go as well
+        assert!(guess(&service, &game_id, "xxxxx").is_err());
+        for wrong in &wrong[..4] {
+            guess(&service, &game_id, wrong).unwrap();
+        }
+        let last = service
+            .submit_guess(SubmitGuess {
+                game_id: game_id.clone(),
+                guess: wrong[4].to_string(),
+            })
+            .unwrap();
+
+        assert_eq!((last.goes, last.state), (6, GameState::Lost));
+        assert!(last.lost && !last.solved);
+        assert_eq!(last.answer, Some(word.clone()));
+        assert!(matches!(
+            guess(&service, &game_id, &word),
+            Err(ApiError::GameFinished(_))
+        ));
+    }
+
+    #[test]
+    fn resigning_forfeits_the_game_without_solving_it() {
+        let (service, repository) = service(false);
+        let game_id = create(&service);
+        let word = answer(&repository, &game_id);
+
+        let status = service.resign(&game_id).unwrap();
+        assert_eq!(status.state, GameState::Forfeited);
+        assert_eq!(status.answer, Some(word));
+        assert!(matches!(
+            service.resign(&game_id),
+            Err(ApiError::GameFinished(_))
+        ));
+
+        let stats = repository.client_stats().unwrap();
+        assert_eq!((stats[0].num_forfeited, stats[0].num_solved), (1, 0));
+        assert_eq!(stats[0].avg_goes, None);
+    }
+
+    #[test]
+    fn the_current_game_is_the_latest_in_progress() {
+        let (service, repository) = service(false);
+        let bob = ClientId::parse("bob").unwrap();
+        assert_eq!(service.current_game(&bob).unwrap(), None);
+
+        let first = create(&service);
+        let second = create(&service);
+        assert_eq!(service.current_game(&bob).unwrap(), Some(second.clone()));
+
+        guess(&service, &second, &answer(&repository, &second)).unwrap();
+        assert_eq!(service.current_game(&bob).unwrap(), Some(first));
+    }
+
+    #[test]
+    fn opener_simulations_are_saved_per_answer_list() {
+        let (service, repository) = service(false);
+        // simulating every answer is slow, so a saved simulation stands in to show it isn't redone
+        let saved = Simulation::from_distribution("crane", BTreeMap::from([(3, 2)]), MAX_GOES);
+        repository
+            .save_simulation(dictionary::answers_version(), &saved)
+            .unwrap();
+        repository
+            .save_simulation(
+                "an older list",
+                &Simulation {
+                    games: 0,
+                    ..saved.clone()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(service.simulate_opener(" CRANE ").unwrap(), saved);
+        assert!(matches!(
+            service.simulate_opener("zzzzz"),
+            Err(ApiError::InvalidParam { .. })
+        ));
+    }
+
+    #[test]
+    fn seeded_services_choose_the_same_answers() {
+        let answers = || {
+            let (service, repository) = configured(|config| config.answer_seed = Some(42));
+
+            (0..5)
+                .map(|_| answer(&repository, &create(&service)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(answers(), answers());
+    }
+
+    #[test]
+    fn injected_selectors_replace_the_configured_one() {
+        let (service, repository) = service(false);
+        let service = service.with_selector(Arc::new(Daily { seed: 3 }));
+
+        let first = answer(&repository, &create(&service));
+        assert_eq!(answer(&repository, &create(&service)), first);
+    }
+
+    #[test]
+    fn merged_guests_leave_their_games_to_the_client() {
+        let (service, repository) = service(false);
+        let guest = service.create_guest().unwrap();
+        let bob = ClientId::parse("bob").unwrap();
+        let game_id = service
+            .create_game(CreateGame {
+                client: guest.client.clone(),
+                invalid_guesses_count: None,
+                mode: None,
+                group: None,
+                daily: false,
+                length: None,
+                language: None,
+                challenge: None,
+            })
+            .unwrap()
+            .game_id;
+        guess(&service, &game_id, &answer(&repository, &game_id)).unwrap();
+
+        assert!(matches!(
+            service.merge_guest(&guest.client, "nope", &bob, None),
+            Err(ApiError::Unauthorized)
+        ));
+        let merged = service
+            .merge_guest(&guest.client, &guest.key, &bob, None)
+            .unwrap();
+
+        assert_eq!((merged.games, merged.stats.num_won), (1, 1));
+        assert_eq!(repository.find_game(&game_id).unwrap().unwrap().client, bob);
+        assert!(events::project(&repository.events(&game_id)).is_ok_and(|x| x.client == bob));
+    }
+
+    #[test]
+    fn read_only_instances_refuse_writes() {
+        let (service, repository) = service(false);
+        repository.set_read_only(Some("down for maintenance"));
+
+        let result = service.create_game(CreateGame {
+            client: ClientId::parse("bob").unwrap(),
+            invalid_guesses_count: None,
+            mode: None,
+            group: None,
+            daily: false,
+            length: None,
+            language: None,
+            challenge: None,
+        });
+        assert!(matches!(result, Err(ApiError::ReadOnly(_))));
+    }
+}
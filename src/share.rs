@@ -0,0 +1,224 @@
+use crate::error::ApiError;
+use crate::game::GameId;
+use crate::json_response;
+use crate::registration;
+use crate::repository::StoredGame;
+use crate::service::Service;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use wordle_core::{GameState, MatchType};
+
+/// Makes text safe to put in HTML or XML, whether as content or as an attribute value
+pub fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The square players share each evaluated letter as
+pub fn emoji(match_type: MatchType) -> char {
+    match match_type {
+        MatchType::Perfect => '🟩',
+        MatchType::Partial => '🟨',
+        MatchType::None => '⬛',
+    }
+}
+
+/// One line of squares per guess, the answer itself never appears
+pub fn emoji_grid(game: &StoredGame) -> Vec<String> {
+    game.mode_state
+        .revealed
+        .iter()
+        .map(|(_, evaluation)| evaluation.iter().map(|x| emoji(x.match_type)).collect())
+        .collect()
+}
+
+/// What players paste to show how a finished game went without giving the answer away, headed
+/// with the daily puzzle's number for daily games, e.g. "Wordle-API #365 4/6" over the grid. A
+/// lost game is scored `X`, and a game without a limit on its goes has no `/6`.
+pub fn text(game: &StoredGame, puzzle_number: Option<u64>) -> String {
+    let mut heading = String::from("Wordle-API");
+    if let Some(number) = puzzle_number {
+        let _ = write!(heading, " #{number}");
+    }
+    match game.state {
+        GameState::Won => {
+            let _ = write!(heading, " {}", game.goes);
+        }
+        _ => heading.push_str(" X"),
+    }
+    if let Some(max) = game.options.max_guesses {
+        let _ = write!(heading, "/{max}");
+    }
+
+    format!("{heading}\n\n{}", emoji_grid(game).join("\n"))
+}
+
+/// A one-line summary of how the game stands, e.g. "solved in 4"
+pub fn outcome(game: &StoredGame) -> String {
+    match game.state {
+        GameState::InProgress => format!("in progress after {}", game.goes),
+        GameState::Won => format!("solved in {}", game.goes),
+        GameState::Lost => format!("lost after {}", game.goes),
+        GameState::Forfeited => format!("forfeited after {}", game.goes),
+        GameState::Expired => format!("expired after {}", game.goes),
+    }
+}
+
+/// A link to a finished game that anyone holding its token can see, until it's revoked
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ShareLink {
+    pub token: String,
+    pub game_id: GameId,
+    /// Whether the link shows the words guessed as well as the grid, which gives the answer away
+    pub show_guesses: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct NewShare {
+    pub show_guesses: bool,
+}
+
+/// A finished game as its share link shows it
+#[derive(Serialize, Debug)]
+pub struct SharedGame {
+    pub player: String,
+    pub mode: &'static str,
+    pub state: GameState,
+    pub goes: usize,
+    pub grid: Vec<String>,
+    /// Only for links made with `show_guesses`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guesses: Option<Vec<String>>,
+}
+
+impl SharedGame {
+    pub fn new(game: &StoredGame, player: String, show_guesses: bool) -> SharedGame {
+        SharedGame {
+            player,
+            mode: game.mode.name(),
+            state: game.state,
+            goes: game.goes,
+            grid: emoji_grid(game),
+            guesses: show_guesses.then(|| {
+                game.mode_state
+                    .revealed
+                    .iter()
+                    .map(|(guess, _)| guess.clone())
+                    .collect()
+            }),
+        }
+    }
+
+    fn outcome(&self) -> String {
+        match self.state {
+            GameState::Won => format!("solved in {}", self.goes),
+            _ => format!("{} after {}", self.state.as_str(), self.goes),
+        }
+    }
+}
+
+/// `POST /game/<game_id>/shares` makes a share link for the finished game, showing the grid alone
+/// unless the body asks for `{"show_guesses": true}`
+pub fn handle_create(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+    let share: NewShare = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    json_response(&service.share_game(&game_id, share.show_guesses)?)
+}
+
+/// `GET /game/<game_id>/shares` lists the game's share links that haven't been revoked
+pub fn handle_list(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+
+    json_response(&service.share_links(&game_id)?)
+}
+
+/// `DELETE /game/<game_id>/shares/<token>` revokes the share link, answering with those left
+pub fn handle_revoke(
+    service: &Service,
+    request: &Request,
+    game_id: &str,
+    token: &str,
+) -> Result<Response, ApiError> {
+    let game_id = GameId::parse(game_id)?;
+    registration::authorize_game(service, request, &game_id)?;
+    service.revoke_share(&game_id, token)?;
+
+    json_response(&service.share_links(&game_id)?)
+}
+
+/// `GET /shared/<token>` is the shared game as a page, or with `?format=json` as JSON
+pub fn handle_view(
+    service: &Service,
+    request: &Request,
+    token: &str,
+) -> Result<Response, ApiError> {
+    let shared = service.shared_game(token)?;
+
+    match request.get_param("format").as_deref() {
+        None | Some("html") => Ok(Response::html(page(&shared))),
+        Some("json") => json_response(&shared),
+        Some(format) => Err(ApiError::InvalidParam {
+            name: String::from("format"),
+            value: format.to_string(),
+        }),
+    }
+}
+
+fn page(shared: &SharedGame) -> String {
+    let title = format!(
+        "{}'s Wordle-API {} game, {}",
+        escape(&shared.player),
+        shared.mode,
+        shared.outcome()
+    );
+
+    let mut rows = String::new();
+    for (i, row) in shared.grid.iter().enumerate() {
+        // guesses are always dictionary words, there's nothing to escape
+        let guess = shared
+            .guesses
+            .as_ref()
+            .and_then(|x| x.get(i))
+            .map_or(String::new(), |x| format!(" {}", x.to_uppercase()));
+        let _ = writeln!(rows, "{row}{guess}");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<meta property="og:title" content="{title}">
+<meta property="og:description" content="{}">
+<style>
+    body {{ font-family: sans-serif; max-width: 480px; margin: 32px auto; padding: 0 16px; }}
+    pre {{ font-size: 24px; line-height: 1.1; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<pre>{rows}</pre>
+</body>
+</html>
+"#,
+        shared.grid.join(" ")
+    )
+}
@@ -0,0 +1,274 @@
+use crate::dictionary;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+use wordle_core::{evaluate_guess, CharMatch, MatchType};
+
+/// Solving in more goes than a classic game of Wordle allows counts as a failure
+pub const MAX_GOES: usize = 6;
+
+/// The best guess to open with against the answer list, and how many answers it's expected to
+/// leave. Every game opens against the whole list, and finding it compares every answer with every
+/// other, so it's only worked out once.
+static OPENING: OnceLock<(&'static str, f64)> = OnceLock::new();
+
+/// A compact encoding of an evaluation, identical for any two evaluations showing the same colours
+pub fn pattern(word: &str, guess: &str) -> u32 {
+    let lowercase = |x: &str| x.bytes().all(|x| x.is_ascii_lowercase());
+    if word.len() != guess.len() || word.len() > 16 || !lowercase(word) || !lowercase(guess) {
+        return encode(evaluate_guess(word, guess).iter().map(|x| x.match_type));
+    }
+
+    // the same colouring as `evaluate_guess` without allocating, as solving compares millions of
+    // pairs of words
+    let (word, guess) = (word.as_bytes(), guess.as_bytes());
+    let mut unmatched = [0u8; 26];
+    let mut codes = [0u32; 16];
+    for i in 0..word.len() {
+        if word[i] == guess[i] {
+            codes[i] = 2;
+        } else {
+            unmatched[usize::from(word[i] - b'a')] += 1;
+        }
+    }
+    for i in 0..guess.len() {
+        let letter = usize::from(guess[i] - b'a');
+        if codes[i] == 0 && unmatched[letter] > 0 {
+            unmatched[letter] -= 1;
+            codes[i] = 1;
+        }
+    }
+
+    codes[..guess.len()].iter().fold(0, |acc, x| acc * 3 + x)
+}
+
+/// The pattern an evaluation shows, as [`pattern`] encodes it
+fn encode(evaluation: impl IntoIterator<Item = MatchType>) -> u32 {
+    evaluation
+        .into_iter()
+        .fold(0, |acc, x| acc * 3 + u32::from(x.code()))
+}
+
+/// The answers that would have produced every evaluation seen so far
+pub fn candidates<'a>(
+    answers: &'a [String],
+    revealed: &[(String, Vec<CharMatch>)],
+) -> Vec<&'a str> {
+    let seen = revealed
+        .iter()
+        .map(|(guess, evaluation)| (guess, encode(evaluation.iter().map(|x| x.match_type))))
+        .collect::<Vec<_>>();
+
+    answers
+        .iter()
+        .map(String::as_str)
+        .filter(|answer| {
+            seen.iter()
+                .all(|(guess, seen)| pattern(answer, guess) == *seen)
+        })
+        .collect()
+}
+
+/// How many candidates would share each possible pattern were `guess` played
+pub fn buckets(guess: &str, candidates: &[&str]) -> HashMap<u32, usize> {
+    let mut buckets = HashMap::new();
+    for candidate in candidates {
+        *buckets.entry(pattern(candidate, guess)).or_insert(0) += 1;
+    }
+
+    buckets
+}
+
+/// The number of candidates expected to remain after playing `guess`, assuming each candidate is
+/// equally likely to be the answer
+pub fn expected_remaining(guess: &str, candidates: &[&str]) -> f64 {
+    let mut counts = vec![0usize; 3usize.pow(guess.len() as u32)];
+    for candidate in candidates {
+        counts[pattern(candidate, guess) as usize] += 1;
+    }
+    let squares: usize = counts.iter().map(|x| x * x).sum();
+
+    squares as f64 / candidates.len() as f64
+}
+
+/// The candidate that leaves the fewest candidates behind on average, earliest first on a tie
+pub fn best_guess<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    scored_best_guess(candidates).map(|(guess, _)| guess)
+}
+
+/// The best opening guess against the answer list, along with the number of answers it's expected
+/// to leave
+pub fn opening() -> (&'static str, f64) {
+    *OPENING.get_or_init(|| {
+        let answers = dictionary::answers()
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        scored_best_guess(&answers).unwrap_or_default()
+    })
+}
+
+/// The best guess along with the number of candidates it's expected to leave
+pub fn scored_best_guess<'a>(candidates: &[&'a str]) -> Option<(&'a str, f64)> {
+    // with two candidates left either is as good as the other
+    if candidates.len() <= 2 {
+        return candidates
+            .first()
+            .map(|x| (*x, expected_remaining(x, candidates)));
+    }
+
+    candidates
+        .iter()
+        .map(|x| (*x, expected_remaining(x, candidates)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// How the reference solver fares across a set of answers when it always opens with the same guess
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Simulation {
+    pub opener: String,
+    pub games: usize,
+    pub mean_goes: f64,
+    /// The share of games taking more goes than a classic game allows
+    pub failure_rate: f64,
+    /// How many games took each number of goes
+    pub distribution: BTreeMap<usize, usize>,
+}
+
+impl Simulation {
+    /// Plays a game against each target, opening with `opener`, failing those over `max_goes`
+    pub fn run(answers: &[String], targets: &[&str], opener: &str, max_goes: usize) -> Simulation {
+        let mut distribution = BTreeMap::new();
+        for answer in targets {
+            let goes = Solver::new(answers).solve(answer, Some(opener)).len();
+            *distribution.entry(goes).or_insert(0) += 1;
+        }
+
+        Simulation::from_distribution(opener, distribution, max_goes)
+    }
+
+    /// Summarises how many games took each number of goes, failing those taking over `max_goes`
+    pub fn from_distribution(
+        opener: &str,
+        distribution: BTreeMap<usize, usize>,
+        max_goes: usize,
+    ) -> Simulation {
+        let games: usize = distribution.values().sum();
+        let total_goes: usize = distribution.iter().map(|(goes, games)| goes * games).sum();
+        let failures: usize = distribution
+            .iter()
+            .filter(|(goes, _)| **goes > max_goes)
+            .map(|(_, games)| games)
+            .sum();
+
+        Simulation {
+            opener: opener.to_string(),
+            games,
+            mean_goes: total_goes as f64 / games.max(1) as f64,
+            failure_rate: failures as f64 / games.max(1) as f64,
+            distribution,
+        }
+    }
+}
+
+/// The reference solver: always guesses a word that could still be the answer, choosing whichever
+/// is expected to narrow the candidates down the most
+pub struct Solver<'a> {
+    candidates: Vec<&'a str>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(answers: &'a [String]) -> Solver<'a> {
+        Solver {
+            candidates: answers.iter().map(|x| x.as_str()).collect(),
+        }
+    }
+
+    pub fn next_guess(&self) -> Option<&'a str> {
+        best_guess(&self.candidates)
+    }
+
+    /// Discards every candidate that wouldn't have produced the evaluation seen for `guess`
+    pub fn observe(&mut self, guess: &str, evaluation: &[MatchType]) {
+        let seen = encode(evaluation.iter().copied());
+
+        self.candidates
+            .retain(|candidate| pattern(candidate, guess) == seen);
+    }
+
+    /// Plays a whole game against `answer`, starting with `opener` if given, returning every guess
+    pub fn solve(mut self, answer: &str, opener: Option<&'a str>) -> Vec<&'a str> {
+        let mut guesses = Vec::new();
+        let mut next = opener.or_else(|| self.next_guess());
+
+        while let Some(guess) = next {
+            guesses.push(guess);
+            if guess == answer {
+                break;
+            }
+
+            let evaluation = evaluate_guess(answer, guess)
+                .iter()
+                .map(|x| x.match_type)
+                .collect::<Vec<_>>();
+            self.observe(guess, &evaluation);
+            next = self.next_guess();
+        }
+
+        guesses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solver::{encode, pattern, Simulation, Solver};
+    use wordle_core::evaluate_guess;
+
+    #[test]
+    fn identical_words_share_the_all_perfect_pattern() {
+        assert_eq!(pattern("crane", "crane"), 3u32.pow(5) - 1);
+        assert_eq!(pattern("crane", "moist"), 0);
+    }
+
+    #[test]
+    fn patterns_colour_repeated_letters_like_evaluations() {
+        for (word, guess) in [("abbey", "babes"), ("crane", "eerie"), ("speed", "geese")] {
+            let evaluation = evaluate_guess(word, guess);
+
+            assert_eq!(
+                pattern(word, guess),
+                encode(evaluation.iter().map(|x| x.match_type))
+            );
+        }
+    }
+
+    #[test]
+    fn simulations_summarise_every_game() {
+        let answers = ["crane", "crate", "trace", "react", "cater", "caret"]
+            .map(String::from)
+            .to_vec();
+        let targets = answers.iter().map(String::as_str).collect::<Vec<_>>();
+
+        let simulation = Simulation::run(&answers, &targets, "crane", 1);
+
+        assert_eq!(simulation.games, 6);
+        assert_eq!(simulation.distribution[&1], 1);
+        assert!((simulation.failure_rate - 5.0 / 6.0).abs() < 1e-9);
+        assert!(simulation.mean_goes > 1.0);
+    }
+
+    #[test]
+    fn solves_every_answer_in_a_small_list() {
+        let answers = ["crane", "crate", "trace", "react", "cater", "caret"]
+            .map(String::from)
+            .to_vec();
+
+        for answer in &answers {
+            let guesses = Solver::new(&answers).solve(answer, None);
+
+            assert_eq!(guesses.last(), Some(&answer.as_str()));
+            assert!(guesses.len() <= answers.len());
+        }
+    }
+}
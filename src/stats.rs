@@ -0,0 +1,352 @@
+use crate::transfer::{Filter, MATCHES};
+use crate::{db, dictionary};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use wordle_core::GameState;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClientStats {
+    pub client: String,
+    /// Their display name, or else the client itself
+    #[serde(default)]
+    pub player: String,
+    pub avg_goes: Option<f64>,
+    pub max_goes: Option<usize>,
+    pub num_solved: usize,
+    pub num_games: usize,
+    pub num_rejected: usize,
+    /// The sum of every hint's penalty, half hints costing the least and green hints the most
+    #[serde(default)]
+    pub hint_penalty: usize,
+    /// Games played with any help from the solver, such as a hint
+    #[serde(default)]
+    pub num_assisted: usize,
+    /// Games solved with help from the solver, counted among both `num_solved` and `num_assisted`
+    #[serde(default)]
+    pub num_assisted_solved: usize,
+    /// Games given up on, which are neither solved nor counted towards the goes
+    #[serde(default)]
+    pub num_forfeited: usize,
+}
+
+/// How a client's finished games went
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Record {
+    pub num_finished: usize,
+    pub num_won: usize,
+    /// Games won in a row, up to the most recently finished
+    pub streak: usize,
+}
+
+impl Record {
+    /// Totals up the outcomes of a client's finished games, oldest first
+    pub fn from_outcomes(outcomes: &[GameState]) -> Record {
+        Record {
+            num_finished: outcomes.len(),
+            num_won: outcomes.iter().filter(|x| **x == GameState::Won).count(),
+            streak: outcomes
+                .iter()
+                .rev()
+                .take_while(|x| **x == GameState::Won)
+                .count(),
+        }
+    }
+
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.num_finished > 0).then(|| self.num_won as f64 / self.num_finished as f64)
+    }
+}
+
+/// Totals across every game on the instance
+#[derive(Serialize)]
+pub struct Summary {
+    pub num_games: usize,
+    pub num_solved: usize,
+    pub num_clients: usize,
+    pub num_rejected: usize,
+    pub avg_goes: Option<f64>,
+}
+
+/// How a single answer has fared across every game played against it
+#[derive(Serialize)]
+pub struct WordStats {
+    pub word: String,
+    pub num_games: usize,
+    pub num_solved: usize,
+    pub avg_goes: Option<f64>,
+}
+
+/// How the games started on a single day went
+#[derive(Serialize)]
+pub struct DaySummary {
+    /// As `YYYY-MM-DD`, in UTC
+    pub day: String,
+    pub num_clients: usize,
+    pub num_games: usize,
+    pub num_solved: usize,
+    pub avg_goes: Option<f64>,
+    /// How many games were solved in each number of goes
+    pub distribution: BTreeMap<usize, usize>,
+}
+
+pub fn client_stats(conn: &Connection) -> Result<Vec<ClientStats>, rusqlite::Error> {
+    filtered_client_stats(conn, &Filter::default())
+}
+
+/// Each client's stats over just the games the filter matches
+pub fn filtered_client_stats(
+    conn: &Connection,
+    filter: &Filter,
+) -> Result<Vec<ClientStats>, rusqlite::Error> {
+    let query = format!(
+        "
+SELECT client, 
+    AVG(CASE WHEN solved = 1 THEN goes END) AS avg_goes, 
+    MAX(CASE WHEN solved = 1 THEN goes END) AS max_goes, 
+    SUM(solved)                             AS num_solved,
+    COUNT(1)                                AS num_games,
+    SUM((
+        SELECT COUNT(1) FROM rejected_guess WHERE rejected_guess.game_id = game.game_id
+    ))                                      AS num_rejected,
+    SUM((
+        SELECT COALESCE(SUM(json_extract(data, '$.penalty')), 0) FROM game_event
+        WHERE game_event.game_id = game.game_id AND kind = 'hinted'
+    ))                                      AS hint_penalty,
+    SUM(EXISTS (
+        SELECT 1 FROM game_event WHERE game_event.game_id = game.game_id AND kind = 'hinted'
+    ))                                      AS num_assisted,
+    SUM(solved = 1 AND EXISTS (
+        SELECT 1 FROM game_event WHERE game_event.game_id = game.game_id AND kind = 'hinted'
+    ))                                      AS num_assisted_solved,
+    SUM(state = 'forfeited')                AS num_forfeited
+FROM game_history AS game
+WHERE {MATCHES}
+GROUP BY client
+    "
+    );
+
+    let mut result = conn.prepare(&query)?;
+
+    let stats = result
+        .query_map(filter.params(), |row| {
+            Ok(ClientStats {
+                client: row.get(0)?,
+                player: row.get(0)?,
+                avg_goes: row.get(1)?,
+                max_goes: row.get(2)?,
+                num_solved: row.get(3)?,
+                num_games: row.get(4)?,
+                num_rejected: row.get(5)?,
+                hint_penalty: row.get(6)?,
+                num_assisted: row.get(7)?,
+                num_assisted_solved: row.get(8)?,
+                num_forfeited: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(stats)
+}
+
+pub fn summary(conn: &Connection) -> Result<Summary, rusqlite::Error> {
+    let query = "
+SELECT COUNT(1)                                AS num_games,
+    COALESCE(SUM(solved), 0)                   AS num_solved,
+    COUNT(DISTINCT client)                     AS num_clients,
+    (SELECT COUNT(1) FROM rejected_guess)      AS num_rejected,
+    AVG(CASE WHEN solved = 1 THEN goes END)    AS avg_goes
+FROM game_history AS game
+    ";
+
+    conn.query_row(query, [], |row| {
+        Ok(Summary {
+            num_games: row.get(0)?,
+            num_solved: row.get(1)?,
+            num_clients: row.get(2)?,
+            num_rejected: row.get(3)?,
+            avg_goes: row.get(4)?,
+        })
+    })
+}
+
+/// How the games opened with one word went
+#[derive(Serialize, Debug)]
+pub struct OpenerStats {
+    pub opener: String,
+    /// Whether the opener is itself one of the answers
+    pub is_answer: bool,
+    pub num_games: usize,
+    pub num_won: usize,
+    pub avg_goes: Option<f64>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OpenerReport {
+    /// When the report was last refreshed, it's never been while `None`
+    pub refreshed_at: Option<String>,
+    /// Most played first
+    pub openers: Vec<OpenerStats>,
+}
+
+const OPENER_REPORT_REFRESHED_AT: &str = "opener_report_refreshed_at";
+
+/// Works out the opener report again from every finished game's first guess, returning how many
+/// different openers it found
+pub fn refresh_opener_report(conn: &Connection) -> Result<usize, rusqlite::Error> {
+    let query = "
+INSERT INTO opener_report (opener, num_games, num_won, avg_goes)
+SELECT opener,
+    COUNT(1)                                     AS num_games,
+    SUM(state = 'won')                           AS num_won,
+    AVG(CASE WHEN state = 'won' THEN goes END)   AS avg_goes
+FROM (
+    SELECT game.state, game.goes, (
+        SELECT json_extract(data, '$.guess') FROM game_event
+        WHERE game_event.game_id = game.game_id AND kind = 'guessed'
+        ORDER BY event_id LIMIT 1
+    ) AS opener
+    FROM game_history AS game
+    WHERE state != 'in_progress'
+)
+WHERE opener IS NOT NULL
+GROUP BY opener
+    ";
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM opener_report", [])?;
+    let openers = tx.execute(query, [])?;
+    db::set_setting(
+        &tx,
+        OPENER_REPORT_REFRESHED_AT,
+        Some(&tx.query_row("SELECT datetime('now')", [], |row| row.get::<_, String>(0))?),
+    )?;
+    tx.commit()?;
+
+    Ok(openers)
+}
+
+/// The `limit` most played openers as of the last refresh
+pub fn opener_report(conn: &Connection, limit: usize) -> Result<OpenerReport, rusqlite::Error> {
+    let openers = conn
+        .prepare(
+            "SELECT opener, num_games, num_won, avg_goes FROM opener_report
+            ORDER BY num_games DESC, opener LIMIT ?1",
+        )?
+        .query_map([limit], |row| {
+            let opener: String = row.get(0)?;
+
+            Ok(OpenerStats {
+                is_answer: dictionary::answers().contains(&opener),
+                opener,
+                num_games: row.get(1)?,
+                num_won: row.get(2)?,
+                avg_goes: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(OpenerReport {
+        refreshed_at: db::setting(conn, OPENER_REPORT_REFRESHED_AT)?,
+        openers,
+    })
+}
+
+/// The most recent `days` days' summaries, newest first. Only days that are over are included, so
+/// nothing is said about games that are still being played.
+pub fn daily_summaries(conn: &Connection, days: usize) -> Result<Vec<DaySummary>, rusqlite::Error> {
+    let query = "
+SELECT date(created_at)                     AS day,
+    COUNT(DISTINCT client)                  AS num_clients,
+    COUNT(1)                                AS num_games,
+    SUM(solved)                             AS num_solved,
+    AVG(CASE WHEN solved = 1 THEN goes END) AS avg_goes
+FROM game_history AS game
+WHERE created_at IS NOT NULL AND date(created_at) < date('now')
+GROUP BY day
+ORDER BY day DESC
+LIMIT ?1
+    ";
+
+    let mut summaries = conn
+        .prepare(query)?
+        .query_map([days], |row| {
+            Ok(DaySummary {
+                day: row.get(0)?,
+                num_clients: row.get(1)?,
+                num_games: row.get(2)?,
+                num_solved: row.get(3)?,
+                avg_goes: row.get(4)?,
+                distribution: BTreeMap::new(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(oldest) = summaries.last().map(|x| x.day.clone()) else {
+        return Ok(summaries);
+    };
+
+    let query = "
+SELECT date(created_at) AS day, goes, COUNT(1)
+FROM game_history AS game
+WHERE solved = 1 AND created_at IS NOT NULL AND date(created_at) BETWEEN ?1 AND date('now', '-1 day')
+GROUP BY day, goes
+    ";
+
+    let mut statement = conn.prepare(query)?;
+    let mut rows = statement.query([oldest])?;
+    while let Some(row) = rows.next()? {
+        let day: String = row.get(0)?;
+        if let Some(summary) = summaries.iter_mut().find(|x| x.day == day) {
+            summary.distribution.insert(row.get(1)?, row.get(2)?);
+        }
+    }
+
+    Ok(summaries)
+}
+
+/// The answers with the lowest solve rate, then the highest average goes, among games that were
+/// actually played
+pub fn hardest_words(conn: &Connection, limit: usize) -> Result<Vec<WordStats>, rusqlite::Error> {
+    let query = "
+SELECT word,
+    COUNT(1)                                AS num_games,
+    SUM(solved)                             AS num_solved,
+    AVG(CASE WHEN solved = 1 THEN goes END) AS avg_goes
+FROM game_history AS game
+WHERE goes > 0
+GROUP BY word
+ORDER BY 1.0 * SUM(solved) / COUNT(1) ASC, avg_goes DESC, num_games DESC
+LIMIT ?1
+    ";
+
+    let mut result = conn.prepare(query)?;
+
+    let stats = result
+        .query_map([limit], |row| {
+            Ok(WordStats {
+                word: row.get(0)?,
+                num_games: row.get(1)?,
+                num_solved: row.get(2)?,
+                avg_goes: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::Record;
+    use wordle_core::GameState::{Forfeited, Lost, Won};
+
+    #[test]
+    fn streaks_count_back_from_the_latest_game() {
+        let record = Record::from_outcomes(&[Won, Lost, Won, Forfeited, Won, Won]);
+
+        assert_eq!((record.num_won, record.streak), (4, 2));
+        assert_eq!(Record::from_outcomes(&[Won, Lost]).streak, 0);
+        assert_eq!(Record::from_outcomes(&[]).win_rate(), None);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::game::ClientId;
+use crate::json_response;
+use crate::service::{CreateGame, Service, SubmitGuess};
+use crate::share;
+use rouille::{Request, Response};
+use serde::{Deserialize, Serialize};
+
+const HELP: &str =
+    "Send /new to start a game, or /new hard for hard mode, then send words to guess them.";
+
+#[derive(Deserialize)]
+struct Update {
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+/// A reply made in the webhook's response, saving a separate call to the Bot API
+#[derive(Serialize)]
+struct SendMessage {
+    method: &'static str,
+    chat_id: i64,
+    text: String,
+}
+
+/// `POST /integrations/telegram` takes Telegram webhook updates, so a bot can be pointed straight
+/// at the server. Each chat plays as its own client and always plays its most recent game. Updates
+/// must carry the secret token the webhook was registered with, and are refused while
+/// `WORDLE_TELEGRAM_SECRET` is unset.
+pub fn handle(service: &Service, request: &Request) -> Result<Response, ApiError> {
+    authorize(service.config(), request)?;
+
+    let update: Update = rouille::input::json_input(request)
+        .map_err(|error| ApiError::InvalidBody(error.to_string()))?;
+
+    // anything but a text message, e.g. an edit or a photo, needs no reply
+    let Some((chat, text)) = update
+        .message
+        .and_then(|x| Some((x.chat.id, x.text?.trim().to_string())))
+    else {
+        return Ok(Response::text(""));
+    };
+
+    let text = match reply(service, chat, &text) {
+        Ok(text) => text,
+        Err(error) if error.status_code() >= 500 => return Err(error),
+        Err(error) => error.to_string(),
+    };
+
+    json_response(&SendMessage {
+        method: "sendMessage",
+        chat_id: chat,
+        text,
+    })
+}
+
+fn authorize(config: &Config, request: &Request) -> Result<(), ApiError> {
+    let provided = request.header("X-Telegram-Bot-Api-Secret-Token");
+
+    match (&config.telegram_secret, provided) {
+        (Some(expected), Some(provided)) if expected == provided => Ok(()),
+        _ => Err(ApiError::Unauthorized),
+    }
+}
+
+fn reply(service: &Service, chat: i64, text: &str) -> Result<String, ApiError> {
+    let client = ClientId::parse(&format!("telegram-{chat}"))?;
+    let mut words = text.split_whitespace();
+    // commands may be addressed to the bot, as in /new@wordle_bot
+    let command = words.next().unwrap_or_default().split('@').next();
+
+    match command {
+        Some("/start" | "/help") => Ok(String::from(HELP)),
+        Some("/new") => {
+            let game = service.create_game(CreateGame {
+                client,
+                invalid_guesses_count: None,
+                mode: words.next().map(str::to_string),
+                group: None,
+                daily: false,
+                length: None,
+                language: None,
+                challenge: None,
+            })?;
+
+            Ok(format!(
+                "Started a {} game, send your first guess",
+                game.options.mode
+            ))
+        }
+        Some(command) if command.starts_with('/') => Ok(String::from(HELP)),
+        _ => {
+            let Some(game_id) = service.current_game(&client)? else {
+                return Ok(String::from(
+                    "There's no game in progress, send /new to start one",
+                ));
+            };
+
+            let answer = service.submit_guess(SubmitGuess {
+                game_id: game_id.clone(),
+                guess: text.to_string(),
+            })?;
+            let game = service.find_game(&game_id)?;
+
+            let mut reply = format!(
+                "{} {}\n{}",
+                answer
+                    .evaluation
+                    .iter()
+                    .map(|x| share::emoji(x.match_type))
+                    .collect::<String>(),
+                answer.guess.as_str().to_uppercase(),
+                share::outcome(&game),
+            );
+            if let Some(word) = answer.answer.filter(|_| !answer.solved) {
+                reply.push_str(&format!(", the word was {}", word.to_uppercase()));
+            }
+
+            Ok(reply)
+        }
+    }
+}
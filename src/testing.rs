@@ -0,0 +1,228 @@
+use crate::config::Config;
+use crate::service::Service;
+use crate::{db, handle_request};
+use rouille::{Request, Response};
+use rusqlite::Connection;
+use serde_json::Value;
+use std::io::Read;
+use std::iter;
+use std::net::SocketAddr;
+use std::sync::mpsc::Sender;
+
+/// The admin token every test server accepts
+pub const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// The whole API backed by a private in-memory database, for exercising the routes without the
+/// binary. Requests can be handled directly, or served on a random local port with [`listen`].
+///
+/// [`listen`]: TestServer::listen
+pub struct TestServer {
+    service: Service,
+    // a shared in-memory database only lives as long as some connection to it is open, and each
+    // tenant has one of its own
+    _keep_alive: Vec<Connection>,
+}
+
+impl TestServer {
+    pub fn new() -> TestServer {
+        TestServer::with_config(|_| ())
+    }
+
+    /// A test server with its configuration adjusted before any request is made
+    pub fn with_config(configure: impl FnOnce(&mut Config)) -> TestServer {
+        let mut config = Config::for_tests();
+        configure(&mut config);
+
+        let service = Service::new(config);
+        let keep_alive = iter::once(service.config())
+            .chain(service.tenants().map(|(_, x)| x.config()))
+            .map(|config| {
+                let mut conn = config
+                    .connection()
+                    .expect("failed to open the test database");
+                db::migrate_to(&mut conn, db::LATEST_VERSION)
+                    .expect("failed to migrate the test database");
+
+                conn
+            })
+            .collect();
+
+        TestServer {
+            service,
+            _keep_alive: keep_alive,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        self.service.config()
+    }
+
+    /// The service behind the routes, for calling operations without going through HTTP
+    pub fn service(&self) -> &Service {
+        &self.service
+    }
+
+    /// A connection to the server's database, for arranging or inspecting state directly
+    pub fn connection(&self) -> Connection {
+        self.config()
+            .connection()
+            .expect("failed to open the test database")
+    }
+
+    /// Handles a request in-process, exactly as the running server would
+    pub fn request(&self, method: &str, url: &str, headers: &[(&str, &str)]) -> TestResponse {
+        self.send(method, url, headers, Vec::new())
+    }
+
+    /// Handles a request with a body
+    pub fn send(
+        &self,
+        method: &str,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> TestResponse {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let request = Request::fake_http(method, url, headers, body);
+
+        TestResponse::from(handle_request(&self.service, &request))
+    }
+
+    pub fn get(&self, url: &str) -> TestResponse {
+        self.request("GET", url, &[])
+    }
+
+    /// Posts a JSON body
+    pub fn post(&self, url: &str, body: &Value) -> TestResponse {
+        let headers = [("Content-Type", "application/json")];
+
+        self.send("POST", url, &headers, body.to_string().into_bytes())
+    }
+
+    /// Puts a JSON body
+    pub fn put(&self, url: &str, body: &Value) -> TestResponse {
+        let headers = [("Content-Type", "application/json")];
+
+        self.send("PUT", url, &headers, body.to_string().into_bytes())
+    }
+
+    /// Makes a request carrying the admin token
+    pub fn admin(&self, method: &str, url: &str) -> TestResponse {
+        let authorization = format!("Bearer {ADMIN_TOKEN}");
+
+        self.request(method, url, &[("Authorization", &authorization)])
+    }
+
+    /// Creates a game for `client`, returning its id
+    pub fn create_game(&self, client: &str) -> String {
+        let response = self.get(&format!("/create/{client}"));
+        assert_eq!(response.status, 200, "{}", response.body);
+
+        response.json()["game_id"]
+            .as_str()
+            .expect("missing game_id")
+            .to_string()
+    }
+
+    /// The hidden word of a game, for playing it to a known outcome
+    pub fn answer(&self, game_id: &str) -> String {
+        self.connection()
+            .query_row(
+                "SELECT word FROM game_history WHERE game_id = ?1",
+                [game_id],
+                |row| row.get(0),
+            )
+            .expect("no such game")
+    }
+
+    /// Serves the API on a random local port until the returned listener is dropped
+    pub fn listen(&self) -> Listener {
+        let service = self.service.clone();
+        let server = rouille::Server::new("127.0.0.1:0", move |request| {
+            handle_request(&service, request)
+        })
+        .expect("failed to bind the test server");
+
+        let addr = server.server_addr();
+        let (_, stop) = server.stoppable();
+
+        Listener { addr, stop }
+    }
+}
+
+impl Default for TestServer {
+    fn default() -> Self {
+        TestServer::new()
+    }
+}
+
+/// A test server listening on a local port
+pub struct Listener {
+    addr: SocketAddr,
+    stop: Sender<()>,
+}
+
+impl Listener {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The absolute url of a path on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        let _ = self.stop.send(());
+    }
+}
+
+/// A handled response, read into memory
+pub struct TestResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// The body as text, with anything that isn't UTF-8 replaced
+    pub body: String,
+    pub bytes: Vec<u8>,
+}
+
+impl TestResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(x, _)| x.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The body parsed as JSON, panicking if it isn't
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body)
+            .unwrap_or_else(|error| panic!("response isn't JSON ({error}): {}", self.body))
+    }
+}
+
+impl From<Response> for TestResponse {
+    fn from(response: Response) -> TestResponse {
+        let (mut reader, _) = response.data.into_reader_and_size();
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .expect("failed to read the response body");
+
+        TestResponse {
+            status: response.status_code,
+            headers: response
+                .headers
+                .into_iter()
+                .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                .collect(),
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+            bytes,
+        }
+    }
+}
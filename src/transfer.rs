@@ -0,0 +1,527 @@
+use crate::error::ApiError;
+use crate::events::GameEvent;
+use crate::game::{GameOptions, GameState};
+use crate::stats::ClientStats;
+use crate::{db, dictionary};
+use rusqlite::types::Type;
+use rusqlite::{Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// A game row exactly as stored, hidden word included
+#[derive(Serialize, Deserialize)]
+pub struct GameRecord {
+    pub game_id: String,
+    pub client: String,
+    pub word: String,
+    pub goes: usize,
+    pub solved: bool,
+    pub state: GameState,
+    pub invalid_guesses_count: bool,
+    #[serde(default = "classic")]
+    pub mode: String,
+    /// The mode's own state as JSON, absent for games that only ever had a single board
+    #[serde(default)]
+    pub mode_state: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    /// Absent for games played until they were won
+    #[serde(default)]
+    pub max_guesses: Option<usize>,
+    /// Dumps taken before other lengths could be played only ever held five-letter games
+    #[serde(default = "standard_length")]
+    pub length: usize,
+    /// Dumps taken before other languages could be played only ever held English games
+    #[serde(default = "standard_language")]
+    pub language: String,
+}
+
+/// Dumps taken before modes existed only ever held classic games
+fn classic() -> String {
+    String::from("classic")
+}
+
+fn standard_length() -> usize {
+    dictionary::LENGTH
+}
+
+fn standard_language() -> String {
+    dictionary::LANGUAGE.to_string()
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RejectionRecord {
+    pub game_id: String,
+    pub guess: String,
+    pub reason: String,
+    pub counted: bool,
+    pub rejected_at: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EventRecord {
+    pub game_id: String,
+    pub event: GameEvent,
+    pub created_at: String,
+}
+
+/// Everything needed to recreate an instance's games elsewhere
+#[derive(Serialize, Deserialize, Default)]
+pub struct Dump {
+    pub games: Vec<GameRecord>,
+    pub rejected_guesses: Vec<RejectionRecord>,
+    /// Absent from dumps taken before events were recorded
+    #[serde(default)]
+    pub events: Vec<EventRecord>,
+}
+
+/// A single line of an NDJSON dump
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Game(GameRecord),
+    RejectedGuess(RejectionRecord),
+    GameEvent(EventRecord),
+    /// A client's aggregates over the exported games, only ever written for others to read
+    Client(ClientStats),
+}
+
+impl Dump {
+    pub fn into_records(self) -> impl Iterator<Item = Record> {
+        self.games
+            .into_iter()
+            .map(Record::Game)
+            .chain(self.rejected_guesses.into_iter().map(Record::RejectedGuess))
+            .chain(self.events.into_iter().map(Record::GameEvent))
+    }
+
+    pub fn from_records(records: impl IntoIterator<Item = Record>) -> Dump {
+        let mut dump = Dump::default();
+        for record in records {
+            match record {
+                Record::Game(game) => dump.games.push(game),
+                Record::RejectedGuess(rejection) => dump.rejected_guesses.push(rejection),
+                Record::GameEvent(event) => dump.events.push(event),
+                // worked out again from the games
+                Record::Client(_) => {}
+            }
+        }
+
+        dump
+    }
+}
+
+/// What to do with an imported game whose id is already taken
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Conflict {
+    /// Abort the whole import, leaving the database untouched
+    Fail,
+    /// Keep the existing game, ignoring the imported one
+    Skip,
+    /// Replace the existing game, and everything recorded against it, with the imported one
+    Overwrite,
+}
+
+#[derive(Serialize, Default, Debug)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+    pub rejected_guesses: usize,
+    pub events: usize,
+}
+
+impl From<TransferError> for ApiError {
+    fn from(error: TransferError) -> Self {
+        match error {
+            TransferError::Conflict(game_id) => ApiError::GameExists(game_id),
+            TransferError::Storage(error) => ApiError::Storage(error),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("game '{0}' already exists")]
+    Conflict(String),
+
+    #[error("storage error: {0}")]
+    Storage(#[from] rusqlite::Error),
+}
+
+/// Which games to export, and with them which rejections and events. Anything left as none
+/// matches every game.
+#[derive(Default, Debug)]
+pub struct Filter {
+    pub client: Option<String>,
+    /// The first day the games were started on, as `YYYY-MM-DD`
+    pub since: Option<String>,
+    /// The last day the games were started on, as `YYYY-MM-DD`
+    pub until: Option<String>,
+}
+
+impl Filter {
+    pub fn params(&self) -> (&Option<String>, &Option<String>, &Option<String>) {
+        (&self.client, &self.since, &self.until)
+    }
+}
+
+/// The games a [`Filter`] matches, given its fields as `?1` to `?3`
+pub const MATCHES: &str = "(?1 IS NULL OR client = ?1)
+    AND (?2 IS NULL OR date(created_at) >= ?2) AND (?3 IS NULL OR date(created_at) <= ?3)";
+
+pub fn export(conn: &Connection) -> Result<Dump, rusqlite::Error> {
+    Ok(Dump {
+        games: games(conn, None)?,
+        rejected_guesses: rejections(conn, None)?,
+        events: events(conn, None)?,
+    })
+}
+
+/// Every game, or just the one with the given id
+pub fn games(conn: &Connection, game_id: Option<&str>) -> Result<Vec<GameRecord>, rusqlite::Error> {
+    conn.prepare(
+        "SELECT game_id, client, word, goes, solved, state, invalid_guesses_count, mode, mode_state,
+            created_at, updated_at, max_guesses, length, language
+        FROM game_history WHERE ?1 IS NULL OR game_id = ?1 ORDER BY seq",
+    )?
+    .query_map([game_id], game_record)?
+    .collect()
+}
+
+/// Up to `limit` of the games the filter matches, archived or not, after the one at `after` in the
+/// order they were created, along with where each of them is in that order
+pub fn games_page(
+    conn: &Connection,
+    filter: &Filter,
+    after: i64,
+    limit: usize,
+) -> Result<Vec<(i64, GameRecord)>, rusqlite::Error> {
+    let (client, since, until) = filter.params();
+
+    conn.prepare(&format!(
+        "SELECT game_id, client, word, goes, solved, state, invalid_guesses_count, mode, mode_state,
+            created_at, updated_at, max_guesses, length, language, seq
+        FROM game_history WHERE {MATCHES} AND seq > ?4 ORDER BY seq LIMIT ?5"
+    ))?
+    .query_map((client, since, until, after, limit), |row| {
+        Ok((row.get(14)?, game_record(row)?))
+    })?
+    .collect()
+}
+
+fn game_record(row: &Row) -> Result<GameRecord, rusqlite::Error> {
+    Ok(GameRecord {
+        game_id: row.get(0)?,
+        client: row.get(1)?,
+        word: row.get(2)?,
+        goes: row.get(3)?,
+        solved: row.get(4)?,
+        state: db::get_state(row, 5)?,
+        invalid_guesses_count: row.get(6)?,
+        mode: row.get(7)?,
+        mode_state: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        max_guesses: row.get(11)?,
+        length: row.get(12)?,
+        language: row.get(13)?,
+    })
+}
+
+/// Every rejected guess, or just those made against the game with the given id
+pub fn rejections(
+    conn: &Connection,
+    game_id: Option<&str>,
+) -> Result<Vec<RejectionRecord>, rusqlite::Error> {
+    conn.prepare(
+        "SELECT game_id, guess, reason, counted, rejected_at
+        FROM rejected_guess WHERE ?1 IS NULL OR game_id = ?1 ORDER BY rejection_id",
+    )?
+    .query_map([game_id], rejection_record)?
+    .collect()
+}
+
+/// Like [`games_page`], for the rejections made against the games the filter matches
+pub fn rejections_page(
+    conn: &Connection,
+    filter: &Filter,
+    after: i64,
+    limit: usize,
+) -> Result<Vec<(i64, RejectionRecord)>, rusqlite::Error> {
+    let (client, since, until) = filter.params();
+
+    conn.prepare(&format!(
+        "SELECT game_id, guess, reason, counted, rejected_at, rejection_id FROM rejected_guess
+        WHERE game_id IN (SELECT game_id FROM game_history WHERE {MATCHES}) AND rejection_id > ?4
+        ORDER BY rejection_id LIMIT ?5"
+    ))?
+    .query_map((client, since, until, after, limit), |row| {
+        Ok((row.get(5)?, rejection_record(row)?))
+    })?
+    .collect()
+}
+
+fn rejection_record(row: &Row) -> Result<RejectionRecord, rusqlite::Error> {
+    Ok(RejectionRecord {
+        game_id: row.get(0)?,
+        guess: row.get(1)?,
+        reason: row.get(2)?,
+        counted: row.get(3)?,
+        rejected_at: row.get(4)?,
+    })
+}
+
+/// Every game's events, or just those of the game with the given id, oldest first
+pub fn events(
+    conn: &Connection,
+    game_id: Option<&str>,
+) -> Result<Vec<EventRecord>, rusqlite::Error> {
+    conn.prepare(
+        "SELECT game_id, data, created_at
+        FROM game_event WHERE ?1 IS NULL OR game_id = ?1 ORDER BY event_id",
+    )?
+    .query_map([game_id], event_record)?
+    .collect()
+}
+
+/// Like [`games_page`], for the events of the games the filter matches, which include every
+/// accepted guess
+pub fn events_page(
+    conn: &Connection,
+    filter: &Filter,
+    after: i64,
+    limit: usize,
+) -> Result<Vec<(i64, EventRecord)>, rusqlite::Error> {
+    let (client, since, until) = filter.params();
+
+    conn.prepare(&format!(
+        "SELECT game_id, data, created_at, event_id FROM game_event
+        WHERE game_id IN (SELECT game_id FROM game_history WHERE {MATCHES}) AND event_id > ?4
+        ORDER BY event_id LIMIT ?5"
+    ))?
+    .query_map((client, since, until, after, limit), |row| {
+        Ok((row.get(3)?, event_record(row)?))
+    })?
+    .collect()
+}
+
+fn event_record(row: &Row) -> Result<EventRecord, rusqlite::Error> {
+    Ok(EventRecord {
+        game_id: row.get(0)?,
+        event: serde_json::from_str(&row.get::<_, String>(1)?).map_err(|error| {
+            rusqlite::Error::FromSqlConversionFailure(1, Type::Text, error.into())
+        })?,
+        created_at: row.get(2)?,
+    })
+}
+
+/// Loads a dump in a single transaction, so a failed import changes nothing
+pub fn import(
+    conn: &mut Connection,
+    dump: Dump,
+    conflict: Conflict,
+) -> Result<ImportSummary, TransferError> {
+    let tx = conn.transaction()?;
+    let mut summary = ImportSummary::default();
+    let mut imported = HashSet::new();
+
+    for game in dump.games {
+        let exists = tx
+            .query_row(
+                "SELECT 1 FROM game_history WHERE game_id = ?1",
+                [&game.game_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+
+        if exists {
+            match conflict {
+                Conflict::Fail => return Err(TransferError::Conflict(game.game_id)),
+                Conflict::Skip => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                Conflict::Overwrite => {
+                    tx.execute(
+                        "DELETE FROM rejected_guess WHERE game_id = ?1",
+                        [&game.game_id],
+                    )?;
+                    tx.execute("DELETE FROM game_guess WHERE game_id = ?1", [&game.game_id])?;
+                    tx.execute("DELETE FROM game_event WHERE game_id = ?1", [&game.game_id])?;
+                    tx.execute("DELETE FROM game WHERE game_id = ?1", [&game.game_id])?;
+                    tx.execute(
+                        "DELETE FROM game_archive WHERE game_id = ?1",
+                        [&game.game_id],
+                    )?;
+                    summary.overwritten += 1;
+                }
+            }
+        } else {
+            summary.imported += 1;
+        }
+
+        tx.execute(
+            "INSERT INTO game (game_id, client, word, goes, solved, state, invalid_guesses_count, mode,
+                mode_state, created_at, updated_at, max_guesses, length, language)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            (
+                &game.game_id,
+                &game.client,
+                &game.word,
+                game.goes,
+                game.solved,
+                game.state.as_str(),
+                game.invalid_guesses_count,
+                &game.mode,
+                &game.mode_state,
+                &game.created_at,
+                &game.updated_at,
+                game.max_guesses,
+                game.length,
+                &game.language,
+            ),
+        )?;
+
+        // a dump from before events were recorded starts the game's history from its row
+        if !dump.events.iter().any(|x| x.game_id == game.game_id) {
+            db::append_events(&tx, &game.game_id, &[snapshot(&game)?])?;
+            summary.events += 1;
+        }
+
+        imported.insert(game.game_id);
+    }
+
+    // rejections only come along with their game, never onto one that was skipped
+    for rejection in dump.rejected_guesses {
+        if !imported.contains(&rejection.game_id) {
+            continue;
+        }
+
+        tx.execute(
+            "INSERT INTO rejected_guess (game_id, guess, reason, counted, rejected_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            (
+                &rejection.game_id,
+                &rejection.guess,
+                &rejection.reason,
+                rejection.counted,
+                &rejection.rejected_at,
+            ),
+        )?;
+        summary.rejected_guesses += 1;
+    }
+
+    for event in dump.events {
+        if !imported.contains(&event.game_id) {
+            continue;
+        }
+
+        tx.execute(
+            "INSERT INTO game_event (game_id, kind, data, created_at) VALUES (?1, ?2, ?3, ?4)",
+            (
+                &event.game_id,
+                event.event.kind(),
+                serde_json::to_string(&event.event)
+                    .map_err(|error| rusqlite::Error::ToSqlConversionFailure(error.into()))?,
+                &event.created_at,
+            ),
+        )?;
+        summary.events += 1;
+    }
+
+    tx.commit()?;
+
+    Ok(summary)
+}
+
+fn snapshot(game: &GameRecord) -> Result<GameEvent, rusqlite::Error> {
+    let mode_state = game
+        .mode_state
+        .as_deref()
+        .map(serde_json::from_str)
+        .transpose()
+        .map_err(|error| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, error.into()))?;
+
+    Ok(GameEvent::Created {
+        client: game.client.clone(),
+        word: game.word.clone(),
+        goes: game.goes,
+        state: game.state,
+        options: GameOptions {
+            invalid_guesses_count: game.invalid_guesses_count,
+            mode: game.mode.clone(),
+            max_guesses: game.max_guesses,
+            length: game.length,
+            language: game.language.clone(),
+        },
+        mode_state,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{migrate_to, LATEST_VERSION};
+    use crate::transfer::{export, import, Conflict, Dump, TransferError};
+    use rusqlite::Connection;
+
+    fn database() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate_to(&mut conn, LATEST_VERSION).unwrap();
+        conn.execute_batch(
+            "INSERT INTO game (game_id, client, word, goes) VALUES ('a', 'bob', 'crane', 2);
+            INSERT INTO rejected_guess (game_id, guess, reason, counted)
+                VALUES ('a', 'xxxxx', 'invalid_word', 0);",
+        )
+        .unwrap();
+
+        conn
+    }
+
+    fn dump(conn: &Connection) -> Dump {
+        export(conn).unwrap()
+    }
+
+    fn round_trip(dump: Dump) -> Dump {
+        Dump::from_records(dump.into_records())
+    }
+
+    #[test]
+    fn imports_an_exported_dump_into_an_empty_database() {
+        let exported = round_trip(dump(&database()));
+
+        let mut target = Connection::open_in_memory().unwrap();
+        migrate_to(&mut target, LATEST_VERSION).unwrap();
+        let summary = import(&mut target, exported, Conflict::Fail).unwrap();
+
+        assert_eq!((summary.imported, summary.rejected_guesses), (1, 1));
+        assert_eq!(dump(&target).games[0].word, "crane");
+
+        // the game was inserted without any events, so its history starts from a snapshot
+        assert_eq!(summary.events, 1);
+        assert_eq!(dump(&target).events[0].event.kind(), "created");
+    }
+
+    #[test]
+    fn applies_the_conflict_strategy_to_existing_games() {
+        let mut conn = database();
+
+        let existing = dump(&conn);
+        let failed = import(&mut conn, existing, Conflict::Fail);
+        assert!(matches!(failed, Err(TransferError::Conflict(_))));
+
+        let existing = dump(&conn);
+        let skipped = import(&mut conn, existing, Conflict::Skip).unwrap();
+        assert_eq!((skipped.skipped, skipped.rejected_guesses), (1, 0));
+
+        let existing = dump(&conn);
+        let overwritten = import(&mut conn, existing, Conflict::Overwrite).unwrap();
+        assert_eq!(
+            (overwritten.overwritten, overwritten.rejected_guesses),
+            (1, 1)
+        );
+        assert_eq!(dump(&conn).rejected_guesses.len(), 1);
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "client")]
+
+use wordle_api::client::{Client, ClientError, NewGame};
+use wordle_api::testing::TestServer;
+
+#[test]
+fn plays_a_game_over_http() {
+    let server = TestServer::new();
+    let listener = server.listen();
+    let client = Client::new(&listener.url("")).unwrap();
+
+    let game = client
+        .create_game(
+            "bot one",
+            &NewGame {
+                mode: Some("hard"),
+                ..NewGame::default()
+            },
+        )
+        .unwrap();
+    assert_eq!(game.options.mode, "hard");
+
+    let result = client
+        .guess(&game.game_id, &server.answer(&game.game_id))
+        .unwrap();
+    assert!(result.solved);
+
+    let stats = client.stats().unwrap();
+    assert_eq!(
+        (stats[0].client.as_str(), stats[0].num_solved),
+        ("bot one", 1)
+    );
+
+    let again = client.guess(&game.game_id, "crane");
+    assert!(matches!(again, Err(ClientError::GameFinished(_))));
+    let fresh = client.create_game("bot one", &NewGame::default()).unwrap();
+    let invalid = client.guess(&fresh.game_id, "zzzzz");
+    assert!(matches!(invalid, Err(ClientError::BadRequest(_))));
+    let unknown = client.guess("00000000-0000-4000-8000-000000000000", "crane");
+    assert!(matches!(unknown, Err(ClientError::NotFound(_))));
+}
+
+#[test]
+fn plays_as_a_registered_client_with_its_key() {
+    let server = TestServer::new();
+    let listener = server.listen();
+    let client = Client::new(&listener.url("")).unwrap();
+
+    let registration = client.register("bot two").unwrap();
+    let unkeyed = client.create_game("bot two", &NewGame::default());
+    assert!(matches!(unkeyed, Err(ClientError::Unauthorized(_))));
+
+    let client = client.with_key(&registration.key);
+    let game = client.create_game("bot two", &NewGame::default()).unwrap();
+    assert!(client.guess(&game.game_id, "crane").is_ok());
+}
+
+#[test]
+fn only_http_and_https_servers_are_called() {
+    assert!(Client::new("https://wordle.example/").is_ok());
+    assert!(matches!(
+        Client::new("ftp://wordle.example"),
+        Err(ClientError::BadRequest(_))
+    ));
+}
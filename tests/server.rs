@@ -0,0 +1,2577 @@
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use wordle_api::cors::Cors;
+use wordle_api::dictionary::WordLists;
+use wordle_api::error::ApiError;
+use wordle_api::events;
+use wordle_api::game::{ClientId, Rollover};
+use wordle_api::service::{CreateGame, SubmitGuess};
+use wordle_api::testing::TestServer;
+
+/// Whether the word is any of the JSON's string values, rather than just part of a longer one or
+/// of a key, which an answer such as "state" or "class" would be by chance
+fn json_shows(value: &Value, word: &str) -> bool {
+    match value {
+        Value::String(x) => x == word,
+        Value::Array(x) => x.iter().any(|x| json_shows(x, word)),
+        Value::Object(x) => x.values().any(|x| json_shows(x, word)),
+        _ => false,
+    }
+}
+
+/// Whether the word is among the page's visible words, leaving out its tags and styles
+fn page_shows(html: &str, word: &str) -> bool {
+    html.split('<')
+        .filter(|x| !x.starts_with("style"))
+        .map(|x| x.split_once('>').map_or(x, |x| x.1))
+        .flat_map(|x| x.split(|x: char| !x.is_ascii_alphabetic()))
+        .any(|x| x.eq_ignore_ascii_case(word))
+}
+
+#[test]
+fn plays_a_game_to_completion() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+
+    let guess = server.get(&format!("/play/{game_id}/guess/{answer}"));
+    assert_eq!(guess.status, 200);
+    assert_eq!(guess.json()["state"], "won");
+    assert_eq!(guess.json()["answer"], answer.as_str());
+
+    let again = server.get(&format!("/play/{game_id}/guess/{answer}"));
+    assert_eq!(again.status, 409);
+
+    let stats = server.get("/stats").json();
+    assert_eq!(stats[0]["client"], "bob");
+    assert_eq!(stats[0]["num_solved"], 1);
+}
+
+#[test]
+fn every_client_shares_the_daily_word_once_a_day() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    let daily = |client: &str| {
+        server.request(
+            "GET",
+            &format!("/daily/{client}"),
+            &[("X-Mock-Time", "2026-10-14T12:00:00Z")],
+        )
+    };
+
+    let bob = daily("bob").json();
+    assert_eq!(bob["puzzle_number"], 1943);
+    assert_eq!(bob["puzzle_date"], "2026-10-14");
+    let bob_id = bob["game_id"].as_str().unwrap();
+    let alice_id = daily("alice").json()["game_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert_eq!(server.answer(bob_id), server.answer(&alice_id));
+    // asking again carries on with the same game
+    assert_eq!(daily("bob").json()["game_id"], bob_id);
+    assert!(server.get("/create/bob").json()["puzzle_number"].is_null());
+
+    assert_eq!(server.get("/daily/bob?mode=multi").status, 400);
+    let tomorrow = server.request(
+        "GET",
+        "/daily/bob",
+        &[("X-Mock-Time", "2026-10-15T12:00:00Z")],
+    );
+    assert_eq!(tomorrow.json()["puzzle_number"], 1944);
+    assert_ne!(tomorrow.json()["game_id"], bob_id);
+
+    // yesterday's game can't be finished once its day has passed
+    let guess = |game_id: &str, at: &str| {
+        let url = format!("/play/{game_id}/guess/crane");
+        server.request("GET", &url, &[("X-Mock-Time", at)]).status
+    };
+    assert_eq!(guess(&alice_id, "2026-10-14T23:00:00Z"), 200);
+    assert_eq!(guess(bob_id, "2026-10-15T00:30:00Z"), 409);
+    assert_eq!(guess(bob_id, "2026-10-14T23:00:00Z"), 409);
+    let status = server.get(&format!("/game/{bob_id}")).json();
+    assert_eq!(status["state"], "expired");
+    assert_eq!(status["answer"], server.answer(bob_id));
+}
+
+#[test]
+fn games_are_created_and_played_with_json_bodies() {
+    let server = TestServer::new();
+    let created = server.post("/games", &json!({ "client": "alice", "mode": "hard" }));
+    assert_eq!(created.status, 200, "{}", created.body);
+    assert_eq!(created.json()["options"]["mode"], "hard");
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+
+    let guessed = server.post(
+        &format!("/games/{game_id}/guesses"),
+        &json!({ "guess": server.answer(&game_id) }),
+    );
+    assert_eq!(guessed.json()["solved"], true);
+    assert_eq!(
+        server
+            .post("/games", &json!({ "client": "alice", "colour": "red" }))
+            .status,
+        400
+    );
+    assert_eq!(
+        server
+            .post(&format!("/games/{game_id}/guesses"), &json!({}))
+            .status,
+        400
+    );
+
+    let deprecated = server.get("/create/alice");
+    assert!(deprecated
+        .headers
+        .iter()
+        .any(|(name, value)| name == "Deprecation" && value == "true"));
+}
+
+#[test]
+fn compact_guesses_evaluate_with_a_digit_per_letter() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    let created = server.request("GET", "/create/bob", &[("X-Mock-Answer", "crane")]);
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+
+    let guessed = server
+        .get(&format!("/play/{game_id}/guess/react?format=compact"))
+        .json();
+    assert_eq!(guessed["evaluation"], "11210");
+    assert_eq!(guessed["guesses"][0]["evaluation"], "11210");
+    assert_eq!(
+        server
+            .get(&format!("/play/{game_id}/guess/crane?format=xml"))
+            .status,
+        400
+    );
+}
+
+#[test]
+fn finished_games_come_with_a_grid_to_share() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    let daily = server.request(
+        "GET",
+        "/daily/bob",
+        &[("X-Mock-Time", "2026-10-14T12:00:00Z")],
+    );
+    let game_id = daily.json()["game_id"].as_str().unwrap().to_string();
+    let answer = server.answer(&game_id);
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+
+    let guessed = server.get(&format!("/play/{game_id}/guess/{wrong}")).json();
+    assert!(guessed["share"].is_null());
+    let solved = server
+        .get(&format!("/play/{game_id}/guess/{answer}"))
+        .json();
+    let share = solved["share"].as_str().unwrap();
+    assert!(share.starts_with("Wordle-API #1943 2/6\n\n"));
+    assert!(share.ends_with("\n🟩🟩🟩🟩🟩"));
+    assert!(!share.contains(&answer));
+}
+
+#[test]
+fn hard_games_refuse_guesses_that_ignore_revealed_letters() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    assert_eq!(server.get("/create/bob?hard=true&mode=multi").status, 400);
+    let created = server.request(
+        "GET",
+        "/create/bob?hard=true",
+        &[("X-Mock-Answer", "crane")],
+    );
+    assert_eq!(created.json()["options"]["mode"], "hard");
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+
+    server.get(&format!("/play/{game_id}/guess/cramp"));
+    let refused = server.get(&format!("/play/{game_id}/guess/slate"));
+    assert_eq!(refused.status, 422);
+    assert_eq!(refused.json()["code"], "rule_violation");
+    assert!(refused.json()["error"].as_str().unwrap().contains("'c'"));
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/crane")).json()["state"],
+        "won"
+    );
+}
+
+#[test]
+fn every_guess_is_sent_back_for_clients_without_a_board() {
+    let server = TestServer::new();
+    let game_id = server.get("/create/bob?invalid_guesses_count=true").json()["game_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let answer = server.answer(&game_id);
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+
+    server.get(&format!("/play/{game_id}/guess/{wrong}"));
+    server.get(&format!("/play/{game_id}/guess/zzzzz"));
+    // a client picking the game back up is shown the board without using a go
+    for _ in 0..2 {
+        let status = server.get(&format!("/game/{game_id}")).json();
+        assert_eq!(
+            (&status["goes"], &status["remaining"]),
+            (&json!(2), &json!(4))
+        );
+        assert_eq!(status["guesses"][0]["guess"], wrong);
+        assert!(status["answer"].is_null());
+    }
+    let won = server
+        .get(&format!("/play/{game_id}/guess/{answer}"))
+        .json();
+
+    let guesses = won["guesses"].as_array().unwrap();
+    let played = guesses
+        .iter()
+        .map(|x| (x["go"].as_u64().unwrap(), x["guess"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(played, [(1, wrong), (3, answer.as_str())]);
+    assert_eq!(guesses[1]["evaluation"], won["evaluation"]);
+    let kept = server
+        .connection()
+        .query_row(
+            "SELECT COUNT(*) FROM game_guess WHERE game_id = ?1",
+            [&game_id],
+            |row| row.get::<_, usize>(0),
+        )
+        .unwrap();
+    assert_eq!(kept, 2);
+    let status = server.get(&format!("/game/{game_id}")).json();
+    assert_eq!(
+        (&status["solved"], &status["remaining"]),
+        (&json!(true), &json!(3))
+    );
+    assert_eq!(status["answer"], answer.as_str());
+}
+
+#[test]
+fn plays_words_of_other_lengths_from_supplied_lists() {
+    let dir = std::env::temp_dir().join(format!("wordle-words-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("answers-6.txt"), "planet\n").unwrap();
+    std::fs::write(dir.join("guesses-6.txt"), "garden\nhello\n").unwrap();
+    let server =
+        TestServer::with_config(|config| config.word_lists = WordLists::load(&dir).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let game = server.get("/create/alice?length=6").json();
+    assert_eq!(game["options"]["length"], 6);
+    let game_id = game["game_id"].as_str().unwrap();
+    assert_eq!(server.answer(game_id), "planet");
+
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/crane")).status,
+        400
+    );
+    let guessed = server.get(&format!("/play/{game_id}/guess/garden")).json();
+    assert_eq!(guessed["evaluation"].as_array().unwrap().len(), 6);
+    let solved = server.get(&format!("/play/{game_id}/guess/planet")).json();
+    assert_eq!(solved["solved"], true);
+
+    assert_eq!(server.get("/create/alice").json()["options"]["length"], 5);
+    for refused in [
+        "/create/alice?length=7",
+        "/create/alice?length=six",
+        "/daily/alice?length=6",
+    ] {
+        assert_eq!(server.get(refused).status, 400, "{refused}");
+    }
+}
+
+#[test]
+fn plays_words_of_other_languages_from_supplied_lists() {
+    let dir = std::env::temp_dir().join(format!("wordle-languages-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("fr")).unwrap();
+    std::fs::write(dir.join("fr").join("answers-5.txt"), "pomme\n").unwrap();
+    std::fs::write(dir.join("fr").join("guesses-5.txt"), "arbre\n").unwrap();
+    let server =
+        TestServer::with_config(|config| config.word_lists = WordLists::load(&dir).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let game = server.get("/create/alice?lang=fr-FR").json();
+    assert_eq!(game["options"]["language"], "fr");
+    let game_id = game["game_id"].as_str().unwrap();
+    assert_eq!(server.answer(game_id), "pomme");
+
+    let refused = server.get(&format!("/play/{game_id}/guess/crane?lang=fr"));
+    assert_eq!(refused.status, 400);
+    assert!(refused.json()["error"]
+        .as_str()
+        .unwrap()
+        .contains("proposition"));
+    let guessed = server.get(&format!("/play/{game_id}/guess/arbre")).json();
+    assert_eq!(guessed["solved"], false);
+
+    // languages without words, and the daily word, are played in English
+    for english in ["/create/alice?lang=de", "/daily/alice?lang=fr"] {
+        assert_eq!(
+            server.get(english).json()["options"]["language"],
+            "en",
+            "{english}"
+        );
+    }
+}
+
+#[test]
+fn challenges_are_played_as_a_game_each_without_giving_the_word_away() {
+    let server = TestServer::new();
+    assert_eq!(
+        server
+            .post("/challenge", &json!({ "client": "alice", "word": "xxxxx" }))
+            .status,
+        400
+    );
+    let challenge = server.post("/challenge", &json!({ "client": "alice", "word": "Crane" }));
+    assert!(!challenge.body.contains("crane"));
+    let challenge_id = challenge.json()["challenge_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let bob = server.get(&format!("/challenge/{challenge_id}/bob")).json();
+    let game_id = bob["game_id"].as_str().unwrap();
+    assert!(!json_shows(&bob, "crane"));
+    assert_eq!(server.answer(game_id), "crane");
+    let status = server.get(&format!("/game/{game_id}")).json();
+    assert_eq!(status["answer"], Value::Null);
+    // asking again carries on with the same game, someone else gets a game of their own
+    assert_eq!(
+        server
+            .get(&format!("/challenge/{challenge_id}/bob?hard=true"))
+            .json()["game_id"],
+        game_id
+    );
+    let carol = server
+        .get(&format!("/challenge/{challenge_id}/carol?hard=true"))
+        .json();
+    assert_ne!(carol["game_id"], game_id);
+    assert_eq!(carol["options"]["mode"], "hard");
+
+    let solved = server.get(&format!("/play/{game_id}/guess/crane")).json();
+    assert_eq!(
+        (&solved["solved"], &solved["answer"]),
+        (&json!(true), &json!("crane"))
+    );
+    assert_eq!(
+        server.get("/challenge/unknown/bob").json()["code"],
+        "challenge_not_found"
+    );
+}
+
+#[test]
+fn repeating_a_guess_costs_no_go() {
+    let server = TestServer::new();
+    let game_id = server.create_game("alice");
+    let answer = server.answer(&game_id);
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+
+    let first = server.get(&format!("/play/{game_id}/guess/{wrong}")).json();
+    let again = server
+        .get(&format!("/play/{game_id}/guess/{}", wrong.to_uppercase()))
+        .json();
+    assert_eq!(
+        (&first["repeated"], &again["repeated"], &again["goes"]),
+        (&json!(false), &json!(true), &json!(1))
+    );
+    assert_eq!(again["evaluation"], first["evaluation"]);
+    assert_eq!(again["guesses"].as_array().map(Vec::len), Some(1));
+    assert_eq!(
+        again["letters"].as_object().map(|x| x.len()),
+        Some(wrong.len())
+    );
+
+    let solved = server
+        .get(&format!("/play/{game_id}/guess/{answer}"))
+        .json();
+    assert_eq!(
+        (&solved["goes"], &solved["solved"]),
+        (&json!(2), &json!(true))
+    );
+}
+
+#[test]
+fn resigning_reveals_the_answer_and_counts_apart_in_stats() {
+    let server = TestServer::new();
+    let game_id = server.create_game("alice");
+    let answer = server.answer(&game_id);
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+    server.get(&format!("/play/{game_id}/guess/{wrong}"));
+
+    let resigned = server
+        .post(&format!("/game/{game_id}/resign"), &json!({}))
+        .json();
+    assert_eq!(
+        (&resigned["state"], &resigned["solved"], &resigned["answer"]),
+        (&json!("forfeited"), &json!(false), &json!(answer))
+    );
+    assert_eq!(
+        server
+            .post(&format!("/game/{game_id}/resign"), &json!({}))
+            .status,
+        409
+    );
+    assert_eq!(
+        server
+            .get(&format!("/play/{game_id}/guess/{answer}"))
+            .status,
+        409
+    );
+
+    // on more than one board, every board's answer is shown, the first as the answer
+    let other = TestServer::new();
+    let multi = other.get("/create/alice?mode=multi").json();
+    let multi_id = multi["game_id"].as_str().unwrap();
+    assert!(other.get(&format!("/game/{multi_id}")).json()["answers"].is_null());
+    other.post(&format!("/game/{multi_id}/resign"), &json!({}));
+    let status = other.get(&format!("/game/{multi_id}")).json();
+    assert_eq!(status["answers"].as_array().unwrap().len(), 4);
+    assert_eq!(status["answers"][0], status["answer"]);
+    assert!(resigned.get("answers").is_none());
+
+    let stats = server.get("/stats").json();
+    assert_eq!(
+        (
+            &stats[0]["num_forfeited"],
+            &stats[0]["num_solved"],
+            &stats[0]["avg_goes"]
+        ),
+        (&json!(1), &json!(0), &Value::Null)
+    );
+}
+
+#[test]
+fn games_are_lost_once_out_of_goes() {
+    let server = TestServer::new();
+    let create = || {
+        let game = server.get("/create/bob?invalid_guesses_count=true").json();
+        assert_eq!(game["options"]["max_guesses"], 6);
+
+        game["game_id"].as_str().unwrap().to_string()
+    };
+
+    let game_id = create();
+    let answer = server.answer(&game_id);
+    let wrong = [
+        "crane", "slate", "moist", "pilot", "audio", "lemon", "water",
+    ]
+    .into_iter()
+    .filter(|x| *x != answer)
+    .collect::<Vec<_>>();
+    for guess in &wrong[..5] {
+        server.get(&format!("/play/{game_id}/guess/{guess}"));
+    }
+    let lost = server.get(&format!("/play/{game_id}/guess/zzzzz")).json();
+    assert_eq!(lost["code"], "invalid_guess");
+    let replay = server.get(&format!("/game/{game_id}/replay")).json();
+    assert_eq!(
+        (&replay["state"], &replay["goes"]),
+        (&json!("lost"), &json!(6))
+    );
+    assert_eq!(replay["answers"], json!([answer]));
+
+    let game_id = create();
+    let answer = server.answer(&game_id);
+    let wrong = wrong.iter().filter(|x| **x != answer).collect::<Vec<_>>();
+    for guess in &wrong[..4] {
+        server.get(&format!("/play/{game_id}/guess/{guess}"));
+    }
+    // the last go may be used up by a word that isn't in the dictionary, too
+    server.get(&format!("/play/{game_id}/guess/zzzzz"));
+    let lost = server
+        .get(&format!("/play/{game_id}/guess/{}", wrong[4]))
+        .json();
+    assert_eq!(
+        (&lost["solved"], &lost["lost"]),
+        (&json!(false), &json!(true))
+    );
+    assert_eq!(lost["answer"], answer.as_str());
+    assert_eq!(
+        server
+            .get(&format!("/play/{game_id}/guess/{answer}"))
+            .status,
+        409
+    );
+}
+
+#[test]
+fn maps_failures_onto_status_codes() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+
+    let unknown = "00000000-0000-4000-8000-000000000000";
+    assert_eq!(
+        server.get(&format!("/play/{unknown}/guess/crane")).status,
+        404
+    );
+    assert_eq!(server.get("/play/nope/guess/crane").status, 400);
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/zzzzz")).status,
+        400
+    );
+    assert_eq!(server.get("/create/bob?mode=nonsense").status, 400);
+    assert_eq!(server.get("/admin/repairs").status, 401);
+    assert_eq!(server.admin("GET", "/admin/repairs").status, 200);
+
+    let error = server.get("/play/nope/guess/crane");
+    assert!(error.header("X-Request-Id").is_some());
+    assert_eq!(
+        error.header("X-Request-Id"),
+        error.json()["request_id"].as_str()
+    );
+
+    for path in ["/nowhere", "/t/nobody/stats"] {
+        let missing = server.get(path);
+        assert_eq!(missing.status, 404);
+        assert_eq!(missing.json()["code"], "not_found");
+    }
+    for response in [server.get("/stats"), error] {
+        assert_eq!(
+            response.header("Content-Type"),
+            Some("application/json; charset=utf-8")
+        );
+    }
+}
+
+#[test]
+fn the_openapi_document_describes_every_field_answered_with() {
+    let server = TestServer::new();
+    let document = server.get("/openapi.json").json();
+    assert_eq!(document["openapi"], "3.0.3");
+    let described = |schema: &str, value: &Value| {
+        let properties = &document["components"]["schemas"][schema]["properties"];
+        for field in value.as_object().unwrap().keys() {
+            assert!(
+                properties.get(field).is_some(),
+                "{schema} doesn't describe {field}"
+            );
+        }
+    };
+
+    let created = server.post("/games", &json!({ "client": "alice" })).json();
+    described("GameIdentity", &created);
+    described("GameOptions", &created["options"]);
+    let game_id = created["game_id"].as_str().unwrap();
+    let answer = server.answer(game_id);
+    let guessed = server
+        .post(
+            &format!("/games/{game_id}/guesses"),
+            &json!({ "guess": answer }),
+        )
+        .json();
+    described("Answer", &guessed);
+    described("PlayedGuess", &guessed["guesses"][0]);
+    described(
+        "GameStatus",
+        &server.get(&format!("/game/{game_id}")).json(),
+    );
+    described("ClientStats", &server.get("/stats").json()[0]);
+    described("Error", &server.get("/nowhere").json());
+}
+
+#[test]
+fn probes_say_whether_games_can_be_served() {
+    let server = TestServer::new();
+    assert_eq!(server.get("/healthz").json()["status"], "ok");
+    let ready = server.get("/readyz");
+    assert_eq!((ready.status, &ready.json()["ready"]), (200, &json!(true)));
+
+    server
+        .connection()
+        .execute_batch("PRAGMA user_version = 1")
+        .unwrap();
+    let behind = server.get("/readyz");
+    assert_eq!(behind.status, 503);
+    assert_eq!(
+        (
+            &behind.json()["checks"]["database"]["ok"],
+            &behind.json()["checks"]["migrations"]["ok"]
+        ),
+        (&json!(true), &json!(false))
+    );
+    assert_eq!(server.get("/healthz").status, 200);
+}
+
+#[test]
+fn metrics_count_what_the_server_has_done() {
+    let server = TestServer::new();
+    let game_id = server.create_game("alice");
+    server.get(&format!("/play/{game_id}/guess/zzzzz"));
+    server.get(&format!("/play/{game_id}/guess/crane"));
+    server.get("/made/up/path");
+
+    let metrics = server.get("/metrics").body;
+    for line in [
+        "wordle_games_created_total 1",
+        "wordle_invalid_guesses_total 1",
+        "wordle_guesses_evaluated_total 1",
+        "wordle_requests_total{method=\"GET\",route=\"/play\",status=\"400\"} 1",
+        "wordle_requests_total{method=\"other\",route=\"other\",status=\"404\"} 1",
+    ] {
+        assert!(metrics.lines().any(|x| x == line), "{line} in {metrics}");
+    }
+    let active = if server.answer(&game_id) == "crane" {
+        0
+    } else {
+        1
+    };
+    assert!(metrics.contains(&format!("wordle_active_games {active}\n")));
+}
+
+#[test]
+fn every_endpoint_is_served_under_its_version() {
+    let server = TestServer::new();
+    let game_id = server.create_game("alice");
+
+    let versioned = server.get(&format!("/v1/game/{game_id}"));
+    assert_eq!(versioned.status, 200);
+    assert_eq!(versioned.header("Api-Version"), Some("1"));
+    assert_eq!(
+        versioned.json(),
+        server.get(&format!("/game/{game_id}")).json()
+    );
+    assert_eq!(server.get("/stats").header("Api-Version"), Some("1"));
+
+    let unknown = server.get("/v2/stats");
+    assert_eq!((unknown.status, unknown.header("Api-Version")), (404, None));
+}
+
+#[test]
+fn messages_are_written_in_the_language_asked_for() {
+    let server = TestServer::new();
+    let unknown = "/play/00000000-0000-4000-8000-000000000000/guess/crane";
+    let french = [("Accept-Language", "de;q=0.9, fr-CA;q=0.8, en;q=0.5")];
+
+    let english = server.get(unknown).json();
+    assert_eq!(english["code"], "game_not_found");
+    assert!(english["error"]
+        .as_str()
+        .unwrap()
+        .contains("does not exist"));
+
+    let error = server.request("GET", unknown, &french);
+    assert_eq!(error.header("Content-Language"), Some("fr"));
+    assert_eq!(error.json()["code"], "game_not_found");
+    assert!(error.json()["error"]
+        .as_str()
+        .unwrap()
+        .contains("n'existe pas"));
+
+    let spanish = server.request("GET", &format!("{unknown}?lang=es"), &french);
+    assert!(spanish.json()["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("la partida"));
+
+    let root = server.request("GET", "/", &french);
+    assert!(root.body.contains("Bienvenue"));
+    assert!(root.body.contains("GET /create/"));
+}
+
+#[test]
+fn servers_do_not_share_games() {
+    let first = TestServer::new();
+    let second = TestServer::new();
+    let game_id = first.create_game("bob");
+
+    assert_eq!(
+        second.get(&format!("/play/{game_id}/guess/crane")).status,
+        404
+    );
+}
+
+#[test]
+fn serves_the_api_on_a_local_port() {
+    let server = TestServer::new();
+    let listener = server.listen();
+
+    let mut stream = TcpStream::connect(listener.addr()).unwrap();
+    write!(
+        stream,
+        "GET /create/bob HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains("game_id"));
+}
+
+#[test]
+fn the_service_can_be_called_without_http() {
+    let server = TestServer::new();
+    let service = server.service();
+
+    let game = service
+        .create_game(CreateGame {
+            client: ClientId::parse("bob").unwrap(),
+            invalid_guesses_count: Some(true),
+            mode: None,
+            group: None,
+            daily: false,
+            length: None,
+            language: None,
+            challenge: None,
+        })
+        .unwrap();
+    assert_eq!(game.options.mode, "classic");
+
+    let invalid = service.submit_guess(SubmitGuess {
+        game_id: game.game_id.clone(),
+        guess: String::from("zzzzz"),
+    });
+    assert!(matches!(invalid, Err(ApiError::InvalidGuess(_))));
+
+    let answer = service
+        .submit_guess(SubmitGuess {
+            game_id: game.game_id.clone(),
+            guess: server.answer(game.game_id.as_str()),
+        })
+        .unwrap();
+    assert_eq!((answer.solved, answer.goes), (true, 2));
+    assert_eq!(service.get_stats().unwrap()[0].num_games, 1);
+}
+
+#[test]
+fn games_can_be_rebuilt_from_their_events() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    server.get(&format!("/play/{game_id}/guess/zzzzz"));
+    server.get(&format!(
+        "/play/{game_id}/guess/{}",
+        server.answer(&game_id)
+    ));
+
+    let mut conn = server.connection();
+    assert!(events::rebuild(&mut conn, false)
+        .unwrap()
+        .rebuilt
+        .is_empty());
+
+    conn.execute("UPDATE game SET goes = 9, state = 'in_progress'", [])
+        .unwrap();
+    let report = events::rebuild(&mut conn, false).unwrap();
+    assert_eq!(report.rebuilt[0].goes, (9, 1));
+    assert_eq!(server.get("/stats").json()[0]["num_solved"], 1);
+}
+
+#[test]
+fn serves_a_playable_page() {
+    let server = TestServer::new();
+    let page = server.get("/ui");
+
+    assert_eq!(page.status, 200);
+    assert!(page
+        .header("Content-Type")
+        .unwrap()
+        .starts_with("text/html"));
+    assert!(page.body.contains("/create/"));
+}
+
+#[test]
+fn serves_assets_with_overrides_from_disk() {
+    let dir = std::env::temp_dir().join(format!("wordle-assets-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("favicon.svg"), "<svg>custom</svg>").unwrap();
+
+    let embedded = TestServer::new();
+    let icon = embedded.get("/assets/favicon.svg");
+    assert_eq!(icon.header("Content-Type"), Some("image/svg+xml"));
+    assert!(icon.header("Cache-Control").unwrap().contains("max-age"));
+    assert_eq!(embedded.get("/assets/../Cargo.toml").status, 404);
+    assert_eq!(embedded.get("/assets/missing.png").status, 404);
+
+    let branded = TestServer::with_config(|config| {
+        config.asset_dir = Some(dir.to_string_lossy().into_owned())
+    });
+    assert_eq!(branded.get("/favicon.ico").body, "<svg>custom</svg>");
+    assert_eq!(branded.get("/ui").status, 200);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn plays_through_discord_interactions() {
+    let server = TestServer::with_config(|config| config.discord_secret = Some(String::from("s3")));
+    let command_by = |user: &str, name: &str, options: Value| {
+        json!({
+            "type": 2,
+            "member": { "user": { "id": user } },
+            "data": { "name": name, "options": options },
+        })
+    };
+    let command = |name: &str, options: Value| command_by("1234", name, options);
+    let relay = |body: &Value, secret: &str| {
+        let authorization = format!("Bearer {secret}");
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("Authorization", authorization.as_str()),
+        ];
+
+        server.send(
+            "POST",
+            "/discord/interactions",
+            &headers,
+            body.to_string().into_bytes(),
+        )
+    };
+
+    assert_eq!(
+        server
+            .post("/discord/interactions", &json!({ "type": 1 }))
+            .status,
+        401
+    );
+    assert_eq!(relay(&json!({ "type": 1 }), "wrong").status, 401);
+    let ping = relay(&json!({ "type": 1 }), "s3");
+    assert_eq!(ping.json()["type"], 1);
+
+    let started = relay(&command("new", json!([])), "s3");
+    assert_eq!(started.json()["type"], 4);
+    let stats = server.get("/stats").json();
+    assert_eq!(stats[0]["client"], "discord-1234");
+
+    let game_id = server
+        .connection()
+        .query_row("SELECT game_id FROM game", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .unwrap();
+    let answer = server.answer(&game_id);
+
+    let options =
+        json!([{ "name": "game", "value": game_id }, { "name": "word", "value": "zzzzz" }]);
+    let invalid = relay(&command("guess", options.clone()), "s3");
+    assert_eq!(invalid.json()["data"]["flags"], 64);
+    let someone_elses = relay(&command_by("999", "guess", options), "s3").json();
+    assert_eq!(someone_elses["data"]["flags"], 64);
+
+    let options =
+        json!([{ "name": "game", "value": game_id }, { "name": "word", "value": answer }]);
+    let solved = relay(&command("guess", options), "s3").json();
+    let content = solved["data"]["content"].as_str().unwrap();
+    assert!(content.contains("solved in 1"), "{content}");
+    assert!(content.contains("🟩🟩🟩🟩🟩"));
+    assert!(content.contains(&format!("||{}||", answer.to_uppercase())));
+
+    let result = server.get(&format!("/discord/{game_id}")).json();
+    assert_eq!(result["content"], content);
+}
+
+#[test]
+fn overlays_never_show_the_answer() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    let guess = if answer == "moist" { "crane" } else { "moist" };
+    server.get(&format!("/play/{game_id}/guess/{guess}"));
+
+    let page = server.get(&format!("/overlay/{game_id}"));
+    assert!(page.body.contains("http-equiv=\"refresh\""));
+    assert_eq!(page.body.matches("class=\"tile").count(), 5);
+
+    let overlay = server
+        .get(&format!("/overlay/{game_id}?format=json"))
+        .json();
+    assert_eq!(overlay["rows"].as_array().unwrap().len(), 1);
+    assert!(!overlay.to_string().contains(&format!("\"{answer}\"")));
+}
+
+#[test]
+fn renders_boards_as_png() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    server.get(&format!("/play/{game_id}/guess/moist"));
+    server.get(&format!("/play/{game_id}/guess/crane"));
+
+    let response = server.request("GET", &format!("/game/{game_id}/image"), &[]);
+    assert_eq!(response.header("Content-Type"), Some("image/png"));
+
+    let png = response.bytes;
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let (width, height) = (&png[16..20], &png[20..24]);
+    assert_eq!(u32::from_be_bytes(width.try_into().unwrap()), 364);
+    assert_eq!(u32::from_be_bytes(height.try_into().unwrap()), 160);
+}
+
+#[test]
+fn links_games_with_qr_codes() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+
+    let svg = server.get(&format!("/game/{game_id}/qr"));
+    assert_eq!(svg.header("Content-Type"), Some("image/svg+xml"));
+    assert!(svg.body.starts_with("<svg"));
+
+    let png = server.get(&format!("/game/{game_id}/qr?format=png"));
+    assert!(png.bytes.starts_with(b"\x89PNG"));
+
+    let unknown = "00000000-0000-4000-8000-000000000000";
+    assert_eq!(server.get(&format!("/game/{unknown}/qr")).status, 404);
+}
+
+#[test]
+fn feeds_summarise_finished_days_only() {
+    let server = TestServer::new();
+    let yesterday = server.create_game("bob");
+    let word = server.answer(&yesterday);
+    server.get(&format!("/play/{yesterday}/guess/{word}"));
+    server.create_game("alice");
+
+    server
+        .connection()
+        .execute(
+            "UPDATE game SET created_at = datetime('now', '-1 day') WHERE game_id = ?1",
+            [&yesterday],
+        )
+        .unwrap();
+
+    let atom = server.get("/feed/atom");
+    assert!(atom
+        .header("Content-Type")
+        .unwrap()
+        .starts_with("application/atom+xml"));
+    assert_eq!(atom.body.matches("<entry>").count(), 1);
+    assert!(atom.body.contains("1 of 1 games solved"));
+
+    let rss = server.get("/feed/rss");
+    assert_eq!(rss.body.matches("<item>").count(), 1);
+    assert_eq!(server.get("/feed/json").status, 400);
+}
+
+#[test]
+fn plays_through_a_telegram_webhook() {
+    let server =
+        TestServer::with_config(|config| config.telegram_secret = Some(String::from("s3")));
+    let update = |text: &str, secret: &str| {
+        let body = json!({ "update_id": 1, "message": { "chat": { "id": 42 }, "text": text } });
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("X-Telegram-Bot-Api-Secret-Token", secret),
+        ];
+
+        server.send(
+            "POST",
+            "/integrations/telegram",
+            &headers,
+            body.to_string().into_bytes(),
+        )
+    };
+
+    assert_eq!(update("/new", "wrong").status, 401);
+
+    let none = update("crane", "s3").json();
+    assert!(none["text"].as_str().unwrap().contains("/new"));
+
+    let started = update("/new@wordle_bot", "s3").json();
+    assert_eq!(
+        (started["method"].as_str(), started["chat_id"].as_i64()),
+        (Some("sendMessage"), Some(42))
+    );
+
+    let game_id = server
+        .connection()
+        .query_row(
+            "SELECT game_id FROM game WHERE client = 'telegram-42'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .unwrap();
+    let answer = server.answer(&game_id);
+
+    let solved = update(&answer, "s3").json();
+    let text = solved["text"].as_str().unwrap();
+    assert!(text.starts_with("🟩🟩🟩🟩🟩"), "{text}");
+    assert!(text.contains("solved in 1"));
+}
+
+#[test]
+fn badges_show_win_rate_and_streak() {
+    let server = TestServer::new();
+    assert!(server.get("/badge/bob.svg").body.contains("no games yet"));
+
+    for _ in 0..2 {
+        let game_id = server.create_game("bob");
+        let answer = server.answer(&game_id);
+        server.get(&format!("/play/{game_id}/guess/{answer}"));
+    }
+
+    let badge = server.get("/badge/bob.svg");
+    assert_eq!(badge.header("Content-Type"), Some("image/svg+xml"));
+    assert!(badge
+        .header("Cache-Control")
+        .unwrap()
+        .contains("max-age=300"));
+    assert!(badge.body.contains("100% won · streak 2"));
+}
+
+#[test]
+fn searches_words_by_constraint() {
+    let server = TestServer::new();
+
+    let found = server.get("/words/search?green=cran.&gray=kx").json();
+    assert!(found["words"].as_array().unwrap().contains(&json!("crane")));
+    assert_eq!(found["count"], found["words"].as_array().unwrap().len());
+
+    let limited = server.get("/words/search?list=guesses&limit=3").json();
+    assert_eq!(limited["words"].as_array().unwrap().len(), 3);
+    assert!(limited["count"].as_u64().unwrap() > 3);
+
+    assert_eq!(server.get("/words/search?yellow=r9").status, 400);
+}
+
+#[test]
+fn hints_count_against_the_client() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+
+    // a green hint, the letter at a position, unless another level is asked for
+    let green = server.get(&format!("/game/{game_id}/hint")).json();
+    assert_eq!(green["level"], "green");
+    let position = green["position"].as_u64().unwrap() as usize;
+    assert!((1..=5).contains(&position));
+    assert_eq!(
+        green["letter"],
+        json!(answer[position - 1..position].to_string())
+    );
+    assert_eq!(green["penalty"], 3);
+
+    let half = server
+        .get(&format!("/game/{game_id}/hint?level=half"))
+        .json();
+    assert_eq!(half["level"], "half");
+    assert!(half.get("position").is_none());
+
+    assert_eq!(
+        server
+            .get(&format!("/game/{game_id}/hint?level=all"))
+            .status,
+        400
+    );
+
+    // each hint used up a go
+    let status = server.get(&format!("/game/{game_id}")).json();
+    assert_eq!((&status["goes"], &status["hints"]), (&json!(2), &json!(2)));
+    let stats = server.get("/stats").json();
+    assert_eq!(stats[0]["hint_penalty"], 4);
+    assert_eq!(stats[0]["num_assisted"], 1);
+
+    let solved = server
+        .get(&format!("/play/{game_id}/guess/{answer}"))
+        .json();
+    assert_eq!(solved["goes"], 3);
+    assert_eq!(server.get("/stats").json()[0]["num_assisted_solved"], 1);
+    assert_eq!(server.get(&format!("/game/{game_id}/hint")).status, 409);
+
+    // the last go is left for guessing
+    let game_id = server.create_game("bob");
+    for _ in 0..5 {
+        server.get(&format!("/game/{game_id}/hint"));
+    }
+    let refused = server.get(&format!("/game/{game_id}/hint")).json();
+    assert_eq!(refused["code"], "rule_violation");
+    assert_eq!(
+        server.get(&format!("/game/{game_id}")).json()["remaining"],
+        1
+    );
+}
+
+#[test]
+fn analyses_finished_games() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    let opener = if answer == "crane" { "slate" } else { "crane" };
+
+    server.get(&format!("/play/{game_id}/guess/{opener}"));
+    assert_eq!(server.get(&format!("/game/{game_id}/analysis")).status, 422);
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+
+    let analysis = server.get(&format!("/game/{game_id}/analysis")).json();
+    assert_eq!(analysis["answer"], json!(answer));
+
+    let guesses = analysis["guesses"].as_array().unwrap();
+    assert_eq!(guesses[0]["guess"], opener);
+    assert_eq!(
+        guesses[0]["candidates_after"],
+        guesses[guesses.len() - 1]["candidates_before"]
+    );
+    for guess in guesses {
+        assert!(guess["skill"].as_u64().unwrap() <= 100);
+        assert!(guess["luck"].as_u64().unwrap() <= 100);
+        assert!(
+            guess["best_expected_remaining"].as_f64().unwrap()
+                <= guess["expected_remaining"].as_f64().unwrap()
+        );
+    }
+    assert!(guesses[0]["best_guess"].is_string());
+}
+
+#[test]
+fn finished_games_rate_their_answer() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    let opener = if answer == "crane" { "slate" } else { "crane" };
+
+    let first = server
+        .get(&format!("/play/{game_id}/guess/{opener}"))
+        .json();
+    assert!(first.get("difficulty").is_none());
+
+    let last = server
+        .get(&format!("/play/{game_id}/guess/{answer}"))
+        .json();
+    assert!(last["difficulty"]["rating"].as_u64().unwrap() < 100);
+    assert_eq!(last["difficulty"]["average_goes"], 2.0);
+}
+
+#[test]
+fn the_reference_bot_plays_seeded_words() {
+    let server = TestServer::new();
+
+    let first = server.get("/bot/play?seed=7&opener=crane").json();
+    assert_eq!(first, server.get("/bot/play?seed=7&opener=crane").json());
+
+    let guesses = first["guesses"].as_array().unwrap();
+    assert_eq!(guesses[0]["guess"], "crane");
+    assert_eq!(guesses[guesses.len() - 1]["guess"], first["answer"]);
+    assert_eq!(guesses[guesses.len() - 1]["candidates"], 1);
+    assert_eq!(first["goes"], guesses.len());
+
+    assert_eq!(server.get("/bot/play").status, 400);
+    assert_eq!(server.get("/bot/play?seed=7&opener=zzzzz").status, 400);
+}
+
+#[test]
+fn opener_simulations_only_take_valid_guesses() {
+    let server = TestServer::new();
+
+    assert_eq!(server.get("/openers/zzzzz").status, 400);
+}
+
+#[test]
+fn opener_reports_are_refreshed_by_maintenance() {
+    let server = TestServer::new();
+    let report = server.get("/openers").json();
+    assert_eq!(report["refreshed_at"], Value::Null);
+
+    for client in ["alice", "bob"] {
+        let game_id = server.create_game(client);
+        let answer = server.answer(&game_id);
+        if answer != "crane" {
+            server.get(&format!("/play/{game_id}/guess/crane"));
+        }
+        server.get(&format!("/play/{game_id}/guess/{answer}"));
+    }
+    // games still in progress aren't counted
+    let unfinished = server.create_game("carol");
+    server.get(&format!("/play/{unfinished}/guess/crane"));
+
+    assert_eq!(server.get("/openers").json()["openers"], json!([]));
+    assert_eq!(server.admin("POST", "/admin/maintenance").status, 200);
+
+    let report = server.get("/openers").json();
+    assert!(report["refreshed_at"].is_string());
+    assert_eq!(report["openers"][0]["opener"], "crane");
+    assert_eq!(report["openers"][0]["num_games"], 2);
+    assert_eq!(report["openers"][0]["num_won"], 2);
+    assert_eq!(report["openers"][0]["is_answer"], true);
+}
+
+#[test]
+fn solver_endpoints_are_rate_limited_separately() {
+    let server = TestServer::with_config(|config| config.solver_rate_limit = Some(2));
+
+    for _ in 0..2 {
+        assert_eq!(server.get("/words/search?green=cran.").status, 200);
+    }
+    let limited = server.get("/bot/play?seed=1");
+    assert_eq!(limited.status, 429);
+    assert!(
+        limited
+            .header("Retry-After")
+            .unwrap()
+            .parse::<u64>()
+            .unwrap()
+            > 0
+    );
+
+    // everything else is unaffected
+    server.create_game("bob");
+    assert_eq!(server.get("/stats").status, 200);
+}
+
+#[test]
+fn achievements_are_awarded_once() {
+    let server = TestServer::new();
+    let won = |client: &str| {
+        let game_id = server.create_game(client);
+        let answer = server.answer(&game_id);
+        let result = server
+            .get(&format!("/play/{game_id}/guess/{answer}"))
+            .json();
+
+        (game_id, result["achievements"].clone())
+    };
+
+    let (first, achievements) = won("bob");
+    assert!(achievements
+        .as_array()
+        .unwrap()
+        .starts_with(&[json!("first_win"), json!("solved_in_two")]));
+
+    let (_, achievements) = won("bob");
+    assert!(!achievements
+        .as_array()
+        .is_some_and(|x| x.contains(&json!("first_win"))));
+
+    let awarded = server.get("/achievements/bob").json();
+    assert_eq!(awarded[0]["achievement"], "first_win");
+    assert_eq!(awarded[0]["game_id"], json!(first));
+    assert_eq!(server.get("/achievements/alice").json(), json!([]));
+
+    // thirty wins in a day are no streak of days, but another day won on after each of them is
+    for _ in 0..30 {
+        let (_, achievements) = won("alice");
+        assert!(!achievements.to_string().contains("thirty_streak"));
+    }
+    server
+        .connection()
+        .execute(
+            "UPDATE game SET created_at = datetime('now', '-' || (
+                SELECT count(*) FROM game AS later WHERE later.client = 'alice' AND later.rowid >= game.rowid
+            ) || ' day') WHERE client = 'alice'",
+            [],
+        )
+        .unwrap();
+    let (_, achievements) = won("alice");
+    assert!(achievements.to_string().contains("thirty_streak"));
+}
+
+#[test]
+fn profiles_name_clients_and_set_their_defaults() {
+    let server = TestServer::new();
+    assert_eq!(
+        server.get("/profiles/bob").json()["display_name"],
+        Value::Null
+    );
+
+    let profile = json!({
+        "display_name": "Bobby <3",
+        "settings": { "mode": "hard" },
+    });
+    assert_eq!(server.put("/profiles/bob", &profile).status, 200);
+    assert_eq!(
+        server.get("/profiles/bob").json()["display_name"],
+        "Bobby <3"
+    );
+
+    let game_id = server.create_game("bob");
+    let overlay = server
+        .get(&format!("/overlay/{game_id}?format=json"))
+        .json();
+    assert_eq!(overlay["player"], "Bobby <3");
+    assert_eq!(overlay["mode"], "hard");
+    assert!(server
+        .get(&format!("/overlay/{game_id}"))
+        .body
+        .contains("Bobby &lt;3"));
+
+    // other clients are shown as themselves
+    let game_id = server.create_game("alice");
+    let overlay = server
+        .get(&format!("/overlay/{game_id}?format=json"))
+        .json();
+    assert_eq!(overlay["player"], "alice");
+    assert_eq!(overlay["mode"], "classic");
+
+    let invalid = json!({ "avatar_url": "javascript:alert(1)" });
+    assert_eq!(server.put("/profiles/bob", &invalid).status, 400);
+    let unknown = json!({ "nickname": "bob" });
+    assert_eq!(server.put("/profiles/bob", &unknown).status, 400);
+}
+
+#[test]
+fn friends_see_each_others_games() {
+    let server = TestServer::new();
+    let code = server.post("/friends/alice/invite", &json!({})).json()["code"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    server.put("/profiles/alice", &json!({ "display_name": "Al" }));
+
+    let friends = server.post("/friends/bob", &json!({ "code": code })).json();
+    assert_eq!(friends, json!([{ "client": "alice", "player": "Al" }]));
+    assert_eq!(
+        server.get("/friends/alice").json(),
+        json!([{ "client": "bob", "player": "bob" }])
+    );
+
+    let play = |client: &str, first_go: bool| {
+        let game_id = server.create_game(client);
+        let answer = server.answer(&game_id);
+        if !first_go {
+            let miss = if answer == "crane" { "slate" } else { "crane" };
+            server.get(&format!("/play/{game_id}/guess/{miss}"));
+        }
+        server.get(&format!("/play/{game_id}/guess/{answer}"));
+
+        answer
+    };
+    let answer = play("alice", true);
+    play("bob", false);
+    play("carol", true);
+    // unfinished games aren't results yet
+    server.create_game("alice");
+
+    let leaderboard = server.get("/friends/bob/leaderboard").json();
+    assert_eq!(leaderboard.as_array().unwrap().len(), 2);
+    assert_eq!(leaderboard[0]["player"], "Al");
+    assert_eq!(leaderboard[0]["rank"], 1);
+    assert_eq!(leaderboard[1]["client"], "bob");
+
+    let daily = server.get("/friends/bob/daily").json();
+    assert_eq!(daily.as_array().unwrap().len(), 2);
+    assert_eq!(daily[0]["player"], "Al");
+    assert_eq!(daily[0]["grid"], json!(["🟩🟩🟩🟩🟩"]));
+    assert_eq!(daily[1]["goes"], 2);
+
+    let feed = server.get("/friends/bob/feed").json();
+    assert_eq!(feed.as_array().unwrap().len(), 1);
+    assert!(!json_shows(&feed, &answer));
+    assert_eq!(server.get("/friends/bob/feed?limit=0").status, 400);
+
+    assert_eq!(
+        server
+            .post("/friends/bob", &json!({ "code": "nope" }))
+            .status,
+        400
+    );
+    assert_eq!(
+        server
+            .post("/friends/bob", &json!({ "client": "bob" }))
+            .status,
+        400
+    );
+
+    let response = server.request("DELETE", "/friends/bob/alice", &[]);
+    assert_eq!(response.json(), json!([]));
+    assert_eq!(server.get("/friends/alice").json(), json!([]));
+}
+
+#[test]
+fn public_profiles_show_only_what_is_left_public() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+
+    let profile = json!({ "display_name": "Bob", "bio": "<b>hi</b>" });
+    server.put("/profiles/bob", &profile);
+
+    let page = server.get("/profile/bob");
+    assert_eq!(page.status, 200);
+    assert!(page.body.contains("&lt;b&gt;hi&lt;/b&gt;"));
+    assert!(!page_shows(&page.body, &answer));
+
+    let public = server.get("/profile/bob?format=json").json();
+    assert_eq!(public["player"], "Bob");
+    assert_eq!(public["stats"]["num_won"], 1);
+    assert_eq!(public["recent_games"][0]["goes"], 1);
+    assert_eq!(public["achievements"][0]["achievement"], "first_win");
+
+    let hidden = json!({ "privacy": { "hide_stats": true, "hide_achievements": true } });
+    server.put("/profiles/bob", &hidden);
+    let public = server.get("/profile/bob?format=json").json();
+    assert_eq!(public["stats"], Value::Null);
+    assert_eq!(public["achievements"], Value::Null);
+    assert_eq!(public["recent_games"].as_array().unwrap().len(), 1);
+
+    let private = json!({ "privacy": { "private": true } });
+    server.put("/profiles/bob", &private);
+    assert_eq!(server.get("/profile/bob").status, 404);
+}
+
+#[test]
+fn reminds_clients_before_their_streak_is_lost() {
+    let server = TestServer::with_config(|config| config.private_webhooks = true);
+    let hook = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", hook.local_addr().unwrap());
+    let received = std::thread::spawn(move || {
+        let (mut stream, _) = hook.accept().unwrap();
+        let mut request = String::new();
+        let mut buffer = [0; 4096];
+        while !request.ends_with('}') {
+            let read = stream.read(&mut buffer).unwrap();
+            request.push_str(&String::from_utf8_lossy(&buffer[..read]));
+        }
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+
+        request
+    });
+
+    let reminder = json!({ "url": url, "remind_at": "00:00" });
+    assert_eq!(server.put("/reminders/bob", &reminder).status, 200);
+    let https = json!({ "url": "https://example.com", "remind_at": "18:00" });
+    assert_eq!(server.put("/reminders/bob", &https).status, 400);
+    // only once the server allows it may a webhook be on its own host
+    let loopback = TestServer::new().put("/reminders/bob", &reminder);
+    assert_eq!(loopback.status, 400);
+    assert_eq!(loopback.json()["code"], "invalid_param");
+
+    // bob won yesterday but hasn't played today, alice has no webhook
+    for client in ["bob", "alice"] {
+        let game_id = server.create_game(client);
+        let answer = server.answer(&game_id);
+        server.get(&format!("/play/{game_id}/guess/{answer}"));
+    }
+    server
+        .connection()
+        .execute("UPDATE game SET created_at = datetime('now', '-1 day')", [])
+        .unwrap();
+
+    let sent = server.admin("POST", "/admin/reminders").json();
+    assert_eq!(
+        sent,
+        json!([{ "client": "bob", "streak": 1, "status": 204 }])
+    );
+    let request = received.join().unwrap();
+    assert!(request.starts_with("POST /hook HTTP/1.1"));
+    assert!(request.contains(r#""event":"streak_reminder""#));
+
+    // once a day at most
+    assert_eq!(server.admin("POST", "/admin/reminders").json(), json!([]));
+}
+
+#[test]
+fn guests_are_merged_into_the_client_they_settle_on() {
+    let server = TestServer::new();
+    let guest = server.post("/guests", &json!({})).json();
+    let client = guest["client"].as_str().unwrap();
+    let key = guest["key"].as_str().unwrap();
+    assert!(client.starts_with("guest-"));
+
+    let as_guest = format!("Bearer {key}");
+    let as_guest = [("Authorization", as_guest.as_str())];
+
+    // the guest wins twice, bob once before them
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+    assert_eq!(server.get(&format!("/create/{client}")).status, 401);
+    for _ in 0..2 {
+        let created = server.request("GET", &format!("/create/{client}"), &as_guest);
+        let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+        let answer = server.answer(&game_id);
+        // nobody else can play the guest's game
+        let guess = format!("/play/{game_id}/guess/{answer}");
+        assert_eq!(server.get(&guess).status, 401);
+        assert_eq!(server.get(&format!("/game/{game_id}/hint")).status, 401);
+        assert_eq!(server.request("GET", &guess, &as_guest).status, 200);
+    }
+    let befriend = json!({ "client": "alice" }).to_string().into_bytes();
+    let headers = [("Content-Type", "application/json"), as_guest[0]];
+    server.send("POST", &format!("/friends/{client}"), &headers, befriend);
+
+    let merge = |key: &str, into: &str, authorization: &str| {
+        let body = json!({ "into": into, "key": key }).to_string().into_bytes();
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("Authorization", authorization),
+        ];
+
+        server.send("POST", &format!("/guests/{client}/merge"), &headers, body)
+    };
+    assert_eq!(merge("nope", "bob", "").status, 401);
+    assert_eq!(merge(key, "guest-someone", "").status, 400);
+
+    // a registered client's stats can only be added to with their key
+    server.post("/clients", &json!({ "client": "carol" }));
+    assert_eq!(merge(key, "carol", "").status, 401);
+    assert_eq!(merge(key, "carol", "Bearer nope").status, 401);
+
+    let merged = merge(key, "bob", "").json();
+    assert_eq!(merged["games"], 2);
+    assert_eq!(merged["stats"]["num_won"], 3);
+    assert_eq!(merged["stats"]["streak"], 3);
+    let friends = server.get("/friends/bob").json();
+    assert_eq!(friends[0]["client"], "alice");
+    assert_eq!(server.get(&format!("/friends/{client}")).json(), json!([]));
+
+    // the guest is gone, so can't be merged again
+    assert_eq!(merge(key, "bob", "").status, 401);
+}
+
+#[test]
+fn inboxes_keep_messages_until_they_are_acknowledged() {
+    let server = TestServer::new();
+    let profile = json!({ "display_name": "Bobby" });
+    assert_eq!(server.put("/profiles/bob", &profile).status, 200);
+    server.post("/friends/bob", &json!({ "client": "alice" }));
+    // befriending someone twice doesn't tell them twice
+    server.post("/friends/bob", &json!({ "client": "alice" }));
+
+    let group = server
+        .post("/groups", &json!({ "name": "Owls", "owner": "teacher" }))
+        .json();
+    let authorization = format!("Bearer {}", group["key"].as_str().unwrap());
+    let url = format!(
+        "/groups/{}/members/alice",
+        group["group_id"].as_str().unwrap()
+    );
+    server.request("PUT", &url, &[("Authorization", &authorization)]);
+
+    let inbox = server.get("/inbox/alice").json();
+    assert_eq!(inbox.as_array().unwrap().len(), 2);
+    assert_eq!(inbox[0]["type"], "befriended");
+    assert_eq!(inbox[0]["client"], "bob");
+    assert_eq!(inbox[0]["player"], "Bobby");
+    assert_eq!(inbox[1]["type"], "added_to_group");
+    assert_eq!(inbox[1]["name"], "Owls");
+    assert_eq!(server.get("/inbox/bob").json(), json!([]));
+
+    let left = server
+        .post(
+            "/inbox/alice/ack",
+            &json!({ "up_to": inbox[0]["message_id"] }),
+        )
+        .json();
+    assert_eq!(left, json!([inbox[1]]));
+    assert_eq!(server.post("/inbox/alice/ack", &json!({})).status, 400);
+}
+
+#[test]
+fn groups_share_a_daily_word() {
+    let server = TestServer::new();
+    let group = server
+        .post(
+            "/groups",
+            &json!({ "name": "Class 4B", "owner": "teacher" }),
+        )
+        .json();
+    let group_id = group["group_id"].as_str().unwrap();
+    let authorization = format!("Bearer {}", group["key"].as_str().unwrap());
+    let owner = [("Authorization", authorization.as_str())];
+
+    for client in ["alice", "bob"] {
+        let url = format!("/groups/{group_id}/members/{client}");
+        assert_eq!(server.request("PUT", &url, &owner).status, 200);
+    }
+    let roster = server.request("GET", &format!("/groups/{group_id}"), &owner);
+    assert_eq!(roster.json()["members"].as_array().unwrap().len(), 2);
+    assert_eq!(server.get(&format!("/groups/{group_id}")).status, 401);
+
+    let join = |client: &str| server.create_game(&format!("{client}?group={group_id}"));
+    let alice = join("alice");
+    let bob = join("bob");
+    assert_eq!(server.answer(&alice), server.answer(&bob));
+    assert_eq!(join("alice"), alice);
+
+    let not_a_member = server.get(&format!("/create/carol?group={group_id}"));
+    assert_eq!(not_a_member.status, 400);
+    let multi = server.get(&format!("/create/alice?group={group_id}&mode=multi"));
+    assert_eq!(multi.status, 400);
+
+    let answer = server.answer(&alice);
+    server.get(&format!("/play/{alice}/guess/{answer}"));
+
+    let url = format!("/groups/{group_id}/dashboard");
+    let dashboard = server.request("GET", &url, &owner).json();
+    assert_eq!(dashboard["num_members"], 2);
+    assert_eq!(dashboard["num_played"], 2);
+    assert_eq!(dashboard["num_won"], 1);
+    assert_eq!(dashboard["members"][0]["state"], "won");
+    assert_eq!(dashboard["members"][1]["goes"], 0);
+    assert!(!json_shows(&dashboard, &answer));
+
+    let long_ago = server.request("GET", &format!("{url}?day=2000-01-01"), &owner);
+    assert_eq!(long_ago.json()["num_played"], 0);
+
+    let url = format!("/groups/{group_id}/members/bob");
+    let roster = server.request("DELETE", &url, &owner).json();
+    assert_eq!(
+        roster["members"],
+        json!([{ "client": "alice", "player": "alice" }])
+    );
+}
+
+#[test]
+fn clubs_total_up_their_members_games() {
+    let server = TestServer::new();
+    for (club, name) in [
+        ("owls", "Night Owls"),
+        ("larks", "Early Larks"),
+        ("wrens", "Wrens"),
+    ] {
+        let created = server.post("/clubs", &json!({ "club": club, "name": name }));
+        assert_eq!(created.status, 200, "{}", created.body);
+    }
+    let taken = server.post("/clubs", &json!({ "club": "owls", "name": "Owls" }));
+    assert_eq!(taken.status, 400);
+    let invalid = server.post("/clubs", &json!({ "club": "No Spaces", "name": "x" }));
+    assert_eq!(invalid.status, 400);
+    assert_eq!(server.get("/clubs/hawks").status, 404);
+
+    let join = |club: &str, client: &str| {
+        server.request("PUT", &format!("/clubs/{club}/members/{client}"), &[])
+    };
+    join("owls", "alice");
+    join("owls", "bob");
+    // joining another club leaves the first
+    let larks = join("larks", "bob").json();
+    assert_eq!(
+        larks["members"],
+        json!([{ "client": "bob", "player": "bob" }])
+    );
+    join("larks", "carol");
+
+    let alice = server.create_game("alice");
+    let answer = server.answer(&alice);
+    server.get(&format!("/play/{alice}/guess/{answer}"));
+
+    let bob = server.create_game("bob");
+    let answer = server.answer(&bob);
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+    server.get(&format!("/play/{bob}/guess/{wrong}"));
+    server.get(&format!("/play/{bob}/guess/{answer}"));
+
+    let owls = server.get("/clubs/owls").json();
+    assert_eq!(owls["num_members"], 1);
+    assert_eq!(owls["num_solved"], 1);
+    assert_eq!(owls["avg_goes"], 1.0);
+
+    let standings = server.get("/clubs/standings").json();
+    let ranked = standings["standings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| (x["rank"].as_u64().unwrap(), x["club"].as_str().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(ranked, [(1, "owls"), (2, "larks"), (3, "wrens")]);
+    assert_eq!(standings["standings"][1]["avg_goes"], 2.0);
+    assert_eq!(standings["standings"][2]["win_rate"], Value::Null);
+
+    let left = server.request("DELETE", "/clubs/larks/members/carol", &[]);
+    assert_eq!(left.json()["num_members"], 1);
+    assert_eq!(server.get("/clubs").json().as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn solves_with_a_hint_rank_below_those_without() {
+    let server = TestServer::new();
+    server.post("/friends/bob", &json!({ "client": "alice" }));
+    for (club, client) in [("owls", "alice"), ("larks", "bob")] {
+        server.post("/clubs", &json!({ "club": club, "name": club }));
+        server.request("PUT", &format!("/clubs/{club}/members/{client}"), &[]);
+    }
+
+    // alice solves twice with a hint, bob once without
+    for (client, hinted) in [("alice", true), ("alice", true), ("bob", false)] {
+        let game_id = server.create_game(client);
+        let answer = server.answer(&game_id);
+        if hinted {
+            server.get(&format!("/game/{game_id}/hint"));
+        }
+        server.get(&format!("/play/{game_id}/guess/{answer}"));
+    }
+
+    let leaderboard = server.get("/friends/bob/leaderboard").json();
+    assert_eq!(leaderboard[0]["client"], "bob");
+    assert_eq!(
+        (
+            &leaderboard[1]["num_solved"],
+            &leaderboard[1]["num_assisted_solved"]
+        ),
+        (&json!(2), &json!(2))
+    );
+
+    let standings = server.get("/clubs/standings").json()["standings"].clone();
+    assert_eq!(standings[0]["club"], "larks");
+    assert_eq!(
+        (&standings[1]["num_won"], &standings[1]["num_assisted"]),
+        (&json!(2), &json!(2))
+    );
+    assert_eq!(standings[1]["win_rate"], 0.0);
+    assert_eq!(standings[1]["avg_goes"], Value::Null);
+}
+
+#[test]
+fn share_links_show_what_they_were_made_to() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    let shares = format!("/game/{game_id}/shares");
+    assert_eq!(server.post(&shares, &json!({})).status, 422);
+
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+    server.get(&format!("/play/{game_id}/guess/{wrong}"));
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+
+    let grid_only = server.post(&shares, &json!({})).json();
+    let with_guesses = server
+        .post(&shares, &json!({ "show_guesses": true }))
+        .json();
+    assert_eq!(grid_only["show_guesses"], false);
+
+    let url = |link: &Value| format!("/shared/{}", link["token"].as_str().unwrap());
+    let shared = server
+        .get(&format!("{}?format=json", url(&grid_only)))
+        .json();
+    assert_eq!(shared["goes"], 2);
+    assert_eq!(shared["grid"][1], "🟩🟩🟩🟩🟩");
+    assert_eq!(shared.get("guesses"), None);
+    assert!(!json_shows(&shared, &answer));
+    assert!(!page_shows(&server.get(&url(&grid_only)).body, &answer));
+
+    let shared = server
+        .get(&format!("{}?format=json", url(&with_guesses)))
+        .json();
+    assert_eq!(shared["guesses"], json!([wrong, answer]));
+    assert!(page_shows(&server.get(&url(&with_guesses)).body, &answer));
+
+    let revoke = format!("{shares}/{}", grid_only["token"].as_str().unwrap());
+    assert_eq!(
+        server.request("DELETE", &revoke, &[]).json(),
+        json!([with_guesses])
+    );
+    assert_eq!(server.get(&url(&grid_only)).status, 404);
+    assert_eq!(server.request("DELETE", &revoke, &[]).status, 404);
+}
+
+#[test]
+fn replays_show_every_step_in_time() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    let at = |time: &'static str| [("X-Mock-Time", time)];
+    let created = server.request(
+        "GET",
+        "/create/bob",
+        &[("X-Mock-Time", "1000"), ("X-Mock-Answer", "crane")],
+    );
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+    let replay = format!("/game/{game_id}/replay");
+
+    server.request("GET", &format!("/play/{game_id}/guess/slate"), &at("1030"));
+    server.request("GET", &format!("/play/{game_id}/guess/xxxxx"), &at("1040"));
+    assert_eq!(
+        server
+            .request("GET", &format!("{replay}?share=true"), &at("1050"))
+            .status,
+        422
+    );
+    let unfinished = server.request("GET", &replay, &at("1050")).json();
+    assert_eq!(unfinished["answers"], Value::Null);
+    server.request("GET", &format!("/play/{game_id}/guess/crane"), &at("1100"));
+
+    let replay = server
+        .request("GET", &format!("{replay}?share=true"), &at("1200"))
+        .json();
+    assert_eq!(replay["state"], "won");
+    assert_eq!(replay["answers"], json!(["crane"]));
+    assert_eq!(replay["duration"], 100);
+    let steps = replay["steps"].as_array().unwrap();
+    let timed = steps
+        .iter()
+        .map(|x| {
+            (
+                x["kind"].as_str().unwrap(),
+                x["elapsed"].as_u64().unwrap(),
+                x["took"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        timed,
+        [
+            ("guessed", 30, 30),
+            ("rejected", 40, 10),
+            ("guessed", 100, 60),
+            ("solved", 100, 0)
+        ]
+    );
+    assert_eq!(steps[0]["guess"], "slate");
+    assert_eq!(steps[2]["evaluation"][0]["match_type"], "Perfect");
+
+    let shared = format!("/replay/{}", replay["share"]["token"].as_str().unwrap());
+    assert_eq!(replay["share"]["expires_at"], 1200 + 24 * 60 * 60);
+    let watched = server.request("GET", &shared, &at("1300")).json();
+    assert_eq!(watched["steps"], replay["steps"]);
+    assert_eq!(watched.get("share"), None);
+    assert_eq!(server.request("GET", &shared, &at("100000")).status, 404);
+}
+
+#[test]
+fn the_operator_can_take_back_a_guess() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    let wrong = if answer == "crane" { "slate" } else { "crane" };
+    let undo = format!("/admin/game/{game_id}/undo");
+    assert_eq!(server.admin("POST", &undo).status, 422);
+
+    server.get(&format!("/play/{game_id}/guess/{wrong}"));
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+    assert_eq!(server.post(&undo, &json!({})).status, 401);
+
+    let undone = server.admin("POST", &undo).json();
+    assert_eq!(undone["state"], "in_progress");
+    assert_eq!(undone["goes"], 1);
+    let kinds = undone["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| x["event"]["type"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, ["created", "guessed"]);
+
+    let played = server
+        .get(&format!("/play/{game_id}/guess/{answer}"))
+        .json();
+    assert_eq!(played["goes"], 2);
+    assert_eq!(played["state"], "won");
+    assert_eq!(played["guesses"].as_array().unwrap().len(), 2);
+
+    let audited = server
+        .connection()
+        .query_row(
+            "SELECT COUNT(*) FROM audit_log WHERE action = 'undo_guess' AND subject = ?1",
+            [&game_id],
+            |row| row.get::<_, usize>(0),
+        )
+        .unwrap();
+    assert_eq!(audited, 1);
+}
+
+#[test]
+fn exports_games_for_the_operator() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    server.get(&format!("/play/{game_id}/guess/xxxxx"));
+    server.create_game("alice");
+
+    assert_eq!(server.get("/export").status, 401);
+
+    let export = server.admin("GET", "/export");
+    assert_eq!(export.status, 200);
+    let records = export
+        .body
+        .lines()
+        .map(|x| serde_json::from_str::<Value>(x).unwrap())
+        .collect::<Vec<_>>();
+    let count = |kind: &str| records.iter().filter(|x| x["type"] == kind).count();
+    assert_eq!(
+        (count("game"), count("rejected_guess"), count("client")),
+        (2, 1, 2)
+    );
+    assert!(records
+        .iter()
+        .any(|x| x["word"] == server.answer(&game_id).as_str()));
+
+    let bob = server.admin("GET", "/export?format=json&client=bob").json();
+    assert_eq!(bob["games"].as_array().unwrap().len(), 1);
+    assert_eq!(bob["clients"][0]["client"], "bob");
+
+    assert_eq!(server.admin("GET", "/export?since=yesterday").status, 400);
+    let future = server
+        .admin("GET", "/export?format=json&since=2999-01-01")
+        .json();
+    assert_eq!(future["games"], json!([]));
+
+    let answer = server.answer(&game_id);
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+    let research = server.admin("GET", "/export?format=research").body;
+    let games = research
+        .lines()
+        .map(|x| serde_json::from_str::<Value>(x).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(games.len(), 1);
+    assert_eq!(games[0]["player"], "p1");
+    assert_eq!(games[0]["guesses"], json!([answer]));
+    assert!(!research.contains("bob"));
+}
+
+#[test]
+fn logs_every_guess_for_the_operator() {
+    let server = TestServer::new();
+    let game_id = server.create_game("bob");
+    let answer = server.answer(&game_id);
+    server.get(&format!("/play/{game_id}/guess/xxxxx"));
+    server.get(&format!("/play/{game_id}/guess/{answer}"));
+    server.create_game("alice");
+
+    assert_eq!(server.get("/admin/guess-log").status, 401);
+
+    let log = |query: &str| {
+        let response = server.admin("GET", &format!("/admin/guess-log{query}"));
+        assert_eq!(response.status, 200, "{}", response.body);
+
+        response
+            .body
+            .lines()
+            .map(|x| serde_json::from_str::<Value>(x).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let entries = log(&format!("?game_id={game_id}"));
+    assert_eq!(
+        entries
+            .iter()
+            .map(|x| (x["guess"].as_str().unwrap(), x["outcome"].as_str().unwrap()))
+            .collect::<Vec<_>>(),
+        [("xxxxx", "invalid_word"), (answer.as_str(), "accepted")]
+    );
+    assert_eq!(entries[1]["client"], "bob");
+    assert_eq!(log("?client=alice"), Vec::<Value>::new());
+    assert_eq!(log("?since=2999-01-01"), Vec::<Value>::new());
+    assert_eq!(
+        server.admin("GET", "/admin/guess-log?game_id=x").status,
+        400
+    );
+
+    // erasing the client is the only thing that takes their guesses out
+    let erasure = server.admin("DELETE", "/admin/client/bob?mode=anonymize");
+    assert_eq!(erasure.json()["logged_guesses"], 2);
+    assert_eq!(log(""), Vec::<Value>::new());
+}
+
+#[test]
+fn imports_another_instances_export() {
+    let source = TestServer::new();
+    let game_id = source.create_game("bob");
+    let answer = source.answer(&game_id);
+    source.get(&format!("/play/{game_id}/guess/{answer}"));
+    let ndjson = source.admin("GET", "/export").body;
+    let json = source.admin("GET", "/export?format=json").body;
+
+    let target = TestServer::new();
+    let authorization = format!("Bearer {}", wordle_api::testing::ADMIN_TOKEN);
+    let import = |query: &str, body: &str| {
+        target.send(
+            "POST",
+            &format!("/import{query}"),
+            &[("Authorization", authorization.as_str())],
+            body.as_bytes().to_vec(),
+        )
+    };
+    assert_eq!(
+        target
+            .send("POST", "/import", &[], ndjson.clone().into_bytes())
+            .status,
+        401
+    );
+
+    let summary = import("", &ndjson);
+    assert_eq!(summary.status, 200, "{}", summary.body);
+    assert_eq!(summary.json()["imported"], 1);
+    assert_eq!(target.get("/stats").json(), source.get("/stats").json());
+    assert_eq!(target.answer(&game_id), answer);
+
+    // the second import only takes effect if it's told what to do with the duplicate
+    assert_eq!(import("?format=json", &json).status, 409);
+    let skipped = import("?format=json&on_conflict=skip", &json).json();
+    assert_eq!(skipped["imported"], 0);
+    assert_eq!(skipped["skipped"], 1);
+    assert_eq!(import("", "not json").status, 400);
+}
+
+#[test]
+fn archived_games_still_count() {
+    let server = TestServer::with_config(|config| config.archive_after_days = Some(30));
+    let old = server.create_game("bob");
+    let answer = server.answer(&old);
+    server.get(&format!("/play/{old}/guess/{answer}"));
+    let unfinished = server.create_game("bob");
+    server
+        .connection()
+        .execute(
+            "UPDATE game SET created_at = datetime('now', '-40 days'),
+                updated_at = datetime('now', '-40 days')",
+            [],
+        )
+        .unwrap();
+    let stats = server.get("/stats").json();
+
+    let report = server.admin("POST", "/admin/maintenance").json();
+    assert_eq!(report["archived"], 1);
+    let hot = server
+        .connection()
+        .query_row("SELECT group_concat(game_id) FROM game", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .unwrap();
+    assert_eq!(hot, unfinished);
+
+    assert_eq!(server.get("/stats").json(), stats);
+    assert_eq!(
+        server.get("/profile/bob?format=json").json()["stats"]["num_won"],
+        1
+    );
+    assert_eq!(
+        server.get(&format!("/play/{old}/guess/{answer}")).status,
+        409
+    );
+    let export = server.admin("GET", "/export?format=json").json();
+    assert_eq!(export["games"][0]["game_id"], old.as_str());
+    assert_eq!(
+        server.admin("POST", "/admin/maintenance").json()["archived"],
+        0
+    );
+}
+
+#[test]
+fn games_are_kept_as_long_as_their_client_wants() {
+    let server = TestServer::new();
+    let finish = |client: &str| {
+        let game_id = server.create_game(client);
+        let answer = server.answer(&game_id);
+        server.get(&format!("/play/{game_id}/guess/xxxxx"));
+        server.get(&format!("/play/{game_id}/guess/{answer}"));
+
+        game_id
+    };
+    let retention = |client: &str, retention: Value| {
+        let profile = json!({ "privacy": { "retention": retention } });
+        let response = server.put(&format!("/profiles/{client}"), &profile);
+        assert_eq!(response.status, 200, "{}", response.body);
+    };
+
+    let old = finish("bob");
+    let recent = finish("bob");
+    let reduced = finish("alice");
+    let kept = finish("carol");
+    server
+        .connection()
+        .execute(
+            "UPDATE game SET updated_at = datetime('now', '-3 days') WHERE game_id IN (?1, ?2)",
+            (&old, &kept),
+        )
+        .unwrap();
+    retention("bob", json!({ "days": 2 }));
+    retention("alice", json!("aggregates_only"));
+    let profile = json!({ "privacy": { "retention": { "days": 0 } } });
+    assert_eq!(server.put("/profiles/carol", &profile).status, 400);
+
+    let report = server.admin("POST", "/admin/maintenance").json();
+    assert_eq!(
+        (&report["expired"], &report["reduced"]),
+        (&json!(1), &json!(1))
+    );
+
+    let export = server.admin("GET", "/export?format=json").json();
+    let games = export["games"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| x["game_id"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(games, [recent.as_str(), reduced.as_str(), kept.as_str()]);
+    let log = server.admin("GET", "/admin/guess-log").body;
+    assert!(!log.contains(&old) && !log.contains(&reduced) && log.contains(&kept));
+
+    // alice's game still counts, but nothing's left of how she played it
+    let alice = server.get("/profile/alice?format=json").json();
+    assert_eq!(alice["stats"]["num_won"], 1);
+    assert_eq!(alice["recent_games"][0]["grid"], json!([]));
+    let rebuild = events::rebuild(&mut server.connection(), true).unwrap();
+    assert!(rebuild.rebuilt.is_empty() && rebuild.failed.is_empty());
+
+    assert_eq!(
+        server.admin("POST", "/admin/maintenance").json()["reduced"],
+        0
+    );
+}
+
+#[test]
+fn mock_servers_pretend_whatever_they_are_asked() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    let authorization = format!("Bearer {}", wordle_api::testing::ADMIN_TOKEN);
+    let at = |time: &str| ("X-Mock-Time", time.to_string());
+    let send = |method: &str, url: &str, headers: &[(&str, String)]| {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect::<Vec<_>>();
+
+        server.request(method, url, &headers)
+    };
+
+    let created = send(
+        "GET",
+        "/create/bob",
+        &[
+            at("2026-10-13T09:00:00Z"),
+            ("X-Mock-Answer", String::from("crane")),
+        ],
+    );
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+    assert_eq!(server.answer(&game_id), "crane");
+    let won = send(
+        "GET",
+        &format!("/play/{game_id}/guess/crane"),
+        &[at("2026-10-13T09:05:00Z")],
+    );
+    assert_eq!(won.json()["state"], "won");
+    let unknown = [("X-Mock-Answer", String::from("zzzzz"))];
+    assert_eq!(send("GET", "/create/bob", &unknown).status, 400);
+    assert_eq!(send("GET", "/create/bob", &[at("yesterday")]).status, 400);
+
+    let export = server.admin("GET", "/export?format=json").json();
+    assert_eq!(export["games"][0]["created_at"], "2026-10-13 09:00:00");
+
+    // the streak running up to yesterday is due a reminder, which is kept rather than sent
+    let reminder = json!({ "url": "http://example.com/hook", "remind_at": "18:00" });
+    assert_eq!(server.put("/reminders/bob", &reminder).status, 200);
+    let sent = send(
+        "POST",
+        "/admin/reminders",
+        &[at("2026-10-14T18:30:00Z"), ("Authorization", authorization)],
+    );
+    assert_eq!(sent.json()[0]["status"], 200);
+    let webhooks = server.get("/mock/webhooks").json();
+    assert_eq!(webhooks[0]["url"], "http://example.com/hook");
+    assert_eq!(webhooks[0]["body"]["day"], "2026-10-14");
+    assert_eq!(webhooks[0]["body"]["streak"], 1);
+    assert_eq!(
+        server.request("DELETE", "/mock/webhooks", &[]).json(),
+        json!([])
+    );
+
+    let real = TestServer::new();
+    assert_eq!(real.get("/mock/webhooks").status, 404);
+    let created = real.request("GET", "/create/bob", &[("X-Mock-Answer", "zzzzz")]);
+    assert_eq!(created.status, 200);
+}
+
+#[test]
+fn tenants_never_see_each_others_games() {
+    let server = TestServer::with_config(|config| {
+        config.tenants = vec![String::from("chess-club"), String::from("school")]
+    });
+    let game_id = server.get("/t/chess-club/create/bob").json()["game_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    server.get("/t/school/create/alice");
+    server.get("/t/school/create/alice");
+
+    let games = |prefix: &str| server.get(&format!("{prefix}/stats")).json();
+    assert_eq!(games(""), json!([]));
+    assert_eq!(games("/t/chess-club")[0]["client"], "bob");
+    assert_eq!(games("/t/school")[0]["num_games"], 2);
+    assert_eq!(games("/t/school").as_array().unwrap().len(), 1);
+
+    // a game is only played under the tenant it was started in
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/crane")).status,
+        404
+    );
+    assert_eq!(
+        server
+            .get(&format!("/t/school/play/{game_id}/guess/crane"))
+            .status,
+        404
+    );
+    assert_eq!(
+        server
+            .get(&format!("/t/chess-club/play/{game_id}/guess/crane"))
+            .status,
+        200
+    );
+    assert_eq!(server.get("/t/unknown/stats").status, 404);
+}
+
+#[test]
+fn tenants_play_their_own_words_over_the_shared_ones() {
+    let dir = std::env::temp_dir().join(format!("wordle-tenant-words-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("tenants").join("school")).unwrap();
+    std::fs::write(dir.join("answers-6.txt"), "planet\n").unwrap();
+    std::fs::write(dir.join("tenants/school/answers-6.txt"), "garden\n").unwrap();
+    let server = TestServer::with_config(|config| {
+        config.word_lists = WordLists::load(&dir).unwrap();
+        config.word_list_dir = Some(dir.to_string_lossy().into_owned());
+        config.tenants = vec![String::from("chess-club"), String::from("school")];
+    });
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // each list has but the one word, so guessing it solves the game
+    let solves = |prefix: &str, word: &str| {
+        let game = server
+            .get(&format!("{prefix}/create/alice?length=6"))
+            .json();
+        let game_id = game["game_id"].as_str().unwrap();
+
+        server
+            .get(&format!("{prefix}/play/{game_id}/guess/{word}"))
+            .json()["solved"]
+            == true
+    };
+    assert!(solves("", "planet"));
+    assert!(solves("/t/chess-club", "planet"));
+    assert!(solves("/t/school", "garden"));
+    assert!(!solves("/t/school", "planet"));
+}
+
+#[test]
+fn scheduled_answers_are_every_groups_word() {
+    let server = TestServer::with_config(|config| *config = config.clone().mock());
+    let tomorrow = wordle_api::game::date(wordle_api::game::today() + 1);
+    let today = wordle_api::game::date(wordle_api::game::today());
+    let authorization = format!("Bearer {}", wordle_api::testing::ADMIN_TOKEN);
+    let admin = [
+        ("Authorization", authorization.as_str()),
+        ("Content-Type", "application/json"),
+    ];
+    let pin = |date: &str, body: Value| {
+        server.send(
+            "PUT",
+            &format!("/admin/schedule/{date}"),
+            &admin,
+            body.to_string().into_bytes(),
+        )
+    };
+
+    let pinned = pin(&tomorrow, json!({ "word": "Crane", "note": "launch day" }));
+    assert_eq!(pinned.status, 200, "{}", pinned.body);
+    assert_eq!(pinned.json()["word"], "crane");
+    assert_eq!(pin(&today, json!({ "word": "crane" })).status, 400);
+    assert_eq!(pin(&tomorrow, json!({ "word": "zzzzz" })).status, 400);
+    assert_eq!(
+        server
+            .send(
+                "PUT",
+                &format!("/admin/schedule/{tomorrow}"),
+                &[],
+                Vec::new()
+            )
+            .status,
+        401
+    );
+
+    let generated = server
+        .admin("POST", "/admin/schedule/generate?days=3")
+        .json();
+    assert_eq!(generated[0]["word"], "crane");
+    assert!(generated[1]["word"].is_string() && generated[1]["pinned"] == false);
+    let listed = server.admin("GET", &format!("/admin/schedule?from={tomorrow}&days=3"));
+    assert_eq!(listed.json(), generated);
+
+    let group = server
+        .post("/groups", &json!({ "name": "Launch", "owner": "teacher" }))
+        .json();
+    let group_id = group["group_id"].as_str().unwrap();
+    let key = format!("Bearer {}", group["key"].as_str().unwrap());
+    let url = format!("/groups/{group_id}/members/bob");
+    server.request("PUT", &url, &[("Authorization", key.as_str())]);
+
+    let time = format!("{tomorrow}T08:00:00Z");
+    let created = server.request(
+        "GET",
+        &format!("/create/bob?group={group_id}"),
+        &[("X-Mock-Time", time.as_str())],
+    );
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+    assert_eq!(server.answer(&game_id), "crane");
+
+    let unpinned = server.admin("DELETE", &format!("/admin/schedule/{tomorrow}"));
+    assert_eq!(unpinned.json()["word"], Value::Null);
+}
+
+#[test]
+fn group_games_roll_over_when_the_operator_says() {
+    let server = TestServer::with_config(|config| {
+        *config = config.clone().mock();
+        config.rollover = Rollover {
+            utc_offset: 0,
+            hour: 6,
+        };
+    });
+    let group = server
+        .post(
+            "/groups",
+            &json!({ "name": "Early birds", "owner": "teacher" }),
+        )
+        .json();
+    let group_id = group["group_id"].as_str().unwrap();
+    let key = format!("Bearer {}", group["key"].as_str().unwrap());
+    let url = format!("/groups/{group_id}/members/bob");
+    server.request("PUT", &url, &[("Authorization", key.as_str())]);
+
+    let create = |time: &str| {
+        server
+            .request(
+                "GET",
+                &format!("/create/bob?group={group_id}"),
+                &[("X-Mock-Time", time)],
+            )
+            .json()
+    };
+    let late = create("2026-10-14T05:00:00Z");
+    assert_eq!(late["puzzle_date"], "2026-10-13");
+    let early = create("2026-10-14T06:00:00Z");
+    assert_eq!(early["puzzle_date"], "2026-10-14");
+    assert_ne!(early["game_id"], late["game_id"]);
+    assert_eq!(server.get("/create/alice").json().get("puzzle_date"), None);
+}
+
+#[test]
+fn requests_keep_the_id_they_were_sent_with() {
+    let server = TestServer::new();
+
+    let sent = server.request("GET", "/stats", &[("X-Request-Id", "report-42")]);
+    assert_eq!(sent.header("X-Request-Id"), Some("report-42"));
+
+    let error = server.request("GET", "/nowhere", &[("X-Request-Id", "report-43")]);
+    assert_eq!(error.header("X-Request-Id"), Some("report-43"));
+    assert_eq!(error.json()["request_id"], "report-43");
+
+    let generated = server.get("/stats");
+    let id = generated.header("X-Request-Id").unwrap();
+    assert!(uuid::Uuid::parse_str(id).is_ok());
+    let unprintable = server.request("GET", "/stats", &[("X-Request-Id", "a b")]);
+    assert_ne!(unprintable.header("X-Request-Id"), Some("a b"));
+}
+
+#[test]
+fn browsers_may_call_from_the_origins_allowed() {
+    let server = TestServer::with_config(|c| {
+        c.cors = Some(Cors::new("https://wordle.example"));
+    });
+    let allowed = [
+        ("Origin", "https://wordle.example"),
+        ("Access-Control-Request-Method", "POST"),
+        ("Access-Control-Request-Headers", "content-type"),
+    ];
+
+    let preflight = server.request("OPTIONS", "/games", &allowed);
+    assert_eq!(preflight.status, 204);
+    assert_eq!(
+        preflight.header("Access-Control-Allow-Origin"),
+        Some("https://wordle.example")
+    );
+    assert!(preflight
+        .header("Access-Control-Allow-Methods")
+        .unwrap()
+        .contains("POST"));
+    assert!(preflight
+        .header("Access-Control-Allow-Headers")
+        .unwrap()
+        .contains("Content-Type"));
+
+    let elsewhere = [("Origin", "https://elsewhere.example"), allowed[1]];
+    let refused = server.request("OPTIONS", "/games", &elsewhere);
+    assert_eq!(refused.header("Access-Control-Allow-Origin"), None);
+    let unlisted = [allowed[0], ("Access-Control-Request-Method", "PATCH")];
+    let refused = server.request("OPTIONS", "/games", &unlisted);
+    assert_eq!(refused.header("Access-Control-Allow-Origin"), None);
+
+    let stats = server.request("GET", "/stats", &allowed[..1]);
+    assert_eq!(stats.status, 200);
+    assert_eq!(
+        stats.header("Access-Control-Allow-Origin"),
+        Some("https://wordle.example")
+    );
+    let exposed = stats.header("Access-Control-Expose-Headers").unwrap();
+    for header in [
+        "X-Request-Id",
+        "X-RateLimit-Limit",
+        "X-RateLimit-Remaining",
+        "X-RateLimit-Reset",
+    ] {
+        assert!(exposed.contains(header), "{header}");
+    }
+    let missing = server.request("GET", "/nowhere", &allowed[..1]);
+    assert_eq!(missing.status, 404);
+    assert!(missing.header("Access-Control-Allow-Origin").is_some());
+
+    assert_eq!(
+        TestServer::new()
+            .request("OPTIONS", "/games", &allowed)
+            .status,
+        404
+    );
+}
+
+#[test]
+fn addresses_and_clients_are_limited_in_how_often_they_play() {
+    let server = TestServer::with_config(|c| c.rate_limit = Some(3));
+
+    let first = server.get("/stats");
+    assert_eq!(first.header("X-RateLimit-Limit"), Some("3"));
+    assert_eq!(first.header("X-RateLimit-Remaining"), Some("2"));
+    server.get("/stats");
+    server.get("/stats");
+    let refused = server.get("/stats");
+    assert_eq!(refused.status, 429);
+    assert_eq!(refused.json()["code"], "rate_limited");
+    assert_eq!(refused.header("X-RateLimit-Remaining"), Some("0"));
+    assert_eq!(refused.header("Retry-After"), Some("20"));
+    assert_eq!(refused.header("X-RateLimit-Reset"), Some("60"));
+
+    let server = TestServer::with_config(|c| c.client_rate_limit = Some(2));
+    let game_id = server.create_game("bot");
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/crane")).status,
+        200
+    );
+    let guessing = server.get(&format!("/play/{game_id}/guess/slate"));
+    assert_eq!(guessing.status, 429);
+    assert!(guessing.header("Retry-After").is_some());
+    assert_eq!(server.get("/create/bot").status, 429);
+    assert_eq!(server.get("/create/alice").status, 200);
+}
+
+#[test]
+fn registered_clients_are_only_played_as_with_their_key() {
+    let server = TestServer::new();
+
+    let registration = server.post("/clients", &json!({ "client": "carol" }));
+    assert_eq!(registration.status, 200);
+    let key = registration.json()["key"].as_str().unwrap().to_string();
+    let again = server.post("/clients", &json!({ "client": "carol" }));
+    assert_eq!(again.status, 409);
+    assert_eq!(again.json()["code"], "client_registered");
+
+    let bearer = format!("Bearer {key}");
+    let keyed = [("Authorization", bearer.as_str())];
+    let wrong = [("Authorization", "Bearer nope")];
+    assert_eq!(server.get("/create/carol").status, 401);
+    assert_eq!(server.request("GET", "/create/carol", &wrong).status, 401);
+    assert_eq!(server.request("GET", "/daily/carol", &[]).status, 401);
+
+    let created = server.request("GET", "/create/carol", &keyed);
+    assert_eq!(created.status, 200);
+    let game_id = created.json()["game_id"].as_str().unwrap().to_string();
+    let play = format!("/play/{game_id}/guess/crane");
+    assert_eq!(server.get(&play).status, 401);
+    assert_eq!(server.request("GET", &play, &keyed).status, 200);
+    let resign = format!("/game/{game_id}/resign");
+    assert_eq!(server.request("POST", &resign, &[]).status, 401);
+    let shares = format!("/game/{game_id}/shares");
+    assert_eq!(server.post(&shares, &json!({})).status, 401);
+    assert_eq!(server.get(&shares).status, 401);
+    assert_eq!(server.request("GET", &shares, &keyed).status, 200);
+    let revoke = format!("{shares}/sometoken");
+    assert_eq!(server.request("DELETE", &revoke, &[]).status, 401);
+
+    for (method, path) in [
+        ("GET", "/reminders/carol"),
+        ("PUT", "/reminders/carol"),
+        ("DELETE", "/reminders/carol"),
+        ("POST", "/friends/carol"),
+        ("DELETE", "/friends/carol/dave"),
+        ("POST", "/friends/carol/invite"),
+        ("GET", "/inbox/carol"),
+        ("POST", "/inbox/carol/ack"),
+        ("PUT", "/clubs/chess/members/carol"),
+        ("DELETE", "/clubs/chess/members/carol"),
+    ] {
+        assert_eq!(server.request(method, path, &[]).status, 401, "{path}");
+    }
+    assert_eq!(
+        server
+            .request("POST", "/friends/carol/invite", &keyed)
+            .status,
+        200
+    );
+
+    for stats in [
+        "/badge/carol.svg",
+        "/achievements/carol",
+        "/profile/carol",
+        "/reminders/carol",
+        "/inbox/carol",
+    ] {
+        assert_eq!(server.get(stats).status, 401);
+        assert_eq!(server.request("GET", stats, &keyed).status, 200);
+    }
+
+    // clients that never registered are played as by anyone, as ever
+    assert_eq!(server.get("/create/dave").status, 200);
+    let guest = server.post("/guests", &json!({})).json();
+    let as_guest = json!({ "client": guest["client"] });
+    assert_eq!(server.post("/clients", &as_guest).status, 409);
+}
+
+#[test]
+fn clients_register_with_a_name_to_be_shown_by() {
+    let server = TestServer::with_config(|c| c.registered_clients_only = true);
+
+    let registering = json!({
+        "client": "erin",
+        "display_name": "Erin",
+        "metadata": { "bot": true }
+    });
+    let registration = server.post("/clients", &registering).json();
+    assert_eq!(registration["display_name"], "Erin");
+    let key = format!("Bearer {}", registration["key"].as_str().unwrap());
+
+    let registered = server.get("/clients/erin").json();
+    assert_eq!(registered["player"], "Erin");
+    assert_eq!(registered["metadata"], json!({ "bot": true }));
+    assert_eq!(
+        server.get("/clients/frank").json()["code"],
+        "client_not_found"
+    );
+
+    let refused = server.get("/create/frank");
+    assert_eq!(refused.status, 404);
+    assert_eq!(refused.json()["code"], "client_not_found");
+    let keyed = [("Authorization", key.as_str())];
+    assert_eq!(server.request("GET", "/create/erin", &keyed).status, 200);
+    let guest = server.post("/guests", &json!({})).json();
+    let playing_as_guest = format!("/create/{}", guest["client"].as_str().unwrap());
+    let guest_key = format!("Bearer {}", guest["key"].as_str().unwrap());
+    let as_guest = [("Authorization", guest_key.as_str())];
+    assert_eq!(
+        server.request("GET", &playing_as_guest, &as_guest).status,
+        200
+    );
+
+    let stats = server.get("/stats").json();
+    let erin = stats
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|x| x["client"] == "erin")
+        .unwrap();
+    assert_eq!(erin["player"], "Erin");
+
+    let unnamed = json!({ "client": "gina", "display_name": " " });
+    assert_eq!(server.post("/clients", &unnamed).status, 400);
+    let bloated = json!({ "client": "gina", "metadata": { "notes": "x".repeat(4096) } });
+    assert_eq!(server.post("/clients", &bloated).status, 400);
+    assert_eq!(server.get("/clients/gina").status, 404);
+}
+
+#[test]
+fn discord_users_only_start_games_once_registered_when_required() {
+    let server = TestServer::with_config(|config| {
+        config.discord_secret = Some(String::from("s3"));
+        config.registered_clients_only = true;
+    });
+    let new = |user: &str| {
+        let body = json!({
+            "type": 2,
+            "user": { "id": user },
+            "data": { "name": "new", "options": [] },
+        });
+        let headers = [
+            ("Content-Type", "application/json"),
+            ("Authorization", "Bearer s3"),
+        ];
+
+        server
+            .send(
+                "POST",
+                "/discord/interactions",
+                &headers,
+                body.to_string().into_bytes(),
+            )
+            .json()
+    };
+
+    assert_eq!(new("1234")["data"]["flags"], 64);
+    server.post("/clients", &json!({ "client": "discord-1234" }));
+    assert!(new("1234")["data"]["flags"].is_null());
+}
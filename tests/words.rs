@@ -0,0 +1,34 @@
+//! The bundled words are replaced for the whole process, so this runs apart from the other tests
+
+use wordle_api::config;
+use wordle_api::testing::TestServer;
+
+#[test]
+fn plays_with_the_operators_own_words() {
+    let dir = std::env::temp_dir().join(format!("wordle-own-words-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("answers.txt"), "ABCDE\ntoo long\n").unwrap();
+    std::fs::write(dir.join("guesses.txt"), "fghij\n").unwrap();
+    std::env::set_var("WORDLE_ANSWERS_FILE", dir.join("answers.txt"));
+    std::env::set_var("WORDLE_GUESSES_FILE", dir.join("guesses.txt"));
+    config::replace_words().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let server = TestServer::new();
+    let game_id = server.create_game("alice");
+    assert_eq!(server.answer(&game_id), "abcde");
+
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/crane")).status,
+        400
+    );
+    assert_eq!(
+        server.get(&format!("/play/{game_id}/guess/fghij")).status,
+        200
+    );
+    let solved = server.get(&format!("/play/{game_id}/guess/abcde")).json();
+    assert_eq!(solved["solved"], true);
+
+    // the words have been played with, so can't be replaced again
+    assert!(config::replace_words().is_err());
+}
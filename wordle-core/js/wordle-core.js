@@ -0,0 +1,51 @@
+// Loads wordle-core built with
+//
+//     cargo build -p wordle-core --release --target wasm32-unknown-unknown --no-default-features --features wasm
+//
+// and evaluates guesses in the browser exactly as the server does:
+//
+//     const { evaluate } = await load(fetch("wordle_core.wasm"));
+//     evaluate("crane", "caret"); // [{ index: 0, character: "c", match_type: "Perfect" }, ...]
+
+const MATCH_TYPES = ["None", "Partial", "Perfect"];
+const INVALID = 2 ** 32 - 1; // usize::MAX on wasm32
+
+export async function load(source) {
+  const { instance } = await WebAssembly.instantiateStreaming(source, {});
+  const wasm = instance.exports;
+  const encoder = new TextEncoder();
+
+  function write(text) {
+    const bytes = encoder.encode(text);
+    const ptr = wasm.alloc(bytes.length);
+    new Uint8Array(wasm.memory.buffer, ptr, bytes.length).set(bytes);
+
+    return [ptr, bytes.length];
+  }
+
+  function evaluate(guess, answer) {
+    const [guessPtr, guessLen] = write(guess);
+    const [answerPtr, answerLen] = write(answer);
+    const out = wasm.alloc(guessLen);
+
+    try {
+      const written = wasm.evaluate(guessPtr, guessLen, answerPtr, answerLen, out) >>> 0;
+      if (written === INVALID) {
+        throw new Error(`can't evaluate '${guess}' against '${answer}'`);
+      }
+
+      const codes = new Uint8Array(wasm.memory.buffer, out, written);
+      return Array.from(guess).map((character, index) => ({
+        index,
+        character,
+        match_type: MATCH_TYPES[codes[index]],
+      }));
+    } finally {
+      wasm.dealloc(guessPtr, guessLen);
+      wasm.dealloc(answerPtr, answerLen);
+      wasm.dealloc(out, guessLen);
+    }
+  }
+
+  return { evaluate };
+}
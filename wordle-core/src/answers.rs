@@ -1,4 +1,4 @@
-pub static FILE_CONTENT: &[&str] = &[
+pub static ANSWERS: &[&str] = &[
     "aback", "abase", "abate", "abbey", "abbot", "abhor", "abide", "abled", "abode", "abort",
     "about", "above", "abuse", "abyss", "acorn", "acrid", "actor", "acute", "adage", "adapt",
     "adept", "admin", "admit", "adobe", "adopt", "adore", "adorn", "adult", "affix", "afire",
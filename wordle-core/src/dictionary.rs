@@ -0,0 +1,145 @@
+use crate::{answers, words};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+/// The language of the bundled words, as a language tag
+pub const LANGUAGE: &str = "en";
+/// How many letters the bundled words have
+pub const LENGTH: usize = 5;
+/// How many letters words may have, given lists of them
+pub const LENGTHS: RangeInclusive<usize> = 4..=8;
+
+static GUESSES: OnceLock<HashSet<String>> = OnceLock::new();
+static ANSWERS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// The canonical form of a word, as stored in the dictionaries and compared against guesses
+pub fn normalize(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+/// Every word accepted as a guess, normalized once on first use
+pub fn guesses() -> &'static HashSet<String> {
+    GUESSES.get_or_init(|| words::GUESSES.iter().map(|x| normalize(x)).collect())
+}
+
+/// Every word that may be picked as the answer to a game, normalized once on first use
+pub fn answers() -> &'static [String] {
+    ANSWERS.get_or_init(|| answers::ANSWERS.iter().map(|x| normalize(x)).collect())
+}
+
+/// Whether the (already normalized) guess is in the dictionary
+pub fn is_valid_guess(guess: &str) -> bool {
+    guesses().contains(guess)
+}
+
+/// Replaces the bundled answers, guesses, or both, with others of one word a line, for as long as
+/// the process runs. Only words of `LENGTH` letters are kept, each answer being accepted as a
+/// guess too. The words can't be replaced once they've been used, nor replaced with no answers at
+/// all, so it's done once at startup, returning whether it was.
+pub fn replace(answers: Option<&str>, guesses: Option<&str>) -> bool {
+    let list = WordList::new(
+        LENGTH,
+        answers.map_or_else(|| answers::ANSWERS.to_vec(), |x| x.lines().collect()),
+        guesses.map_or_else(|| words::GUESSES.to_vec(), |x| x.lines().collect()),
+    );
+    if list.answers.is_empty() || ANSWERS.get().is_some() || GUESSES.get().is_some() {
+        return false;
+    }
+
+    ANSWERS.set(list.answers.into_owned()).is_ok() && GUESSES.set(list.guesses.into_owned()).is_ok()
+}
+
+/// The answers and accepted guesses for words of a single length
+#[derive(Clone, Debug)]
+pub struct WordList {
+    length: usize,
+    answers: Cow<'static, [String]>,
+    guesses: Cow<'static, HashSet<String>>,
+}
+
+impl WordList {
+    /// The bundled words
+    pub fn standard() -> WordList {
+        WordList {
+            length: LENGTH,
+            answers: Cow::Borrowed(answers()),
+            guesses: Cow::Borrowed(guesses()),
+        }
+    }
+
+    /// Lists of the given words, normalized, leaving out any that aren't `length` letters. Every
+    /// answer is accepted as a guess too.
+    pub fn new<'a>(
+        length: usize,
+        answers: impl IntoIterator<Item = &'a str>,
+        guesses: impl IntoIterator<Item = &'a str>,
+    ) -> WordList {
+        let fits = |word: &String| {
+            word.chars().count() == length && word.chars().all(|x| x.is_ascii_lowercase())
+        };
+        let answers = answers
+            .into_iter()
+            .map(normalize)
+            .filter(fits)
+            .collect::<Vec<_>>();
+        let guesses = guesses
+            .into_iter()
+            .map(normalize)
+            .filter(fits)
+            .chain(answers.iter().cloned())
+            .collect();
+
+        WordList {
+            length,
+            answers: Cow::Owned(answers),
+            guesses: Cow::Owned(guesses),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    pub fn answers(&self) -> &[String] {
+        &self.answers
+    }
+
+    /// Whether the (already normalized) guess is in the list
+    pub fn is_valid_guess(&self, guess: &str) -> bool {
+        self.guesses.contains(guess)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dictionary::{answers, is_valid_guess, normalize, WordList};
+
+    #[test]
+    fn normalizes_case_and_whitespace() {
+        assert_eq!(normalize("  CraNe\n"), "crane");
+    }
+
+    #[test]
+    fn accepts_normalized_guesses() {
+        assert!(is_valid_guess(&normalize(" HELLO ")));
+        assert!(!is_valid_guess(&normalize("hellx")));
+    }
+
+    #[test]
+    fn every_answer_can_be_guessed() {
+        assert!(answers().iter().all(|x| is_valid_guess(x)));
+    }
+
+    #[test]
+    fn lists_only_keep_words_of_their_length() {
+        let list = WordList::new(6, [" Planet", "hello", "orange"], ["garden", "it's a"]);
+
+        assert_eq!(list.answers(), ["planet", "orange"]);
+        assert!(list.is_valid_guess("garden"));
+        assert!(list.is_valid_guess("planet"));
+        assert!(!list.is_valid_guess("hello"));
+        assert!(!list.is_valid_guess("it's a"));
+    }
+}
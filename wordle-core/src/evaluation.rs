@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum MatchType {
+    Perfect,
+    Partial,
+    None,
+}
+
+impl MatchType {
+    /// A compact encoding, ordered from no match to a perfect one
+    pub fn code(self) -> u8 {
+        match self {
+            MatchType::None => 0,
+            MatchType::Partial => 1,
+            MatchType::Perfect => 2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub struct CharMatch {
+    pub index: usize,
+    pub character: char,
+    pub match_type: MatchType,
+}
+
+/// Colours each character of the guess against the word. A character can only be matched once, and
+/// perfect matches are found first, so a repeated letter in the guess is only marked `Partial` as
+/// many times as the word has it left unmatched. Words may be of any length, a guess of another
+/// length than the word's only matching where the two overlap.
+pub fn evaluate_guess(word: &str, guess: &str) -> Vec<CharMatch> {
+    let mut guess_chars_used = guess.chars().map(|_| false).collect::<Vec<_>>();
+    let mut word_chars = word.chars().collect::<Vec<char>>();
+    let mut evaluation = guess
+        .chars()
+        .clone()
+        .enumerate()
+        .map(|(index, x)| CharMatch {
+            index,
+            character: x,
+            match_type: MatchType::None,
+        })
+        .collect::<Vec<CharMatch>>();
+
+    // find the perfect matches
+    guess.chars().enumerate().for_each(|(i, guess_char)| {
+        if word_chars.get(i) == Some(&guess_char) {
+            evaluation[i] = CharMatch {
+                index: i,
+                character: guess_char,
+                match_type: MatchType::Perfect,
+            };
+
+            // prevent this character being re-used for a match
+            word_chars[i] = '_';
+            guess_chars_used[i] = true
+        }
+    });
+
+    // find the partial matches
+    guess.chars().enumerate().for_each(|(i, guess_char)| {
+        if guess_chars_used[i] {
+            return;
+        }
+
+        let word_index_match = word_chars.iter().position(|&x| x == guess_char);
+
+        if let Some(word_index) = word_index_match {
+            evaluation[i] = CharMatch {
+                index: i,
+                character: guess_char,
+                match_type: MatchType::Partial,
+            };
+
+            // prevent this character being re-used for a match
+            word_chars[word_index] = '_';
+            guess_chars_used[i] = true
+        }
+    });
+
+    evaluation
+}
+
+/// The evaluation as one digit per character, each its match's [`MatchType::code`], e.g. `"21001"`
+pub fn compact(evaluation: &[CharMatch]) -> String {
+    evaluation
+        .iter()
+        .map(|x| char::from(b'0' + x.match_type.code()))
+        .collect()
+}
+
+/// The best each letter has been matched across the evaluations, a letter found in place in any
+/// of them being `Perfect` however it was matched elsewhere, as a keyboard would show it
+pub fn letters<'a>(
+    evaluations: impl IntoIterator<Item = &'a Vec<CharMatch>>,
+) -> BTreeMap<char, MatchType> {
+    let mut letters = BTreeMap::new();
+    for x in evaluations.into_iter().flatten() {
+        letters
+            .entry(x.character)
+            .and_modify(|best: &mut MatchType| {
+                if x.match_type.code() > best.code() {
+                    *best = x.match_type;
+                }
+            })
+            .or_insert(x.match_type);
+    }
+
+    letters
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evaluation::{compact, evaluate_guess, letters, MatchType};
+
+    macro_rules! evaluation_test {
+        ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let (guess, target, expected) = $value;
+
+                let evaluation = evaluate_guess(&String::from(target), &guess);
+                let actual = evaluation
+                    .iter()
+                    .map(|x| x.match_type)
+                    .collect::<Vec<_>>();
+
+                assert_eq!(actual, expected, "Guess '{guess}' for word '{target}'")
+            }
+        )*
+        }
+    }
+
+    evaluation_test! {
+        eval_01: (
+            "cauld",
+            "salad",
+            vec![
+                MatchType::None,
+                MatchType::Perfect,
+                MatchType::None,
+                MatchType::Partial,
+                MatchType::Perfect,
+            ],
+        ),
+        eval_02: (
+            "llama",
+            "hello",
+            vec![
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::None,
+                MatchType::None,
+                MatchType::None,
+            ],
+        ),
+        eval_03: (
+            "hello",
+            "llama",
+            vec![
+                MatchType::None,
+                MatchType::None,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::None,
+            ],
+        ),
+        eval_04: (
+            "allan",
+            "llama",
+            vec![
+                MatchType::Partial,
+                MatchType::Perfect,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::None,
+            ],
+        ),
+        eval_05: (
+            "camel",
+            "shout",
+            vec![
+                MatchType::None,
+                MatchType::None,
+                MatchType::None,
+                MatchType::None,
+                MatchType::None,
+            ],
+        ),
+        eval_06: (
+            "camel",
+            "camel",
+            vec![
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+            ],
+        ),
+        eval_07: (
+            "allan",
+            "allan",
+            vec![
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+            ],
+        ),
+        eval_08: (
+            "vegan",
+            "moral",
+            vec![
+                MatchType::None,
+                MatchType::None,
+                MatchType::None,
+                MatchType::Perfect,
+                MatchType::None,
+            ],
+        ),
+        eval_09: (
+            "oggol",
+            "googl",
+            vec![
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::Perfect,
+            ],
+        ),
+        eval_10: ( // checking only the first of a double letter can match a partial, i.e. second `o` should be no match
+            "look",
+            "omfg",
+            vec![
+                MatchType::None,
+                MatchType::Partial,
+                MatchType::None,
+                MatchType::None,
+            ],
+        ),
+        eval_11: ( // checking a perfectly matched letter cannot be used for a secondary partial match
+            "look",
+            "grok",
+            vec![
+                MatchType::None,
+                MatchType::None,
+                MatchType::Perfect,
+                MatchType::Perfect,
+            ],
+        ),
+        eval_12: (
+            "owler",
+            "mower",
+            vec![
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::None,
+                MatchType::Perfect,
+                MatchType::Perfect,
+            ],
+        ),
+        eval_13: (
+            "orange",
+            "garden",
+            vec![
+                MatchType::None,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::Partial,
+                MatchType::Partial,
+            ],
+        ),
+        eval_14: ( // a guess longer than the word can't match past its end
+            "planets",
+            "planet",
+            vec![
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::Perfect,
+                MatchType::None,
+            ],
+        ),
+    }
+
+    #[test]
+    fn compact_evaluations_are_a_digit_per_character() {
+        assert_eq!(compact(&evaluate_guess("salad", "cauld")), "02012");
+    }
+
+    #[test]
+    fn letters_keep_their_best_match() {
+        let evaluations = [
+            evaluate_guess("hello", "llama"),
+            evaluate_guess("hello", "world"),
+        ];
+
+        let letters = letters(&evaluations);
+
+        assert_eq!(letters[&'l'], MatchType::Perfect);
+        assert_eq!(letters[&'o'], MatchType::Partial);
+        assert_eq!(letters[&'a'], MatchType::None);
+        assert!(!letters.contains_key(&'h'));
+    }
+}
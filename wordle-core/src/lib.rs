@@ -0,0 +1,26 @@
+//! The rules of wordle: the dictionaries of guesses and answers, evaluating guesses, the lifecycle
+//! of a game, and choosing answers, free of any HTTP or storage concerns so that they can be
+//! embedded anywhere, a bot or the browser as much as the server.
+//!
+//! Everything that needs randomness is behind the default `rand` feature. Without it, evaluation
+//! and game states still build for `wasm32-unknown-unknown`, and the `wasm` feature adds the
+//! exports a browser needs to evaluate guesses exactly as the server does.
+
+mod answers;
+pub mod dictionary;
+mod evaluation;
+#[cfg(feature = "rand")]
+mod mode;
+#[cfg(feature = "rand")]
+mod selector;
+mod state;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod words;
+
+pub use evaluation::{compact, evaluate_guess, letters, CharMatch, MatchType};
+#[cfg(feature = "rand")]
+pub use mode::{Adversarial, Board, Classic, GameMode, Hard, ModeRegistry, ModeState, MultiBoard};
+#[cfg(feature = "rand")]
+pub use selector::{AnswerSelector, Daily, Fixed, NoRepeat, Random, Selection, Weighted};
+pub use state::GameState;
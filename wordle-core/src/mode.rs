@@ -0,0 +1,376 @@
+use crate::evaluation::{evaluate_guess, CharMatch, MatchType};
+use crate::selector::{AnswerSelector, Selection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single hidden word, or for modes that decide the answer as they go, every word it could
+/// still be
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Board {
+    pub candidates: Vec<String>,
+    pub solved: bool,
+}
+
+impl Board {
+    fn new(word: &str) -> Board {
+        Board {
+            candidates: vec![word.to_string()],
+            solved: false,
+        }
+    }
+
+    /// The board's answer, or for an undecided board the word it would currently reveal
+    pub fn word(&self) -> &str {
+        self.candidates.first().map(String::as_str).unwrap_or("")
+    }
+}
+
+/// Everything a mode needs to carry from one guess to the next, persisted alongside the game
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ModeState {
+    pub boards: Vec<Board>,
+    /// Every guess played so far and its evaluation against the first board
+    pub revealed: Vec<(String, Vec<CharMatch>)>,
+}
+
+impl ModeState {
+    pub fn new(boards: Vec<Board>) -> ModeState {
+        ModeState {
+            boards,
+            revealed: Vec::new(),
+        }
+    }
+
+    /// The state of a plain single-word game
+    pub fn single(word: &str) -> ModeState {
+        ModeState::new(vec![Board::new(word)])
+    }
+
+    /// The answer to every board, in order
+    pub fn words(&self) -> Vec<String> {
+        self.boards.iter().map(|x| x.word().to_string()).collect()
+    }
+}
+
+/// A variant of the game. Each mode decides how answers are chosen, which dictionary words are
+/// acceptable guesses, how a guess is evaluated and when the game is won, so that a new variant
+/// only needs a new implementation registered in the [`ModeRegistry`].
+pub trait GameMode: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Chooses the hidden words for a new game
+    fn start(
+        &self,
+        answers: &[String],
+        selector: &dyn AnswerSelector,
+        selection: &Selection,
+    ) -> ModeState {
+        ModeState::single(selector.select(answers, selection).unwrap_or_default())
+    }
+
+    /// How many goes a game gets when games are usually given `max`, or `None` for as many as it
+    /// takes
+    fn guess_limit(&self, max: usize) -> Option<usize> {
+        Some(max)
+    }
+
+    /// Refuses a dictionary word that the mode's rules don't allow, with an explanation
+    fn validate(&self, _state: &ModeState, _guess: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Evaluates the guess against every board, updating the state to reflect it
+    fn evaluate(&self, state: &mut ModeState, guess: &str) -> Vec<Vec<CharMatch>> {
+        let evaluations = state
+            .boards
+            .iter_mut()
+            .map(|board| {
+                let evaluation = evaluate_guess(board.word(), guess);
+                board.solved |= board.word() == guess;
+
+                evaluation
+            })
+            .collect::<Vec<_>>();
+
+        reveal(state, guess, &evaluations);
+
+        evaluations
+    }
+
+    fn is_won(&self, state: &ModeState) -> bool {
+        state.boards.iter().all(|x| x.solved)
+    }
+}
+
+fn reveal(state: &mut ModeState, guess: &str, evaluations: &[Vec<CharMatch>]) {
+    if let Some(evaluation) = evaluations.first() {
+        state.revealed.push((guess.to_string(), evaluation.clone()));
+    }
+}
+
+/// The original game: one word, any dictionary word may be guessed
+pub struct Classic;
+
+impl GameMode for Classic {
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+}
+
+/// Every letter revealed as `Perfect` must stay in place, and every letter revealed as `Partial`
+/// must be reused, in every later guess
+pub struct Hard;
+
+impl GameMode for Hard {
+    fn name(&self) -> &'static str {
+        "hard"
+    }
+
+    fn validate(&self, state: &ModeState, guess: &str) -> Result<(), String> {
+        let guess_chars = guess.chars().collect::<Vec<_>>();
+
+        for (previous, evaluation) in &state.revealed {
+            for x in evaluation {
+                if x.match_type == MatchType::Perfect
+                    && guess_chars.get(x.index) != Some(&x.character)
+                {
+                    return Err(format!(
+                        "letter {} must be '{}', as revealed by '{previous}'",
+                        x.index + 1,
+                        x.character
+                    ));
+                }
+            }
+
+            let mut required = HashMap::new();
+            for x in evaluation
+                .iter()
+                .filter(|x| x.match_type != MatchType::None)
+            {
+                *required.entry(x.character).or_insert(0) += 1;
+            }
+
+            for (character, count) in required {
+                if guess_chars.iter().filter(|x| **x == character).count() < count {
+                    return Err(format!(
+                        "guess must contain '{character}', as revealed by '{previous}'"
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Never commits to an answer: every guess is evaluated against whichever group of remaining
+/// candidates is largest, so the answer is only pinned down once nothing else is left
+pub struct Adversarial;
+
+impl GameMode for Adversarial {
+    fn name(&self) -> &'static str {
+        "adversarial"
+    }
+
+    /// The point is to see how few goes it can be done in, however many that is
+    fn guess_limit(&self, _max: usize) -> Option<usize> {
+        None
+    }
+
+    fn start(
+        &self,
+        answers: &[String],
+        _selector: &dyn AnswerSelector,
+        _selection: &Selection,
+    ) -> ModeState {
+        ModeState::new(vec![Board {
+            candidates: answers.to_vec(),
+            solved: false,
+        }])
+    }
+
+    fn evaluate(&self, state: &mut ModeState, guess: &str) -> Vec<Vec<CharMatch>> {
+        let board = &mut state.boards[0];
+
+        let mut buckets: HashMap<Vec<MatchType>, Vec<String>> = HashMap::new();
+        for candidate in board.candidates.drain(..) {
+            let pattern = evaluate_guess(&candidate, guess)
+                .into_iter()
+                .map(|x| x.match_type)
+                .collect();
+            buckets.entry(pattern).or_default().push(candidate);
+        }
+
+        // the largest group wins, ties going to whichever reveals the fewest perfect letters so
+        // that the choice doesn't depend on hash ordering
+        let (_, candidates) = buckets
+            .into_iter()
+            .max_by_key(|(pattern, candidates)| {
+                let perfect = pattern.iter().filter(|x| **x == MatchType::Perfect).count();
+                (
+                    candidates.len(),
+                    usize::MAX - perfect,
+                    candidates[0].clone(),
+                )
+            })
+            .unwrap_or_default();
+
+        board.candidates = candidates;
+        board.solved = board.candidates.len() == 1 && board.word() == guess;
+
+        let evaluations = vec![evaluate_guess(board.word(), guess)];
+        reveal(state, guess, &evaluations);
+
+        evaluations
+    }
+}
+
+/// Several words played at once: every guess is evaluated against every board, and the game is
+/// won once each board has been solved
+pub struct MultiBoard {
+    pub boards: usize,
+}
+
+impl GameMode for MultiBoard {
+    fn name(&self) -> &'static str {
+        "multi"
+    }
+
+    /// A go more for each board after the first, so that every board can be solved in the usual
+    /// number of goes after the one before it
+    fn guess_limit(&self, max: usize) -> Option<usize> {
+        Some(max + self.boards.saturating_sub(1))
+    }
+
+    fn start(
+        &self,
+        answers: &[String],
+        selector: &dyn AnswerSelector,
+        selection: &Selection,
+    ) -> ModeState {
+        let mut remaining = answers.to_vec();
+        let mut boards = Vec::new();
+
+        // every board gets a different word, for as long as there are different words to give
+        while boards.len() < self.boards {
+            let Some(word) = selector.select(&remaining, selection).map(String::from) else {
+                break;
+            };
+            remaining.retain(|x| *x != word);
+            boards.push(Board::new(&word));
+        }
+
+        ModeState::new(boards)
+    }
+}
+
+/// Every mode a game can be played in, looked up by name
+pub struct ModeRegistry {
+    modes: Vec<Box<dyn GameMode>>,
+}
+
+impl ModeRegistry {
+    pub fn new() -> ModeRegistry {
+        ModeRegistry { modes: Vec::new() }
+    }
+
+    /// Classic, hard, adversarial, and a four-board multi mode
+    pub fn standard() -> ModeRegistry {
+        let mut registry = ModeRegistry::new();
+        registry.register(Box::new(Classic));
+        registry.register(Box::new(Hard));
+        registry.register(Box::new(Adversarial));
+        registry.register(Box::new(MultiBoard { boards: 4 }));
+
+        registry
+    }
+
+    /// Adds a mode, replacing any already registered under the same name
+    pub fn register(&mut self, mode: Box<dyn GameMode>) {
+        self.modes.retain(|x| x.name() != mode.name());
+        self.modes.push(mode);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn GameMode> {
+        self.modes
+            .iter()
+            .find(|x| x.name() == name)
+            .map(|x| x.as_ref())
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.modes.iter().map(|x| x.name())
+    }
+}
+
+impl Default for ModeRegistry {
+    fn default() -> Self {
+        ModeRegistry::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mode::{Adversarial, GameMode, Hard, ModeRegistry, ModeState, MultiBoard};
+    use crate::selector::{Random, Selection};
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn hard_mode_requires_revealed_letters() {
+        let mut state = ModeState::single("crane");
+        Hard.evaluate(&mut state, "cloak");
+
+        assert!(Hard.validate(&state, "ovary").is_err());
+        assert!(Hard.validate(&state, "cloud").is_err());
+        assert!(Hard.validate(&state, "champ").is_ok());
+    }
+
+    #[test]
+    fn adversarial_mode_only_concedes_the_last_candidate() {
+        let answers = words(&["crane", "crate", "grate"]);
+        let mut state = Adversarial.start(&answers, &Random::seeded(1), &Selection::default());
+
+        Adversarial.evaluate(&mut state, "slump");
+        assert_eq!(state.boards[0].candidates, answers);
+
+        // every candidate now differs, so the one sharing the fewest perfect letters is kept
+        Adversarial.evaluate(&mut state, "crane");
+        assert_eq!(state.boards[0].candidates, words(&["grate"]));
+        assert!(!Adversarial.is_won(&state));
+
+        Adversarial.evaluate(&mut state, "grate");
+        assert!(Adversarial.is_won(&state));
+    }
+
+    #[test]
+    fn multi_board_mode_is_won_once_every_board_is_solved() {
+        let mode = MultiBoard { boards: 2 };
+        let answers = words(&["crane", "moist"]);
+        let mut state = mode.start(&answers, &Random::seeded(1), &Selection::default());
+        let [first, second] = [
+            state.boards[0].word().to_string(),
+            state.boards[1].word().to_string(),
+        ];
+
+        assert_eq!(mode.evaluate(&mut state, &first).len(), 2);
+        assert!(!mode.is_won(&state));
+
+        mode.evaluate(&mut state, &second);
+        assert!(mode.is_won(&state));
+    }
+
+    #[test]
+    fn registry_looks_modes_up_by_name() {
+        let registry = ModeRegistry::standard();
+
+        assert_eq!(registry.get("hard").map(|x| x.name()), Some("hard"));
+        assert!(registry.get("nonsense").is_none());
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            vec!["classic", "hard", "adversarial", "multi"]
+        );
+    }
+}
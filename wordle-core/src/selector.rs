@@ -0,0 +1,243 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// What an [`AnswerSelector`] may take into account when choosing an answer
+#[derive(Default)]
+pub struct Selection<'a> {
+    /// Whoever the game is for, if anyone
+    pub client: Option<&'a str>,
+    /// Days since the Unix epoch, in whatever timezone the caller's days roll over in
+    pub day: u64,
+    /// Answers to steer clear of, such as those the client has already played
+    pub previous: HashSet<String>,
+}
+
+/// Chooses the answer to a new game from a list of candidates. Every game's answer is chosen
+/// through one of these, so how answers are picked, and whether that's repeatable, is decided in one
+/// place rather than by whoever happens to need a word.
+pub trait AnswerSelector: Send + Sync {
+    /// The chosen answer, or `None` if there are no answers to choose from
+    fn select<'a>(&self, answers: &'a [String], selection: &Selection) -> Option<&'a str>;
+}
+
+/// Picks uniformly from its own random number generator, which can be seeded to make the sequence
+/// of answers repeatable
+pub struct Random {
+    rng: Mutex<Box<dyn RngCore + Send>>,
+}
+
+impl Random {
+    pub fn new(rng: impl RngCore + Send + 'static) -> Random {
+        Random {
+            rng: Mutex::new(Box::new(rng)),
+        }
+    }
+
+    pub fn from_entropy() -> Random {
+        Random::new(StdRng::from_entropy())
+    }
+
+    pub fn seeded(seed: u64) -> Random {
+        Random::new(StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        let mut rng = self.rng.lock().unwrap_or_else(|x| x.into_inner());
+
+        f(rng.as_mut())
+    }
+}
+
+impl AnswerSelector for Random {
+    fn select<'a>(&self, answers: &'a [String], _selection: &Selection) -> Option<&'a str> {
+        if answers.is_empty() {
+            return None;
+        }
+
+        let index = self.with_rng(|rng| rng.gen_range(0..answers.len()));
+
+        Some(&answers[index])
+    }
+}
+
+/// The same answer for everyone on a given day, a pure function of the seed and the day so that
+/// it survives restarts and doesn't depend on the version of `rand`
+pub struct Daily {
+    pub seed: u64,
+}
+
+impl AnswerSelector for Daily {
+    fn select<'a>(&self, answers: &'a [String], selection: &Selection) -> Option<&'a str> {
+        if answers.is_empty() {
+            return None;
+        }
+
+        let index = splitmix64(self.seed ^ selection.day) % answers.len() as u64;
+
+        Some(&answers[index as usize])
+    }
+}
+
+/// An answer decided ahead of time, or whatever `otherwise` chooses if it isn't one of the answers
+pub struct Fixed<S> {
+    pub word: String,
+    pub otherwise: S,
+}
+
+impl<S: AnswerSelector> AnswerSelector for Fixed<S> {
+    fn select<'a>(&self, answers: &'a [String], selection: &Selection) -> Option<&'a str> {
+        match answers.iter().find(|x| **x == self.word) {
+            Some(word) => Some(word),
+            None => self.otherwise.select(answers, selection),
+        }
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    z ^ (z >> 31)
+}
+
+/// Favours some answers over others. Answers without a weight have a weight of one, and answers
+/// weighted zero are never chosen.
+pub struct Weighted {
+    pub weights: HashMap<String, f64>,
+    pub random: Random,
+}
+
+impl AnswerSelector for Weighted {
+    fn select<'a>(&self, answers: &'a [String], _selection: &Selection) -> Option<&'a str> {
+        let weights = answers
+            .iter()
+            .map(|x| self.weights.get(x).copied().unwrap_or(1.0).max(0.0));
+        let distribution = WeightedIndex::new(weights).ok()?;
+
+        let index = self.random.with_rng(|rng| distribution.sample(rng));
+
+        Some(&answers[index])
+    }
+}
+
+/// Leaves out the answers in [`Selection::previous`], choosing from the rest with another
+/// selector, and only repeating one once every answer has been used
+pub struct NoRepeat<S> {
+    pub inner: S,
+}
+
+impl<S: AnswerSelector> AnswerSelector for NoRepeat<S> {
+    fn select<'a>(&self, answers: &'a [String], selection: &Selection) -> Option<&'a str> {
+        let fresh = answers
+            .iter()
+            .filter(|x| !selection.previous.contains(*x))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if fresh.is_empty() {
+            return self.inner.select(answers, selection);
+        }
+
+        let chosen = self.inner.select(&fresh, selection)?;
+        answers.iter().find(|x| *x == chosen).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::selector::{AnswerSelector, Daily, Fixed, NoRepeat, Random, Selection, Weighted};
+    use std::collections::HashMap;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|x| x.to_string()).collect()
+    }
+
+    fn sequence(selector: &impl AnswerSelector, answers: &[String]) -> Vec<String> {
+        (0..10)
+            .map(|_| {
+                selector
+                    .select(answers, &Selection::default())
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn seeded_selection_is_repeatable() {
+        let answers = words(&["crane", "moist", "pilot", "slump"]);
+
+        assert_eq!(
+            sequence(&Random::seeded(7), &answers),
+            sequence(&Random::seeded(7), &answers)
+        );
+        assert!(Random::seeded(7)
+            .select(&[], &Selection::default())
+            .is_none());
+    }
+
+    #[test]
+    fn daily_selection_only_changes_with_the_day() {
+        let answers = words(&["crane", "moist", "pilot", "slump", "tiger", "vapor"]);
+        let daily = Daily { seed: 1 };
+        let on = |day| {
+            let selection = Selection {
+                day,
+                ..Selection::default()
+            };
+
+            daily.select(&answers, &selection).unwrap().to_string()
+        };
+
+        assert_eq!(on(19000), on(19000));
+        assert!((19000..19010).any(|day| on(day) != on(19000)));
+    }
+
+    #[test]
+    fn fixed_answers_fall_back_unless_they_are_answers() {
+        let answers = words(&["crane", "moist"]);
+        let fixed = |word: &str| Fixed {
+            word: word.to_string(),
+            otherwise: Daily { seed: 1 },
+        };
+        let selection = Selection::default();
+
+        assert_eq!(fixed("moist").select(&answers, &selection), Some("moist"));
+        assert_eq!(
+            fixed("zzzzz").select(&answers, &selection),
+            Daily { seed: 1 }.select(&answers, &selection)
+        );
+    }
+
+    #[test]
+    fn weighted_selection_never_picks_a_zero_weight() {
+        let answers = words(&["crane", "moist"]);
+        let weighted = Weighted {
+            weights: HashMap::from([(String::from("crane"), 0.0)]),
+            random: Random::seeded(1),
+        };
+
+        assert!(sequence(&weighted, &answers).iter().all(|x| x == "moist"));
+    }
+
+    #[test]
+    fn no_repeat_selection_avoids_previous_answers_until_none_are_left() {
+        let answers = words(&["crane", "moist"]);
+        let selector = NoRepeat {
+            inner: Random::seeded(1),
+        };
+        let mut selection = Selection {
+            previous: ["crane"].map(String::from).into(),
+            ..Selection::default()
+        };
+
+        assert_eq!(selector.select(&answers, &selection), Some("moist"));
+
+        selection.previous.insert(String::from("moist"));
+        assert!(selector.select(&answers, &selection).is_some());
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// The lifecycle of a game. Every game starts `InProgress` and moves to exactly one of the
+/// finished states, after which it accepts no further guesses.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum GameState {
+    InProgress,
+    Won,
+    Lost,
+    Forfeited,
+    Expired,
+}
+
+impl GameState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GameState::InProgress => "in_progress",
+            GameState::Won => "won",
+            GameState::Lost => "lost",
+            GameState::Forfeited => "forfeited",
+            GameState::Expired => "expired",
+        }
+    }
+
+    pub fn is_finished(self) -> bool {
+        self != GameState::InProgress
+    }
+
+    /// The state after a guess has been played, or `None` if the game can no longer be played. A
+    /// wrong guess that used up the last go loses the game.
+    pub fn guess(self, correct: bool, out_of_goes: bool) -> Option<GameState> {
+        match self {
+            GameState::InProgress if correct => Some(GameState::Won),
+            GameState::InProgress if out_of_goes => Some(GameState::Lost),
+            GameState::InProgress => Some(GameState::InProgress),
+            _ => None,
+        }
+    }
+
+    /// The state after the player gives up, or `None` if the game's already finished
+    pub fn forfeit(self) -> Option<GameState> {
+        match self {
+            GameState::InProgress => Some(GameState::Forfeited),
+            _ => None,
+        }
+    }
+
+    /// The state once the game can no longer be finished, such as a daily game whose day has
+    /// passed, or `None` if it's already finished
+    pub fn expire(self) -> Option<GameState> {
+        match self {
+            GameState::InProgress => Some(GameState::Expired),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for GameState {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "in_progress" => Ok(GameState::InProgress),
+            "won" => Ok(GameState::Won),
+            "lost" => Ok(GameState::Lost),
+            "forfeited" => Ok(GameState::Forfeited),
+            "expired" => Ok(GameState::Expired),
+            _ => Err(format!("'{value}' is not a game state")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::GameState;
+
+    #[test]
+    fn correct_guess_wins() {
+        assert_eq!(
+            GameState::InProgress.guess(true, false),
+            Some(GameState::Won)
+        );
+        assert_eq!(
+            GameState::InProgress.guess(false, false),
+            Some(GameState::InProgress)
+        );
+    }
+
+    #[test]
+    fn the_last_go_wins_or_loses() {
+        assert_eq!(
+            GameState::InProgress.guess(true, true),
+            Some(GameState::Won)
+        );
+        assert_eq!(
+            GameState::InProgress.guess(false, true),
+            Some(GameState::Lost)
+        );
+    }
+
+    #[test]
+    fn finished_games_reject_guesses() {
+        for state in [
+            GameState::Won,
+            GameState::Lost,
+            GameState::Forfeited,
+            GameState::Expired,
+        ] {
+            assert!(state.is_finished());
+            assert_eq!(state.guess(true, false), None);
+            assert_eq!(state.forfeit(), None);
+            assert_eq!(state.expire(), None);
+        }
+    }
+
+    #[test]
+    fn games_in_progress_expire() {
+        assert_eq!(GameState::InProgress.expire(), Some(GameState::Expired));
+    }
+}
@@ -0,0 +1,104 @@
+//! Raw WebAssembly exports, kept to plain numbers and pointers so that loading the module needs
+//! nothing beyond `WebAssembly.instantiate`. `js/wordle-core.js` wraps them in an
+//! `evaluate(guess, answer)` returning the same evaluation as the API.
+
+use crate::evaluation::evaluate_guess;
+use std::slice;
+
+/// Returned by [`evaluate`] when the guess and answer can't be compared
+pub const INVALID: usize = usize::MAX;
+
+/// Reserves `len` bytes of the module's memory, for passing strings in and evaluations out
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+
+    ptr
+}
+
+/// Releases memory reserved by [`alloc`]
+///
+/// # Safety
+///
+/// `ptr` and `len` must be exactly as given to and returned by a single call to [`alloc`].
+#[no_mangle]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+/// Evaluates the UTF-8 guess against the UTF-8 answer, writing one [`MatchType::code`] per
+/// character of the guess to `out` and returning how many were written, or [`INVALID`] if either
+/// isn't UTF-8 or they differ in length
+///
+/// # Safety
+///
+/// Each pointer must be valid for its length, and `out` for at least `guess_len` bytes.
+///
+/// [`MatchType::code`]: crate::MatchType::code
+#[no_mangle]
+pub unsafe extern "C" fn evaluate(
+    guess_ptr: *const u8,
+    guess_len: usize,
+    answer_ptr: *const u8,
+    answer_len: usize,
+    out: *mut u8,
+) -> usize {
+    let guess = slice::from_raw_parts(guess_ptr, guess_len);
+    let answer = slice::from_raw_parts(answer_ptr, answer_len);
+
+    match codes(guess, answer) {
+        Some(codes) => {
+            slice::from_raw_parts_mut(out, codes.len()).copy_from_slice(&codes);
+
+            codes.len()
+        }
+        None => INVALID,
+    }
+}
+
+fn codes(guess: &[u8], answer: &[u8]) -> Option<Vec<u8>> {
+    let guess = std::str::from_utf8(guess).ok()?;
+    let answer = std::str::from_utf8(answer).ok()?;
+
+    if guess.chars().count() != answer.chars().count() {
+        return None;
+    }
+
+    Some(
+        evaluate_guess(answer, guess)
+            .iter()
+            .map(|x| x.match_type.code())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wasm::{alloc, dealloc, evaluate, INVALID};
+
+    fn call(guess: &str, answer: &str) -> Option<Vec<u8>> {
+        let out = alloc(guess.len());
+        let written = unsafe {
+            evaluate(
+                guess.as_ptr(),
+                guess.len(),
+                answer.as_ptr(),
+                answer.len(),
+                out,
+            )
+        };
+        let codes = (written != INVALID)
+            .then(|| unsafe { std::slice::from_raw_parts(out, written) }.to_vec());
+        unsafe { dealloc(out, guess.len()) };
+
+        codes
+    }
+
+    #[test]
+    fn writes_one_code_per_character() {
+        assert_eq!(call("cauld", "salad"), Some(vec![0, 2, 0, 1, 2]));
+        assert_eq!(call("crane", "cat"), None);
+    }
+}
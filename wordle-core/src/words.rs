@@ -1,4 +1,4 @@
-pub static FILE_CONTENT: &[&str] = &[
+pub static GUESSES: &[&str] = &[
     "aahed", "aalii", "aapas", "aargh", "aarti", "abaca", "abaci", "aback", "abacs", "abaft",
     "abaht", "abaka", "abamp", "aband", "abase", "abash", "abask", "abate", "abaya", "abbas",
     "abbed", "abbes", "abbey", "abbot", "abcee", "abeam", "abear", "abeat", "abeer", "abele",